@@ -88,6 +88,8 @@ settings_tab_diagnostics = Info
 # Settings - General tab
 settings_use_cache = Use cache
 settings_use_cache_desc = Speeds up subsequent scans (hash/images)
+settings_resume_interrupted_scans = Resume interrupted scans
+settings_resume_interrupted_scans_desc = Continue duplicate/image scan from last checkpoint (requires cache)
 settings_ignore_hidden = Ignore hidden files
 settings_ignore_hidden_desc = Files and folders starting with '.'
 settings_show_notification = Notify when scan finishes
@@ -100,6 +102,11 @@ settings_scan_label = SCAN
 settings_filters_label = FILTERS (some tools)
 settings_min_file_size = Min. file size
 settings_max_file_size = Max. file size
+settings_min_file_age = Only files older than
+settings_max_read_speed = Max. read speed
+settings_max_read_speed_desc = Applied also to running scan
+settings_idle_io_priority = Low priority reading
+settings_idle_io_priority_desc = Read files only when other apps don't use storage
 settings_language = Language
 settings_language_restart = Requires app restart
 settings_common_label = COMMON SETTINGS
@@ -298,6 +305,12 @@ option_music_method_tags = Tags
 option_music_method_audio = Audio
 option_min_size_none = None
 option_max_size_unlimited = Unlimited
+option_min_age_none = None
+option_min_age_week = 1 week
+option_min_age_month = 1 month
+option_min_age_six_months = 6 months
+option_min_age_year = 1 year
+option_read_speed_unlimited = Unlimited
 option_audio_preset_identical = Identical
 option_audio_preset_clip = Clip in longer
 option_audio_preset_similar = Similar
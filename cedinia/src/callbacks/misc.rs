@@ -401,6 +401,7 @@ pub(crate) fn wire_save_settings_now(
         let win = weak.upgrade().expect("Failed to upgrade app :(");
         let settings = collect_settings_from_gui(&win);
         save_settings(&settings);
+        crate::settings::apply_io_throttling(&settings);
         crate::settings::save_dirs(&included_dirs.borrow(), &excluded_dirs.borrow(), &referenced_dirs.borrow());
 
         #[cfg(target_os = "android")]
@@ -112,6 +112,7 @@ fn build_common_filters(win: &MainWindow, excluded_paths: Vec<PathBuf>, referenc
     let items = StringComboBoxItems::new();
     let min_file_size_bytes = items.min_file_size.get(g.get_min_file_size_idx() as usize).map_or(0, |e| e.value.to_bytes());
     let max_file_size_bytes = items.max_file_size.get(g.get_max_file_size_idx() as usize).and_then(|e| e.value.to_bytes());
+    let min_file_age_seconds = items.min_file_age.get(g.get_min_file_age_idx() as usize).map_or(0, |e| e.value.to_seconds());
     let split_csv = |s: slint::SharedString| -> Vec<String> { s.as_str().split(',').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect() };
     let mut excluded_items = split_csv(g.get_excluded_items());
     let cache_dir = crate::thumbnail_loader::thumbnail_cache_dir();
@@ -128,8 +129,10 @@ fn build_common_filters(win: &MainWindow, excluded_paths: Vec<PathBuf>, referenc
         excluded_extensions: split_csv(g.get_excluded_extensions()),
         min_file_size_bytes,
         max_file_size_bytes,
+        min_file_age_seconds,
         recursive_search: true,
         use_cache: g.get_use_cache(),
+        resume: g.get_resume_interrupted_scans(),
         hide_hard_links: false,
         delete_outdated_cache: true,
         save_also_as_json: false,
@@ -2,6 +2,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crossbeam_channel::{Receiver, Sender, unbounded};
 use czkawka_core::common::model::{CheckingMethod, HashType};
@@ -33,8 +34,10 @@ pub struct CommonFilters {
     pub excluded_paths: Vec<PathBuf>,
     pub min_file_size_bytes: u64,
     pub max_file_size_bytes: Option<u64>,
+    pub min_file_age_seconds: u64,
     pub recursive_search: bool,
     pub use_cache: bool,
+    pub resume: bool,
     pub hide_hard_links: bool,
     pub delete_outdated_cache: bool,
     pub save_also_as_json: bool,
@@ -51,8 +54,10 @@ impl Default for CommonFilters {
             excluded_paths: Vec::new(),
             min_file_size_bytes: 0,
             max_file_size_bytes: None,
+            min_file_age_seconds: 0,
             recursive_search: true,
             use_cache: true,
+            resume: false,
             hide_hard_links: false,
             delete_outdated_cache: true,
             save_also_as_json: false,
@@ -378,8 +383,13 @@ pub(crate) fn apply_filters<T: CommonData>(tool: &mut T, filters: &CommonFilters
     if let Some(max) = filters.max_file_size_bytes {
         tool.set_maximal_file_size(max);
     }
+    if filters.min_file_age_seconds > 0 {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        tool.set_maximal_modified_date(now.saturating_sub(filters.min_file_age_seconds));
+    }
     tool.set_recursive_search(filters.recursive_search);
     tool.set_use_cache(filters.use_cache);
+    tool.set_resume(filters.resume);
     tool.set_hide_hard_links(filters.hide_hard_links);
     tool.set_delete_outdated_cache(filters.delete_outdated_cache);
     tool.set_save_also_as_json(filters.save_also_as_json);
@@ -69,7 +69,13 @@ pub(crate) fn scan_duplicate_files<H: ScanResultHandler>(
     use czkawka_core::common::model::CheckingMethod;
     use czkawka_core::tools::duplicate::{DuplicateEntry, DuplicateFinder, DuplicateFinderParameters};
     let (ptx, fwd) = spawn_progress_forwarder(Arc::clone(handler), scan_id);
-    let params = DuplicateFinderParameters::new(check_method, hash_type, filters.use_cache, 8 * 1024, 0, false);
+    let params = DuplicateFinderParameters {
+        check_method,
+        hash_type,
+        use_prehash_cache: filters.use_cache,
+        minimal_cache_file_size: 8 * 1024,
+        ..Default::default()
+    };
     let mut tool = DuplicateFinder::new(params);
     tool.set_included_paths(dirs);
     apply_filters(&mut tool, filters);
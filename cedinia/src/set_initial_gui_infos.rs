@@ -23,6 +23,16 @@ pub(crate) fn set_initial_gui_infos(app: &MainWindow) {
         display_names(&items.min_file_size),
         "GeneralSettings.min_file_size_options out of sync with Rust"
     );
+    assert_eq!(
+        slint_vec(general.get_min_file_age_options()),
+        display_names(&items.min_file_age),
+        "GeneralSettings.min_file_age_options out of sync with Rust"
+    );
+    assert_eq!(
+        slint_vec(general.get_max_read_speed_options()),
+        display_names(&items.max_read_speed),
+        "GeneralSettings.max_read_speed_options out of sync with Rust"
+    );
 
     assert_eq!(
         slint_vec(dup.get_check_method_options()),
@@ -75,6 +75,48 @@ impl MaxFileSize {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinFileAge {
+    None,
+    OneWeek,
+    OneMonth,
+    SixMonths,
+    OneYear,
+}
+
+impl MinFileAge {
+    pub fn to_seconds(self) -> u64 {
+        const DAY: u64 = 24 * 60 * 60;
+        match self {
+            Self::None => 0,
+            Self::OneWeek => 7 * DAY,
+            Self::OneMonth => 30 * DAY,
+            Self::SixMonths => 182 * DAY,
+            Self::OneYear => 365 * DAY,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaxReadSpeed {
+    Unlimited,
+    FiveMb,
+    TwentyMb,
+    FiftyMb,
+}
+
+impl MaxReadSpeed {
+    /// 0 means no limit.
+    pub fn to_bytes_per_second(self) -> u64 {
+        match self {
+            Self::Unlimited => 0,
+            Self::FiveMb => 5 * 1_024 * 1_024,
+            Self::TwentyMb => 20 * 1_024 * 1_024,
+            Self::FiftyMb => 50 * 1_024 * 1_024,
+        }
+    }
+}
+
 /// Parameters corresponding to one of the audio-similarity presets for Similar Videos.
 #[derive(Debug, Clone)]
 pub struct AudioPresetParams {
@@ -87,6 +129,8 @@ pub struct AudioPresetParams {
 pub struct StringComboBoxItems {
     pub min_file_size: Vec<StringComboBoxItem<MinFileSize>>,
     pub max_file_size: Vec<StringComboBoxItem<MaxFileSize>>,
+    pub min_file_age: Vec<StringComboBoxItem<MinFileAge>>,
+    pub max_read_speed: Vec<StringComboBoxItem<MaxReadSpeed>>,
     pub duplicates_check_method: Vec<StringComboBoxItem<CheckingMethod>>,
     pub duplicates_hash_type: Vec<StringComboBoxItem<HashType>>,
     pub hash_size: Vec<StringComboBoxItem<u8>>,
@@ -124,6 +168,21 @@ impl StringComboBoxItems {
             ("unlimited", MaxFileSize::Unlimited, DisplaySpec::Translatable("option_max_size_unlimited")),
         ]);
 
+        let min_file_age = Self::convert_i18n(&[
+            ("none", MinFileAge::None, DisplaySpec::Translatable("option_min_age_none")),
+            ("1w", MinFileAge::OneWeek, DisplaySpec::Translatable("option_min_age_week")),
+            ("1mo", MinFileAge::OneMonth, DisplaySpec::Translatable("option_min_age_month")),
+            ("6mo", MinFileAge::SixMonths, DisplaySpec::Translatable("option_min_age_six_months")),
+            ("1y", MinFileAge::OneYear, DisplaySpec::Translatable("option_min_age_year")),
+        ]);
+
+        let max_read_speed = Self::convert_i18n(&[
+            ("unlimited", MaxReadSpeed::Unlimited, DisplaySpec::Translatable("option_read_speed_unlimited")),
+            ("5mb", MaxReadSpeed::FiveMb, DisplaySpec::Const("5 MB/s")),
+            ("20mb", MaxReadSpeed::TwentyMb, DisplaySpec::Const("20 MB/s")),
+            ("50mb", MaxReadSpeed::FiftyMb, DisplaySpec::Const("50 MB/s")),
+        ]);
+
         let duplicates_check_method = Self::convert_i18n(&[
             ("hash", CheckingMethod::Hash, DisplaySpec::Translatable("option_check_method_hash")),
             ("name", CheckingMethod::Name, DisplaySpec::Translatable("option_check_method_name")),
@@ -135,6 +194,7 @@ impl StringComboBoxItems {
             ("blake3", "Blake3", HashType::Blake3),
             ("crc32", "CRC32", HashType::Crc32),
             ("xxh3", "XXH3", HashType::Xxh3),
+            ("sha256", "SHA256", HashType::Sha256),
         ]);
 
         let hash_size = Self::convert(&[("8", "8", 8u8), ("16", "16", 16), ("32", "32", 32), ("64", "64", 64)]);
@@ -220,6 +280,8 @@ impl StringComboBoxItems {
         Self {
             min_file_size,
             max_file_size,
+            min_file_age,
+            max_read_speed,
             duplicates_check_method,
             duplicates_hash_type,
             hash_size,
@@ -318,6 +380,7 @@ mod tests {
         assert_eq!(StringComboBoxItems::idx_from_config_name("blake3", &items.duplicates_hash_type), 0);
         assert_eq!(StringComboBoxItems::idx_from_config_name("crc32", &items.duplicates_hash_type), 1);
         assert_eq!(StringComboBoxItems::idx_from_config_name("xxh3", &items.duplicates_hash_type), 2);
+        assert_eq!(StringComboBoxItems::idx_from_config_name("sha256", &items.duplicates_hash_type), 3);
     }
 
     #[test]
@@ -386,6 +449,16 @@ mod tests {
         assert_eq!(MinFileSize::None.to_bytes(), 0);
     }
 
+    #[test]
+    fn min_file_age_none_is_zero_seconds() {
+        assert_eq!(MinFileAge::None.to_seconds(), 0);
+    }
+
+    #[test]
+    fn max_read_speed_unlimited_is_zero() {
+        assert_eq!(MaxReadSpeed::Unlimited.to_bytes_per_second(), 0);
+    }
+
     #[test]
     fn max_file_size_unlimited_is_none() {
         assert_eq!(MaxFileSize::Unlimited.to_bytes(), None);
@@ -396,6 +469,8 @@ mod tests {
         let items = StringComboBoxItems::new();
         assert!(!items.min_file_size.is_empty());
         assert!(!items.max_file_size.is_empty());
+        assert!(!items.min_file_age.is_empty());
+        assert!(!items.max_read_speed.is_empty());
         assert!(!items.duplicates_check_method.is_empty());
         assert!(!items.duplicates_hash_type.is_empty());
         assert!(!items.hash_size.is_empty());
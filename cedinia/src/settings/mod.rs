@@ -3,6 +3,7 @@ pub mod gui_settings_values;
 use std::path::PathBuf;
 
 use czkawka_core::common::config_cache_path::get_config_cache_path;
+use czkawka_core::common::io_throttle::{set_idle_priority, set_read_speed_limit};
 use log::{error, info};
 use serde::{Deserialize, Serialize};
 
@@ -35,6 +36,12 @@ fn default_min_file_size() -> String {
 fn default_max_file_size() -> String {
     "unlimited".to_string()
 }
+fn default_min_file_age() -> String {
+    "none".to_string()
+}
+fn default_max_read_speed() -> String {
+    "unlimited".to_string()
+}
 fn default_language() -> String {
     "auto".to_string()
 }
@@ -68,6 +75,8 @@ fn default_excluded_items() -> String {
 pub struct CediniaSettings {
     #[serde(default = "ttrue")]
     pub use_cache: bool,
+    #[serde(default)]
+    pub resume_interrupted_scans: bool,
     #[serde(default = "ttrue")]
     pub ignore_hidden: bool,
     #[serde(default)]
@@ -78,6 +87,12 @@ pub struct CediniaSettings {
     pub min_file_size: String,
     #[serde(default = "default_max_file_size")]
     pub max_file_size: String,
+    #[serde(default = "default_min_file_age")]
+    pub min_file_age: String,
+    #[serde(default = "default_max_read_speed")]
+    pub max_read_speed: String,
+    #[serde(default)]
+    pub idle_io_priority: bool,
     #[serde(default = "default_language")]
     pub language: String,
     #[serde(default = "default_excluded_items")]
@@ -300,10 +315,20 @@ use crate::{
     BadNamesSettings, BigFilesSettings, BrokenFilesSettings, DuplicateSettings, GeneralSettings, MainWindow, SameMusicSettings, SimilarImagesSettings, SimilarVideosSettings,
 };
 
+// Throttling is global in core, so changing it affects also already running scan
+pub fn apply_io_throttling(s: &CediniaSettings) {
+    let items = StringComboBoxItems::new();
+    let read_speed_idx = StringComboBoxItems::idx_from_config_name(&s.max_read_speed, &items.max_read_speed);
+    let read_speed_limit = items.max_read_speed.get(read_speed_idx).map_or(0, |e| e.value.to_bytes_per_second());
+    set_read_speed_limit(read_speed_limit);
+    set_idle_priority(s.idle_io_priority);
+}
+
 pub fn apply_settings_to_gui(win: &MainWindow, s: &CediniaSettings) {
     let items = StringComboBoxItems::new();
 
     win.global::<GeneralSettings>().set_use_cache(s.use_cache);
+    win.global::<GeneralSettings>().set_resume_interrupted_scans(s.resume_interrupted_scans);
     win.global::<GeneralSettings>().set_ignore_hidden(s.ignore_hidden);
     win.global::<GeneralSettings>().set_show_notification(s.show_notification);
     win.global::<GeneralSettings>().set_notify_only_background(s.notify_only_background);
@@ -311,6 +336,12 @@ pub fn apply_settings_to_gui(win: &MainWindow, s: &CediniaSettings) {
     win.global::<GeneralSettings>().set_min_file_size_idx(min_idx as i32);
     let max_idx = StringComboBoxItems::idx_from_config_name(&s.max_file_size, &items.max_file_size);
     win.global::<GeneralSettings>().set_max_file_size_idx(max_idx as i32);
+    let min_age_idx = StringComboBoxItems::idx_from_config_name(&s.min_file_age, &items.min_file_age);
+    win.global::<GeneralSettings>().set_min_file_age_idx(min_age_idx as i32);
+    let read_speed_idx = StringComboBoxItems::idx_from_config_name(&s.max_read_speed, &items.max_read_speed);
+    win.global::<GeneralSettings>().set_max_read_speed_idx(read_speed_idx as i32);
+    win.global::<GeneralSettings>().set_idle_io_priority(s.idle_io_priority);
+    apply_io_throttling(s);
     let lang_idx = czkawka_core::localizer_core::LANGUAGE_LIST
         .iter()
         .position(|l| l.short_name == s.language.as_str())
@@ -408,11 +439,15 @@ pub fn collect_settings_from_gui(win: &MainWindow) -> CediniaSettings {
 
     CediniaSettings {
         use_cache: g.get_use_cache(),
+        resume_interrupted_scans: g.get_resume_interrupted_scans(),
         ignore_hidden: g.get_ignore_hidden(),
         show_notification: g.get_show_notification(),
         notify_only_background: g.get_notify_only_background(),
         min_file_size: StringComboBoxItems::config_name_from_idx(&items.min_file_size, g.get_min_file_size_idx(), "none"),
         max_file_size: StringComboBoxItems::config_name_from_idx(&items.max_file_size, g.get_max_file_size_idx(), "unlimited"),
+        min_file_age: StringComboBoxItems::config_name_from_idx(&items.min_file_age, g.get_min_file_age_idx(), "none"),
+        max_read_speed: StringComboBoxItems::config_name_from_idx(&items.max_read_speed, g.get_max_read_speed_idx(), "unlimited"),
+        idle_io_priority: g.get_idle_io_priority(),
         language: czkawka_core::localizer_core::LANGUAGE_LIST
             .get(g.get_language_idx() as usize)
             .map_or_else(|| "en".to_string(), |l| l.short_name.to_string()),
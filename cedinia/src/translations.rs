@@ -82,6 +82,8 @@ pub(crate) fn translate_items(app: &MainWindow) {
 
     t.set_settings_use_cache_text(flc!("settings_use_cache").into());
     t.set_settings_use_cache_desc_text(flc!("settings_use_cache_desc").into());
+    t.set_settings_resume_interrupted_scans_text(flc!("settings_resume_interrupted_scans").into());
+    t.set_settings_resume_interrupted_scans_desc_text(flc!("settings_resume_interrupted_scans_desc").into());
     t.set_settings_ignore_hidden_text(flc!("settings_ignore_hidden").into());
     t.set_settings_ignore_hidden_desc_text(flc!("settings_ignore_hidden_desc").into());
     t.set_settings_show_notification_text(flc!("settings_show_notification").into());
@@ -94,6 +96,11 @@ pub(crate) fn translate_items(app: &MainWindow) {
     t.set_settings_filters_label_text(flc!("settings_filters_label").into());
     t.set_settings_min_file_size_text(flc!("settings_min_file_size").into());
     t.set_settings_max_file_size_text(flc!("settings_max_file_size").into());
+    t.set_settings_min_file_age_text(flc!("settings_min_file_age").into());
+    t.set_settings_max_read_speed_text(flc!("settings_max_read_speed").into());
+    t.set_settings_max_read_speed_desc_text(flc!("settings_max_read_speed_desc").into());
+    t.set_settings_idle_io_priority_text(flc!("settings_idle_io_priority").into());
+    t.set_settings_idle_io_priority_desc_text(flc!("settings_idle_io_priority_desc").into());
     t.set_settings_language_text(flc!("settings_language").into());
     t.set_settings_language_restart_text(flc!("settings_language_restart").into());
     t.set_settings_common_label_text(flc!("settings_common_label").into());
@@ -291,6 +298,8 @@ pub(crate) fn translate_items(app: &MainWindow) {
     let g = app.global::<GeneralSettings>();
     g.set_min_file_size_options(make_options(&combo_items.min_file_size));
     g.set_max_file_size_options(make_options(&combo_items.max_file_size));
+    g.set_min_file_age_options(make_options(&combo_items.min_file_age));
+    g.set_max_read_speed_options(make_options(&combo_items.max_read_speed));
 
     let dup = app.global::<DuplicateSettings>();
     dup.set_check_method_options(make_options(&combo_items.duplicates_check_method));
@@ -5,7 +5,9 @@ use clap::builder::Styles;
 #[cfg(not(feature = "no_colors"))]
 use clap::builder::styling::AnsiColor;
 use czkawka_core::CZKAWKA_VERSION;
-use czkawka_core::common::model::{CheckingMethod, HashType};
+use czkawka_core::common::keep_rules::KeepRule;
+use czkawka_core::common::model::{CheckingMethod, HashKey, HashType};
+use czkawka_core::common::quarantine::QuarantineSettings;
 use czkawka_core::common::tool_data::DeleteMethod;
 use czkawka_core::re_exported::{FilterType, HashAlg};
 use czkawka_core::tools::broken_files::CheckedTypes;
@@ -20,12 +22,14 @@ use czkawka_core::tools::video_optimizer::{NoiseReductionMethod, VideoCodec};
 use log::error;
 
 use crate::parsers::{
-    parse_audio_length_ratio, parse_audio_maximum_difference, parse_audio_similarity_percent, parse_broken_files, parse_checking_method_duplicate,
-    parse_checking_method_same_music, parse_crop_mechanism, parse_delete_method, parse_duration_tolerance_pct, parse_geometric_invariance, parse_hash_type, parse_image_hash_size,
-    parse_match_fraction, parse_max_samples, parse_maximal_file_size, parse_maximum_difference, parse_min_crop_size, parse_minimal_file_size, parse_minimum_segment_duration,
-    parse_music_duplicate_type, parse_noise_reduction, parse_scan_duration, parse_similar_hash_algorithm, parse_similar_image_filter, parse_skip_forward_amount, parse_tolerance,
-    parse_video_codec, parse_window_count,
+    parse_any_delete_method, parse_audio_length_ratio, parse_audio_maximum_difference, parse_audio_similarity_percent, parse_broken_files, parse_checking_method_duplicate,
+    parse_checking_method_same_music, parse_crop_mechanism, parse_delete_method, parse_duration_tolerance_pct, parse_geometric_invariance, parse_hash_key_file, parse_hash_type,
+    parse_image_hash_size, parse_keep_rule, parse_match_fraction, parse_max_samples, parse_maximal_file_size, parse_maximum_difference, parse_min_crop_size,
+    parse_minimal_file_size, parse_minimum_segment_duration, parse_modified_date, parse_music_duplicate_type, parse_noise_reduction, parse_permission_mode, parse_scan_duration,
+    parse_similar_hash_algorithm, parse_similar_image_filter, parse_skip_forward_amount, parse_tolerance, parse_video_codec, parse_window_count,
 };
+#[cfg(target_family = "unix")]
+use crate::parsers::{parse_group, parse_owner};
 
 #[cfg(not(feature = "no_colors"))]
 pub const CLAP_STYLING: Styles = Styles::styled()
@@ -66,6 +70,24 @@ pub enum Commands {
         after_help = "EXAMPLE:\n    czkawka dup -d /home/rafal -e /home/rafal/Obrazy  -m 25 -x 7z rar IMAGE -s hash -f results.txt -D aeo"
     )]
     Duplicates(DuplicatesArgs),
+    #[clap(
+        name = "dup-folders",
+        about = "Finds folders with identical content",
+        after_help = "EXAMPLE:\n    czkawka dup-folders -d /home/rafal/Photos /media/backup -S -f results.txt -D aeo"
+    )]
+    DuplicateFolders(DuplicateFoldersArgs),
+    #[clap(
+        name = "integrity",
+        about = "Checks files against hash manifest to find silently corrupted, modified, missing and new files",
+        after_help = "EXAMPLE:\n    czkawka integrity -d /mnt/nas/photos -m /mnt/nas/photos/checksums.b3 -f results.txt"
+    )]
+    IntegrityChecker(IntegrityCheckerArgs),
+    #[clap(
+        name = "compare",
+        about = "Compares two directory trees and finds files only in one of them, changed files and moved files",
+        after_help = "EXAMPLE:\n    czkawka compare -d /home/rafal/Photos -d /media/backup/Photos -f results.txt"
+    )]
+    DirectoryComparison(DirectoryComparisonArgs),
     #[clap(
         name = "empty-folders",
         about = "Finds empty folders",
@@ -136,6 +158,56 @@ pub enum Commands {
         after_help = "EXAMPLE:\n    czkawka exif-remover -d /home/rafal -f results.txt"
     )]
     ExifRemover(ExifRemoverArgs),
+    #[clap(
+        name = "apply",
+        about = "Removes, moves or links items from results saved as JSON",
+        after_help = "EXAMPLE:\n    czkawka apply -r results.json -D AEN -Q"
+    )]
+    Apply(ApplyArgs),
+    #[clap(
+        name = "undo",
+        about = "Reverts last operation which removed, moved, renamed or linked files",
+        after_help = "EXAMPLE:\n    czkawka undo -Q"
+    )]
+    Undo(UndoArgs),
+    #[clap(
+        name = "quarantine",
+        about = "Lists, restores or purges items moved to quarantine folder",
+        after_help = "EXAMPLE:\n    czkawka quarantine -q /srv/quarantine purge"
+    )]
+    Quarantine(QuarantineArgs),
+    #[clap(
+        name = "serve",
+        about = "Starts server, which runs scans requested over JSON-RPC",
+        after_help = "EXAMPLE:\n    czkawka serve --address 127.0.0.1:7890"
+    )]
+    Serve(ServeArgs),
+}
+
+impl Commands {
+    // Options shared by scans, commands which do not scan have none
+    pub fn common_cli_items_mut(&mut self) -> Option<&mut CommonCliItems> {
+        match self {
+            Self::Duplicates(args) => Some(&mut args.common_cli_items),
+            Self::DuplicateFolders(args) => Some(&mut args.common_cli_items),
+            Self::IntegrityChecker(args) => Some(&mut args.common_cli_items),
+            Self::DirectoryComparison(args) => Some(&mut args.common_cli_items),
+            Self::EmptyFolders(args) => Some(&mut args.common_cli_items),
+            Self::BiggestFiles(args) => Some(&mut args.common_cli_items),
+            Self::EmptyFiles(args) => Some(&mut args.common_cli_items),
+            Self::Temporary(args) => Some(&mut args.common_cli_items),
+            Self::SimilarImages(args) => Some(&mut args.common_cli_items),
+            Self::SameMusic(args) => Some(&mut args.common_cli_items),
+            Self::InvalidSymlinks(args) => Some(&mut args.common_cli_items),
+            Self::BrokenFiles(args) => Some(&mut args.common_cli_items),
+            Self::SimilarVideos(args) => Some(&mut args.common_cli_items),
+            Self::BadExtensions(args) => Some(&mut args.common_cli_items),
+            Self::BadNames(args) => Some(&mut args.common_cli_items),
+            Self::VideoOptimizer(args) => Some(&mut args.common_cli_items),
+            Self::ExifRemover(args) => Some(&mut args.common_cli_items),
+            Self::Apply(_) | Self::Undo(_) | Self::Quarantine(_) | Self::Serve(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, clap::Args)]
@@ -192,10 +264,19 @@ pub struct DuplicatesArgs {
         long,
         default_value = "HASH",
         value_parser = parse_checking_method_duplicate,
-        help = "Search method (NAME, SIZE, SIZE_NAME, HASH)",
-        long_help = "Methods to search files.\nNAME - Fast but rarely usable,\nSIZE - Fast but not accurate, checking by the file's size,\nSIZE_NAME - Checking by both the file's size and name,\nHASH - The slowest method, checking by the hash of the entire file"
+        help = "Search method (NAME, SIZE, SIZE_NAME, HASH, CONTENT_CHUNKS)",
+        long_help = "Methods to search files.\nNAME - Fast but rarely usable,\nSIZE - Fast but not accurate, checking by the file's size,\nSIZE_NAME - Checking by both the file's size and name,\nHASH - The slowest method, checking by the hash of the entire file,\nCONTENT_CHUNKS - Finds files with similar, but not identical content(e.g. appended logs or edited documents), by comparing content-defined chunks,\nSAMPLED_HASH - Much faster than HASH for very big files, but probabilistic, checking by the hash of the start, end and evenly spaced samples of the file"
     )]
     pub search_method: CheckingMethod,
+    #[clap(
+        short = 'G',
+        long,
+        default_value = "80",
+        value_parser = clap::value_parser!(u8).range(1..=100),
+        help = "Minimal similarity percentage of content (1-100)",
+        long_help = "Minimal percentage of content chunks of smaller file, which must be also found in the other file, to treat files as similar. Content appended to file, e.g. to logs, does not lower similarity. Works only with the CONTENT_CHUNKS search method."
+    )]
+    pub minimal_similarity: u8,
     #[clap(flatten)]
     pub delete_method: DMethod,
     #[clap(
@@ -203,14 +284,149 @@ pub struct DuplicatesArgs {
         long,
         default_value = "BLAKE3",
         value_parser = parse_hash_type,
-        help = "Hash type (BLAKE3, CRC32, XXH3)",
-        long_help = "Hash algorithm used to calculate file hashes. BLAKE3 is recommended for most cases (fast and secure), CRC32 is faster but less reliable, XXH3 is very fast but not cryptographically secure."
+        help = "Hash type (BLAKE3, CRC32, XXH3, SHA256)",
+        long_help = "Hash algorithm used to calculate file hashes. BLAKE3 is recommended for most cases (fast and secure), CRC32 is faster but less reliable, XXH3 is very fast but not cryptographically secure, SHA256 is slow, but its hashes can be checked with standard sha256sum tool."
     )]
     pub hash_type: HashType,
     #[clap(flatten)]
     pub case_sensitive_name_comparison: CaseSensitiveNameComparison,
     #[clap(flatten)]
     pub allow_hard_links: AllowHardLinks,
+    #[clap(flatten)]
+    pub resume: Resume,
+    #[clap(
+        short = 'V',
+        long,
+        help = "Verify content byte by byte before deleting or linking",
+        long_help = "Before deleting or linking, compare every file byte by byte with the file that is kept. Files with different content, or changed since the scan, are skipped. Works with all search methods except CONTENT_CHUNKS."
+    )]
+    pub verify: bool,
+    #[clap(
+        short = 'A',
+        long,
+        help = "Search for duplicates also inside archives",
+        long_help = "Files inside zip, 7z, tar (also compressed with gz, bz2, zst or xz) and single gz, bz2, zst or xz compressed files are checked too and shown as \"archive.zip!/path/in/archive\". They are only reported and never deleted or linked. Works only with the SIZE and HASH search methods."
+    )]
+    pub search_in_archives: bool,
+    #[clap(
+        short = 'j',
+        long,
+        default_value = "16",
+        value_parser = clap::value_parser!(u32).range(1..=1024),
+        help = "Number of samples hashed in every file (1-1024)",
+        long_help = "Number of evenly spaced 64 KiB samples, which are hashed together with the start and end of every file. More samples make results more reliable, but slower. Works only with the SAMPLED_HASH search method."
+    )]
+    pub sample_count: u32,
+    #[clap(
+        short = 'F',
+        long,
+        help = "Confirm full hash before deleting or linking",
+        long_help = "Before deleting or linking, calculate full hash of every file and compare it with the hash of the file that is kept. Files with different hash, or changed since the scan, are skipped. With the SAMPLED_HASH search method, files can be deleted or linked only when this option or --verify is used."
+    )]
+    pub confirm_full_hash: bool,
+    #[clap(
+        long,
+        default_value = "0",
+        value_name = "MB",
+        help = "Limit memory used to group files in MB (0 = no limit)",
+        long_help = "Files grouped by size are kept in temporary files in cache folder, and only as many size groups as fit into this limit are hashed at once. Allows to scan tens of millions of files on computers with little memory, at the cost of speed. Cache of hashes and found duplicates are still kept in memory, so real usage may be higher. Works only with HASH and SAMPLED_HASH search methods. Value 0 (default) disables limit."
+    )]
+    pub memory_limit: u64,
+    #[clap(
+        short = 'K',
+        long,
+        value_name = "manifest-file-name",
+        help = "Save hashes of found duplicates as checksum manifest",
+        long_help = "Saves full hashes of all scanned files, also of files without duplicates, to a manifest file, compatible with sha256sum (SHA256 hash type) or b3sum (BLAKE3 hash type), so results can be checked with `sha256sum -c`, `b3sum -c` or `b3sum --keyed -c` (with --hash-key-file). Files with unique size are hashed only to be saved in manifest, so scan takes longer. Works only with the HASH search method."
+    )]
+    pub hash_manifest: Option<PathBuf>,
+    #[clap(flatten)]
+    pub hash_key: HashKeyFile,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct DuplicateFoldersArgs {
+    #[clap(flatten)]
+    pub common_cli_items: CommonCliItems,
+    #[clap(flatten)]
+    pub reference_directories: ReferenceDirectories,
+    #[clap(
+        short = 'c',
+        long,
+        value_parser = parse_minimal_file_size,
+        default_value = "257144",
+        help = "Minimum size of files stored in the hash cache (bytes)",
+        long_help = "Minimum file size (in bytes) to be included in the hash cache. The cache is shared with the duplicate finder, so files hashed there do not need to be read again."
+    )]
+    pub minimal_cached_file_size: u64,
+    #[clap(flatten)]
+    pub delete_method: DMethod,
+    #[clap(
+        short = 't',
+        long,
+        default_value = "BLAKE3",
+        value_parser = parse_hash_type,
+        help = "Hash type (BLAKE3, CRC32, XXH3, SHA256)",
+        long_help = "Hash algorithm used to calculate file hashes. BLAKE3 is recommended for most cases (fast and secure), CRC32 is faster but less reliable, XXH3 is very fast but not cryptographically secure, SHA256 is slow, but its hashes can be checked with standard sha256sum tool."
+    )]
+    pub hash_type: HashType,
+    #[clap(
+        short = 'S',
+        long,
+        help = "Also find folders contained in other folders",
+        long_help = "Also find folders whose whole content (file names and contents, including subfolders) exists inside a bigger folder. Finding such folders takes more time."
+    )]
+    pub subsets: bool,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct IntegrityCheckerArgs {
+    #[clap(flatten)]
+    pub common_cli_items: CommonCliItems,
+    #[clap(
+        short = 'm',
+        long,
+        required = true,
+        value_name = "manifest-file-name",
+        help = "Hash manifest to check files against",
+        long_help = "Hash manifest in sha256sum/b3sum format, e.g. saved by `dup -K`, `sha256sum` or `b3sum`. Relative paths inside manifest are resolved against folder containing manifest. Files changed without modification after manifest was created are reported as corrupted."
+    )]
+    pub manifest: PathBuf,
+    #[clap(
+        short = 't',
+        long,
+        default_value = "BLAKE3",
+        value_parser = parse_hash_type,
+        help = "Hash type used in manifest (BLAKE3, CRC32, XXH3, SHA256)",
+        long_help = "Hash algorithm which was used to create manifest. Use BLAKE3 for manifests created by b3sum and SHA256 for manifests created by sha256sum."
+    )]
+    pub hash_type: HashType,
+    #[clap(flatten)]
+    pub hash_key: HashKeyFile,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct DirectoryComparisonArgs {
+    #[clap(flatten)]
+    pub common_cli_items: CommonCliItems,
+    #[clap(
+        short = 'c',
+        long,
+        value_parser = parse_minimal_file_size,
+        default_value = "257144",
+        help = "Minimum size of files stored in the hash cache (bytes)",
+        long_help = "Minimum file size (in bytes) to be included in the hash cache. The cache is shared with the duplicate finder, so files hashed there do not need to be read again."
+    )]
+    pub minimal_cached_file_size: u64,
+    #[clap(
+        short = 't',
+        long,
+        default_value = "BLAKE3",
+        value_parser = parse_hash_type,
+        help = "Hash type (BLAKE3, CRC32, XXH3, SHA256)",
+        long_help = "Hash algorithm used to compare content of files with the same size. BLAKE3 is recommended for most cases (fast and secure), CRC32 is faster but less reliable, XXH3 is very fast but not cryptographically secure, SHA256 is slow, but widely supported."
+    )]
+    pub hash_type: HashType,
 }
 
 #[derive(Debug, clap::Args)]
@@ -320,6 +536,8 @@ pub struct SimilarImagesArgs {
     #[clap(flatten)]
     pub allow_hard_links: AllowHardLinks,
     #[clap(flatten)]
+    pub resume: Resume,
+    #[clap(flatten)]
     pub ignore_same_size: IgnoreSameSize,
     #[clap(flatten)]
     pub ignore_same_resolution: IgnoreSameResolution,
@@ -452,6 +670,8 @@ pub struct BrokenFilesArgs {
     pub common_cli_items: CommonCliItems,
     #[clap(flatten)]
     pub delete_method: SDMethod,
+    #[clap(flatten)]
+    pub resume: Resume,
     #[clap(
         short,
         long,
@@ -474,6 +694,8 @@ pub struct SimilarVideosArgs {
     #[clap(flatten)]
     pub allow_hard_links: AllowHardLinks,
     #[clap(flatten)]
+    pub resume: Resume,
+    #[clap(flatten)]
     pub ignore_same_size: IgnoreSameSize,
     #[clap(flatten)]
     pub ignore_same_resolution: IgnoreSameResolution,
@@ -915,6 +1137,182 @@ pub struct ExifRemoverArgs {
     pub override_file: bool,
 }
 
+#[derive(Debug, clap::Args)]
+pub struct ApplyArgs {
+    #[clap(
+        short,
+        long,
+        value_name = "FILE",
+        help = "Results saved as JSON",
+        long_help = "Results saved with -C or -p by any tool except compare, integrity and dup-folders. File may be edited before, e.g. to remove groups or items, which should stay untouched. Items, which no longer exist or were modified after saving results, are skipped."
+    )]
+    pub results_file: PathBuf,
+    #[clap(
+        short = 'D',
+        long,
+        default_value = "NONE",
+        value_parser = parse_any_delete_method,
+        help = "Method choosing items (DELETE, AEN, AEO, ON, OO, AEB, AES, OB, OS, HARD, REFLINK)",
+        long_help = "Method for selecting items from each group, same as in dup tool. DELETE selects all items and is the only method available for results without groups, e.g. empty files. By default selected items are removed, HARD and REFLINK replace them with links instead."
+    )]
+    pub delete_method: DeleteMethod,
+    #[clap(
+        long,
+        value_name = "FOLDER",
+        conflicts_with = "symlink",
+        help = "Move selected items to folder instead of removing them",
+        long_help = "Moves selected items to given folder, where their whole original path is recreated, so items with same names do not overwrite each other."
+    )]
+    pub move_to: Option<PathBuf>,
+    #[clap(
+        long,
+        help = "Replace selected items with symlinks",
+        long_help = "Replaces selected items with symlinks to item kept in their group, so method must keep one item in each group."
+    )]
+    pub symlink: bool,
+    #[clap(
+        short = 'Q',
+        long,
+        help = "Dry run - preview operations",
+        long_help = "Performs a dry run showing what operations would be performed without actually executing them."
+    )]
+    pub dry_run: bool,
+    #[clap(
+        short = 'y',
+        long,
+        help = "Move items to trash",
+        long_help = "Instead of permanently deleting files, move them to the system trash/recycle bin where they can be recovered."
+    )]
+    pub move_to_trash: bool,
+    #[clap(flatten)]
+    pub quarantine: QuarantineOptions,
+    #[clap(
+        long = "keep-rule",
+        value_name = "RULE",
+        value_parser = parse_keep_rule,
+        help = "Rule choosing item kept in each group, may be used multiple times",
+        long_help = "Chooses item kept in each group by rules checked in given order, next rule is used only when items are equal according to previous ones. Criterion of delete method is used as the last rule. Works only with methods keeping one item - AEN, AEO, AEB, AES, HARD and REFLINK.\nAvailable rules:\npath:FOLDER - prefer items inside folder, relative folder is resolved against current folder\nshortest-path / longest-path - prefer item with shortest/longest full path\nresolution - prefer highest resolution (images, videos)\nbitrate - prefer highest bitrate (videos, music)\nno-copy - prefer names without copy markers like \" - Copy\", \"_copy\", \"Copy of \" or \"(1)\"\noldest / newest - prefer oldest/newest modification date\nbiggest / smallest - prefer biggest/smallest size"
+    )]
+    pub keep_rules: Vec<KeepRule>,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct UndoArgs {
+    #[clap(
+        short = 'Q',
+        long,
+        help = "Dry run - preview operations",
+        long_help = "Shows which items would be restored, without changing anything and without removing operation from journal."
+    )]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct QuarantineArgs {
+    #[clap(
+        short = 'q',
+        long,
+        value_name = "FOLDER",
+        help = "Quarantine folder",
+        long_help = "Folder passed with --quarantine when removing items."
+    )]
+    pub folder: PathBuf,
+    #[clap(subcommand)]
+    pub action: QuarantineAction,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum QuarantineAction {
+    #[clap(name = "list", about = "List quarantined items")]
+    List,
+    #[clap(name = "purge", about = "Permanently remove expired items")]
+    Purge(QuarantinePurgeArgs),
+    #[clap(name = "restore", about = "Move items back to their original paths")]
+    Restore(QuarantineRestoreArgs),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct QuarantinePurgeArgs {
+    #[clap(long, help = "Remove all items, also not expired ones")]
+    pub all: bool,
+    #[clap(
+        short = 'Q',
+        long,
+        help = "Dry run - preview operations",
+        long_help = "Performs a dry run showing what operations would be performed without actually executing them."
+    )]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct QuarantineRestoreArgs {
+    #[clap(
+        short,
+        long = "path",
+        value_name = "PATH",
+        help = "Restore only items inside path",
+        long_help = "Restores only items, whose original path is inside given path. Can be used multiple times. By default all items are restored."
+    )]
+    pub paths: Vec<PathBuf>,
+    #[clap(
+        short = 'Q',
+        long,
+        help = "Dry run - preview operations",
+        long_help = "Performs a dry run showing what operations would be performed without actually executing them."
+    )]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ServeArgs {
+    #[clap(
+        short,
+        long,
+        default_value = "127.0.0.1:7890",
+        help = "Address to listen on",
+        long_help = "TCP address on which server listens for JSON-RPC requests. Address other than localhost requires token file."
+    )]
+    pub address: String,
+    #[clap(
+        long,
+        value_name = "PATH",
+        help = "File with token required from clients",
+        long_help = "Path of file containing token, which every client must send with authenticate method before other requests. Required when server listens on address other than localhost."
+    )]
+    pub token_file: Option<PathBuf>,
+    #[cfg(target_family = "unix")]
+    #[clap(
+        short,
+        long,
+        value_name = "PATH",
+        help = "Listen on Unix socket instead of TCP address",
+        long_help = "Path of Unix socket on which server listens instead of TCP address. Access to server can be limited by permissions of socket folder."
+    )]
+    pub socket: Option<PathBuf>,
+    #[clap(
+        short = 'T',
+        long,
+        default_value = "0",
+        help = "Number of threads to use (0 = all available)",
+        long_help = "Limits the number of threads used by all scans. Thread pool is shared by all scans, so thread number passed to scans is ignored."
+    )]
+    pub thread_number: usize,
+    #[clap(
+        long,
+        default_value = "0",
+        value_name = "MB_PER_SECOND",
+        help = "Limit speed of reading files in MB/s by all scans (0 = no limit)",
+        long_help = "Limits total speed of reading content of files by all scans. Limit is shared by whole server, so scans cannot set it. Value 0 (default) disables limit."
+    )]
+    pub max_read_speed: u64,
+    #[clap(
+        long,
+        help = "Read files with lowest CPU and disk priority in all scans",
+        long_help = "Threads reading content of files in all scans use lowest CPU priority(nice 19) and, on Linux, idle IO priority. Priority is shared by whole server, so scans cannot set it."
+    )]
+    pub idle_priority: bool,
+}
+
 #[derive(Debug, clap::Args)]
 pub struct CommonCliItems {
     #[clap(
@@ -925,14 +1323,54 @@ pub struct CommonCliItems {
         long_help = "Limits the number of threads used for scanning. Value 0 (default) will use all available CPU threads. Lower values can reduce CPU usage."
     )]
     pub thread_number: usize,
+    #[clap(
+        long,
+        default_value = "0",
+        value_name = "NUMBER",
+        help = "Number of threads reading files from one device (0 = no limit)",
+        long_help = "Files are grouped by device they are placed on and each device is read by at most this number of threads at once. Used when hashing or reading content of files (duplicates, similar images, broken files). Value 0 (default) disables per device scheduling."
+    )]
+    pub io_threads_per_device: usize,
+    #[clap(
+        long,
+        default_value = "0",
+        value_name = "NUMBER",
+        help = "Number of threads reading files from one rotational disk (0 = same as --io-threads-per-device)",
+        long_help = "Like --io-threads-per-device, but used only for rotational disks(HDD), which are detected on Linux. Parallel reads from one HDD are usually much slower than reading files one by one, so 1 is recommended, while SSDs may use more threads."
+    )]
+    pub io_threads_per_hdd: usize,
+    #[clap(
+        long,
+        default_value = "0",
+        value_name = "MB_PER_SECOND",
+        help = "Limit speed of reading files in MB/s (0 = no limit)",
+        long_help = "Limits total speed of reading content of files by all threads, e.g. when hashing duplicates or checking broken files. Useful to not slow down file servers used by other people during scan. In server mode it must be passed to serve instead. Value 0 (default) disables limit."
+    )]
+    pub max_read_speed: u64,
+    #[clap(
+        long,
+        help = "Read files with lowest CPU and disk priority",
+        long_help = "Threads reading content of files use lowest CPU priority(nice 19) and, on Linux, idle IO priority, so other programs are served first. In server mode it must be passed to serve instead."
+    )]
+    pub idle_priority: bool,
     #[clap(
         short,
         long,
-        required = true,
+        required_unless_present = "file_list",
         help = "Directory(ies) to search",
         long_help = "List of directory(ies) to search (absolute paths). These directories will be scanned but not set as reference folders."
     )]
     pub directories: Vec<PathBuf>,
+    #[clap(
+        long,
+        value_name = "FILE",
+        help = "Scan paths listed in file, use - to read them from stdin",
+        long_help = "Reads list of paths to scan from file or from stdin when - is used, e.g. output of `find -print0`, `fd` or `git ls-files -z`. Paths may be separated by new lines or by NUL characters. Only listed files are checked, directories in list are not walked, so lists with every file of tree do not scan it twice. Size, extension, excluded directories and excluded items filters are still applied. Can be combined with -d."
+    )]
+    pub file_list: Option<PathBuf>,
+    // Paths read from file list before scan is started
+    #[clap(skip)]
+    pub listed_files: Vec<PathBuf>,
     #[clap(
         short,
         long,
@@ -963,12 +1401,32 @@ pub struct CommonCliItems {
         long_help = "List of file extensions to exclude from search."
     )]
     pub excluded_extensions: Vec<String>,
+    #[clap(
+        long,
+        value_name = "DATE_OR_AGE",
+        value_parser = parse_modified_date,
+        help = "Only check files modified after this date or age",
+        long_help = "Skips files modified before given point in time. Accepts date (YYYY-MM-DD), date with time (YYYY-MM-DD HH:MM:SS), both in UTC, or age relative to now like 12h, 30d, 2w or 1y - e.g. 30d checks only files modified in last 30 days."
+    )]
+    pub modified_after: Option<u64>,
+    #[clap(
+        long,
+        value_name = "DATE_OR_AGE",
+        value_parser = parse_modified_date,
+        help = "Only check files modified before this date or age",
+        long_help = "Skips files modified after given point in time. Accepts date (YYYY-MM-DD), date with time (YYYY-MM-DD HH:MM:SS), both in UTC, or age relative to now like 12h, 30d, 2w or 1y - e.g. 180d checks only files older than about six months."
+    )]
+    pub modified_before: Option<u64>,
     #[clap(flatten)]
     pub file_to_save: FileToSave,
     #[clap(flatten)]
     pub json_compact_file_to_save: JsonCompactFileToSave,
     #[clap(flatten)]
     pub json_pretty_file_to_save: JsonPrettyFileToSave,
+    #[clap(flatten)]
+    pub csv_file_to_save: CsvFileToSave,
+    #[clap(flatten)]
+    pub ndjson_file_to_save: NdjsonFileToSave,
     #[clap(
         short = 'R',
         long,
@@ -984,6 +1442,40 @@ pub struct CommonCliItems {
         long_help = "Prevents scanning files on different filesystems (useful to avoid scanning mounted drives, network shares, etc.)"
     )]
     pub exclude_other_filesystems: bool,
+    #[cfg(target_family = "unix")]
+    #[clap(
+        long,
+        value_name = "USER",
+        value_parser = parse_owner,
+        help = "Only check files owned by this user",
+        long_help = "Skips files not owned by given user. Accepts user name or numeric uid."
+    )]
+    pub owner: Option<u32>,
+    #[cfg(target_family = "unix")]
+    #[clap(
+        long,
+        value_name = "GROUP",
+        value_parser = parse_group,
+        help = "Only check files belonging to this group",
+        long_help = "Skips files not belonging to given group. Accepts group name or numeric gid."
+    )]
+    pub group: Option<u32>,
+    #[cfg(target_family = "unix")]
+    #[clap(
+        long,
+        value_name = "MODE",
+        value_parser = parse_permission_mode,
+        help = "Only check files with all of these permission bits set",
+        long_help = "Skips files which don't have all given permission bits set, like `find -perm -MODE`. Mode is octal e.g. 644 checks files readable by everyone and writable by owner, 4000 checks setuid files."
+    )]
+    pub permissions: Option<u32>,
+    #[cfg(target_family = "unix")]
+    #[clap(
+        long,
+        help = "Skip files that current user cannot delete",
+        long_help = "Skips files placed in folders without write permission for current user, so they could not be deleted, moved or replaced by links. Such folders are still scanned for subfolders."
+    )]
+    pub skip_not_deletable: bool,
     #[clap(flatten)]
     pub do_not_print: DoNotPrint,
     #[clap(
@@ -1000,6 +1492,24 @@ pub struct CommonCliItems {
         long_help = "Disables the cache system. This will make scanning slower but ensures fresh results without cached data."
     )]
     pub disable_cache: bool,
+    #[clap(
+        long,
+        help = "Reuse content of unchanged directories from previous scan",
+        long_help = "Stores entries of scanned directories together with size, modification date and owner of files in a persistent index and on next scans reads again only directories whose modification date changed. Files modified in place do not change modification date of their directory, so such changes are noticed only when directory is modified - use --verify with duplicates, to check files again before removing them. This makes rescans of big, rarely changing trees much faster."
+    )]
+    pub incremental: bool,
+    #[clap(
+        long,
+        help = "Skip items matched by .gitignore and .czkawkaignore files",
+        long_help = "Reads .gitignore and .czkawkaignore files found in scanned directories and skips files and folders matched by them, exactly like git does. Rules are applied to directory with ignore file and all its subdirectories, negated patterns are supported. Ignore files placed above scanned directories are not used."
+    )]
+    pub respect_ignore_files: bool,
+    #[clap(
+        long,
+        help = "Follow symlinks to directories",
+        long_help = "Descends into directories pointed by symlinks. Files inside are reported with paths containing the link that was used to reach them. Every directory is scanned only once, so symlink loops and directories reachable through several links are handled safely. Symlinks to files are never followed."
+    )]
+    pub follow_symlinks: bool,
 }
 
 #[derive(Debug, clap::Args, Clone, Copy)]
@@ -1020,15 +1530,15 @@ pub struct DoNotPrint {
     pub do_not_print_messages: bool,
 }
 
-#[derive(Debug, clap::Args, Clone, Copy)]
+#[derive(Debug, clap::Args, Clone)]
 pub struct DMethod {
     #[clap(
         short = 'D',
         long,
         default_value = "NONE",
         value_parser = parse_delete_method,
-        help = "Delete method (AEN, AEO, ON, OO, AEB, AES, OB, OS, HARD)",
-        long_help = "Method for selecting which files to delete from duplicate groups:\nAEN - All files Except Newest (keeps only newest)\nAEO - All files Except Oldest (keeps only oldest)\nON - Only the Newest deleted (keeps all but newest)\nOO - Only the Oldest deleted (keeps all but oldest)\nAEB - All files Except Biggest (keeps only biggest)\nAES - All files Except Smallest (keeps only smallest)\nOB - Only the Biggest deleted (keeps all but biggest)\nOS - Only the Smallest deleted (keeps all but smallest)\nHARD - create hard links to save space\nNONE - do not delete files (default)"
+        help = "Delete method (AEN, AEO, ON, OO, AEB, AES, OB, OS, HARD, REFLINK)",
+        long_help = "Method for selecting which files to delete from duplicate groups:\nAEN - All files Except Newest (keeps only newest)\nAEO - All files Except Oldest (keeps only oldest)\nON - Only the Newest deleted (keeps all but newest)\nOO - Only the Oldest deleted (keeps all but oldest)\nAEB - All files Except Biggest (keeps only biggest)\nAES - All files Except Smallest (keeps only smallest)\nOB - Only the Biggest deleted (keeps all but biggest)\nOS - Only the Smallest deleted (keeps all but smallest)\nHARD - create hard links to save space\nREFLINK - replace identical files with copy-on-write clones, which save space but keep separate metadata (only duplicates found by hash, requires e.g. Btrfs or XFS)\nNONE - do not delete files (default)"
     )]
     pub delete_method: DeleteMethod,
     #[clap(
@@ -1045,10 +1555,40 @@ pub struct DMethod {
         long_help = "Instead of permanently deleting files, move them to the system trash/recycle bin where they can be recovered."
     )]
     pub move_to_trash: bool,
+    #[clap(flatten)]
+    pub quarantine: QuarantineOptions,
+    #[clap(
+        long = "keep-rule",
+        value_name = "RULE",
+        value_parser = parse_keep_rule,
+        help = "Rule choosing item kept in each group, may be used multiple times",
+        long_help = "Chooses item kept in each group by rules checked in given order, next rule is used only when items are equal according to previous ones. Criterion of delete method is used as the last rule. Works only with methods keeping one item - AEN, AEO, AEB, AES, HARD and REFLINK.\nAvailable rules:\npath:FOLDER - prefer items inside folder, relative folder is resolved against current folder\nshortest-path / longest-path - prefer item with shortest/longest full path\nresolution - prefer highest resolution (images, videos)\nbitrate - prefer highest bitrate (videos, music)\nno-copy - prefer names without copy markers like \" - Copy\", \"_copy\", \"Copy of \" or \"(1)\"\noldest / newest - prefer oldest/newest modification date\nbiggest / smallest - prefer biggest/smallest size"
+    )]
+    pub keep_rules: Vec<KeepRule>,
+}
+
+#[derive(Debug, clap::Args, Clone)]
+pub struct QuarantineOptions {
+    #[clap(
+        long = "quarantine",
+        value_name = "FOLDER",
+        conflicts_with = "move_to_trash",
+        help = "Move removed items to quarantine folder",
+        long_help = "Instead of deleting items, moves them to given folder, where their whole original path is recreated and every item is saved in manifest. Quarantined items can be restored or purged later with quarantine command. Useful where system trash is not available, e.g. on headless servers and network mounts."
+    )]
+    pub quarantine_folder: Option<PathBuf>,
+    #[clap(
+        long,
+        default_value = "30",
+        value_name = "DAYS",
+        help = "Days after which quarantined items expire",
+        long_help = "Quarantined items expire after given number of days and are removed by quarantine purge command. 0 means that items never expire."
+    )]
+    pub quarantine_expiry_days: u32,
 }
 
 // Simple delete method - delete files or not
-#[derive(Debug, clap::Args, Clone, Copy)]
+#[derive(Debug, clap::Args, Clone)]
 pub struct SDMethod {
     #[clap(short = 'D', long, help = "Delete found items", long_help = "Automatically delete all found items matching the criteria.")]
     pub delete_files: bool,
@@ -1066,6 +1606,8 @@ pub struct SDMethod {
         long_help = "Instead of permanently deleting files, move them to the system trash/recycle bin where they can be recovered."
     )]
     pub move_to_trash: bool,
+    #[clap(flatten)]
+    pub quarantine: QuarantineOptions,
 }
 
 #[derive(Debug, clap::Args)]
@@ -1115,6 +1657,28 @@ pub struct JsonPrettyFileToSave {
     pub pretty_file_to_save: Option<PathBuf>,
 }
 
+#[derive(Debug, clap::Args)]
+pub struct CsvFileToSave {
+    #[clap(
+        long,
+        value_name = "csv-file-name",
+        help = "Save results to CSV file",
+        long_help = "Saves the search results into a CSV file with one row per item. Items from the same group share the value in the group_id column, so results can be loaded into spreadsheets or databases."
+    )]
+    pub csv_file_to_save: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct NdjsonFileToSave {
+    #[clap(
+        long,
+        value_name = "ndjson-file-name",
+        help = "Save results to newline delimited JSON file",
+        long_help = "Saves the search results into a newline delimited JSON file with one item per line. Every item contains group_id of its group, so it can be processed line by line, e.g. with jq."
+    )]
+    pub ndjson_file_to_save: Option<PathBuf>,
+}
+
 #[derive(Debug, clap::Args)]
 pub struct AllowHardLinks {
     #[clap(
@@ -1126,6 +1690,16 @@ pub struct AllowHardLinks {
     pub allow_hard_links: bool,
 }
 
+#[derive(Debug, clap::Args)]
+pub struct Resume {
+    #[clap(
+        long,
+        help = "Resume interrupted scan",
+        long_help = "Saves checkpoints of scan and continues scan, which was stopped or interrupted e.g. by reboot, from its last checkpoint. Checkpoints are created only when this option is used, so interrupted scan must also be started with it. Checkpoint is used only when scan is started with exactly same directories, filters and parameters. Checkpoints are stored in cache folder, so they require enabled cache."
+    )]
+    pub resume: bool,
+}
+
 #[derive(Debug, clap::Args)]
 pub struct CaseSensitiveNameComparison {
     #[clap(
@@ -1159,6 +1733,16 @@ pub struct IgnoreSameResolution {
     pub ignore_same_resolution: bool,
 }
 
+impl QuarantineOptions {
+    // Folder is made absolute, so items are quarantined to the same place regardless of current directory in server mode
+    pub fn settings(&self) -> Option<QuarantineSettings> {
+        self.quarantine_folder.as_ref().map(|folder| QuarantineSettings {
+            folder: std::path::absolute(folder).unwrap_or_else(|_| folder.clone()),
+            expiry_days: self.quarantine_expiry_days,
+        })
+    }
+}
+
 impl FileToSave {
     pub(crate) fn file_name(&self) -> Option<&str> {
         if let Some(file_name) = &self.file_to_save {
@@ -1186,6 +1770,49 @@ impl JsonPrettyFileToSave {
         None
     }
 }
+impl CsvFileToSave {
+    pub(crate) fn file_name(&self) -> Option<&str> {
+        if let Some(file_name) = &self.csv_file_to_save {
+            return file_name.to_str();
+        }
+
+        None
+    }
+}
+impl NdjsonFileToSave {
+    pub(crate) fn file_name(&self) -> Option<&str> {
+        if let Some(file_name) = &self.ndjson_file_to_save {
+            return file_name.to_str();
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct HashKeyFile {
+    #[clap(
+        long,
+        value_name = "key-file-name",
+        value_parser = parse_hash_key_file,
+        help = "File with 32 byte key, which changes BLAKE3 into keyed BLAKE3",
+        long_help = "File containing exactly 32 bytes of secret key. With BLAKE3 hash type, files are hashed with keyed BLAKE3, the same as `b3sum --keyed`, so hashes cannot be recreated or forged without the key. Ignored with other hash types."
+    )]
+    pub hash_key_file: Option<HashKey>,
+}
+
+impl HashKeyFile {
+    pub fn apply_to(&self, hash_type: HashType) -> HashType {
+        match (hash_type, self.hash_key_file) {
+            (HashType::Blake3, Some(key)) => HashType::Blake3Keyed(key),
+            (_, Some(_)) => {
+                error!("WARNING: Hash key can be used only with BLAKE3 hash type, so it is ignored.");
+                hash_type
+            }
+            (_, None) => hash_type,
+        }
+    }
+}
 
 pub fn validate_file_sizes(minimal: u64, maximal: u64) {
     if maximal < minimal {
@@ -0,0 +1,754 @@
+// Server mode, which allows dashboards and scripts to run scans without starting new process for each of them.
+// Requests and responses are JSON-RPC 2.0 messages, one per line, sent over localhost TCP connection or Unix socket.
+// Scans are started with same arguments as CLI subcommands, so all options work exactly like in CLI.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+#[cfg(target_family = "unix")]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::{env, io, iter, process, thread};
+
+use clap::Parser;
+use crossbeam_channel::{Sender, unbounded};
+use czkawka_core::common::consts::DEFAULT_THREAD_SIZE;
+use czkawka_core::common::progress_data::ProgressData;
+use czkawka_core::common::tool_data::{CommonData, DeleteMethod};
+use czkawka_core::common::traits::AllTraits;
+use czkawka_core::helpers::messages::Messages;
+use log::{error, info};
+use serde_json::{Value, json};
+
+use crate::commands::{Args, Commands, ServeArgs};
+use crate::parsers::parse_any_delete_method;
+use crate::{CliOutput, read_file_list, run_command, set_io_priority_once, set_number_of_threads_once};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SCAN_NOT_FOUND: i64 = -32000;
+const SCAN_BUSY: i64 = -32001;
+const RESULTS_ERROR: i64 = -32002;
+const UNAUTHORIZED: i64 = -32003;
+
+// Every connection has its own thread and every scan keeps its results in memory until removed
+const MAX_CONNECTIONS: usize = 32;
+const MAX_SCANS: usize = 100;
+
+// Object safe part of tool traits, needed to keep tools of different types after scan
+pub trait ScanTool: Send {
+    fn results_as_json(&self, file_name: &str) -> io::Result<()>;
+    fn messages(&self) -> &Messages;
+    fn found_any_items(&self) -> bool;
+    fn delete_items(&mut self, delete_method: DeleteMethod, dry_run: bool, move_to_trash: bool, stop_flag: &Arc<AtomicBool>);
+}
+
+impl<T: AllTraits + Send> ScanTool for T {
+    fn results_as_json(&self, file_name: &str) -> io::Result<()> {
+        self.save_results_to_file_as_json(file_name, false)
+    }
+
+    fn messages(&self) -> &Messages {
+        self.get_text_messages()
+    }
+
+    fn found_any_items(&self) -> bool {
+        CommonData::found_any_items(self)
+    }
+
+    fn delete_items(&mut self, delete_method: DeleteMethod, dry_run: bool, move_to_trash: bool, stop_flag: &Arc<AtomicBool>) {
+        self.set_delete_method(delete_method);
+        self.set_dry_run(dry_run);
+        self.set_move_to_trash(move_to_trash);
+        let _ = self.delete_files(stop_flag, None);
+    }
+}
+
+// Decides which delete methods can be used, same as in CLI subcommands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DeleteSupport {
+    Nothing,
+    Items,
+    Groups,
+}
+
+impl DeleteSupport {
+    fn from_command(command: &Commands) -> Self {
+        match command {
+            Commands::Duplicates(_) | Commands::DuplicateFolders(_) | Commands::SimilarImages(_) | Commands::SameMusic(_) | Commands::SimilarVideos(_) => Self::Groups,
+            Commands::EmptyFolders(_)
+            | Commands::BiggestFiles(_)
+            | Commands::EmptyFiles(_)
+            | Commands::Temporary(_)
+            | Commands::InvalidSymlinks(_)
+            | Commands::BrokenFiles(_)
+            | Commands::BadExtensions(_)
+            | Commands::BadNames(_)
+            | Commands::ExifRemover(_) => Self::Items,
+            Commands::IntegrityChecker(_)
+            | Commands::DirectoryComparison(_)
+            | Commands::VideoOptimizer(_)
+            | Commands::Apply(_)
+            | Commands::Undo(_)
+            | Commands::Quarantine(_)
+            | Commands::Serve(_) => Self::Nothing,
+        }
+    }
+
+    fn is_supported(self, delete_method: DeleteMethod) -> bool {
+        match self {
+            Self::Nothing => false,
+            Self::Items => matches!(delete_method, DeleteMethod::Delete | DeleteMethod::None),
+            Self::Groups => delete_method != DeleteMethod::Delete,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScanStatus {
+    Running,
+    Finished,
+    Stopped,
+    Deleting,
+}
+
+impl ScanStatus {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Finished => "finished",
+            Self::Stopped => "stopped",
+            Self::Deleting => "deleting",
+        }
+    }
+}
+
+struct ScanJob {
+    args: Vec<String>,
+    delete_support: DeleteSupport,
+    stop_flag: Arc<AtomicBool>,
+    state: Mutex<ScanState>,
+}
+
+struct ScanState {
+    status: ScanStatus,
+    progress: Option<ProgressData>,
+    watchers: Vec<Sender<ProgressData>>,
+    // Taken out of state when deleting files, so other requests are not blocked by long deletion
+    output: Option<CliOutput>,
+}
+
+impl ScanJob {
+    fn update_progress(&self, progress: ProgressData) {
+        let mut state = self.state.lock().expect("Cannot fail");
+        state.progress = Some(progress);
+        state.watchers.retain(|watcher| watcher.send(progress).is_ok());
+    }
+
+    // Dropping watchers ends their progress streams
+    fn finish(&self, output: CliOutput) {
+        let mut state = self.state.lock().expect("Cannot fail");
+        state.status = if self.stop_flag.load(Ordering::Relaxed) {
+            ScanStatus::Stopped
+        } else {
+            ScanStatus::Finished
+        };
+        state.watchers.clear();
+        state.output = Some(output);
+    }
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+// Puts output back to scan after deleting items, even if deleting panicked
+struct DeletingGuard<'a> {
+    job: &'a ScanJob,
+    output: Option<CliOutput>,
+    previous_status: ScanStatus,
+}
+
+impl Drop for DeletingGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.job.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.status = self.previous_status;
+        state.output = self.output.take();
+    }
+}
+
+#[derive(Default)]
+struct Server {
+    scans: Mutex<BTreeMap<u64, Arc<ScanJob>>>,
+    next_scan_id: AtomicU64,
+    connections: AtomicUsize,
+    // When set, every connection must send it with authenticate method before other requests
+    token: Option<String>,
+}
+
+// Frees connection slot when connection thread ends
+struct ConnectionSlot(Arc<Server>);
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.connections.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+trait Connection: Read + Write + Send + Sized + 'static {
+    fn try_clone_connection(&self) -> io::Result<Self>;
+}
+
+impl Connection for TcpStream {
+    fn try_clone_connection(&self) -> io::Result<Self> {
+        self.try_clone()
+    }
+}
+
+#[cfg(target_family = "unix")]
+impl Connection for UnixStream {
+    fn try_clone_connection(&self) -> io::Result<Self> {
+        self.try_clone()
+    }
+}
+
+pub(crate) fn serve(serve_args: &ServeArgs) -> io::Result<()> {
+    set_number_of_threads_once(serve_args.thread_number);
+    set_io_priority_once(serve_args.max_read_speed, serve_args.idle_priority);
+    let token = match &serve_args.token_file {
+        Some(token_file) => {
+            let token = fs::read_to_string(token_file)?.trim().to_string();
+            if token.is_empty() {
+                return Err(io::Error::other(format!("Token file \"{}\" is empty", token_file.to_string_lossy())));
+            }
+            Some(token)
+        }
+        None => None,
+    };
+    let server = Arc::new(Server { token, ..Default::default() });
+
+    #[cfg(target_family = "unix")]
+    if let Some(socket) = &serve_args.socket {
+        // Socket file is left after previous server, which was killed
+        if socket.exists() && UnixStream::connect(socket).is_err() {
+            fs::remove_file(socket)?;
+        }
+        let listener = UnixListener::bind(socket)?;
+        info!("Server listens on Unix socket \"{}\"", socket.to_string_lossy());
+        accept_connections(&server, listener.incoming());
+        return Ok(());
+    }
+
+    let listener = TcpListener::bind(&serve_args.address)?;
+    // Anyone from network could delete files, so only local clients can connect without token
+    if !listener.local_addr()?.ip().is_loopback() && server.token.is_none() {
+        return Err(io::Error::other(format!(
+            "Address {} is not localhost, so token file must be set with --token-file",
+            serve_args.address
+        )));
+    }
+    info!("Server listens on {}", listener.local_addr()?);
+    accept_connections(&server, listener.incoming());
+    Ok(())
+}
+
+fn accept_connections<C: Connection>(server: &Arc<Server>, incoming: impl Iterator<Item = io::Result<C>>) {
+    for connection in incoming {
+        let (reader, mut writer) = match connection.and_then(|connection| Ok((connection.try_clone_connection()?, connection))) {
+            Ok(connection) => connection,
+            Err(e) => {
+                error!("Cannot accept connection - {e}");
+                continue;
+            }
+        };
+        if server.connections.fetch_add(1, Ordering::Relaxed) >= MAX_CONNECTIONS {
+            server.connections.fetch_sub(1, Ordering::Relaxed);
+            let _ = write_message(
+                &mut writer,
+                &error_response(&Value::Null, &RpcError::new(SCAN_BUSY, format!("Server already has {MAX_CONNECTIONS} connections"))),
+            );
+            continue;
+        }
+        let slot = ConnectionSlot(server.clone());
+        if let Err(e) = thread::Builder::new().spawn(move || {
+            handle_connection(&slot.0, BufReader::new(reader), writer);
+            drop(slot);
+        }) {
+            error!("Cannot start connection thread - {e}");
+        }
+    }
+}
+
+fn handle_connection(server: &Server, reader: impl BufRead, mut writer: impl Write) {
+    let mut authenticated = server.token.is_none();
+    for line in reader.lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = server.handle_request(&line, &mut authenticated, &mut |notification| write_message(&mut writer, notification));
+        if let Some(response) = response
+            && write_message(&mut writer, &response).is_err()
+        {
+            break;
+        }
+    }
+}
+
+fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    writeln!(writer, "{message}")?;
+    writer.flush()
+}
+
+impl Server {
+    // Returns None for notifications(requests without id), which must not be answered
+    fn handle_request(&self, line: &str, authenticated: &mut bool, notify: &mut dyn FnMut(&Value) -> io::Result<()>) -> Option<Value> {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => return Some(error_response(&Value::Null, &RpcError::new(PARSE_ERROR, format!("Cannot parse request - {e}")))),
+        };
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return Some(error_response(&id.unwrap_or_default(), &RpcError::new(INVALID_REQUEST, "Request must contain method name")));
+        };
+        let params = request.get("params").cloned().unwrap_or_default();
+
+        let result = match method {
+            "authenticate" => self.authenticate(&params, authenticated),
+            _ if !*authenticated => Err(RpcError::new(UNAUTHORIZED, "Connection must be authenticated with token first")),
+            "start_scan" => self.start_scan(&params),
+            "list_scans" => Ok(self.list_scans()),
+            "get_status" => self.get_job(&params).map(|(scan_id, job)| status_json(scan_id, &job)),
+            "watch_progress" => self.watch_progress(&params, notify),
+            "get_results" => self.get_results(&params),
+            "delete" => self.delete(&params),
+            "stop_scan" => self.get_job(&params).map(|(_scan_id, job)| {
+                job.stop_flag.store(true, Ordering::Relaxed);
+                Value::Null
+            }),
+            "remove_scan" => self.remove_scan(&params),
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("Unknown method \"{method}\""))),
+        };
+
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(e) => error_response(&id, &e),
+        })
+    }
+
+    fn authenticate(&self, params: &Value, authenticated: &mut bool) -> Result<Value, RpcError> {
+        let token = params.get("token").and_then(Value::as_str);
+        if self.token.is_some() && token != self.token.as_deref() {
+            return Err(RpcError::new(UNAUTHORIZED, "Invalid token"));
+        }
+        *authenticated = true;
+        Ok(Value::Null)
+    }
+
+    fn start_scan(&self, params: &Value) -> Result<Value, RpcError> {
+        let args: Vec<String> = params
+            .get("args")
+            .and_then(Value::as_array)
+            .and_then(|args| args.iter().map(|arg| arg.as_str().map(str::to_string)).collect())
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Parameter \"args\" must be list of strings"))?;
+        let mut command = Args::try_parse_from(iter::once("czkawka_cli".to_string()).chain(args.iter().cloned()))
+            .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?
+            .command;
+        prepare_command(&mut command)?;
+
+        let mut scans = self.scans.lock().expect("Cannot fail");
+        if scans.len() >= MAX_SCANS {
+            return Err(RpcError::new(
+                SCAN_BUSY,
+                format!("Server already keeps {MAX_SCANS} scans, remove some of them with remove_scan"),
+            ));
+        }
+        let scan_id = self.next_scan_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let job = Arc::new(ScanJob {
+            args,
+            delete_support: DeleteSupport::from_command(&command),
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            state: Mutex::new(ScanState {
+                status: ScanStatus::Running,
+                progress: None,
+                watchers: Vec::new(),
+                output: None,
+            }),
+        });
+        scans.insert(scan_id, job.clone());
+        drop(scans);
+
+        thread::Builder::new()
+            .stack_size(DEFAULT_THREAD_SIZE)
+            .spawn(move || {
+                let (progress_sender, progress_receiver) = unbounded();
+                let progress_job = job.clone();
+                let progress_thread = thread::spawn(move || {
+                    for progress in progress_receiver {
+                        progress_job.update_progress(progress);
+                    }
+                });
+                let output = run_command(command, &job.stop_flag, &progress_sender);
+                // All progress must be sent to watchers before their streams are ended
+                drop(progress_sender);
+                let _ = progress_thread.join();
+                job.finish(output);
+            })
+            .map_err(|e| {
+                self.scans.lock().expect("Cannot fail").remove(&scan_id);
+                RpcError::new(SCAN_BUSY, format!("Cannot start scan thread - {e}"))
+            })?;
+
+        Ok(json!({"scan_id": scan_id}))
+    }
+
+    fn list_scans(&self) -> Value {
+        let scans = self.scans.lock().expect("Cannot fail");
+        Value::Array(
+            scans
+                .iter()
+                .map(|(scan_id, job)| {
+                    let status = job.state.lock().expect("Cannot fail").status;
+                    json!({"scan_id": scan_id, "args": job.args, "status": status.name()})
+                })
+                .collect(),
+        )
+    }
+
+    fn get_job(&self, params: &Value) -> Result<(u64, Arc<ScanJob>), RpcError> {
+        let scan_id = params
+            .get("scan_id")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Parameter \"scan_id\" must be number"))?;
+        let job = self.scans.lock().expect("Cannot fail").get(&scan_id).cloned();
+        job.map(|job| (scan_id, job))
+            .ok_or_else(|| RpcError::new(SCAN_NOT_FOUND, format!("Scan {scan_id} not found")))
+    }
+
+    // Sends progress notifications until scan ends, then responds with status of scan
+    fn watch_progress(&self, params: &Value, notify: &mut dyn FnMut(&Value) -> io::Result<()>) -> Result<Value, RpcError> {
+        let (scan_id, job) = self.get_job(params)?;
+        // Sender is dropped when scan already ended, so stream ends immediately
+        let progress_receiver = {
+            let (progress_sender, progress_receiver) = unbounded();
+            let mut state = job.state.lock().expect("Cannot fail");
+            if state.status == ScanStatus::Running {
+                state.watchers.push(progress_sender);
+            }
+            progress_receiver
+        };
+        for progress in progress_receiver {
+            let notification = json!({"jsonrpc": "2.0", "method": "progress", "params": progress_json(scan_id, progress)});
+            // Client disconnected, so there is no one to inform
+            if notify(&notification).is_err() {
+                break;
+            }
+        }
+        Ok(status_json(scan_id, &job))
+    }
+
+    fn get_results(&self, params: &Value) -> Result<Value, RpcError> {
+        let (scan_id, job) = self.get_job(params)?;
+        let state = job.state.lock().expect("Cannot fail");
+        let Some(output) = &state.output else {
+            return Err(RpcError::new(SCAN_BUSY, format!("Scan {scan_id} is {}", state.status.name())));
+        };
+
+        // Applying saved results is not a scan, so it has only text output
+        let Some(tool) = &output.tool else {
+            return Ok(json!({"scan_id": scan_id, "status": state.status.name(), "found_any_items": false, "results": null, "output": output.output}));
+        };
+
+        // Tools can save results only to file
+        let file_name = env::temp_dir().join(format!("czkawka_cli_serve_{}_{scan_id}.json", process::id()));
+        let file_name = file_name.to_string_lossy().to_string();
+        let results = tool
+            .results_as_json(&file_name)
+            .and_then(|()| File::open(&file_name))
+            .and_then(|file| serde_json::from_reader::<_, Value>(BufReader::new(file)).map_err(io::Error::other));
+        let _ = fs::remove_file(&file_name);
+        let results = results.map_err(|e| RpcError::new(RESULTS_ERROR, format!("Cannot save results - {e}")))?;
+
+        Ok(json!({
+            "scan_id": scan_id,
+            "status": state.status.name(),
+            "found_any_items": tool.found_any_items(),
+            "results": results,
+            "messages": messages_json(tool.messages()),
+            "output": output.output,
+        }))
+    }
+
+    fn delete(&self, params: &Value) -> Result<Value, RpcError> {
+        let (scan_id, job) = self.get_job(params)?;
+        let delete_method = match params.get("delete_method").and_then(Value::as_str) {
+            Some(method) => parse_any_delete_method(method).map_err(|e| RpcError::new(INVALID_PARAMS, e))?,
+            None => return Err(RpcError::new(INVALID_PARAMS, "Parameter \"delete_method\" must be string")),
+        };
+        let dry_run = params.get("dry_run").and_then(Value::as_bool).unwrap_or(false);
+        let move_to_trash = params.get("move_to_trash").and_then(Value::as_bool).unwrap_or(false);
+
+        // Tools panic when asked for delete method, which they do not support
+        if job.delete_support == DeleteSupport::Nothing {
+            return Err(RpcError::new(INVALID_PARAMS, format!("Scan {scan_id} cannot delete items")));
+        }
+        if !job.delete_support.is_supported(delete_method) {
+            return Err(RpcError::new(INVALID_PARAMS, format!("Delete method {delete_method:?} is not supported by scan {scan_id}")));
+        }
+
+        let mut guard = {
+            let mut state = job.state.lock().expect("Cannot fail");
+            let previous_status = state.status;
+            if state.output.as_ref().is_some_and(|output| output.tool.is_none()) {
+                return Err(RpcError::new(RESULTS_ERROR, format!("Scan {scan_id} has no items to delete")));
+            }
+            let Some(output) = state.output.take() else {
+                return Err(RpcError::new(SCAN_BUSY, format!("Scan {scan_id} is {}", state.status.name())));
+            };
+            state.status = ScanStatus::Deleting;
+            DeletingGuard {
+                job: &job,
+                output: Some(output),
+                previous_status,
+            }
+        };
+
+        // Messages of tool contain also messages from scan, so only new ones are returned
+        let new_messages = if let Some(tool) = guard.output.as_mut().and_then(|output| output.tool.as_mut()) {
+            let old_messages = tool.messages().clone();
+            job.stop_flag.store(false, Ordering::Relaxed);
+            tool.delete_items(delete_method, dry_run, move_to_trash, &job.stop_flag);
+            let messages = tool.messages();
+            Messages {
+                critical: messages.critical.clone().filter(|critical| old_messages.critical.as_ref() != Some(critical)),
+                messages: messages.messages.iter().skip(old_messages.messages.len()).cloned().collect(),
+                warnings: messages.warnings.iter().skip(old_messages.warnings.len()).cloned().collect(),
+                errors: messages.errors.iter().skip(old_messages.errors.len()).cloned().collect(),
+            }
+        } else {
+            Messages::new()
+        };
+        drop(guard);
+
+        Ok(json!({"scan_id": scan_id, "messages": messages_json(&new_messages)}))
+    }
+
+    // Running scan is stopped, but its thread ends only when tool notices stop flag
+    fn remove_scan(&self, params: &Value) -> Result<Value, RpcError> {
+        let (scan_id, job) = self.get_job(params)?;
+        if job.state.lock().expect("Cannot fail").status == ScanStatus::Deleting {
+            return Err(RpcError::new(SCAN_BUSY, format!("Scan {scan_id} is deleting files")));
+        }
+        job.stop_flag.store(true, Ordering::Relaxed);
+        self.scans.lock().expect("Cannot fail").remove(&scan_id);
+        Ok(Value::Null)
+    }
+}
+
+// Some commands would end whole process in CLI or change settings of other scans, so must be rejected before starting them
+fn prepare_command(command: &mut Commands) -> Result<(), RpcError> {
+    if matches!(command, Commands::Serve(_)) {
+        return Err(RpcError::new(INVALID_PARAMS, "Server cannot be started by another server"));
+    }
+    if let Commands::DirectoryComparison(directory_comparison) = command
+        && directory_comparison.common_cli_items.directories.len() != 2
+    {
+        return Err(RpcError::new(INVALID_PARAMS, "compare requires exactly two directories passed with separate -d flags"));
+    }
+
+    let Some(common_cli_items) = command.common_cli_items_mut() else {
+        return Ok(());
+    };
+    if common_cli_items.max_read_speed != 0 || common_cli_items.idle_priority {
+        return Err(RpcError::new(
+            INVALID_PARAMS,
+            "Read speed limit and idle priority are shared by all scans, so can be set only when starting server",
+        ));
+    }
+    // Stdin of server is not connected to client
+    if common_cli_items.file_list.as_deref() == Some(Path::new("-")) {
+        return Err(RpcError::new(INVALID_PARAMS, "File list cannot be read from stdin in server mode"));
+    }
+    read_file_list(common_cli_items).map_err(|e| RpcError::new(INVALID_PARAMS, e))
+}
+
+fn error_response(id: &Value, error: &RpcError) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": error.code, "message": error.message}})
+}
+
+fn status_json(scan_id: u64, job: &ScanJob) -> Value {
+    let state = job.state.lock().expect("Cannot fail");
+    json!({
+        "scan_id": scan_id,
+        "status": state.status.name(),
+        "progress": state.progress.map(|progress| progress_json(scan_id, progress)),
+    })
+}
+
+fn progress_json(scan_id: u64, progress: ProgressData) -> Value {
+    let display = progress.to_display();
+    json!({
+        "scan_id": scan_id,
+        "stage": format!("{:?}", progress.stage),
+        "label": display.label,
+        "all_progress": display.all_progress,
+        "current_progress": display.current_progress,
+        "entries_checked": progress.entries_checked,
+        "entries_to_check": progress.entries_to_check,
+        "bytes_checked": progress.bytes_checked,
+        "bytes_to_check": progress.bytes_to_check,
+    })
+}
+
+fn messages_json(messages: &Messages) -> Value {
+    json!({
+        "critical": messages.critical,
+        "messages": messages.messages,
+        "warnings": messages.warnings,
+        "errors": messages.errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(server: &Server, line: &str) -> Value {
+        server.handle_request(line, &mut true, &mut |_| Ok(())).expect("Request with id must be answered")
+    }
+
+    fn add_finished_scan(server: &Server, scan_id: u64, delete_support: DeleteSupport) {
+        let job = Arc::new(ScanJob {
+            args: Vec::new(),
+            delete_support,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            state: Mutex::new(ScanState {
+                status: ScanStatus::Finished,
+                progress: None,
+                watchers: Vec::new(),
+                output: Some(CliOutput {
+                    found_any_files: false,
+                    ignored_error_code_on_found: false,
+                    output: String::new(),
+                    tool: None,
+                }),
+            }),
+        });
+        server.scans.lock().expect("Cannot fail").insert(scan_id, job);
+    }
+
+    #[test]
+    fn test_invalid_requests() {
+        let server = Server::default();
+
+        assert_eq!(request(&server, "{not json")["error"]["code"], PARSE_ERROR);
+        assert_eq!(request(&server, r#"{"jsonrpc":"2.0","id":1}"#)["error"]["code"], INVALID_REQUEST);
+        assert_eq!(request(&server, r#"{"jsonrpc":"2.0","id":2,"method":"unknown"}"#)["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(
+            request(&server, r#"{"jsonrpc":"2.0","id":3,"method":"get_status","params":{"scan_id":5}}"#)["error"]["code"],
+            SCAN_NOT_FOUND
+        );
+        assert_eq!(
+            request(&server, r#"{"jsonrpc":"2.0","id":4,"method":"start_scan","params":{"args":["serve"]}}"#)["error"]["code"],
+            INVALID_PARAMS
+        );
+        assert_eq!(
+            request(&server, r#"{"jsonrpc":"2.0","id":5,"method":"start_scan","params":{"args":["dup","--unknown-flag"]}}"#)["error"]["code"],
+            INVALID_PARAMS
+        );
+        assert_eq!(
+            request(&server, r#"{"jsonrpc":"2.0","id":6,"method":"start_scan","params":{"args":["dup","--file-list","-"]}}"#)["error"]["code"],
+            INVALID_PARAMS
+        );
+        assert_eq!(request(&server, r#"{"jsonrpc":"2.0","id":7,"method":"list_scans"}"#)["result"], json!([]));
+
+        // Notifications are not answered, even if they are invalid
+        assert_eq!(server.handle_request(r#"{"jsonrpc":"2.0","method":"unknown"}"#, &mut true, &mut |_| Ok(())), None);
+    }
+
+    #[test]
+    fn test_scan_settings_of_whole_server_are_rejected() {
+        let server = Server::default();
+        let temp_dir = tempfile::TempDir::new().expect("Cannot create temporary folder");
+        let start_scan = |args: Value| request(&server, &json!({"jsonrpc": "2.0", "id": 1, "method": "start_scan", "params": {"args": args}}).to_string());
+
+        // Folder can be opened as file, but cannot be read, so error appears only when reading list
+        let response = start_scan(json!(["dup", "--file-list", temp_dir.path()]));
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        assert!(response["error"]["message"].as_str().is_some_and(|message| message.starts_with("cannot read file list")));
+        let response = start_scan(json!(["dup", "--file-list", temp_dir.path().join("missing.txt")]));
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        assert_eq!(start_scan(json!(["dup", "-d", ".", "--max-read-speed", "10"]))["error"]["code"], INVALID_PARAMS);
+        assert_eq!(start_scan(json!(["dup", "-d", ".", "--idle-priority"]))["error"]["code"], INVALID_PARAMS);
+        assert_eq!(request(&server, r#"{"jsonrpc":"2.0","id":2,"method":"list_scans"}"#)["result"], json!([]));
+    }
+
+    #[test]
+    fn test_unsupported_delete_keeps_results() {
+        let server = Server::default();
+        add_finished_scan(&server, 1, DeleteSupport::Nothing);
+        add_finished_scan(&server, 2, DeleteSupport::Items);
+
+        // e.g. compare cannot delete anything and empty files have no groups to choose newest file from
+        let response = request(&server, r#"{"jsonrpc":"2.0","id":1,"method":"delete","params":{"scan_id":1,"delete_method":"delete"}}"#);
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        let response = request(&server, r#"{"jsonrpc":"2.0","id":2,"method":"delete","params":{"scan_id":2,"delete_method":"aen"}}"#);
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        for scan_id in [1, 2] {
+            let (_scan_id, job) = server.get_job(&json!({"scan_id": scan_id})).expect("Scan was added");
+            let state = job.state.lock().expect("Cannot fail");
+            assert_eq!(state.status, ScanStatus::Finished);
+            assert!(state.output.is_some());
+        }
+    }
+
+    #[test]
+    fn test_token_is_required_before_other_requests() {
+        let server = Server {
+            token: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let mut authenticated = false;
+        let mut request = |line: &str| server.handle_request(line, &mut authenticated, &mut |_| Ok(())).expect("Request with id must be answered");
+
+        assert_eq!(request(r#"{"jsonrpc":"2.0","id":1,"method":"list_scans"}"#)["error"]["code"], UNAUTHORIZED);
+        assert_eq!(
+            request(r#"{"jsonrpc":"2.0","id":2,"method":"authenticate","params":{"token":"wrong"}}"#)["error"]["code"],
+            UNAUTHORIZED
+        );
+        assert_eq!(
+            request(r#"{"jsonrpc":"2.0","id":3,"method":"authenticate","params":{"token":"secret"}}"#)["result"],
+            Value::Null
+        );
+        assert_eq!(request(r#"{"jsonrpc":"2.0","id":4,"method":"list_scans"}"#)["result"], json!([]));
+    }
+
+    #[test]
+    fn test_number_of_scans_is_limited() {
+        let server = Server::default();
+        for scan_id in 0..MAX_SCANS as u64 {
+            add_finished_scan(&server, scan_id, DeleteSupport::Nothing);
+        }
+        let response = request(&server, r#"{"jsonrpc":"2.0","id":1,"method":"start_scan","params":{"args":["empty-files","-d","."]}}"#);
+        assert_eq!(response["error"]["code"], SCAN_BUSY);
+    }
+}
@@ -1,27 +1,41 @@
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::fs::File;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Once};
 use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use clap::Parser;
 use commands::Commands;
 use crossbeam_channel::{Receiver, Sender, unbounded};
 use czkawka_core::common::config_cache_path::{print_infos_and_warnings, set_config_cache_path};
 use czkawka_core::common::consts::DEFAULT_THREAD_SIZE;
+use czkawka_core::common::flat_results::ResultsFormat;
 use czkawka_core::common::image::register_image_decoding_hooks;
+use czkawka_core::common::io_throttle::{set_idle_priority, set_read_speed_limit};
 use czkawka_core::common::logger::{filtering_messages, print_version_mode, setup_logger};
+#[cfg(target_family = "unix")]
+use czkawka_core::common::ownership::OwnershipFilter;
+use czkawka_core::common::path_list::read_path_list;
 use czkawka_core::common::progress_data::ProgressData;
+use czkawka_core::common::quarantine::{load_manifest, purge_quarantine, restore_from_quarantine};
+use czkawka_core::common::saved_results::{ApplyOperation, ApplyParameters, SavedResults};
 use czkawka_core::common::set_number_of_threads;
-use czkawka_core::common::tool_data::{CommonData, DeleteMethod};
-use czkawka_core::common::traits::{AllTraits, FixingItems, PrintResults, Search};
+use czkawka_core::common::tool_data::{CommonData, DeleteMethod, DeviceIoLimits};
+use czkawka_core::common::traits::{AllTraits, FixingItems, Search};
+use czkawka_core::common::undo_journal::undo_last_operation;
+use czkawka_core::helpers::messages::Messages;
 use czkawka_core::tools::bad_extensions::{BadExtensions, BadExtensionsFixParams, BadExtensionsParameters};
 use czkawka_core::tools::bad_names::{BadNames, BadNamesParameters, NameFixerParams, NameIssues};
 use czkawka_core::tools::big_file::{BigFile, BigFileParameters, SearchMode};
 use czkawka_core::tools::broken_files::{BrokenFiles, BrokenFilesParameters, CheckedTypes};
+use czkawka_core::tools::directory_comparison::{DirectoryComparison, DirectoryComparisonParameters};
 use czkawka_core::tools::duplicate::{DuplicateFinder, DuplicateFinderParameters};
+use czkawka_core::tools::duplicate_folders::{DuplicateFolders, DuplicateFoldersParameters};
 use czkawka_core::tools::empty_files::{EmptyFiles, EmptyFilesParameters};
 use czkawka_core::tools::empty_folder::EmptyFolder;
 use czkawka_core::tools::exif_remover::{ExifRemover, ExifRemoverParameters, ExifTagsFixerParams};
+use czkawka_core::tools::integrity_checker::{IntegrityChecker, IntegrityCheckerParameters};
 use czkawka_core::tools::invalid_symlinks::InvalidSymlinks;
 use czkawka_core::tools::same_music::{SameMusic, SameMusicParameters};
 use czkawka_core::tools::similar_images::{SimilarImages, SimilarImagesParameters};
@@ -31,23 +45,28 @@ use czkawka_core::tools::video_optimizer::{
     HardwareEncoder, VideoCropFixParams, VideoCropParams, VideoCroppingMechanism, VideoOptimizer, VideoOptimizerFixParams, VideoOptimizerParameters, VideoTranscodeFixParams,
     VideoTranscodeParams,
 };
+use humansize::{BINARY, format_size};
 use log::{debug, error, info};
 
 use crate::commands::{
-    Args, BadExtensionsArgs, BadNamesArgs, BiggestFilesArgs, BrokenFilesArgs, CommonCliItems, DMethod, DuplicatesArgs, EmptyFilesArgs, EmptyFoldersArgs, ExifRemoverArgs,
-    InvalidSymlinksArgs, SDMethod, SameMusicArgs, SimilarImagesArgs, SimilarVideosArgs, TemporaryArgs, VideoOptimizerArgs, validate_file_sizes,
+    ApplyArgs, Args, BadExtensionsArgs, BadNamesArgs, BiggestFilesArgs, BrokenFilesArgs, CommonCliItems, DMethod, DirectoryComparisonArgs, DuplicateFoldersArgs, DuplicatesArgs,
+    EmptyFilesArgs, EmptyFoldersArgs, ExifRemoverArgs, IntegrityCheckerArgs, InvalidSymlinksArgs, QuarantineAction, QuarantineArgs, SDMethod, SameMusicArgs, SimilarImagesArgs,
+    SimilarVideosArgs, TemporaryArgs, UndoArgs, VideoOptimizerArgs, validate_file_sizes,
 };
+use crate::daemon::ScanTool;
 use crate::progress::connect_progress;
 
 mod commands;
+mod daemon;
 mod parsers;
 mod progress;
 
-#[derive(Debug)]
 pub struct CliOutput {
     pub found_any_files: bool,
     pub ignored_error_code_on_found: bool,
     pub output: String,
+    // Kept to allow server to return results and delete files after scan, empty when command is not a scan
+    pub tool: Option<Box<dyn ScanTool>>,
 }
 
 fn main() {
@@ -56,7 +75,7 @@ fn main() {
         use clap::CommandFactory;
         Args::command().debug_assert();
     }
-    let command = Args::parse().command;
+    let mut command = Args::parse().command;
 
     let config_cache_path_set_result = set_config_cache_path("Czkawka", "Czkawka");
     setup_logger(true, "czkawka_cli", filtering_messages);
@@ -67,28 +86,28 @@ fn main() {
         debug!("Running command - {command:?}");
     }
 
+    if let Commands::Serve(serve_args) = command {
+        if let Err(e) = daemon::serve(&serve_args) {
+            error!("Server failed - {e}");
+            std::process::exit(1);
+        }
+        std::process::exit(0);
+    }
+
+    if let Some(common_cli_items) = command.common_cli_items_mut()
+        && let Err(e) = read_file_list(common_cli_items)
+    {
+        use clap::CommandFactory;
+        Args::command().error(clap::error::ErrorKind::Io, e).exit();
+    }
+
     let (progress_sender, progress_receiver): (Sender<ProgressData>, Receiver<ProgressData>) = unbounded();
     let stop_flag = Arc::new(AtomicBool::new(false));
     let store_flag_cloned = stop_flag.clone();
 
     let calculate_thread = thread::Builder::new()
         .stack_size(DEFAULT_THREAD_SIZE)
-        .spawn(move || match command {
-            Commands::Duplicates(duplicates_args) => duplicates(duplicates_args, &stop_flag, &progress_sender),
-            Commands::EmptyFolders(empty_folders_args) => empty_folders(empty_folders_args, &stop_flag, &progress_sender),
-            Commands::BiggestFiles(biggest_files_args) => biggest_files(biggest_files_args, &stop_flag, &progress_sender),
-            Commands::EmptyFiles(empty_files_args) => empty_files(empty_files_args, &stop_flag, &progress_sender),
-            Commands::Temporary(temporary_args) => temporary(temporary_args, &stop_flag, &progress_sender),
-            Commands::SimilarImages(similar_images_args) => similar_images(similar_images_args, &stop_flag, &progress_sender),
-            Commands::SameMusic(same_music_args) => same_music(same_music_args, &stop_flag, &progress_sender),
-            Commands::InvalidSymlinks(invalid_symlinks_args) => invalid_symlinks(invalid_symlinks_args, &stop_flag, &progress_sender),
-            Commands::BrokenFiles(broken_files_args) => broken_files(broken_files_args, &stop_flag, &progress_sender),
-            Commands::SimilarVideos(similar_videos_args) => similar_videos(similar_videos_args, &stop_flag, &progress_sender),
-            Commands::BadExtensions(bad_extensions_args) => bad_extensions(bad_extensions_args, &stop_flag, &progress_sender),
-            Commands::BadNames(bad_names_args) => bad_names(bad_names_args, &stop_flag, &progress_sender),
-            Commands::VideoOptimizer(video_optimizer_args) => video_optimizer(video_optimizer_args, &stop_flag, &progress_sender),
-            Commands::ExifRemover(exif_remover_args) => exif_remover(exif_remover_args, &stop_flag, &progress_sender),
-        })
+        .spawn(move || run_command(command, &stop_flag, &progress_sender))
         .expect("Failed to spawn calculation thread");
 
     ctrlc::set_handler(move || {
@@ -116,61 +135,179 @@ fn main() {
     }
 }
 
+fn run_command(command: Commands, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    match command {
+        Commands::Duplicates(duplicates_args) => duplicates(duplicates_args, stop_flag, progress_sender),
+        Commands::DuplicateFolders(duplicate_folders_args) => duplicate_folders(duplicate_folders_args, stop_flag, progress_sender),
+        Commands::IntegrityChecker(integrity_checker_args) => integrity_checker(integrity_checker_args, stop_flag, progress_sender),
+        Commands::DirectoryComparison(directory_comparison_args) => directory_comparison(directory_comparison_args, stop_flag, progress_sender),
+        Commands::EmptyFolders(empty_folders_args) => empty_folders(empty_folders_args, stop_flag, progress_sender),
+        Commands::BiggestFiles(biggest_files_args) => biggest_files(biggest_files_args, stop_flag, progress_sender),
+        Commands::EmptyFiles(empty_files_args) => empty_files(empty_files_args, stop_flag, progress_sender),
+        Commands::Temporary(temporary_args) => temporary(temporary_args, stop_flag, progress_sender),
+        Commands::SimilarImages(similar_images_args) => similar_images(similar_images_args, stop_flag, progress_sender),
+        Commands::SameMusic(same_music_args) => same_music(same_music_args, stop_flag, progress_sender),
+        Commands::InvalidSymlinks(invalid_symlinks_args) => invalid_symlinks(invalid_symlinks_args, stop_flag, progress_sender),
+        Commands::BrokenFiles(broken_files_args) => broken_files(broken_files_args, stop_flag, progress_sender),
+        Commands::SimilarVideos(similar_videos_args) => similar_videos(similar_videos_args, stop_flag, progress_sender),
+        Commands::BadExtensions(bad_extensions_args) => bad_extensions(bad_extensions_args, stop_flag, progress_sender),
+        Commands::BadNames(bad_names_args) => bad_names(bad_names_args, stop_flag, progress_sender),
+        Commands::VideoOptimizer(video_optimizer_args) => video_optimizer(video_optimizer_args, stop_flag, progress_sender),
+        Commands::ExifRemover(exif_remover_args) => exif_remover(exif_remover_args, stop_flag, progress_sender),
+        Commands::Apply(apply_args) => apply(apply_args, stop_flag, progress_sender),
+        Commands::Undo(undo_args) => undo(&undo_args),
+        Commands::Quarantine(quarantine_args) => quarantine(&quarantine_args),
+        Commands::Serve(_) => unreachable!("Server is started before running any tool"),
+    }
+}
+
 fn duplicates(duplicates: DuplicatesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
     let DuplicatesArgs {
-        common_cli_items,
+        mut common_cli_items,
         reference_directories,
         minimal_file_size,
         maximal_file_size,
         minimal_cached_file_size,
         search_method,
+        minimal_similarity,
         delete_method,
         hash_type,
         allow_hard_links,
+        resume,
         case_sensitive_name_comparison,
         minimal_prehash_cache_file_size,
         use_prehash_cache,
+        verify,
+        search_in_archives,
+        sample_count,
+        confirm_full_hash,
+        memory_limit,
+        hash_manifest,
+        hash_key,
     } = duplicates;
 
     validate_file_sizes(minimal_file_size, maximal_file_size);
 
-    let params = DuplicateFinderParameters::new(
-        search_method,
-        hash_type,
+    let params = DuplicateFinderParameters {
+        check_method: search_method,
+        hash_type: hash_key.apply_to(hash_type),
         use_prehash_cache,
-        minimal_cached_file_size,
+        minimal_cache_file_size: minimal_cached_file_size,
         minimal_prehash_cache_file_size,
-        case_sensitive_name_comparison.case_sensitive_name_comparison,
-    );
+        case_sensitive_name_comparison: case_sensitive_name_comparison.case_sensitive_name_comparison,
+        verify_before_action: verify,
+        minimal_content_similarity: minimal_similarity,
+        search_in_archives,
+        sampled_hash_samples: sample_count,
+        confirm_with_full_hash: confirm_full_hash,
+        memory_limit: memory_limit * 1024 * 1024,
+        hash_all_files: hash_manifest.is_some(),
+    };
     let mut tool = DuplicateFinder::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, Some(reference_directories.reference_directories.as_ref()));
+    set_common_settings(&mut tool, &mut common_cli_items, Some(reference_directories.reference_directories.as_ref()));
     tool.set_minimal_file_size(minimal_file_size);
     tool.set_maximal_file_size(maximal_file_size);
     tool.set_hide_hard_links(!allow_hard_links.allow_hard_links);
-    set_advanced_delete(&mut tool, delete_method);
+    tool.set_resume(resume.resume);
+    set_advanced_delete(&mut tool, &delete_method);
+
+    tool.search(stop_flag, Some(progress_sender));
+
+    if let Some(hash_manifest) = hash_manifest
+        && let Err(e) = tool.save_hash_manifest(&hash_manifest.to_string_lossy())
+    {
+        error!("Failed to save hash manifest to file {e}");
+    }
+
+    save_and_write_results_to_writer(tool, &common_cli_items)
+}
+
+fn duplicate_folders(duplicate_folders: DuplicateFoldersArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let DuplicateFoldersArgs {
+        mut common_cli_items,
+        reference_directories,
+        minimal_cached_file_size,
+        delete_method,
+        hash_type,
+        subsets,
+    } = duplicate_folders;
+
+    let params = DuplicateFoldersParameters::new(hash_type, subsets, minimal_cached_file_size);
+    let mut tool = DuplicateFolders::new(params);
+
+    set_common_settings(&mut tool, &mut common_cli_items, Some(reference_directories.reference_directories.as_ref()));
+    set_advanced_delete(&mut tool, &delete_method);
+
+    tool.search(stop_flag, Some(progress_sender));
+
+    save_and_write_results_to_writer(tool, &common_cli_items)
+}
+
+fn integrity_checker(integrity_checker: IntegrityCheckerArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let IntegrityCheckerArgs {
+        mut common_cli_items,
+        manifest,
+        hash_type,
+        hash_key,
+    } = integrity_checker;
+
+    let params = IntegrityCheckerParameters::new(manifest, hash_key.apply_to(hash_type));
+    let mut tool = IntegrityChecker::new(params);
+
+    set_common_settings(&mut tool, &mut common_cli_items, None);
+
+    tool.search(stop_flag, Some(progress_sender));
+
+    save_and_write_results_to_writer(tool, &common_cli_items)
+}
+
+fn directory_comparison(directory_comparison: DirectoryComparisonArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let DirectoryComparisonArgs {
+        mut common_cli_items,
+        minimal_cached_file_size,
+        hash_type,
+    } = directory_comparison;
+
+    let [first_directory, second_directory] = common_cli_items.directories.as_slice() else {
+        use clap::CommandFactory;
+        Args::command()
+            .error(
+                clap::error::ErrorKind::WrongNumberOfValues,
+                "compare requires exactly two directories passed with separate -d flags",
+            )
+            .exit();
+    };
+
+    let params = DirectoryComparisonParameters::new(first_directory.clone(), second_directory.clone(), hash_type, minimal_cached_file_size);
+    let mut tool = DirectoryComparison::new(params);
+
+    set_common_settings(&mut tool, &mut common_cli_items, None);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(tool, &common_cli_items)
 }
 
 fn empty_folders(empty_folders: EmptyFoldersArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
-    let EmptyFoldersArgs { common_cli_items, delete_method } = empty_folders;
+    let EmptyFoldersArgs {
+        mut common_cli_items,
+        delete_method,
+    } = empty_folders;
 
     let mut tool = EmptyFolder::new();
 
-    set_common_settings(&mut tool, &common_cli_items, None);
-    set_simple_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, &mut common_cli_items, None);
+    set_simple_delete(&mut tool, &delete_method);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(tool, &common_cli_items)
 }
 
 fn biggest_files(biggest_files: BiggestFilesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
     let BiggestFilesArgs {
-        common_cli_items,
+        mut common_cli_items,
         number_of_files,
         delete_method,
         smallest_mode,
@@ -180,17 +317,17 @@ fn biggest_files(biggest_files: BiggestFilesArgs, stop_flag: &Arc<AtomicBool>, p
     let params = BigFileParameters::new(number_of_files, big_files_mode);
     let mut tool = BigFile::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, None);
-    set_simple_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, &mut common_cli_items, None);
+    set_simple_delete(&mut tool, &delete_method);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(tool, &common_cli_items)
 }
 
 fn empty_files(empty_files: EmptyFilesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
     let EmptyFilesArgs {
-        common_cli_items,
+        mut common_cli_items,
         delete_method,
         zero_byte_content,
         non_printable_content,
@@ -202,17 +339,17 @@ fn empty_files(empty_files: EmptyFilesArgs, stop_flag: &Arc<AtomicBool>, progres
     };
     let mut tool = EmptyFiles::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, None);
-    set_simple_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, &mut common_cli_items, None);
+    set_simple_delete(&mut tool, &delete_method);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(tool, &common_cli_items)
 }
 
 fn temporary(temporary: TemporaryArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
     let TemporaryArgs {
-        common_cli_items,
+        mut common_cli_items,
         delete_method,
         extensions,
     } = temporary;
@@ -224,17 +361,17 @@ fn temporary(temporary: TemporaryArgs, stop_flag: &Arc<AtomicBool>, progress_sen
     };
     let mut tool = Temporary::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, None);
-    set_simple_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, &mut common_cli_items, None);
+    set_simple_delete(&mut tool, &delete_method);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(tool, &common_cli_items)
 }
 
 fn similar_images(similar_images: SimilarImagesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
     let SimilarImagesArgs {
-        common_cli_items,
+        mut common_cli_items,
         reference_directories,
         minimal_file_size,
         maximal_file_size,
@@ -245,6 +382,7 @@ fn similar_images(similar_images: SimilarImagesArgs, stop_flag: &Arc<AtomicBool>
         geometric_invariance,
         delete_method,
         allow_hard_links,
+        resume,
         ignore_same_size,
         ignore_same_resolution,
     } = similar_images;
@@ -262,20 +400,21 @@ fn similar_images(similar_images: SimilarImagesArgs, stop_flag: &Arc<AtomicBool>
     );
     let mut tool = SimilarImages::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, Some(reference_directories.reference_directories.as_ref()));
+    set_common_settings(&mut tool, &mut common_cli_items, Some(reference_directories.reference_directories.as_ref()));
     tool.set_minimal_file_size(minimal_file_size);
     tool.set_maximal_file_size(maximal_file_size);
     tool.set_hide_hard_links(!allow_hard_links.allow_hard_links);
-    set_advanced_delete(&mut tool, delete_method);
+    tool.set_resume(resume.resume);
+    set_advanced_delete(&mut tool, &delete_method);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(tool, &common_cli_items)
 }
 
 fn same_music(same_music: SameMusicArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
     let SameMusicArgs {
-        common_cli_items,
+        mut common_cli_items,
         reference_directories,
         delete_method,
         minimal_file_size,
@@ -300,33 +439,37 @@ fn same_music(same_music: SameMusicArgs, stop_flag: &Arc<AtomicBool>, progress_s
     );
     let mut tool = SameMusic::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, Some(reference_directories.reference_directories.as_ref()));
+    set_common_settings(&mut tool, &mut common_cli_items, Some(reference_directories.reference_directories.as_ref()));
     tool.set_minimal_file_size(minimal_file_size);
     tool.set_maximal_file_size(maximal_file_size);
-    set_advanced_delete(&mut tool, delete_method);
+    set_advanced_delete(&mut tool, &delete_method);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(tool, &common_cli_items)
 }
 
 fn invalid_symlinks(invalid_symlinks: InvalidSymlinksArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
-    let InvalidSymlinksArgs { common_cli_items, delete_method } = invalid_symlinks;
+    let InvalidSymlinksArgs {
+        mut common_cli_items,
+        delete_method,
+    } = invalid_symlinks;
 
     let mut tool = InvalidSymlinks::new();
 
-    set_common_settings(&mut tool, &common_cli_items, None);
-    set_simple_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, &mut common_cli_items, None);
+    set_simple_delete(&mut tool, &delete_method);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(tool, &common_cli_items)
 }
 
 fn broken_files(broken_files: BrokenFilesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
     let BrokenFilesArgs {
-        common_cli_items,
+        mut common_cli_items,
         delete_method,
+        resume,
         checked_types,
     } = broken_files;
 
@@ -337,23 +480,25 @@ fn broken_files(broken_files: BrokenFilesArgs, stop_flag: &Arc<AtomicBool>, prog
     let params = BrokenFilesParameters::new(checked_type);
     let mut tool = BrokenFiles::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, None);
-    set_simple_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, &mut common_cli_items, None);
+    set_simple_delete(&mut tool, &delete_method);
+    tool.set_resume(resume.resume);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(tool, &common_cli_items)
 }
 
 fn similar_videos(similar_videos: SimilarVideosArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
     let SimilarVideosArgs {
         reference_directories,
-        common_cli_items,
+        mut common_cli_items,
         tolerance,
         minimal_file_size,
         maximal_file_size,
         delete_method,
         allow_hard_links,
+        resume,
         ignore_same_size,
         ignore_same_resolution,
         skip_forward_amount,
@@ -399,24 +544,28 @@ fn similar_videos(similar_videos: SimilarVideosArgs, stop_flag: &Arc<AtomicBool>
     );
     let mut tool = SimilarVideos::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, Some(reference_directories.reference_directories.as_ref()));
+    set_common_settings(&mut tool, &mut common_cli_items, Some(reference_directories.reference_directories.as_ref()));
     tool.set_minimal_file_size(minimal_file_size);
     tool.set_maximal_file_size(maximal_file_size);
     tool.set_hide_hard_links(!allow_hard_links.allow_hard_links);
-    set_advanced_delete(&mut tool, delete_method);
+    tool.set_resume(resume.resume);
+    set_advanced_delete(&mut tool, &delete_method);
 
     tool.search(stop_flag, Some(progress_sender));
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(tool, &common_cli_items)
 }
 
 fn bad_extensions(bad_extensions: BadExtensionsArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
-    let BadExtensionsArgs { common_cli_items, fix_extensions } = bad_extensions;
+    let BadExtensionsArgs {
+        mut common_cli_items,
+        fix_extensions,
+    } = bad_extensions;
 
     let params = BadExtensionsParameters::new();
     let mut tool = BadExtensions::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, None);
+    set_common_settings(&mut tool, &mut common_cli_items, None);
 
     tool.search(stop_flag, Some(progress_sender));
 
@@ -425,12 +574,12 @@ fn bad_extensions(bad_extensions: BadExtensionsArgs, stop_flag: &Arc<AtomicBool>
         tool.fix_items(stop_flag, Some(progress_sender), fix_params);
     }
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(tool, &common_cli_items)
 }
 
 fn bad_names(bad_names: BadNamesArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
     let BadNamesArgs {
-        common_cli_items,
+        mut common_cli_items,
         delete_method,
         uppercase_extension,
         emoji_used,
@@ -460,8 +609,8 @@ fn bad_names(bad_names: BadNamesArgs, stop_flag: &Arc<AtomicBool>, progress_send
     let params = BadNamesParameters::new(name_issues);
     let mut tool = BadNames::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, None);
-    set_simple_delete(&mut tool, delete_method);
+    set_common_settings(&mut tool, &mut common_cli_items, None);
+    set_simple_delete(&mut tool, &delete_method);
 
     tool.search(stop_flag, Some(progress_sender));
 
@@ -470,13 +619,13 @@ fn bad_names(bad_names: BadNamesArgs, stop_flag: &Arc<AtomicBool>, progress_send
         tool.fix_items(stop_flag, Some(progress_sender), fix_params);
     }
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(tool, &common_cli_items)
 }
 
 fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
     use crate::commands::{CropArgs, TranscodeArgs, VideoOptimizerMode as CliVideoOptimizerMode};
 
-    let VideoOptimizerArgs { common_cli_items, mode } = video_optimizer;
+    let VideoOptimizerArgs { mut common_cli_items, mode } = video_optimizer;
 
     match mode {
         CliVideoOptimizerMode::Transcode(transcode_args) => {
@@ -513,7 +662,7 @@ fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBo
             ));
 
             let mut tool = VideoOptimizer::new(params);
-            set_common_settings(&mut tool, &common_cli_items, None);
+            set_common_settings(&mut tool, &mut common_cli_items, None);
             tool.search(stop_flag, Some(progress_sender));
 
             if fix_videos {
@@ -533,7 +682,7 @@ fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBo
                 tool.fix_items(stop_flag, Some(progress_sender), fix_params);
             }
 
-            save_and_write_results_to_writer(&tool, &common_cli_items)
+            save_and_write_results_to_writer(tool, &common_cli_items)
         }
         CliVideoOptimizerMode::Crop(crop_args) => {
             let CropArgs {
@@ -572,7 +721,7 @@ fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBo
             ));
 
             let mut tool = VideoOptimizer::new(params);
-            set_common_settings(&mut tool, &common_cli_items, None);
+            set_common_settings(&mut tool, &mut common_cli_items, None);
             tool.search(stop_flag, Some(progress_sender));
 
             if fix_videos {
@@ -585,14 +734,14 @@ fn video_optimizer(video_optimizer: VideoOptimizerArgs, stop_flag: &Arc<AtomicBo
                 tool.fix_items(stop_flag, Some(progress_sender), fix_params);
             }
 
-            save_and_write_results_to_writer(&tool, &common_cli_items)
+            save_and_write_results_to_writer(tool, &common_cli_items)
         }
     }
 }
 
 fn exif_remover(exif_remover: ExifRemoverArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
     let ExifRemoverArgs {
-        common_cli_items,
+        mut common_cli_items,
         ignored_tags,
         fix_exif,
         override_file,
@@ -603,7 +752,7 @@ fn exif_remover(exif_remover: ExifRemoverArgs, stop_flag: &Arc<AtomicBool>, prog
     let params = ExifRemoverParameters::new(ignored_tags_vec);
     let mut tool = ExifRemover::new(params);
 
-    set_common_settings(&mut tool, &common_cli_items, None);
+    set_common_settings(&mut tool, &mut common_cli_items, None);
 
     tool.search(stop_flag, Some(progress_sender));
 
@@ -612,10 +761,111 @@ fn exif_remover(exif_remover: ExifRemoverArgs, stop_flag: &Arc<AtomicBool>, prog
         tool.fix_items(stop_flag, Some(progress_sender), fix_params);
     }
 
-    save_and_write_results_to_writer(&tool, &common_cli_items)
+    save_and_write_results_to_writer(tool, &common_cli_items)
+}
+
+fn undo(undo: &UndoArgs) -> CliOutput {
+    let messages = undo_last_operation(undo.dry_run);
+
+    let mut output = Vec::new();
+    let _ = messages.print_messages_to_writer(&mut output).map_err(|e| {
+        error!("Failed to print messages to output: {e}");
+    });
+    CliOutput {
+        found_any_files: false,
+        ignored_error_code_on_found: false,
+        output: String::from_utf8_lossy(&output).to_string(),
+        tool: None,
+    }
+}
+
+fn quarantine(quarantine: &QuarantineArgs) -> CliOutput {
+    let mut messages = Messages::new();
+    match &quarantine.action {
+        QuarantineAction::List => match load_manifest(&quarantine.folder) {
+            Ok(entries) => {
+                let current_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+                let entries: Vec<_> = entries.into_iter().filter(|entry| entry.quarantine_path.exists()).collect();
+                let total_size: u64 = entries.iter().map(|entry| entry.size).sum();
+                messages
+                    .messages
+                    .push(format!("Found {} quarantined items, taking {}", entries.len(), format_size(total_size, BINARY)));
+                for entry in entries {
+                    let expiry = match entry.expires_at {
+                        Some(expires_at) if expires_at <= current_time => "expired".to_string(),
+                        Some(expires_at) => format!("expires in {} days", (expires_at - current_time).div_ceil(24 * 60 * 60)),
+                        None => "never expires".to_string(),
+                    };
+                    messages
+                        .messages
+                        .push(format!("\"{}\" - {} ({expiry})", entry.original_path.to_string_lossy(), format_size(entry.size, BINARY)));
+                }
+            }
+            Err(e) => messages.errors.push(e),
+        },
+        QuarantineAction::Purge(purge) => messages.extend_with_another_messages(purge_quarantine(&quarantine.folder, purge.all, purge.dry_run)),
+        QuarantineAction::Restore(restore) => messages.extend_with_another_messages(restore_from_quarantine(&quarantine.folder, &restore.paths, restore.dry_run)),
+    }
+
+    let mut output = Vec::new();
+    let _ = messages.print_messages_to_writer(&mut output).map_err(|e| {
+        error!("Failed to print messages to output: {e}");
+    });
+    CliOutput {
+        found_any_files: false,
+        ignored_error_code_on_found: false,
+        output: String::from_utf8_lossy(&output).to_string(),
+        tool: None,
+    }
 }
 
-fn save_and_write_results_to_writer<T: CommonData + PrintResults>(component: &T, common_cli_items: &CommonCliItems) -> CliOutput {
+fn apply(apply: ApplyArgs, stop_flag: &Arc<AtomicBool>, progress_sender: &Sender<ProgressData>) -> CliOutput {
+    let ApplyArgs {
+        results_file,
+        delete_method,
+        move_to,
+        symlink,
+        dry_run,
+        move_to_trash,
+        quarantine,
+        keep_rules,
+    } = apply;
+
+    let mut messages = Messages::new();
+    match SavedResults::load(&results_file) {
+        Ok(mut results) => {
+            results.validate(&mut messages);
+            let operation = match (move_to, symlink) {
+                (Some(folder), _) => ApplyOperation::MoveTo(folder),
+                (None, true) => ApplyOperation::Symlink,
+                (None, false) => ApplyOperation::Remove,
+            };
+            let params = ApplyParameters {
+                delete_method,
+                operation,
+                dry_run,
+                move_to_trash,
+                quarantine: quarantine.settings(),
+                keep_rules,
+            };
+            messages.extend_with_another_messages(results.apply(&params, stop_flag, Some(progress_sender)));
+        }
+        Err(e) => messages.errors.push(e),
+    }
+
+    let mut output = Vec::new();
+    let _ = messages.print_messages_to_writer(&mut output).map_err(|e| {
+        error!("Failed to print messages to output: {e}");
+    });
+    CliOutput {
+        found_any_files: false,
+        ignored_error_code_on_found: false,
+        output: String::from_utf8_lossy(&output).to_string(),
+        tool: None,
+    }
+}
+
+fn save_and_write_results_to_writer<T: AllTraits + Send + 'static>(component: T, common_cli_items: &CommonCliItems) -> CliOutput {
     if let Some(file_name) = common_cli_items.file_to_save.file_name()
         && let Err(e) = component.print_results_to_file(file_name)
     {
@@ -631,6 +881,16 @@ fn save_and_write_results_to_writer<T: CommonData + PrintResults>(component: &T,
     {
         error!("Failed to save pretty json results to file {e}");
     }
+    if let Some(file_name) = common_cli_items.csv_file_to_save.file_name()
+        && let Err(e) = component.save_results_to_file(file_name, ResultsFormat::Csv)
+    {
+        error!("Failed to save csv results to file {e}");
+    }
+    if let Some(file_name) = common_cli_items.ndjson_file_to_save.file_name()
+        && let Err(e) = component.save_results_to_file(file_name, ResultsFormat::Ndjson)
+    {
+        error!("Failed to save ndjson results to file {e}");
+    }
 
     let mut buf_writer = std::io::BufWriter::new(Vec::new());
     if !common_cli_items.do_not_print.do_not_print_results {
@@ -649,6 +909,7 @@ fn save_and_write_results_to_writer<T: CommonData + PrintResults>(component: &T,
         found_any_files: component.found_any_items(),
         ignored_error_code_on_found: common_cli_items.ignore_error_code_on_found,
         output: String::new(),
+        tool: Some(Box::new(component)),
     };
 
     if let Ok(file_vec) = buf_writer.into_inner()
@@ -660,7 +921,7 @@ fn save_and_write_results_to_writer<T: CommonData + PrintResults>(component: &T,
     cli_output
 }
 
-fn set_simple_delete<T>(component: &mut T, s_delete: SDMethod)
+fn set_simple_delete<T>(component: &mut T, s_delete: &SDMethod)
 where
     T: AllTraits,
 {
@@ -669,24 +930,60 @@ where
     }
     component.set_dry_run(s_delete.dry_run);
     component.set_move_to_trash(s_delete.move_to_trash);
+    component.set_quarantine(s_delete.quarantine.settings());
 }
 
-fn set_advanced_delete<T>(component: &mut T, a_delete: DMethod)
+fn set_advanced_delete<T>(component: &mut T, a_delete: &DMethod)
 where
     T: AllTraits,
 {
     component.set_delete_method(a_delete.delete_method);
     component.set_dry_run(a_delete.dry_run);
     component.set_move_to_trash(a_delete.move_to_trash);
+    component.set_quarantine(a_delete.quarantine.settings());
+    component.set_keep_rules(a_delete.keep_rules.clone());
+}
+
+// Paths are read before scan is started, so error can be shown to user or sent to server client, instead of ending scan thread
+pub(crate) fn read_file_list(common_cli_items: &mut CommonCliItems) -> Result<(), String> {
+    let Some(file_list) = &common_cli_items.file_list else {
+        return Ok(());
+    };
+    let result = if file_list == Path::new("-") {
+        read_path_list(std::io::stdin().lock())
+    } else {
+        File::open(file_list).and_then(read_path_list)
+    };
+    common_cli_items.listed_files = result.map_err(|e| format!("cannot read file list \"{}\", reason {e}", file_list.to_string_lossy()))?;
+    Ok(())
 }
 
-fn set_common_settings<T>(component: &mut T, common_cli_items: &CommonCliItems, reference_directories: Option<&Vec<PathBuf>>)
+// Read speed limit and idle priority are used by all threads of process, so in server mode they are set only when server is started
+pub(crate) fn set_io_priority_once(max_read_speed: u64, idle_priority: bool) {
+    static IO_PRIORITY_SET: Once = Once::new();
+    IO_PRIORITY_SET.call_once(|| {
+        set_read_speed_limit(max_read_speed.saturating_mul(1024 * 1024));
+        set_idle_priority(idle_priority);
+    });
+}
+
+// Global thread pool can be created only once, so in server mode all scans use thread number set when server was started
+fn set_number_of_threads_once(thread_number: usize) {
+    static THREAD_NUMBER_SET: Once = Once::new();
+    THREAD_NUMBER_SET.call_once(|| set_number_of_threads(thread_number));
+}
+
+fn set_common_settings<T>(component: &mut T, common_cli_items: &mut CommonCliItems, reference_directories: Option<&Vec<PathBuf>>)
 where
     T: AllTraits,
 {
-    set_number_of_threads(common_cli_items.thread_number);
+    set_number_of_threads_once(common_cli_items.thread_number);
+    set_io_priority_once(common_cli_items.max_read_speed, common_cli_items.idle_priority);
 
     let mut included_directories = common_cli_items.directories.clone();
+    if common_cli_items.file_list.is_some() {
+        component.set_listed_files(std::mem::take(&mut common_cli_items.listed_files));
+    }
     if let Some(reference_directories) = reference_directories {
         included_directories.extend_from_slice(reference_directories);
         component.set_reference_paths(reference_directories.clone());
@@ -698,7 +995,27 @@ where
     component.set_recursive_search(!common_cli_items.not_recursive);
     #[cfg(target_family = "unix")]
     component.set_exclude_other_filesystems(common_cli_items.exclude_other_filesystems);
+    #[cfg(target_family = "unix")]
+    component.set_ownership_filter(OwnershipFilter {
+        uid: common_cli_items.owner,
+        gid: common_cli_items.group,
+        required_mode: common_cli_items.permissions,
+        skip_not_deletable: common_cli_items.skip_not_deletable,
+    });
     component.set_allowed_extensions(common_cli_items.allowed_extensions.clone());
     component.set_excluded_extensions(common_cli_items.excluded_extensions.clone());
+    if let Some(modified_after) = common_cli_items.modified_after {
+        component.set_minimal_modified_date(modified_after);
+    }
+    if let Some(modified_before) = common_cli_items.modified_before {
+        component.set_maximal_modified_date(modified_before);
+    }
     component.set_use_cache(!common_cli_items.disable_cache);
+    component.set_use_file_index(common_cli_items.incremental);
+    component.set_use_ignore_files(common_cli_items.respect_ignore_files);
+    component.set_follow_symlinks(common_cli_items.follow_symlinks);
+    component.set_device_io_limits(DeviceIoLimits {
+        threads_per_device: common_cli_items.io_threads_per_device,
+        threads_per_rotational_device: common_cli_items.io_threads_per_hdd,
+    });
 }
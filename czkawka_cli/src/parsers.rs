@@ -1,4 +1,9 @@
-use czkawka_core::common::model::{CheckingMethod, HashType};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use czkawka_core::common::hash_manifest::load_hash_key;
+use czkawka_core::common::keep_rules::KeepRule;
+use czkawka_core::common::model::{CheckingMethod, HashKey, HashType};
 use czkawka_core::common::tool_data::DeleteMethod;
 use czkawka_core::re_exported::{FilterType, HashAlg};
 use czkawka_core::tools::broken_files::CheckedTypes;
@@ -152,10 +157,15 @@ pub(crate) fn parse_hash_type(src: &str) -> Result<HashType, &'static str> {
         "blake3" => Ok(HashType::Blake3),
         "crc32" => Ok(HashType::Crc32),
         "xxh3" => Ok(HashType::Xxh3),
-        _ => Err("Couldn't parse the hash type (allowed: BLAKE3, CRC32, XXH3)"),
+        "sha256" => Ok(HashType::Sha256),
+        _ => Err("Couldn't parse the hash type (allowed: BLAKE3, CRC32, XXH3, SHA256)"),
     }
 }
 
+pub(crate) fn parse_hash_key_file(src: &str) -> Result<HashKey, String> {
+    load_hash_key(Path::new(src))
+}
+
 pub(crate) fn parse_tolerance(src: &str) -> Result<i32, &'static str> {
     match src.parse::<i32>() {
         Ok(t) => {
@@ -175,7 +185,9 @@ pub(crate) fn parse_checking_method_duplicate(src: &str) -> Result<CheckingMetho
         "size" => Ok(CheckingMethod::Size),
         "size_name" => Ok(CheckingMethod::SizeName),
         "hash" => Ok(CheckingMethod::Hash),
-        _ => Err("Couldn't parse the search method (allowed: NAME, SIZE, SIZE_NAME, HASH)"),
+        "content_chunks" => Ok(CheckingMethod::ContentChunks),
+        "sampled_hash" => Ok(CheckingMethod::SampledHash),
+        _ => Err("Couldn't parse the search method (allowed: NAME, SIZE, SIZE_NAME, HASH, CONTENT_CHUNKS, SAMPLED_HASH)"),
     }
 }
 
@@ -233,13 +245,27 @@ pub(crate) fn parse_delete_method(src: &str) -> Result<DeleteMethod, &'static st
         "aen" => Ok(DeleteMethod::AllExceptNewest),
         "aeo" => Ok(DeleteMethod::AllExceptOldest),
         "hard" => Ok(DeleteMethod::HardLink),
+        "reflink" => Ok(DeleteMethod::Reflink),
         "on" => Ok(DeleteMethod::OneNewest),
         "oo" => Ok(DeleteMethod::OneOldest),
         "aeb" => Ok(DeleteMethod::AllExceptBiggest),
         "aes" => Ok(DeleteMethod::AllExceptSmallest),
         "ob" => Ok(DeleteMethod::OneBiggest),
         "os" => Ok(DeleteMethod::OneSmallest),
-        _ => Err("Couldn't parse the delete method (allowed: AEN, AEO, ON, OO, HARD, AEB, AES, OB, OS)"),
+        _ => Err("Couldn't parse the delete method (allowed: AEN, AEO, ON, OO, HARD, REFLINK, AEB, AES, OB, OS)"),
+    }
+}
+
+pub(crate) fn parse_keep_rule(src: &str) -> Result<KeepRule, String> {
+    src.parse()
+}
+
+// Simple tools and saved results without groups support only removing all items
+pub(crate) fn parse_any_delete_method(src: &str) -> Result<DeleteMethod, &'static str> {
+    if src.eq_ignore_ascii_case("delete") {
+        Ok(DeleteMethod::Delete)
+    } else {
+        parse_delete_method(src).map_err(|_| "Couldn't parse the delete method (allowed: DELETE, AEN, AEO, ON, OO, HARD, REFLINK, AEB, AES, OB, OS)")
     }
 }
 
@@ -269,6 +295,80 @@ pub(crate) fn parse_maximal_file_size(src: &str) -> Result<u64, String> {
     }
 }
 
+pub(crate) fn parse_modified_date(src: &str) -> Result<u64, String> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    parse_modified_date_relative_to(src, now)
+}
+
+// Accepts date(YYYY-MM-DD), date with time(YYYY-MM-DD HH:MM:SS), both in UTC, or age relative to now like 12h, 30d, 2w or 1y
+fn parse_modified_date_relative_to(src: &str, now: u64) -> Result<u64, String> {
+    let src = src.trim();
+    const ALLOWED_FORMATS: &str = "allowed: YYYY-MM-DD, YYYY-MM-DD HH:MM:SS or age like 12h, 30d, 2w, 1y";
+
+    if let Some(unit_idx) = src.find(['h', 'd', 'w', 'y'])
+        && unit_idx + 1 == src.len()
+        && let Some((number, unit)) = src.split_at_checked(unit_idx)
+    {
+        let number = number.parse::<u64>().map_err(|e| format!("Couldn't parse age \"{src}\" - {e} ({ALLOWED_FORMATS})"))?;
+        let unit_seconds = match unit {
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            _ => 365 * 24 * 60 * 60,
+        };
+        return Ok(now.saturating_sub(number.saturating_mul(unit_seconds)));
+    }
+
+    let (date, time) = src.split_once([' ', 'T']).unwrap_or((src, "00:00:00"));
+    let parse_numbers = |text: &str, separator: char| -> Result<Vec<u64>, String> {
+        text.split(separator)
+            .map(|number| number.parse::<u64>().map_err(|e| format!("Couldn't parse date \"{src}\" - {e} ({ALLOWED_FORMATS})")))
+            .collect()
+    };
+    let (&[year, month, day], &[hour, minute, second]) = (parse_numbers(date, '-')?.as_slice(), parse_numbers(time, ':')?.as_slice()) else {
+        return Err(format!("Couldn't parse date \"{src}\" ({ALLOWED_FORMATS})"));
+    };
+    let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if is_leap_year => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    if year < 1970 || !(1..=12).contains(&month) || !(1..=days_in_month).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+        return Err(format!("Date \"{src}\" is out of range"));
+    }
+
+    // Days from civil algorithm, valid for all dates after 1970
+    let shifted_year = if month <= 2 { year - 1 } else { year };
+    let era = shifted_year / 400;
+    let year_of_era = shifted_year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    Ok(days * 24 * 60 * 60 + hour * 60 * 60 + minute * 60 + second)
+}
+
+#[cfg(target_family = "unix")]
+pub(crate) fn parse_owner(src: &str) -> Result<u32, String> {
+    czkawka_core::common::ownership::resolve_user(src).ok_or_else(|| format!("Couldn't find user \"{src}\""))
+}
+
+#[cfg(target_family = "unix")]
+pub(crate) fn parse_group(src: &str) -> Result<u32, String> {
+    czkawka_core::common::ownership::resolve_group(src).ok_or_else(|| format!("Couldn't find group \"{src}\""))
+}
+
+// Octal permission bits like in chmod e.g. 644 or 0755
+pub(crate) fn parse_permission_mode(src: &str) -> Result<u32, String> {
+    match u32::from_str_radix(src, 8) {
+        Ok(mode) if mode <= 0o7777 => Ok(mode),
+        Ok(_) => Err(format!("Permissions \"{src}\" are out of range (allowed: 0-7777)")),
+        Err(e) => Err(format!("Couldn't parse octal permissions \"{src}\" - {e}")),
+    }
+}
+
 pub(crate) fn parse_similar_image_filter(src: &str) -> Result<FilterType, String> {
     let filter_type = match src.to_lowercase().as_str() {
         "lanczos3" => FilterType::Lanczos3,
@@ -365,10 +465,37 @@ pub(crate) fn parse_noise_reduction(src: &str) -> Result<NoiseReductionMethod, S
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_permission_mode() {
+        assert_eq!(parse_permission_mode("644"), Ok(0o644));
+        assert_eq!(parse_permission_mode("0755"), Ok(0o755));
+        parse_permission_mode("888").unwrap_err();
+        parse_permission_mode("17777").unwrap_err();
+    }
+
     #[test]
     fn test_parse_geometric_invariance() {
         assert_eq!(parse_geometric_invariance("off"), Ok(GeometricInvariance::Off));
         assert_eq!(parse_geometric_invariance("mirror-flip"), Ok(GeometricInvariance::MirrorFlip));
         assert_eq!(parse_geometric_invariance("mirror-flip-rotate90"), Ok(GeometricInvariance::MirrorFlipRotate90));
     }
+
+    #[test]
+    fn test_parse_modified_date() {
+        assert_eq!(parse_modified_date_relative_to("1970-01-01", 0), Ok(0));
+        assert_eq!(parse_modified_date_relative_to("2000-03-01", 0), Ok(951_868_800));
+        assert_eq!(parse_modified_date_relative_to("2024-02-29 12:30:15", 0), Ok(1_709_209_815));
+        assert_eq!(parse_modified_date_relative_to("2024-02-29T12:30:15", 0), Ok(1_709_209_815));
+        assert_eq!(parse_modified_date_relative_to("30d", 100 * 24 * 60 * 60), Ok(70 * 24 * 60 * 60));
+        assert_eq!(parse_modified_date_relative_to("2h", 3 * 60 * 60), Ok(60 * 60));
+        assert_eq!(parse_modified_date_relative_to("1y", 1000), Ok(0));
+
+        parse_modified_date_relative_to("d", 0).unwrap_err();
+        parse_modified_date_relative_to("2024-13-01", 0).unwrap_err();
+        parse_modified_date_relative_to("2024-02-31", 0).unwrap_err();
+        parse_modified_date_relative_to("2023-02-29", 0).unwrap_err();
+        parse_modified_date_relative_to("2024-04-31", 0).unwrap_err();
+        parse_modified_date_relative_to("2024-01", 0).unwrap_err();
+        parse_modified_date_relative_to("yesterday", 0).unwrap_err();
+    }
 }
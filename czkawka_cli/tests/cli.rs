@@ -0,0 +1,64 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+use tempfile::TempDir;
+
+fn run_cli(current_dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_czkawka_cli"))
+        .current_dir(current_dir)
+        .args(args)
+        .output()
+        .expect("Failed to run czkawka_cli")
+}
+
+#[test]
+fn test_integrity_with_relative_manifest_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    fs::create_dir(root.join("data")).unwrap();
+    fs::write(root.join("data").join("a.txt"), b"abc").unwrap();
+    fs::write(root.join("data").join("b.txt"), b"abd").unwrap();
+
+    // Files have the same size, so both are hashed and saved, even if they are not duplicates
+    run_cli(&root, &["dup", "-d", "data", "-m", "1", "-K", "absolute.b3"]);
+    let absolute_manifest = fs::read_to_string(root.join("absolute.b3")).unwrap();
+    assert_eq!(absolute_manifest.lines().count(), 2);
+
+    // Relative paths in manifest are resolved against folder containing manifest, even if manifest path is relative too
+    let prefix = format!("{}{}", root.to_string_lossy(), std::path::MAIN_SEPARATOR);
+    fs::write(root.join("checksums.b3"), absolute_manifest.replace(&prefix, "")).unwrap();
+
+    let output = run_cli(&root, &["integrity", "-d", "data", "-m", "checksums.b3"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Checked 2 files from manifest"), "{stdout}");
+    assert!(stdout.contains("All files match manifest."), "{stdout}");
+}
+
+#[test]
+fn test_file_list_from_stdin() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    for name in ["a.txt", "b.txt", "not_listed.txt"] {
+        fs::write(root.join(name), b"same content").unwrap();
+    }
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_czkawka_cli"))
+        .current_dir(&root)
+        .args(["dup", "--file-list", "-", "-m", "1"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run czkawka_cli");
+    // NUL separated paths, same as produced by `find -print0`
+    let file_list = format!("{}\0{}\0", root.join("a.txt").to_string_lossy(), root.join("b.txt").to_string_lossy());
+    child.stdin.take().expect("Stdin must be piped").write_all(file_list.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let group = format!("- 2 files\n\"{}\"\n\"{}\"\n", root.join("a.txt").to_string_lossy(), root.join("b.txt").to_string_lossy());
+    assert!(stdout.contains(&group), "{stdout}");
+    assert!(!stdout.contains("not_listed.txt"), "{stdout}");
+}
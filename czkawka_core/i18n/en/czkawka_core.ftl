@@ -36,6 +36,7 @@ core_ffmpeg_not_found_windows = Be sure that ffmpeg.exe and ffprobe.exe are avai
 
 core_invalid_symlink_infinite_recursion = Infinite recursion
 core_invalid_symlink_non_existent_destination = Non-existent destination file
+core_folder_already_visited = Skipping "{ $path }", because it leads to already visited folder
 
 core_messages_limit_reached_characters = Number of messages exceeded the set limit ({$current}/{$limit} characters), so the output was truncated. To read the full output, disable the limiting option in settings.
 core_messages_limit_reached_lines = Number of messages exceeded the set limit ({$current}/{$limit} lines), so the output was truncated. To read the full output, disable the limiting option in settings.
@@ -43,6 +44,79 @@ core_messages_limit_reached_lines = Number of messages exceeded the set limit ({
 core_error_moving_to_trash = Error while moving "{ $file }" to the trash: { $error }
 core_error_removing = Error while removing "{ $file }": { $error }
 core_hardlink_unsupported_filesystem = The filesystem at "{ $path }" does not support hard links ({ $reason })
+core_reflink_unsupported_filesystem = The filesystem at "{ $path }" does not support copy-on-write clones (reflinks), only e.g. Btrfs and XFS do ({ $reason })
+core_reflink_size_mismatch = Cannot reflink "{ $file }" to "{ $original }", because they have different sizes
+core_reflink_requires_hash = Reflinks can only replace files with identical content, so they are available only when searching duplicates by hash
+core_keep_rules_not_used = Keep rules are used only by delete methods keeping one item of each group, so they were ignored
+core_similar_content_cannot_be_linked = Files with similar content are not identical, so they cannot be replaced by hardlinks or reflinks, use removing instead
+core_sampled_hash_requires_confirmation = Files with same sampled hash may still have different content, so they can be deleted or linked only when content verification or full hash confirmation is enabled
+core_folders_cannot_be_linked = Folders cannot be replaced by hardlinks or reflinks, use removing instead
+core_folder_changed_since_scan = Folder "{ $path }" was not removed, because its content differs from content checked during scan (it was changed or contains files skipped by filters)
+core_folder_contains_unchecked_item = Folder was not removed, because "{ $path }" is neither file nor folder, so it could not be compared
+core_folder_verification_cannot_read = Folder was not removed, because "{ $path }" cannot be read, reason { $reason }
+core_verification_file_changed = File "{ $file }" was changed after scan, so it was skipped
+core_verification_content_mismatch = Content of "{ $file }" differs from "{ $original }", so it was skipped
+core_similar_content_cannot_be_verified = Files with similar content are not identical, so they cannot be verified byte by byte before deleting
+core_hash_manifest_unsupported = Hash manifest can only be saved after searching by hash with BLAKE3, keyed BLAKE3 or SHA256 hash type
+core_cannot_open_hash_key = Cannot open hash key file "{ $file }", reason { $reason }
+core_invalid_hash_key_length = Hash key file "{ $file }" must contain exactly 32 bytes, but contains { $length }
+core_cannot_open_hash_manifest = Cannot open hash manifest "{ $file }", reason { $reason }
+core_invalid_hash_manifest_line = Line { $line } of hash manifest has invalid format, so it was skipped
+core_hash_manifest_wrong_hash_type = Hash of "{ $file }" in manifest has different length than hashes of selected hash type
+core_verification_cannot_read = Cannot verify content of "{ $file }", so it was skipped, reason { $reason }
+core_cannot_read_archive = Cannot read archive "{ $file }", reason { $reason }
+core_cannot_parse_ignore_file = Cannot parse ignore file "{ $file }", reason { $reason }
+core_compared_directories_must_exist = Directory comparison requires exactly two existing directories
+core_compared_directories_nested = Compared directories "{ $first }" and "{ $second }" cannot be placed inside each other
+core_resumed_from_checkpoint = Scan was resumed from checkpoint of interrupted scan
+core_no_checkpoint_to_resume = No checkpoint of interrupted scan with same settings was found, so scan was started from beginning
+core_resume_requires_cache = Interrupted scans can be resumed only when cache is enabled
+core_resume_not_supported_with_memory_limit = Interrupted scans cannot be resumed when memory limit is set
+core_saved_results_item_missing = "{ $path }" no longer exists, so it is skipped
+core_saved_results_item_changed = "{ $path }" was modified after saving results, so it is skipped
+core_saved_results_item_without_metadata = "{ $path }" was saved without size or modification date, so it cannot be checked and is skipped
+core_saved_results_folder_in_group = "{ $path }" is folder from group, which cannot be checked again without hash type used by scan, so it is skipped
+core_saved_results_cannot_open = Cannot open results file "{ $file }", reason { $reason }
+core_saved_results_cannot_load = Cannot load results from file "{ $file }", reason { $reason }
+core_saved_results_would_move = Would move "{ $path }" to "{ $destination }"
+core_saved_results_would_symlink = Would replace "{ $path }" by symlink to "{ $destination }"
+core_saved_results_failed_move = Failed to move "{ $path }" to "{ $destination }", reason { $reason }
+core_saved_results_failed_symlink = Failed to replace "{ $path }" by symlink to "{ $destination }", reason { $reason }
+core_undo_journal_cannot_open = Cannot open undo journal "{ $path }", reason { $reason }
+core_undo_journal_cannot_read = Cannot read undo journal "{ $path }", reason { $reason }
+core_undo_journal_cannot_save = Cannot save undo journal "{ $path }", reason { $reason }
+core_undo_journal_not_available = Config folder is not available, so undo journal cannot be used
+core_undo_journal_empty = Undo journal is empty, nothing to revert
+core_undo_permanently_deleted = "{ $path }" was permanently deleted and cannot be restored
+core_undo_would_restore = Would restore "{ $path }"
+core_undo_failed_restore = Failed to restore "{ $path }", reason { $reason }
+core_undo_restored = Restored { $restored } of { $all } items
+core_undo_path_used = Original path is already used by other file
+core_undo_not_symlink = Path is no longer a symlink
+core_undo_missing_new_path = Journal entry does not contain new path
+core_undo_content_not_available = Content is no longer available, reason { $reason }
+core_undo_content_changed = Content of "{ $path }" changed since link was created
+core_undo_not_in_trash = Item was not found in trash
+core_undo_trash_not_supported = Restoring items from trash is not supported on this platform
+core_cannot_lock_file = Cannot lock file "{ $path }", reason { $reason }
+core_destination_already_exists = Destination "{ $path }" already exists
+core_cannot_copy_item = Item cannot be renamed and copying it failed, reason { $reason }
+core_copied_item_not_removed = Item was copied, but "{ $path }" cannot be removed, reason { $reason }
+core_quarantine_manifest_cannot_open = Cannot open quarantine manifest "{ $path }", reason { $reason }
+core_quarantine_manifest_cannot_read = Cannot read quarantine manifest "{ $path }", reason { $reason }
+core_quarantine_manifest_cannot_save = Cannot save quarantine manifest "{ $path }", reason { $reason }
+core_quarantine_item_inside_folder = Cannot quarantine "{ $path }", because it is already inside quarantine folder
+core_quarantine_cannot_move = Cannot move "{ $path }" to quarantine, reason { $reason }
+core_quarantine_would_purge = Would purge "{ $path }"
+core_quarantine_cannot_purge = Cannot purge "{ $path }", reason { $reason }
+core_quarantine_purged = Purged { $items } items, freed { $size }
+core_quarantine_would_restore = Would restore "{ $path }"
+core_quarantine_cannot_restore = Cannot restore "{ $path }", reason { $reason }
+core_quarantine_restored = Restored { $items } items
+core_saved_results_not_grouped = Results do not contain groups of similar files, so listed items can only be removed or moved
+core_saved_results_symlink_requires_kept_item = Items can be replaced by symlinks only with method, which keeps one item in each group
+core_saved_results_links_cannot_be_moved = Hardlinks and reflinks cannot be combined with moving or symlinking items
+core_saved_results_unsupported_tool = Results of this tool contain neither groups of similar files nor single list of found items, so they cannot be applied
 
 core_no_similarity_method_selected = Cannot find similar music files without a selected similarity method
 
@@ -135,8 +209,15 @@ stage_saving_exif_cache = Saving EXIF cache
 stage_hiding_links = Hiding hard links { $items_stats }
 stage_analyzed_partial_hash = Analyzed partial hash of { $items_stats } files ({ $size_stats })
 stage_analyzed_full_hash = Analyzed full hash of { $items_stats } files ({ $size_stats })
+stage_analyzed_sampled_hash = Analyzed sampled hash of { $items_stats } files ({ $size_stats })
+stage_verified_files = Verified content of { $items_stats } files ({ $size_stats })
+stage_calculated_chunks = Split { $items_stats } files into chunks ({ $size_stats })
+stage_compared_chunks = Compared chunks of { $items_stats } files
+stage_read_archives = Read content of { $items_stats } archives
 stage_checked_tags = Checked tags of { $items_stats }
 stage_compared_tags = Compared tags of { $items_stats }
+stage_compared_folders = Compared { $items_stats } folders
+stage_verified_folders = Verified content of { $items_stats } folders ({ $size_stats })
 stage_checked_content = Checked content of { $items_stats } ({ $size_stats })
 stage_compared_content = Compared content of { $items_stats }
 stage_hashed_images = Hashed { $items_stats } images ({ $size_stats })
@@ -149,6 +230,7 @@ stage_checked_files = Checked { $items_stats } file ({ $size_stats })
 stage_checked_files_bad_extensions = Checked { $items_stats } file
 stage_checked_files_bad_names = Checked { $items_stats } file
 stage_checking_empty_files_content = Checking content of { $items_stats } files ({ $size_stats })
+stage_checked_files_integrity = Checked integrity of { $items_stats } files ({ $size_stats })
 # File operations (delete/rename/move/...); the "no_size" variant is used when no byte total is known
 stage_deleting_files = Deleting { $items_stats } file ({ $size_stats })
 stage_deleting_no_size_files = Deleting { $items_stats } file
@@ -157,6 +239,8 @@ stage_moving_files = Moving { $items_stats } file ({ $size_stats })
 stage_moving_no_size_files = Moving { $items_stats } file
 stage_hardlinking_files = Hardlinking { $items_stats } file ({ $size_stats })
 stage_hardlinking_no_size_files = Hardlinking { $items_stats } file
+stage_reflinking_files = Reflinking { $items_stats } file ({ $size_stats })
+stage_reflinking_no_size_files = Reflinking { $items_stats } file
 stage_symlinking_files = Symlinking { $items_stats } file ({ $size_stats })
 stage_symlinking_no_size_files = Symlinking { $items_stats } file
 stage_optimizing_videos = Optimized { $items_stats } video ({ $size_stats })
@@ -0,0 +1,401 @@
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use crate::flc;
+
+// Files inside archives are represented by virtual paths like `backup.zip!/folder/file.txt`
+pub const ARCHIVE_MEMBER_SEPARATOR: &str = "!/";
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Compression {
+    Gz,
+    Bz2,
+    Zst,
+    Xz,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum ArchiveType {
+    Zip,
+    SevenZip,
+    Tar,
+    CompressedTar(Compression),
+    // Single compressed file e.g. `log.txt.gz`, which contains only one member - `log.txt`
+    CompressedFile(Compression),
+}
+
+// Longer extensions must be checked before shorter, so `.tar.gz` is not treated as single compressed file
+const ARCHIVE_EXTENSIONS: &[(&str, ArchiveType)] = &[
+    (".tar.gz", ArchiveType::CompressedTar(Compression::Gz)),
+    (".tgz", ArchiveType::CompressedTar(Compression::Gz)),
+    (".tar.bz2", ArchiveType::CompressedTar(Compression::Bz2)),
+    (".tbz2", ArchiveType::CompressedTar(Compression::Bz2)),
+    (".tar.zst", ArchiveType::CompressedTar(Compression::Zst)),
+    (".tzst", ArchiveType::CompressedTar(Compression::Zst)),
+    (".tar.xz", ArchiveType::CompressedTar(Compression::Xz)),
+    (".txz", ArchiveType::CompressedTar(Compression::Xz)),
+    (".zip", ArchiveType::Zip),
+    (".7z", ArchiveType::SevenZip),
+    (".tar", ArchiveType::Tar),
+    (".gz", ArchiveType::CompressedFile(Compression::Gz)),
+    (".bz2", ArchiveType::CompressedFile(Compression::Bz2)),
+    (".zst", ArchiveType::CompressedFile(Compression::Zst)),
+    (".xz", ArchiveType::CompressedFile(Compression::Xz)),
+];
+
+fn get_archive_type(path: &Path) -> Option<(ArchiveType, usize)> {
+    let file_name = path.file_name()?.to_string_lossy().to_lowercase();
+    ARCHIVE_EXTENSIONS
+        .iter()
+        .find(|(extension, _)| file_name.len() > extension.len() && file_name.ends_with(extension))
+        .map(|(extension, archive_type)| (*archive_type, extension.len()))
+}
+
+pub fn is_supported_archive(path: &Path) -> bool {
+    get_archive_type(path).is_some()
+}
+
+pub fn get_archive_member_path(archive_path: &Path, member: &str) -> PathBuf {
+    let mut path = OsString::from(archive_path.as_os_str());
+    path.push(ARCHIVE_MEMBER_SEPARATOR);
+    path.push(member);
+    PathBuf::from(path)
+}
+
+// Returns path of archive and name of member inside it, if path points to file inside supported archive
+pub fn split_archive_member_path(path: &Path) -> Option<(&Path, &str)> {
+    let path_str = path.to_str()?;
+    path_str.match_indices(ARCHIVE_MEMBER_SEPARATOR).find_map(|(idx, separator)| {
+        let archive_path = Path::new(path_str.get(..idx)?);
+        if !is_supported_archive(archive_path) {
+            return None;
+        }
+        Some((archive_path, path_str.get(idx + separator.len()..)?))
+    })
+}
+
+pub fn is_archive_member_path(path: &Path) -> bool {
+    split_archive_member_path(path).is_some()
+}
+
+// Files inside archives do not exist on disk, so for them, path of archive is returned
+pub fn get_path_on_disk(path: &Path) -> &Path {
+    split_archive_member_path(path).map_or(path, |(archive_path, _member)| archive_path)
+}
+
+// Returns names and uncompressed sizes of all regular files inside archive
+// Encrypted members are skipped, because they cannot be read without password
+pub fn list_archive_members(archive_path: &Path) -> Result<Vec<(String, u64)>, String> {
+    let archive_error = |e: String| flc!("core_cannot_read_archive", file = archive_path.to_string_lossy(), reason = e);
+    let Some((archive_type, extension_len)) = get_archive_type(archive_path) else {
+        return Err(archive_error("unsupported archive type".to_string()));
+    };
+
+    match archive_type {
+        ArchiveType::Zip => {
+            let file = File::open(archive_path).map_err(|e| archive_error(e.to_string()))?;
+            let mut archive = zip::ZipArchive::new(BufReader::new(file)).map_err(|e| archive_error(e.to_string()))?;
+            let mut members = Vec::new();
+            for idx in 0..archive.len() {
+                let member = archive.by_index_raw(idx).map_err(|e| archive_error(e.to_string()))?;
+                if member.is_file() && !member.encrypted() {
+                    members.push((member.name().to_string(), member.size()));
+                }
+            }
+            Ok(members)
+        }
+        ArchiveType::SevenZip => {
+            let archive = sevenz_rust2::Archive::open(archive_path).map_err(|e| archive_error(e.to_string()))?;
+            Ok(archive
+                .files
+                .iter()
+                .filter(|entry| !entry.is_directory() && !entry.is_anti_item())
+                .map(|entry| (entry.name().to_string(), entry.size()))
+                .collect())
+        }
+        ArchiveType::Tar => {
+            let file = File::open(archive_path).map_err(|e| archive_error(e.to_string()))?;
+            list_tar_members(BufReader::new(file)).map_err(|e| archive_error(e.to_string()))
+        }
+        ArchiveType::CompressedTar(compression) => {
+            let decoder = open_decoder(archive_path, compression).map_err(|e| archive_error(e.to_string()))?;
+            list_tar_members(decoder).map_err(|e| archive_error(e.to_string()))
+        }
+        ArchiveType::CompressedFile(compression) => {
+            // Size of decompressed data is not stored in header of every format, so whole file needs to be decompressed
+            // Tools which later read content of member, should use `get_compressed_file_member` and read it only once instead
+            let mut decoder = open_decoder(archive_path, compression).map_err(|e| archive_error(e.to_string()))?;
+            let size = io::copy(&mut decoder, &mut io::sink()).map_err(|e| archive_error(e.to_string()))?;
+            Ok(vec![(get_compressed_file_member_name(archive_path, extension_len), size)])
+        }
+    }
+}
+
+// Passes content of every chosen member to `read_func`, reading whole archive only once, so each member is decompressed once
+// Members are visited in order in which they are stored and `read_func` returning false stops reading
+// Tar based archives and solid 7z archives cannot be randomly accessed, so reading them one member at a time would decompress same data again and again
+pub fn read_archive_members(archive_path: &Path, members: &HashSet<&str>, mut read_func: impl FnMut(&str, &mut dyn Read) -> bool) -> Result<(), String> {
+    let archive_error = |e: String| flc!("core_cannot_read_archive", file = archive_path.to_string_lossy(), reason = e);
+    let Some((archive_type, extension_len)) = get_archive_type(archive_path) else {
+        return Err(archive_error("unsupported archive type".to_string()));
+    };
+
+    match archive_type {
+        ArchiveType::Zip => {
+            let file = File::open(archive_path).map_err(|e| archive_error(e.to_string()))?;
+            let mut archive = zip::ZipArchive::new(BufReader::new(file)).map_err(|e| archive_error(e.to_string()))?;
+            // Zip can be randomly accessed, so members are read directly, one after another
+            for idx in 0..archive.len() {
+                let Some(name) = archive.name_for_index(idx).filter(|name| members.contains(name)).map(str::to_string) else {
+                    continue;
+                };
+                let mut member_reader = archive.by_index(idx).map_err(|e| archive_error(e.to_string()))?;
+                if !member_reader.is_file() {
+                    continue;
+                }
+                if !read_func(&name, &mut member_reader) {
+                    break;
+                }
+            }
+            Ok(())
+        }
+        ArchiveType::SevenZip => {
+            let mut archive = sevenz_rust2::ArchiveReader::open(archive_path, sevenz_rust2::Password::empty()).map_err(|e| archive_error(e.to_string()))?;
+            archive
+                .for_each_entries(|entry, member_reader| {
+                    if entry.is_directory() || !members.contains(entry.name()) {
+                        return Ok(true);
+                    }
+                    Ok(read_func(entry.name(), member_reader))
+                })
+                .map_err(|e| archive_error(e.to_string()))
+        }
+        ArchiveType::Tar => {
+            let file = File::open(archive_path).map_err(|e| archive_error(e.to_string()))?;
+            read_tar_members(BufReader::new(file), members, read_func).map_err(|e| archive_error(e.to_string()))
+        }
+        ArchiveType::CompressedTar(compression) => {
+            let decoder = open_decoder(archive_path, compression).map_err(|e| archive_error(e.to_string()))?;
+            read_tar_members(decoder, members, read_func).map_err(|e| archive_error(e.to_string()))
+        }
+        ArchiveType::CompressedFile(compression) => {
+            let member = get_compressed_file_member_name(archive_path, extension_len);
+            if members.contains(member.as_str()) {
+                let mut decoder = open_decoder(archive_path, compression).map_err(|e| archive_error(e.to_string()))?;
+                read_func(&member, &mut decoder);
+            }
+            Ok(())
+        }
+    }
+}
+
+// Returns name of only member of single compressed file like `log.txt.gz`, size of which is unknown until it is decompressed
+pub fn get_compressed_file_member(archive_path: &Path) -> Option<String> {
+    match get_archive_type(archive_path)? {
+        (ArchiveType::CompressedFile(_), extension_len) => Some(get_compressed_file_member_name(archive_path, extension_len)),
+        _ => None,
+    }
+}
+
+fn get_compressed_file_member_name(archive_path: &Path, extension_len: usize) -> String {
+    let file_name = archive_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    file_name.get(..file_name.len().saturating_sub(extension_len)).unwrap_or_default().to_string()
+}
+
+fn open_decoder(archive_path: &Path, compression: Compression) -> io::Result<Box<dyn Read>> {
+    let file = BufReader::new(File::open(archive_path)?);
+    Ok(match compression {
+        Compression::Gz => Box::new(flate2::read::MultiGzDecoder::new(file)),
+        Compression::Bz2 => Box::new(bzip2_rs::DecoderReader::new(file)),
+        Compression::Zst => Box::new(ruzstd::decoding::StreamingDecoder::new(file).map_err(io::Error::other)?),
+        Compression::Xz => Box::new(lzma_rust2::XzReader::new(file, true)),
+    })
+}
+
+fn list_tar_members<R: Read>(reader: R) -> io::Result<Vec<(String, u64)>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut members = Vec::new();
+    for entry in archive.entries()? {
+        let entry = entry?;
+        if entry.header().entry_type().is_file() {
+            members.push((entry.path()?.to_string_lossy().to_string(), entry.size()));
+        }
+    }
+    Ok(members)
+}
+
+fn read_tar_members<R: Read>(reader: R, members: &HashSet<&str>, mut read_func: impl FnMut(&str, &mut dyn Read) -> bool) -> io::Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().to_string();
+        if members.contains(name.as_str()) && !read_func(&name, &mut entry) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::Write;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn read_members_to_vec(archive_path: &Path, members: &[&str]) -> Result<Vec<(String, Vec<u8>)>, String> {
+        let mut contents = Vec::new();
+        read_archive_members(archive_path, &members.iter().copied().collect(), |member, reader| {
+            let mut content = Vec::new();
+            reader.read_to_end(&mut content).unwrap();
+            contents.push((member.to_string(), content));
+            true
+        })?;
+        Ok(contents)
+    }
+
+    fn create_tar<W: Write>(writer: W, files: &[(&str, &[u8])]) -> W {
+        let mut builder = tar::Builder::new(writer);
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *content).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_split_archive_member_path() {
+        let path = get_archive_member_path(Path::new("/home/user/backup.tar.gz"), "folder/file.txt");
+        assert_eq!(path, PathBuf::from("/home/user/backup.tar.gz!/folder/file.txt"));
+        assert_eq!(split_archive_member_path(&path), Some((Path::new("/home/user/backup.tar.gz"), "folder/file.txt")));
+        assert_eq!(get_path_on_disk(&path), Path::new("/home/user/backup.tar.gz"));
+
+        // Only paths pointing inside supported archives are virtual
+        assert!(!is_archive_member_path(Path::new("/home/user/wow!/file.txt")));
+        assert!(!is_archive_member_path(Path::new("/home/user/backup.zip")));
+        assert_eq!(get_path_on_disk(Path::new("/home/user/wow!/file.txt")), Path::new("/home/user/wow!/file.txt"));
+        assert_eq!(
+            split_archive_member_path(Path::new("/home/wow!/backup.ZIP!/file.txt")),
+            Some((Path::new("/home/wow!/backup.ZIP"), "file.txt"))
+        );
+    }
+
+    #[test]
+    fn test_read_zip_members() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("archive.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&archive_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        writer.add_directory("folder/", options).unwrap();
+        writer.start_file("folder/file.txt", options).unwrap();
+        writer.write_all(b"zip content").unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(list_archive_members(&archive_path).unwrap(), vec![("folder/file.txt".to_string(), 11)]);
+        assert_eq!(
+            read_members_to_vec(&archive_path, &["folder/file.txt"]).unwrap(),
+            vec![("folder/file.txt".to_string(), b"zip content".to_vec())]
+        );
+        assert!(read_members_to_vec(&archive_path, &["missing.txt"]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_tar_gz_members() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("archive.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(File::create(&archive_path).unwrap(), flate2::Compression::default());
+        create_tar(encoder, &[("first.txt", b"first"), ("second.txt", b"second content"), ("third.txt", b"third")])
+            .finish()
+            .unwrap();
+
+        assert_eq!(
+            list_archive_members(&archive_path).unwrap(),
+            vec![("first.txt".to_string(), 5), ("second.txt".to_string(), 14), ("third.txt".to_string(), 5)]
+        );
+        // All chosen members are read in one pass, in order in which they are stored
+        assert_eq!(
+            read_members_to_vec(&archive_path, &["third.txt", "first.txt", "missing.txt"]).unwrap(),
+            vec![("first.txt".to_string(), b"first".to_vec()), ("third.txt".to_string(), b"third".to_vec())]
+        );
+
+        // Returning false from reading function stops reading archive
+        let mut read_members = Vec::new();
+        read_archive_members(&archive_path, &HashSet::from(["first.txt", "second.txt"]), |member, _reader| {
+            read_members.push(member.to_string());
+            false
+        })
+        .unwrap();
+        assert_eq!(read_members, vec!["first.txt".to_string()]);
+    }
+
+    #[test]
+    fn test_read_compressed_file_member() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("log.txt.gz");
+        let mut encoder = flate2::write::GzEncoder::new(File::create(&archive_path).unwrap(), flate2::Compression::default());
+        encoder.write_all(b"log line").unwrap();
+        encoder.finish().unwrap();
+
+        assert_eq!(list_archive_members(&archive_path).unwrap(), vec![("log.txt".to_string(), 8)]);
+        assert_eq!(get_compressed_file_member(&archive_path), Some("log.txt".to_string()));
+        assert_eq!(
+            read_members_to_vec(&archive_path, &["log.txt"]).unwrap(),
+            vec![("log.txt".to_string(), b"log line".to_vec())]
+        );
+        assert!(read_members_to_vec(&archive_path, &["other.txt"]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_xz_archives() {
+        let temp_dir = TempDir::new().unwrap();
+        let xz_compress = |content: &[u8]| {
+            let mut compressed = Vec::new();
+            lzma_rs::xz_compress(&mut content.to_vec().as_slice(), &mut compressed).unwrap();
+            compressed
+        };
+
+        let file_path = temp_dir.path().join("log.txt.xz");
+        fs::write(&file_path, xz_compress(b"xz log line")).unwrap();
+        assert_eq!(get_compressed_file_member(&file_path), Some("log.txt".to_string()));
+        assert_eq!(list_archive_members(&file_path).unwrap(), vec![("log.txt".to_string(), 11)]);
+        assert_eq!(
+            read_members_to_vec(&file_path, &["log.txt"]).unwrap(),
+            vec![("log.txt".to_string(), b"xz log line".to_vec())]
+        );
+
+        let tar_content = create_tar(Vec::new(), &[("first.txt", b"first"), ("second.txt", b"second content")]);
+        for name in ["archive.tar.xz", "archive.txz"] {
+            let archive_path = temp_dir.path().join(name);
+            fs::write(&archive_path, xz_compress(&tar_content)).unwrap();
+            assert_eq!(get_compressed_file_member(&archive_path), None);
+            assert_eq!(
+                list_archive_members(&archive_path).unwrap(),
+                vec![("first.txt".to_string(), 5), ("second.txt".to_string(), 14)]
+            );
+            assert_eq!(
+                read_members_to_vec(&archive_path, &["second.txt"]).unwrap(),
+                vec![("second.txt".to_string(), b"second content".to_vec())]
+            );
+        }
+    }
+
+    #[test]
+    fn test_list_broken_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("broken.zip");
+        fs::write(&archive_path, b"not a zip").unwrap();
+
+        list_archive_members(&archive_path).unwrap_err();
+    }
+}
@@ -3,7 +3,7 @@
 mod cleaning;
 
 use std::collections::BTreeMap;
-use std::io::{BufReader, BufWriter};
+use std::io::{BufReader, BufWriter, Write};
 use std::path::Path;
 use std::{fs, mem};
 
@@ -15,10 +15,13 @@ use indexmap::IndexMap;
 use log::{debug, error};
 use once_cell::sync::Lazy;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
+use crate::common::archive::get_path_on_disk;
 use crate::common::cache::cleaning::{should_clean_cache, update_cleaning_timestamp};
 use crate::common::config_cache_path::open_cache_folder;
+use crate::common::spill_store::{MemoryUsage, SpillStore, SpilledGroups};
 use crate::common::tool_data::CommonData;
 use crate::common::traits::ResultEntry;
 use crate::flc;
@@ -90,6 +93,92 @@ where
     text_messages
 }
 
+// Saves entries grouped by path, when several entries of one file exist, the one with newest modification date is used
+// Entries are written one by one, so they do not need to be collected in memory
+#[fun_time(message = "save_cache_to_file_spilled", level = "debug")]
+pub(crate) fn save_cache_to_file_spilled<T>(cache_file_name: &str, entries: &SpillStore<String, T>, save_also_as_json: bool, minimum_file_size: u64) -> Messages
+where
+    T: Serialize + DeserializeOwned + ResultEntry + MemoryUsage + Clone,
+{
+    let mut text_messages = Messages::new();
+    // Binary cache starts with number of entries, so they are read twice - first only to count them
+    let entries_number = match entries.groups() {
+        Ok(mut groups) => {
+            let entries_number = newest_entries(&mut groups, minimum_file_size).count();
+            if let Some(e) = groups.take_error() {
+                text_messages.warnings.push(flc!("core_failed_to_write_data_to_cache", file = cache_file_name, reason = e));
+                return text_messages;
+            }
+            entries_number
+        }
+        Err(e) => {
+            text_messages.warnings.push(flc!("core_failed_to_write_data_to_cache", file = cache_file_name, reason = e));
+            return text_messages;
+        }
+    };
+
+    let Some(((file_handler, cache_file), (file_handler_json, _cache_file_json))) = open_cache_folder(cache_file_name, true, save_also_as_json, &mut text_messages.warnings) else {
+        debug!("Failed to save cache to file {cache_file_name} because not exists");
+        return text_messages;
+    };
+    let mut writer = BufWriter::new(file_handler.expect("Cannot fail, because for saving, this always exists"));
+    let mut writer_json = file_handler_json.filter(|_| save_also_as_json).map(BufWriter::new);
+    if let Err(e) = write_spilled_cache(entries, entries_number, minimum_file_size, &mut writer, writer_json.as_mut()) {
+        debug!("Failed to save cache to file \"{}\" - {e}", cache_file.to_string_lossy());
+        text_messages
+            .warnings
+            .push(flc!("core_failed_to_write_data_to_cache", file = cache_file.to_string_lossy(), reason = e));
+        return text_messages;
+    }
+    debug!("Saved cache to binary file \"{}\" with size {}", cache_file.to_string_lossy(), get_cache_size(&cache_file));
+
+    text_messages.messages.push(flc!("core_properly_saved_cache_entries", count = entries_number));
+    text_messages
+}
+
+fn newest_entries<T: ResultEntry + DeserializeOwned>(groups: &mut SpilledGroups<String, T>, minimum_file_size: u64) -> impl Iterator<Item = T> + '_ {
+    groups
+        .filter_map(|(_path, versions)| versions.into_iter().max_by_key(ResultEntry::get_modified_date))
+        .filter(move |entry| entry.get_size() >= minimum_file_size)
+}
+
+// Produces the same content as serializing Vec<T>
+fn write_spilled_cache<T, W: Write>(
+    entries: &SpillStore<String, T>,
+    entries_number: usize,
+    minimum_file_size: u64,
+    writer: &mut W,
+    mut writer_json: Option<&mut W>,
+) -> Result<(), String>
+where
+    T: Serialize + DeserializeOwned + ResultEntry + MemoryUsage + Clone,
+{
+    let options = bincode::DefaultOptions::new().with_limit(MEMORY_LIMIT);
+    options.serialize_into(&mut *writer, &(entries_number as u64)).map_err(|e| e.to_string())?;
+    if let Some(writer_json) = writer_json.as_mut() {
+        writer_json.write_all(b"[").map_err(|e| e.to_string())?;
+    }
+
+    let mut groups = entries.groups()?;
+    for (idx, entry) in newest_entries(&mut groups, minimum_file_size).enumerate() {
+        options.serialize_into(&mut *writer, &entry).map_err(|e| e.to_string())?;
+        if let Some(writer_json) = writer_json.as_mut() {
+            if idx > 0 {
+                writer_json.write_all(b",").map_err(|e| e.to_string())?;
+            }
+            serde_json::to_writer(&mut **writer_json, &entry).map_err(|e| e.to_string())?;
+        }
+    }
+    if let Some(e) = groups.take_error() {
+        return Err(e);
+    }
+
+    if let Some(writer_json) = writer_json {
+        writer_json.write_all(b"]").and_then(|()| writer_json.flush()).map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())
+}
+
 pub(crate) fn extract_loaded_cache<T>(
     loaded_hash_map: &BTreeMap<String, T>,
     files_to_check: BTreeMap<String, T>,
@@ -256,7 +345,7 @@ where
                     return false;
                 }
 
-                if effective_delete_outdated && !file_entry.get_path().exists() {
+                if effective_delete_outdated && !get_path_on_disk(file_entry.get_path()).exists() {
                     return false;
                 }
 
@@ -288,6 +377,64 @@ where
     (text_messages, None)
 }
 
+// Entries are read one by one, so cache does not need to fit into memory
+// Json cache is used only when binary one is missing, so then it is loaded at once
+#[fun_time(message = "load_cache_from_file_streamed", level = "debug")]
+pub(crate) fn load_cache_from_file_streamed<T>(cache_file_name: &str, delete_outdated_cache: bool, mut add_entry: impl FnMut(T)) -> Messages
+where
+    for<'a> T: Deserialize<'a> + ResultEntry + Sized + Send + Sync + Clone,
+{
+    const CHUNK_SIZE: usize = 10_000;
+
+    let mut text_messages = Messages::new();
+    let Some(((Some(file_handler), cache_file), _)) = open_cache_folder(cache_file_name, false, false, &mut text_messages.warnings) else {
+        let (messages, loaded_entries) = load_cache_from_file_generalized(cache_file_name, delete_outdated_cache, |_: &T| true);
+        loaded_entries.into_iter().flatten().for_each(add_entry);
+        return messages;
+    };
+
+    let effective_delete_outdated = delete_outdated_cache && should_clean_cache(cache_file_name);
+    let options = bincode::DefaultOptions::new().with_limit(MEMORY_LIMIT);
+    let mut reader = BufReader::new(file_handler);
+    let mut loaded_entries = 0;
+    let mut add_chunk = |chunk: Vec<T>| {
+        let chunk: Vec<T> = if effective_delete_outdated {
+            chunk.into_par_iter().filter(|file_entry| get_path_on_disk(file_entry.get_path()).exists()).collect()
+        } else {
+            chunk
+        };
+        loaded_entries += chunk.len();
+        chunk.into_iter().for_each(&mut add_entry);
+    };
+
+    // Cache is saved as Vec<T>, so entries are preceded by their number
+    let read_result = options.deserialize_from::<_, u64>(&mut reader).and_then(|entries_number| {
+        let mut chunk = Vec::new();
+        for _ in 0..entries_number {
+            chunk.push(options.deserialize_from::<_, T>(&mut reader)?);
+            if chunk.len() == CHUNK_SIZE {
+                add_chunk(mem::take(&mut chunk));
+            }
+        }
+        add_chunk(chunk);
+        Ok(())
+    });
+    // Entries read before error are correct, so they are still used
+    if let Err(e) = read_result {
+        text_messages
+            .warnings
+            .push(flc!("core_failed_to_load_data_from_cache", file = cache_file.to_string_lossy(), reason = e.to_string()));
+        error!("Failed to load cache from file {} - {e}", cache_file.to_string_lossy());
+    }
+    if effective_delete_outdated {
+        update_cleaning_timestamp(cache_file_name);
+    }
+
+    text_messages.messages.push(flc!("core_properly_loaded_cache_entries", count = loaded_entries));
+    debug!("Loaded cache from file {cache_file_name} - {loaded_entries} results - size {}", get_cache_size(&cache_file));
+    text_messages
+}
+
 pub(crate) fn load_and_split_cache_generalized_by_size<C: CommonData, K>(
     cache_file_name: &str,
     use_cache: bool,
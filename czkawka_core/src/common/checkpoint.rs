@@ -0,0 +1,356 @@
+// Checkpoints of long scans, which allow to continue interrupted scan(stopped by user or by e.g. reboot) from last finished stage.
+// Checkpoint contains state saved after finishing stage and results of currently processed stage, which are appended to log file.
+// Checkpoint is identified by hash of scan inputs and parameters, so resumed scan must use exactly same settings as interrupted one.
+// Checkpoints are created only when resuming is requested, so the first scan with resume enabled creates them and next ones continue it.
+// Checkpoints are stored in cache folder, so they are used only when cache is enabled.
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Seek, Write};
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use bincode::Options;
+use log::{debug, warn};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::common::config_cache_path::get_config_cache_path;
+use crate::common::tool_data::CommonToolData;
+use crate::common::traits::ResultEntry;
+use crate::flc;
+
+const CHECKPOINT_VERSION: u8 = 101;
+
+const MEMORY_LIMIT: u64 = 8 * 1024 * 1024 * 1024;
+// Results are only appended, so flushing is cheap, but still should not be done after every file
+const PARTIAL_RESULTS_FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) struct Checkpoint {
+    // None when checkpoints are disabled
+    file_stem: Option<String>,
+    // Partial results belong to stage saved in checkpoint, so they are used only when this stage was resumed
+    resumed: AtomicBool,
+}
+
+impl Checkpoint {
+    pub(crate) fn new<P: Debug>(common_data: &CommonToolData, parameters: &P) -> Self {
+        if !common_data.resume || !common_data.use_cache {
+            return Self::disabled();
+        }
+        // Debug representation contains all values, which change found files or their order
+        let inputs = format!(
+            "{:?}|{:?}|{:?}|{:?}|{}|{}|{}|{}|{}|{:?}|{}|{}|{}|{:?}|{}|{:?}",
+            common_data.directories,
+            common_data.extensions,
+            common_data.excluded_items,
+            common_data.tool_type,
+            common_data.recursive_search,
+            common_data.minimal_file_size,
+            common_data.maximal_file_size,
+            common_data.minimal_modified_date,
+            common_data.maximal_modified_date,
+            common_data.ownership_filter,
+            common_data.follow_symlinks,
+            common_data.hide_hard_links,
+            common_data.use_ignore_files,
+            common_data.directories.exclude_other_filesystems,
+            common_data.use_file_index,
+            parameters,
+        );
+        let inputs_hash = blake3::hash(inputs.as_bytes()).to_hex();
+        let file_stem = format!("checkpoint_{:?}_{inputs_hash}_{CHECKPOINT_VERSION}", common_data.tool_type).to_lowercase();
+        Self {
+            file_stem: Some(file_stem),
+            resumed: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn disabled() -> Self {
+        Self {
+            file_stem: None,
+            resumed: AtomicBool::new(false),
+        }
+    }
+
+    fn get_file(&self, partial: bool) -> Option<PathBuf> {
+        let file_stem = self.file_stem.as_ref()?;
+        let file_name = if partial { format!("{file_stem}_partial.bin") } else { format!("{file_stem}.bin") };
+        Some(get_config_cache_path()?.cache_folder.join(file_name))
+    }
+
+    // Returns state of last finished stage, only if resuming was requested and checkpoint was created with same inputs and parameters
+    pub(crate) fn resume<S: DeserializeOwned>(&self, common_data: &mut CommonToolData) -> Option<S> {
+        if !common_data.resume {
+            return None;
+        }
+        let Some(file) = self.get_file(false) else {
+            common_data.text_messages.warnings.push(flc!("core_resume_requires_cache"));
+            return None;
+        };
+        let stage = load_from_file(&file);
+        if stage.is_some() {
+            debug!("Loaded checkpoint \"{}\"", file.to_string_lossy());
+            common_data.text_messages.messages.push(flc!("core_resumed_from_checkpoint"));
+            self.resumed.store(true, Ordering::Relaxed);
+        } else {
+            common_data.text_messages.messages.push(flc!("core_no_checkpoint_to_resume"));
+        }
+        stage
+    }
+
+    // Results of stage that was interrupted, they must be checked if still match files
+    // Last result may be cut when computer was turned off, so it is removed from file, to allow appending next ones
+    pub(crate) fn load_partial_results<E: DeserializeOwned>(&self) -> Vec<E> {
+        if !self.resumed.load(Ordering::Relaxed) {
+            return Vec::new();
+        }
+        let Some(file) = self.get_file(true) else {
+            return Vec::new();
+        };
+        let Ok(file_handler) = File::open(&file) else {
+            return Vec::new();
+        };
+        let mut reader = BufReader::new(file_handler);
+        let mut results = Vec::new();
+        let mut valid_length = 0;
+        while reader.fill_buf().is_ok_and(|buffer| !buffer.is_empty()) {
+            match bincode::DefaultOptions::new().with_limit(MEMORY_LIMIT).deserialize_from(&mut reader) {
+                Ok(result) => {
+                    results.push(result);
+                    valid_length = reader.stream_position().unwrap_or(valid_length);
+                }
+                Err(e) => {
+                    warn!("Ignoring damaged end of partial results \"{}\" - {e}", file.to_string_lossy());
+                    if let Err(e) = OpenOptions::new().write(true).open(&file).and_then(|file_handler| file_handler.set_len(valid_length)) {
+                        warn!("Cannot truncate partial results \"{}\" - {e}", file.to_string_lossy());
+                    }
+                    break;
+                }
+            }
+        }
+        debug!("Loaded {} partial results from \"{}\"", results.len(), file.to_string_lossy());
+        results
+    }
+
+    // Files processed before scan was interrupted are used like cached ones, but only when they were not modified since then
+    pub(crate) fn move_partial_results_by_path<E: DeserializeOwned + ResultEntry>(
+        &self,
+        records_already_cached: &mut BTreeMap<String, E>,
+        non_cached_files_to_check: &mut BTreeMap<String, E>,
+    ) {
+        for processed in self.load_partial_results::<E>() {
+            let path = processed.get_path().to_string_lossy().to_string();
+            if let Some(file_entry) = non_cached_files_to_check.get(&path)
+                && file_entry.get_size() == processed.get_size()
+                && file_entry.get_modified_date() == processed.get_modified_date()
+            {
+                non_cached_files_to_check.remove(&path);
+                records_already_cached.insert(path, processed);
+            }
+        }
+    }
+
+    // Partial results of previous stage are no longer needed, when next stage is saved
+    pub(crate) fn save_stage<S: Serialize>(&self, stage: &S) {
+        let (Some(file), Some(partial_file)) = (self.get_file(false), self.get_file(true)) else {
+            return;
+        };
+        save_to_file(&file, stage);
+        let _ = fs::remove_file(partial_file);
+    }
+
+    // Results of resumed stage are appended to already saved ones, otherwise file is started from scratch
+    pub(crate) fn partial_results<E: Serialize>(&self) -> PartialResults<E> {
+        let writer = self.get_file(true).and_then(|file| {
+            let mut options = OpenOptions::new();
+            if self.resumed.load(Ordering::Relaxed) {
+                options.append(true).create(true);
+            } else {
+                options.write(true).create(true).truncate(true);
+            }
+            match options.open(&file) {
+                Ok(file_handler) => Some(PartialResultsWriter {
+                    writer: BufWriter::new(file_handler),
+                    last_flush: Instant::now(),
+                }),
+                Err(e) => {
+                    warn!("Cannot open partial results \"{}\" - {e}", file.to_string_lossy());
+                    None
+                }
+            }
+        });
+        PartialResults {
+            writer: Mutex::new(writer),
+            _phantom: PhantomData,
+        }
+    }
+
+    // Called when scan is finished, so checkpoint will not be used again
+    pub(crate) fn remove(&self) {
+        for file in [self.get_file(false), self.get_file(true)].into_iter().flatten() {
+            let _ = fs::remove_file(file);
+        }
+    }
+}
+
+struct PartialResultsWriter {
+    writer: BufWriter<File>,
+    last_flush: Instant,
+}
+
+// Appends results of currently processed stage from all threads to log file, which is flushed periodically
+pub(crate) struct PartialResults<E: Serialize> {
+    // None when checkpoints are disabled or writing failed
+    writer: Mutex<Option<PartialResultsWriter>>,
+    _phantom: PhantomData<fn(&E)>,
+}
+
+impl<E: Serialize> PartialResults<E> {
+    pub(crate) fn add(&self, result: &E) {
+        let mut writer = self.writer.lock().expect("Cannot fail");
+        let Some(partial_writer) = writer.as_mut() else {
+            return;
+        };
+        let options = bincode::DefaultOptions::new().with_limit(MEMORY_LIMIT);
+        let mut result = options.serialize_into(&mut partial_writer.writer, result).map_err(|e| e.to_string());
+        if result.is_ok() && partial_writer.last_flush.elapsed() >= PARTIAL_RESULTS_FLUSH_INTERVAL {
+            result = partial_writer.writer.flush().map_err(|e| e.to_string());
+            partial_writer.last_flush = Instant::now();
+        }
+        if let Err(e) = result {
+            warn!("Cannot save partial results, next ones will not be saved - {e}");
+            *writer = None;
+        }
+    }
+
+    // Used when stage was stopped, to not lose results computed since last periodic flush
+    pub(crate) fn flush(&self) {
+        let mut writer = self.writer.lock().expect("Cannot fail");
+        let Some(partial_writer) = writer.as_mut() else {
+            return;
+        };
+        if let Err(e) = partial_writer.writer.flush().and_then(|()| partial_writer.writer.get_ref().sync_data()) {
+            warn!("Cannot save partial results - {e}");
+        }
+    }
+}
+
+fn load_from_file<T: DeserializeOwned>(file: &PathBuf) -> Option<T> {
+    let file_handler = File::open(file).ok()?;
+    let options = bincode::DefaultOptions::new().with_limit(MEMORY_LIMIT);
+    match options.deserialize_from(BufReader::new(file_handler)) {
+        Ok(data) => Some(data),
+        Err(e) => {
+            warn!("Cannot load checkpoint \"{}\" - {e}", file.to_string_lossy());
+            None
+        }
+    }
+}
+
+// Data is written to temporary file first, so checkpoint is never left half-written, e.g. when computer is turned off
+fn save_to_file<T: Serialize + ?Sized>(file: &PathBuf, data: &T) {
+    let temp_file = file.with_extension(format!("{}_{}.tmp", process::id(), TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)));
+    let result = File::create(&temp_file).map_err(|e| e.to_string()).and_then(|file_handler| {
+        let mut writer = BufWriter::new(file_handler);
+        let options = bincode::DefaultOptions::new().with_limit(MEMORY_LIMIT);
+        options.serialize_into(&mut writer, data).map_err(|e| e.to_string())?;
+        writer.flush().map_err(|e| e.to_string())?;
+        writer.into_inner().map_err(|e| e.to_string())?.sync_all().map_err(|e| e.to_string())
+    });
+    let result = result.and_then(|()| fs::rename(&temp_file, file).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("Cannot save checkpoint \"{}\" - {e}", file.to_string_lossy());
+        let _ = fs::remove_file(&temp_file);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::model::ToolType;
+
+    #[test]
+    fn test_checkpoint_save_resume_remove() {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let temp_cache = tempfile::TempDir::new().expect("Failed to create temp cache dir");
+            let temp_config = tempfile::TempDir::new().expect("Failed to create temp config dir");
+            crate::common::config_cache_path::set_config_cache_path_test(temp_cache.path().to_path_buf(), temp_config.path().to_path_buf());
+            std::mem::forget(temp_cache);
+            std::mem::forget(temp_config);
+        });
+
+        let mut common_data = CommonToolData::new(ToolType::SimilarImages);
+        common_data.directories.included_directories = vec![PathBuf::from("/checkpoint_test")];
+
+        // Without resume, nothing is saved
+        let checkpoint = Checkpoint::new(&common_data, &1);
+        checkpoint.save_stage(&vec![1u32, 2]);
+        assert_eq!(checkpoint.get_file(false), None);
+
+        common_data.resume = true;
+        let checkpoint = Checkpoint::new(&common_data, &1);
+        assert_eq!(checkpoint.resume::<Vec<u32>>(&mut common_data), None);
+        assert!(checkpoint.load_partial_results::<String>().is_empty());
+
+        checkpoint.save_stage(&vec![1u32, 2]);
+        let partial_results = checkpoint.partial_results();
+        partial_results.add(&"file.jpg".to_string());
+        partial_results.flush();
+        drop(partial_results);
+
+        // Result cut e.g. by turning off computer is ignored and next results are appended after last valid one
+        let partial_file = checkpoint.get_file(true).expect("Checkpoint is enabled");
+        OpenOptions::new()
+            .append(true)
+            .open(&partial_file)
+            .and_then(|mut file_handler| file_handler.write_all(&[200]))
+            .expect("Failed to damage partial results");
+
+        let checkpoint = Checkpoint::new(&common_data, &1);
+        assert_eq!(checkpoint.resume::<Vec<u32>>(&mut common_data), Some(vec![1, 2]));
+        assert_eq!(checkpoint.load_partial_results::<String>(), vec!["file.jpg".to_string()]);
+        checkpoint.partial_results().add(&"second.jpg".to_string());
+
+        let checkpoint = Checkpoint::new(&common_data, &1);
+        assert_eq!(checkpoint.resume::<Vec<u32>>(&mut common_data), Some(vec![1, 2]));
+        assert_eq!(checkpoint.load_partial_results::<String>(), vec!["file.jpg".to_string(), "second.jpg".to_string()]);
+        // Different parameters must not use checkpoint of other scan
+        assert_eq!(Checkpoint::new(&common_data, &2).resume::<Vec<u32>>(&mut common_data), None);
+
+        checkpoint.remove();
+        assert_eq!(checkpoint.resume::<Vec<u32>>(&mut common_data), None);
+        assert!(!partial_file.exists());
+
+        common_data.use_cache = false;
+        assert!(Checkpoint::new(&common_data, &1).resume::<Vec<u32>>(&mut common_data).is_none());
+        assert!(common_data.text_messages.warnings.contains(&flc!("core_resume_requires_cache")));
+    }
+
+    #[test]
+    fn test_checkpoint_depends_on_traversal_settings() {
+        let mut common_data = CommonToolData::new(ToolType::Duplicate);
+        common_data.resume = true;
+        let original = Checkpoint::new(&common_data, &1).file_stem.expect("Checkpoint is enabled");
+
+        let changes: [fn(&mut CommonToolData); 3] = [
+            |cd| cd.use_ignore_files = true,
+            |cd| cd.directories.exclude_other_filesystems = Some(true),
+            |cd| cd.use_file_index = true,
+        ];
+        for change in changes {
+            let mut changed_data = common_data.clone();
+            change(&mut changed_data);
+            assert_ne!(Checkpoint::new(&changed_data, &1).file_stem.expect("Checkpoint is enabled"), original);
+        }
+    }
+}
@@ -20,6 +20,11 @@ pub fn get_config_cache_path() -> Option<ConfigCachePath> {
     CONFIG_CACHE_PATH.get().expect("Cannot fail if set_config_cache_path was called before").clone()
 }
 
+// Used by code which may run before paths are set(e.g. in tests) and works without them
+pub(crate) fn try_get_config_cache_path() -> Option<ConfigCachePath> {
+    CONFIG_CACHE_PATH.get().cloned().flatten()
+}
+
 /// On Android `ProjectDirs` always returns `None` because there is no concept of a home
 /// directory accessible via standard UNIX paths.  Instead we use the app-private data
 /// directory exposed by the Android runtime through the `DATA_DIR` or `HOME` env variable.
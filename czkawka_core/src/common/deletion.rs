@@ -7,12 +7,19 @@ use humansize::{BINARY, format_size};
 use log::info;
 use rayon::prelude::*;
 
-use crate::common::model::WorkContinueStatus;
+use crate::common::archive::is_archive_member_path;
+use crate::common::keep_rules::{KeepRule, KeepRuleEntry, choose_item_to_keep};
+use crate::common::model::{ToolType, WorkContinueStatus};
 use crate::common::progress_data::{ProgressData, ToolStage};
 use crate::common::progress_stop_handler::check_if_stop_received;
+use crate::common::quarantine::Quarantine;
 use crate::common::tool_data::{CommonToolData, DeleteMethod};
 use crate::common::traits::ResultEntry;
-use crate::common::{make_hard_link, remove_folder_if_contains_only_empty_folders, remove_single_file};
+use crate::common::undo_journal::{JournalOperation, JournalRecorder};
+use crate::common::{
+    check_if_folder_contains_only_empty_folders, make_hard_link, make_reflink, remove_folder_if_contains_only_empty_folders, remove_single_file, remove_single_folder,
+};
+use crate::flc;
 use crate::helpers::delayed_sender::DelayedSender;
 use crate::helpers::messages::Messages;
 
@@ -20,6 +27,7 @@ use crate::helpers::messages::Messages;
 pub struct DeleteResult {
     deleted_files: usize,
     hardlinked_files: usize,
+    reflinked_files: usize,
     gained_bytes: u64,
     failed_to_delete_files: usize,
     errors: Vec<String>,
@@ -37,22 +45,45 @@ impl DeleteResult {
 pub enum DeleteItemType<T: ResultEntry + Sized + Send + Sync> {
     DeletingFiles(Vec<T>),
     DeletingFolders(Vec<T>),
+    // Unlike `DeletingFolders`, removes folders together with all their content
+    DeletingFolderTrees(Vec<T>),
     HardlinkingFiles(Vec<(T, Vec<T>)>),
+    ReflinkingFiles(Vec<(T, Vec<T>)>),
 }
 
 impl<T: ResultEntry + Sized + Send + Sync> DeleteItemType<T> {
+    // Files inside archives are only reported, they can be neither removed nor used as source or destination of links
+    fn without_archive_members(self) -> Self {
+        let without_members = |items: Vec<T>| items.into_iter().filter(|item| !is_archive_member_path(item.get_path())).collect::<Vec<_>>();
+        let without_members_links = |items: Vec<(T, Vec<T>)>| {
+            items
+                .into_iter()
+                .filter(|(original, _files)| !is_archive_member_path(original.get_path()))
+                .map(|(original, files)| (original, without_members(files)))
+                .filter(|(_original, files)| !files.is_empty())
+                .collect::<Vec<_>>()
+        };
+        match self {
+            Self::DeletingFiles(items) => Self::DeletingFiles(without_members(items)),
+            Self::DeletingFolders(items) => Self::DeletingFolders(without_members(items)),
+            Self::DeletingFolderTrees(items) => Self::DeletingFolderTrees(without_members(items)),
+            Self::HardlinkingFiles(items) => Self::HardlinkingFiles(without_members_links(items)),
+            Self::ReflinkingFiles(items) => Self::ReflinkingFiles(without_members_links(items)),
+        }
+    }
+
     fn calculate_size_to_delete(&self) -> u64 {
         match &self {
-            Self::DeletingFiles(items) | Self::DeletingFolders(items) => items.iter().map(|item| item.get_size()).sum(),
+            Self::DeletingFiles(items) | Self::DeletingFolders(items) | Self::DeletingFolderTrees(items) => items.iter().map(|item| item.get_size()).sum(),
             // Sum the linked files, not the originals, to match what progress accounts.
-            Self::HardlinkingFiles(items) => items.iter().flat_map(|(_original, files)| files.iter().map(ResultEntry::get_size)).sum(),
+            Self::HardlinkingFiles(items) | Self::ReflinkingFiles(items) => items.iter().flat_map(|(_original, files)| files.iter().map(ResultEntry::get_size)).sum(),
         }
     }
 
     fn calculate_entries_to_delete(&self) -> usize {
         match &self {
-            Self::DeletingFiles(items) | Self::DeletingFolders(items) => items.len(),
-            Self::HardlinkingFiles(items) => items.iter().map(|(_original, files)| files.len()).sum(),
+            Self::DeletingFiles(items) | Self::DeletingFolders(items) | Self::DeletingFolderTrees(items) => items.len(),
+            Self::HardlinkingFiles(items) | Self::ReflinkingFiles(items) => items.iter().map(|(_original, files)| files.len()).sum(),
         }
     }
 }
@@ -73,23 +104,39 @@ pub(crate) fn delete_simple_elements_and_add_to_messages<T: ResultEntry + Sized
     }
 }
 
-pub(crate) fn delete_advanced_elements_and_add_to_messages<T: ResultEntry + Sized + Send + Sync + Clone>(
+pub(crate) fn delete_advanced_elements_and_add_to_messages<T: KeepRuleEntry + Sized + Send + Sync + Clone>(
     cd: &mut CommonToolData,
     stop_flag: &Arc<AtomicBool>,
     progress_sender: Option<&Sender<ProgressData>>,
     files_to_process: Vec<Vec<T>>,
 ) -> WorkContinueStatus {
     let delete_method = cd.delete_method;
-    let sorting_by_size = matches!(
-        delete_method,
-        DeleteMethod::AllExceptBiggest | DeleteMethod::AllExceptSmallest | DeleteMethod::OneBiggest | DeleteMethod::OneSmallest
-    );
-    let sort_items = |mut input: Vec<T>| -> Vec<T> {
-        input.sort_unstable_by_key(if sorting_by_size { ResultEntry::get_size } else { ResultEntry::get_modified_date });
-        input
-    };
+    if !cd.keep_rules.is_empty() && KeepRule::from_delete_method(delete_method).is_none() {
+        cd.text_messages.warnings.push(flc!("core_keep_rules_not_used"));
+    }
 
-    let delete_results = if delete_method == DeleteMethod::HardLink {
+    // Files inside archives are removed before choosing which file is kept, so they never replace the only file on disk
+    // Reference file is still kept, because it is never removed
+    let use_reference_folders = cd.use_reference_folders;
+    let files_to_process = files_to_process
+        .into_iter()
+        .map(|values| {
+            values
+                .into_iter()
+                .enumerate()
+                .filter(|(idx, item)| (use_reference_folders && *idx == 0) || !is_archive_member_path(item.get_path()))
+                .map(|(_idx, item)| item)
+                .collect::<Vec<_>>()
+        })
+        .filter(|values| values.len() > 1)
+        .collect::<Vec<_>>();
+
+    let delete_results = if matches!(delete_method, DeleteMethod::HardLink | DeleteMethod::Reflink) {
+        // Other tools group files that only look alike, so their content cannot be shared
+        if delete_method == DeleteMethod::Reflink && cd.tool_type != ToolType::Duplicate {
+            cd.text_messages.errors.push(flc!("core_reflink_requires_hash"));
+            return WorkContinueStatus::Continue;
+        }
         let res = files_to_process
             .into_iter()
             .map(|values| {
@@ -98,61 +145,33 @@ pub(crate) fn delete_advanced_elements_and_add_to_messages<T: ResultEntry + Size
                 if cd.use_reference_folders {
                     // The reference should be the first item.
                     original = all_values.remove(0);
-                    all_values = sort_items(all_values);
+                    all_values = sort_items_for_method(delete_method, all_values);
+                } else if let Some(idx) = item_to_keep(delete_method, &cd.keep_rules, &all_values) {
+                    original = all_values.remove(idx);
                 } else {
-                    all_values = sort_items(all_values);
+                    all_values = sort_items_for_method(delete_method, all_values);
                     original = all_values.remove(0);
                 }
                 (original, all_values)
             })
             .collect::<Vec<_>>();
-        delete_elements(cd, stop_flag, progress_sender, DeleteItemType::HardlinkingFiles(res))
+        let delete_item_type = if delete_method == DeleteMethod::Reflink {
+            DeleteItemType::ReflinkingFiles(res)
+        } else {
+            DeleteItemType::HardlinkingFiles(res)
+        };
+        delete_elements(cd, stop_flag, progress_sender, delete_item_type)
     } else {
         let res = files_to_process
             .into_iter()
-            .flat_map(|values| {
-                let mut all_values = values;
-                if cd.use_reference_folders {
-                    match all_values.len() {
-                        0 | 1 => unreachable!("Using reference folders you should not get less than 2 items"),
-                        2 => {
-                            // The reference should be the first item, and should not be deleted.
-                            all_values.remove(0);
-                            return all_values;
-                        }
-                        _ => {
-                            // The reference should be the first item, and should not be deleted.
-                            all_values.remove(0);
-                            all_values = sort_items(all_values);
-                        }
-                    }
-                } else {
-                    all_values = sort_items(all_values);
-                }
-                let len = all_values.len();
-                match delete_method {
-                    DeleteMethod::Delete => all_values,
-                    DeleteMethod::AllExceptNewest | DeleteMethod::AllExceptBiggest => {
-                        all_values.truncate(len - 1);
-                        all_values
-                    }
-                    DeleteMethod::AllExceptOldest | DeleteMethod::AllExceptSmallest => {
-                        all_values.remove(0);
-                        all_values
-                    }
-                    DeleteMethod::OneOldest | DeleteMethod::OneSmallest => {
-                        all_values.truncate(1);
-                        all_values
-                    }
-                    DeleteMethod::OneNewest | DeleteMethod::OneBiggest => {
-                        all_values.drain(..len - 1);
-                        all_values
-                    }
-                    DeleteMethod::HardLink | DeleteMethod::None => unreachable!("HardLink and None should be handled before"),
-                }
-            })
+            .flat_map(|values| select_items_to_remove(delete_method, &cd.keep_rules, cd.use_reference_folders, values))
             .collect::<Vec<_>>();
-        delete_elements(cd, stop_flag, progress_sender, DeleteItemType::DeletingFiles(res))
+        let delete_item_type = if cd.tool_type == ToolType::DuplicateFolders {
+            DeleteItemType::DeletingFolderTrees(res)
+        } else {
+            DeleteItemType::DeletingFiles(res)
+        };
+        delete_elements(cd, stop_flag, progress_sender, delete_item_type)
     };
 
     if check_if_stop_received(stop_flag) {
@@ -163,17 +182,106 @@ pub(crate) fn delete_advanced_elements_and_add_to_messages<T: ResultEntry + Size
     }
 }
 
-#[expect(clippy::needless_pass_by_value)]
+fn sort_items_for_method<T: ResultEntry>(delete_method: DeleteMethod, mut items: Vec<T>) -> Vec<T> {
+    let sorting_by_size = matches!(
+        delete_method,
+        DeleteMethod::AllExceptBiggest | DeleteMethod::AllExceptSmallest | DeleteMethod::OneBiggest | DeleteMethod::OneSmallest
+    );
+    items.sort_unstable_by_key(if sorting_by_size { ResultEntry::get_size } else { ResultEntry::get_modified_date });
+    items
+}
+
+// Keep rules replace criterion of methods keeping only one item of group, this criterion is then used as the last rule
+fn item_to_keep<T: KeepRuleEntry>(delete_method: DeleteMethod, keep_rules: &[KeepRule], items: &[T]) -> Option<usize> {
+    if keep_rules.is_empty() {
+        return None;
+    }
+    let method_rule = KeepRule::from_delete_method(delete_method)?;
+    let rules = keep_rules.iter().cloned().chain(std::iter::once(method_rule)).collect::<Vec<_>>();
+    choose_item_to_keep(&rules, items)
+}
+
+// Separates item which is kept with given method from other items of group, so they can be compared with it before any action
+pub(crate) fn split_item_to_keep<T: KeepRuleEntry>(delete_method: DeleteMethod, keep_rules: &[KeepRule], values: Vec<T>) -> (T, Vec<T>) {
+    let mut all_values = values;
+    let idx = match item_to_keep(delete_method, keep_rules, &all_values) {
+        Some(idx) => idx,
+        None => {
+            all_values = sort_items_for_method(delete_method, all_values);
+            match delete_method {
+                DeleteMethod::AllExceptNewest | DeleteMethod::AllExceptBiggest | DeleteMethod::OneOldest | DeleteMethod::OneSmallest => all_values.len() - 1,
+                _ => 0,
+            }
+        }
+    };
+    let kept = all_values.remove(idx);
+    (kept, all_values)
+}
+
+// Chooses items of group, which should be removed with given method, reference item(the first one) is never chosen
+pub(crate) fn select_items_to_remove<T: KeepRuleEntry>(delete_method: DeleteMethod, keep_rules: &[KeepRule], use_reference_folders: bool, values: Vec<T>) -> Vec<T> {
+    let mut all_values = values;
+    if use_reference_folders {
+        match all_values.len() {
+            0 | 1 => unreachable!("Using reference folders you should not get less than 2 items"),
+            2 => {
+                // The reference should be the first item, and should not be deleted.
+                all_values.remove(0);
+                return all_values;
+            }
+            _ => {
+                // The reference should be the first item, and should not be deleted.
+                all_values.remove(0);
+            }
+        }
+    }
+    if let Some(idx) = item_to_keep(delete_method, keep_rules, &all_values) {
+        all_values.remove(idx);
+        return all_values;
+    }
+    all_values = sort_items_for_method(delete_method, all_values);
+    let len = all_values.len();
+    match delete_method {
+        DeleteMethod::Delete => all_values,
+        DeleteMethod::AllExceptNewest | DeleteMethod::AllExceptBiggest => {
+            all_values.truncate(len - 1);
+            all_values
+        }
+        DeleteMethod::AllExceptOldest | DeleteMethod::AllExceptSmallest => {
+            all_values.remove(0);
+            all_values
+        }
+        DeleteMethod::OneOldest | DeleteMethod::OneSmallest => {
+            all_values.truncate(1);
+            all_values
+        }
+        DeleteMethod::OneNewest | DeleteMethod::OneBiggest => {
+            all_values.drain(..len - 1);
+            all_values
+        }
+        DeleteMethod::HardLink | DeleteMethod::Reflink | DeleteMethod::None => unreachable!("HardLink, Reflink and None should be handled before"),
+    }
+}
+
 pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
     cd: &CommonToolData,
     stop_flag: &Arc<AtomicBool>,
     progress_sender: Option<&Sender<ProgressData>>,
     delete_item_type: DeleteItemType<T>,
 ) -> DeleteResult {
+    let delete_item_type = delete_item_type.without_archive_members();
     let dry_run = cd.dry_run;
     let move_to_trash = cd.move_to_trash;
     let is_hardlinking = matches!(delete_item_type, DeleteItemType::HardlinkingFiles(_));
-    let stage = if is_hardlinking { ToolStage::HardlinkingFiles } else { ToolStage::DeletingFiles };
+    let is_reflinking = matches!(delete_item_type, DeleteItemType::ReflinkingFiles(_));
+    let is_linking = is_hardlinking || is_reflinking;
+    let stage = if is_hardlinking {
+        ToolStage::HardlinkingFiles
+    } else if is_reflinking {
+        ToolStage::ReflinkingFiles
+    } else {
+        ToolStage::DeletingFiles
+    };
     let progress = ProgressData::new(stage, delete_item_type.calculate_entries_to_delete(), delete_item_type.calculate_size_to_delete());
 
     let msg_common = format!(
@@ -183,17 +291,23 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
     );
     if is_hardlinking {
         info!("Hardlinking {msg_common}");
+    } else if is_reflinking {
+        info!("Reflinking {msg_common}");
     } else {
         info!("Deleting {msg_common}");
     }
 
     let delayed_sender = progress_sender.map(|e| DelayedSender::new(e.clone(), Duration::from_millis(200)));
 
+    let journal = JournalRecorder::new();
+    let deletion_operation = if move_to_trash { JournalOperation::Trash } else { JournalOperation::Delete };
+    let quarantine = cd.quarantine.clone().map(Quarantine::new);
+
     let bytes_processed = Arc::new(std::sync::atomic::AtomicU64::new(0));
     let files_processed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
 
     let res = match delete_item_type {
-        DeleteItemType::DeletingFiles(ref items) | DeleteItemType::DeletingFolders(ref items) => items
+        DeleteItemType::DeletingFiles(ref items) | DeleteItemType::DeletingFolders(ref items) | DeleteItemType::DeletingFolderTrees(ref items) => items
             .into_par_iter()
             .map(|e| {
                 if check_if_stop_received(stop_flag) {
@@ -214,10 +328,22 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
                     return Some(vec![(e, None, None)]);
                 }
 
-                let delete_res = if matches!(delete_item_type, DeleteItemType::DeletingFiles(_)) {
-                    remove_single_file(e.get_path(), move_to_trash)
+                let delete_res = if let Some(quarantine) = &quarantine {
+                    let check_res = if matches!(delete_item_type, DeleteItemType::DeletingFolders(_)) {
+                        check_if_folder_contains_only_empty_folders(e.get_path())
+                    } else {
+                        Ok(())
+                    };
+                    check_res
+                        .and_then(|()| quarantine.quarantine_item(e.get_path(), item_size))
+                        .map(|quarantine_path| journal.record(JournalOperation::Move, e.get_path(), Some(&quarantine_path), item_size))
                 } else {
-                    remove_folder_if_contains_only_empty_folders(e.get_path(), move_to_trash)
+                    let remove_res = match delete_item_type {
+                        DeleteItemType::DeletingFiles(_) => remove_single_file(e.get_path(), move_to_trash),
+                        DeleteItemType::DeletingFolderTrees(_) => remove_single_folder(e.get_path(), move_to_trash),
+                        _ => remove_folder_if_contains_only_empty_folders(e.get_path(), move_to_trash),
+                    };
+                    remove_res.map(|()| journal.record(deletion_operation, e.get_path(), None, item_size))
                 };
 
                 match delete_res {
@@ -228,7 +354,7 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
             .while_some()
             .flatten()
             .collect::<Vec<_>>(),
-        DeleteItemType::HardlinkingFiles(ref items) => items
+        DeleteItemType::HardlinkingFiles(ref items) | DeleteItemType::ReflinkingFiles(ref items) => items
             .into_par_iter()
             .map(|(original, files)| {
                 if check_if_stop_received(stop_flag) {
@@ -251,10 +377,20 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
                 let res = files
                     .iter()
                     .map(|file| {
-                        let err = match make_hard_link(original.get_path(), file.get_path()) {
-                            Ok(()) => None,
+                        let (link_res, operation) = if is_reflinking {
+                            (make_reflink(original.get_path(), file.get_path()), "reflink")
+                        } else {
+                            (make_hard_link(original.get_path(), file.get_path()), "hardlink")
+                        };
+                        let err = match link_res {
+                            // Reflinked files are still separate files, so there is nothing to revert
+                            Ok(()) if is_reflinking => None,
+                            Ok(()) => {
+                                journal.record(JournalOperation::Hardlink, file.get_path(), Some(original.get_path()), file.get_size());
+                                None
+                            }
                             Err(err) => Some(format!(
-                                "Failed to hardlink \"{}\" to \"{}\": {err}",
+                                "Failed to {operation} \"{}\" to \"{}\": {err}",
                                 file.get_path().to_string_lossy(),
                                 original.get_path().to_string_lossy(),
                             )),
@@ -278,10 +414,11 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
             delete_result.failed_to_delete_files += 1;
         } else {
             if dry_run {
-                if is_hardlinking {
+                if is_linking {
                     let original = maybe_original.expect("Should be defined");
                     delete_result.infos.push(format!(
-                        "Would hardlink: \"{}\" to \"{}\"",
+                        "Would {}: \"{}\" to \"{}\"",
+                        if is_reflinking { "reflink" } else { "hardlink" },
                         file_entry.get_path().to_string_lossy(),
                         original.to_string_lossy()
                     ));
@@ -291,6 +428,8 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
             }
             if is_hardlinking {
                 delete_result.hardlinked_files += 1;
+            } else if is_reflinking {
+                delete_result.reflinked_files += 1;
             } else {
                 delete_result.deleted_files += 1;
             }
@@ -306,6 +445,13 @@ pub(crate) fn delete_elements<T: ResultEntry + Sized + Send + Sync>(
                 format_size(delete_result.gained_bytes, BINARY),
                 delete_result.failed_to_delete_files
             );
+        } else if is_reflinking {
+            info!(
+                "{} items reflinked, {} gained, {} failed to reflink",
+                delete_result.reflinked_files,
+                format_size(delete_result.gained_bytes, BINARY),
+                delete_result.failed_to_delete_files
+            );
         } else {
             info!(
                 "{} items deleted, {} gained, {} failed to delete",
@@ -365,6 +511,7 @@ mod tests {
         let delete_result = DeleteResult {
             deleted_files: 5,
             hardlinked_files: 0,
+            reflinked_files: 0,
             gained_bytes: 1024,
             failed_to_delete_files: 2,
             errors: vec!["Error 1".to_string(), "Error 2".to_string()],
@@ -416,6 +563,34 @@ mod tests {
         assert_eq!(hardlink_files.calculate_entries_to_delete(), 3);
     }
 
+    #[test]
+    fn test_archive_members_are_not_deleted() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("file.txt");
+        fs::write(&file, "test content").unwrap();
+        let file_entry = FileEntry {
+            path: file.clone(),
+            size: 12,
+            modified_date: 1,
+        };
+        let member_entry = FileEntry {
+            path: temp_dir.path().join("backup.zip!/file.txt"),
+            size: 12,
+            modified_date: 2,
+        };
+
+        let mut tool = MockTool::new();
+        tool.common_data.delete_method = DeleteMethod::AllExceptOldest;
+        let result = delete_advanced_elements_and_add_to_messages(&mut tool.common_data, &Arc::default(), None, vec![vec![file_entry, member_entry.clone()]]);
+        assert_eq!(result, WorkContinueStatus::Continue);
+        assert!(tool.common_data.text_messages.errors.is_empty());
+        assert!(file.exists());
+
+        let delete_result = delete_elements(&tool.common_data, &Arc::default(), None, DeleteItemType::DeletingFiles(vec![member_entry]));
+        assert_eq!(delete_result.deleted_files, 0);
+        assert!(delete_result.errors.is_empty());
+    }
+
     #[test]
     fn test_delete_elements_dry_run() {
         let temp_dir = TempDir::new().unwrap();
@@ -654,6 +829,44 @@ mod tests {
         assert!(!file3.exists(), "Newest file should be deleted");
     }
 
+    #[test]
+    fn test_delete_advanced_elements_with_keep_rules() {
+        let temp_dir = TempDir::new().unwrap();
+        let originals = temp_dir.path().join("originals");
+        fs::create_dir_all(&originals).unwrap();
+        let file1 = temp_dir.path().join("file1.txt");
+        let file2 = originals.join("file2(1).txt");
+        let file3 = originals.join("file3.txt");
+        let file4 = originals.join("file4.txt");
+        for (idx, file) in [&file1, &file2, &file3, &file4].into_iter().enumerate() {
+            fs::write(file, idx.to_string()).unwrap();
+        }
+
+        let files_group = vec![
+            [(&file1, 1), (&file2, 2), (&file3, 3), (&file4, 4)]
+                .into_iter()
+                .map(|(path, modified_date)| FileEntry {
+                    path: path.clone(),
+                    size: 1,
+                    modified_date,
+                })
+                .collect::<Vec<_>>(),
+        ];
+
+        let mut tool = MockTool::new();
+        tool.common_data.delete_method = DeleteMethod::AllExceptOldest;
+        tool.set_keep_rules(vec![KeepRule::PathInside(originals), KeepRule::WithoutCopyMarkers]);
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let status = tool.delete_advanced_elements_and_add_to_messages(&stop_flag, None, files_group);
+
+        assert_eq!(status, WorkContinueStatus::Continue, "Should continue");
+        assert!(!file1.exists(), "File outside preferred folder should be deleted");
+        assert!(!file2.exists(), "Copy should be deleted");
+        assert!(file3.exists(), "Oldest file matching all rules should be kept");
+        assert!(!file4.exists(), "Newer file should be deleted");
+    }
+
     #[test]
     fn test_delete_advanced_elements_one_oldest() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,202 @@
+// Scheduling of file reads per device.
+// Reading many files in parallel from one spinning disk makes its head jump between them, which is a lot slower than reading
+// them one after another, so work is split into groups placed on same device and each group has its own limit of threads.
+use std::collections::HashMap;
+use std::fs;
+#[cfg(target_family = "unix")]
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use log::debug;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeviceIoLimits {
+    // 0 - no limit, all threads may read from same device
+    pub threads_per_device: usize,
+    // Used instead of threads_per_device for rotational devices(detected only on Linux), 0 - no separate limit
+    pub threads_per_rotational_device: usize,
+}
+
+impl DeviceIoLimits {
+    pub fn is_active(&self) -> bool {
+        self.threads_per_device > 0 || self.threads_per_rotational_device > 0
+    }
+}
+
+// Device id, None when it cannot be checked - such files are treated as placed on one common device
+type DeviceId = Option<u64>;
+
+pub(crate) struct DeviceIoScheduler {
+    limits: DeviceIoLimits,
+    devices_of_folders: Mutex<HashMap<PathBuf, DeviceId>>,
+}
+
+impl DeviceIoScheduler {
+    pub(crate) fn new(limits: DeviceIoLimits) -> Self {
+        Self {
+            limits,
+            devices_of_folders: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        self.limits.is_active()
+    }
+
+    // Files are almost always on same device as their folder, so device is checked only once per folder
+    fn get_device(&self, path: &Path) -> DeviceId {
+        let folder = path.parent().unwrap_or(path);
+        if let Some(device) = self.devices_of_folders.lock().expect("Cannot fail").get(folder) {
+            return *device;
+        }
+        let device = get_device_id(folder);
+        self.devices_of_folders.lock().expect("Cannot fail").insert(folder.to_path_buf(), device);
+        device
+    }
+
+    fn get_threads_for_device(&self, device: DeviceId) -> usize {
+        let threads = match device {
+            Some(device) if self.limits.threads_per_rotational_device > 0 && is_rotational_device(device) => self.limits.threads_per_rotational_device,
+            _ => self.limits.threads_per_device,
+        };
+        if threads == 0 { rayon::current_num_threads() } else { threads }
+    }
+
+    // Works like `into_par_iter().map(f).while_some().collect()`, but every device is processed by its own limited number of workers.
+    // Order of results is same as order of items
+    pub(crate) fn map_grouped_by_device<T, R, P, F>(&self, items: Vec<T>, get_path: P, f: F) -> Vec<R>
+    where
+        T: Send,
+        R: Send,
+        P: Fn(&T) -> &Path,
+        F: Fn(T) -> Option<R> + Sync,
+    {
+        let mut groups: HashMap<DeviceId, Vec<(usize, T)>> = HashMap::new();
+        for (idx, item) in items.into_iter().enumerate() {
+            let device = self.get_device(get_path(&item));
+            groups.entry(device).or_default().push((idx, item));
+        }
+
+        let stopped = AtomicBool::new(false);
+        let results: Mutex<Vec<(usize, R)>> = Mutex::new(Vec::new());
+        rayon::scope(|scope| {
+            for (device, group) in groups {
+                let workers = self.get_threads_for_device(device).min(group.len());
+                debug!("Processing {} items from device {device:?} with {workers} threads", group.len());
+                let queue = Arc::new(Mutex::new(group.into_iter()));
+                for _ in 0..workers {
+                    let queue = queue.clone();
+                    let (f, stopped, results) = (&f, &stopped, &results);
+                    scope.spawn(move |_| {
+                        while !stopped.load(Ordering::Relaxed) {
+                            let Some((idx, item)) = queue.lock().expect("Cannot fail").next() else {
+                                break;
+                            };
+                            match f(item) {
+                                Some(result) => results.lock().expect("Cannot fail").push((idx, result)),
+                                None => stopped.store(true, Ordering::Relaxed),
+                            }
+                        }
+                    });
+                }
+            }
+        });
+
+        let mut results = results.into_inner().expect("Cannot fail");
+        results.sort_unstable_by_key(|(idx, _)| *idx);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    // Splits items into groups placed on same device, so each of them may be later processed by map_grouped_by_device
+    pub(crate) fn split_by_device<T, P>(&self, items: Vec<T>, get_path: P) -> Vec<Vec<T>>
+    where
+        P: Fn(&T) -> &Path,
+    {
+        let mut groups: Vec<(DeviceId, Vec<T>)> = Vec::new();
+        for item in items {
+            let device = self.get_device(get_path(&item));
+            match groups.iter_mut().find(|(group_device, _)| *group_device == device) {
+                Some((_, group)) => group.push(item),
+                None => groups.push((device, vec![item])),
+            }
+        }
+        groups.into_iter().map(|(_, group)| group).collect()
+    }
+}
+
+// Archive members have virtual paths, so device of first existing parent is used
+#[cfg(target_family = "unix")]
+fn get_device_id(folder: &Path) -> DeviceId {
+    folder.ancestors().find_map(|item| fs::metadata(item).ok()).map(|metadata| metadata.dev())
+}
+
+#[cfg(not(target_family = "unix"))]
+fn get_device_id(_folder: &Path) -> DeviceId {
+    None
+}
+
+// Partitions don't have queue folder, so it is taken from parent block device
+#[cfg(target_os = "linux")]
+fn is_rotational_device(device: u64) -> bool {
+    let major = ((device >> 8) & 0xfff) | ((device >> 32) & !0xfff);
+    let minor = (device & 0xff) | ((device >> 12) & !0xff);
+    let device_folder = PathBuf::from(format!("/sys/dev/block/{major}:{minor}"));
+    [device_folder.join("queue/rotational"), device_folder.join("../queue/rotational")]
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .is_some_and(|content| content.trim() == "1")
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_rotational_device(_device: u64) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_map_grouped_by_device_limits_threads() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+        let paths: Vec<PathBuf> = (0..8).map(|idx| temp_dir.path().join(format!("{idx}.txt"))).collect();
+        let scheduler = DeviceIoScheduler::new(DeviceIoLimits {
+            threads_per_device: 1,
+            threads_per_rotational_device: 0,
+        });
+        assert_eq!(scheduler.split_by_device(paths.clone(), |path| path.as_path()).len(), 1);
+
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+        let results = scheduler.map_grouped_by_device(
+            paths.clone(),
+            |path| path.as_path(),
+            |path| {
+                let current = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(current, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(5));
+                running.fetch_sub(1, Ordering::SeqCst);
+                Some(path)
+            },
+        );
+        assert_eq!(results, paths);
+        assert_eq!(max_running.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_map_grouped_by_device_stops() {
+        let scheduler = DeviceIoScheduler::new(DeviceIoLimits {
+            threads_per_device: 1,
+            threads_per_rotational_device: 0,
+        });
+        let items: Vec<PathBuf> = (0..8).map(|idx| PathBuf::from(format!("/{idx}.txt"))).collect();
+        let results = scheduler.map_grouped_by_device(items, |path| path.as_path(), |path| (path != Path::new("/3.txt")).then_some(path));
+        assert_eq!(results.len(), 3);
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::fs::{DirEntry, FileType, Metadata};
 #[cfg(target_family = "unix")]
@@ -6,7 +6,7 @@ use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
-use std::time::UNIX_EPOCH;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crossbeam_channel::Sender;
 use fun_time::fun_time;
@@ -15,8 +15,11 @@ use rayon::prelude::*;
 
 use crate::common::directories::Directories;
 use crate::common::extensions::Extensions;
+use crate::common::file_index::{FileIndex, IndexedDirectory, IndexedFile, get_directory_modification};
+use crate::common::ignore_files::IgnoreRules;
 use crate::common::items::ExcludedItems;
 use crate::common::model::{CheckingMethod, FileEntry, ToolType};
+use crate::common::ownership::{OwnershipFilter, get_file_ownership};
 use crate::common::progress_data::{ProgressData, ToolStage};
 use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
 use crate::common::tool_data::CommonToolData;
@@ -44,6 +47,9 @@ pub struct DirTraversalBuilder<'b, F> {
     progress_sender: Option<&'b Sender<ProgressData>>,
     minimal_file_size: Option<u64>,
     maximal_file_size: Option<u64>,
+    minimal_modified_date: u64,
+    maximal_modified_date: u64,
+    ownership_filter: OwnershipFilter,
     collect: Collect,
     recursive_search: bool,
     directories: Option<Directories>,
@@ -51,6 +57,9 @@ pub struct DirTraversalBuilder<'b, F> {
     extensions: Option<Extensions>,
     tool_type: ToolType,
     checking_method: CheckingMethod,
+    use_file_index: bool,
+    use_ignore_files: bool,
+    follow_symlinks: bool,
 }
 
 #[derive(Debug)]
@@ -66,9 +75,15 @@ pub struct DirTraversal<'b, F> {
     extensions: Extensions,
     minimal_file_size: u64,
     maximal_file_size: u64,
+    minimal_modified_date: u64,
+    maximal_modified_date: u64,
+    ownership_filter: OwnershipFilter,
     tool_type: ToolType,
     checking_method: CheckingMethod,
     collect: Collect,
+    use_file_index: bool,
+    use_ignore_files: bool,
+    follow_symlinks: bool,
 }
 
 impl Default for DirTraversalBuilder<'_, ()> {
@@ -87,6 +102,9 @@ impl DirTraversalBuilder<'_, ()> {
             progress_sender: None,
             minimal_file_size: None,
             maximal_file_size: None,
+            minimal_modified_date: 0,
+            maximal_modified_date: u64::MAX,
+            ownership_filter: OwnershipFilter::default(),
             collect: Collect::Files,
             recursive_search: false,
             directories: None,
@@ -94,6 +112,9 @@ impl DirTraversalBuilder<'_, ()> {
             excluded_items: None,
             tool_type: ToolType::None,
             checking_method: CheckingMethod::None,
+            use_file_index: false,
+            use_ignore_files: false,
+            follow_symlinks: false,
         }
     }
 }
@@ -102,13 +123,20 @@ impl<'b, F> DirTraversalBuilder<'b, F> {
     pub(crate) fn common_data(mut self, common_tool_data: &CommonToolData) -> Self {
         self.root_dirs = common_tool_data.directories.included_directories.clone();
         self.root_files = common_tool_data.directories.included_files.clone();
+        self.root_files.extend_from_slice(&common_tool_data.directories.listed_files);
         self.extensions = Some(common_tool_data.extensions.clone());
         self.excluded_items = Some(common_tool_data.excluded_items.clone());
         self.recursive_search = common_tool_data.recursive_search;
         self.minimal_file_size = Some(common_tool_data.minimal_file_size);
         self.maximal_file_size = Some(common_tool_data.maximal_file_size);
+        self.minimal_modified_date = common_tool_data.minimal_modified_date;
+        self.maximal_modified_date = common_tool_data.maximal_modified_date;
+        self.ownership_filter = common_tool_data.ownership_filter;
         self.tool_type = common_tool_data.tool_type;
         self.directories = Some(common_tool_data.directories.clone());
+        self.use_file_index = common_tool_data.use_file_index;
+        self.use_ignore_files = common_tool_data.use_ignore_files;
+        self.follow_symlinks = common_tool_data.follow_symlinks;
         self
     }
 
@@ -160,9 +188,15 @@ impl<'b, F> DirTraversalBuilder<'b, F> {
             recursive_search: self.recursive_search,
             maximal_file_size: self.maximal_file_size,
             minimal_file_size: self.minimal_file_size,
+            minimal_modified_date: self.minimal_modified_date,
+            maximal_modified_date: self.maximal_modified_date,
+            ownership_filter: self.ownership_filter,
             collect: self.collect,
             tool_type: self.tool_type,
             checking_method: self.checking_method,
+            use_file_index: self.use_file_index,
+            use_ignore_files: self.use_ignore_files,
+            follow_symlinks: self.follow_symlinks,
         }
     }
 
@@ -175,6 +209,9 @@ impl<'b, F> DirTraversalBuilder<'b, F> {
             progress_sender: self.progress_sender,
             minimal_file_size: self.minimal_file_size.unwrap_or(0),
             maximal_file_size: self.maximal_file_size.unwrap_or(u64::MAX),
+            minimal_modified_date: self.minimal_modified_date,
+            maximal_modified_date: self.maximal_modified_date,
+            ownership_filter: self.ownership_filter,
             collect: self.collect,
             directories: self.directories.expect("could not build"),
             excluded_items: self.excluded_items.expect("could not build"),
@@ -182,6 +219,9 @@ impl<'b, F> DirTraversalBuilder<'b, F> {
             recursive_search: self.recursive_search,
             tool_type: self.tool_type,
             checking_method: self.checking_method,
+            use_file_index: self.use_file_index,
+            use_ignore_files: self.use_ignore_files,
+            follow_symlinks: self.follow_symlinks,
         }
     }
 }
@@ -211,15 +251,26 @@ where
     F: Fn(&FileEntry) -> T,
     T: Ord + PartialOrd,
 {
-    #[fun_time(message = "run(collecting files/dirs)", level = "debug")]
     pub(crate) fn run(self) -> DirTraversalResult<T> {
+        let mut grouped_file_entries: BTreeMap<T, Vec<FileEntry>> = BTreeMap::new();
+        let Some(warnings) = self.run_with(|key, fe| grouped_file_entries.entry(key).or_default().push(fe)) else {
+            return DirTraversalResult::Stopped;
+        };
+
+        debug!("Collected {} files", grouped_file_entries.values().map(Vec::len).sum::<usize>());
+        DirTraversalResult::SuccessFiles { grouped_file_entries, warnings }
+    }
+
+    // Passes found entries with their group keys to add_entry, instead of keeping them in memory, returns warnings or None when stopped
+    #[fun_time(message = "run_with(collecting files/dirs)", level = "debug")]
+    pub(crate) fn run_with(self, mut add_entry: impl FnMut(T, FileEntry)) -> Option<Vec<String>> {
         assert_ne!(self.tool_type, ToolType::None, "Tool type cannot be None");
 
         let mut all_warnings = Vec::new();
-        let mut grouped_file_entries: BTreeMap<T, Vec<FileEntry>> = BTreeMap::new();
 
         // Add root folders and files for finding
-        let mut folders_to_check: Vec<PathBuf> = self.root_dirs.clone();
+        // Each folder carries ignore rules of its parent folders
+        let mut folders_to_check: Vec<(PathBuf, Option<Arc<IgnoreRules>>)> = self.root_dirs.iter().map(|root_dir| (root_dir.clone(), None)).collect();
         let mut files_to_check: Vec<PathBuf> = self.root_files.clone();
 
         let collect_stage = if self.tool_type == ToolType::EmptyFolders {
@@ -237,10 +288,29 @@ where
             recursive_search,
             minimal_file_size,
             maximal_file_size,
+            minimal_modified_date,
+            maximal_modified_date,
+            ownership_filter,
             stop_flag,
+            use_file_index,
+            use_ignore_files,
+            follow_symlinks,
             ..
         } = self;
 
+        // Only links to folders are followed - removing link to file would not remove file itself.
+        // Every folder is visited only once, which protects against loops and files reachable through several links
+        let follow_symlinks = follow_symlinks && collect == Collect::Files;
+        let mut visited_folders: HashSet<FolderId> = HashSet::new();
+        if follow_symlinks {
+            folders_to_check = filter_visited_folders(folders_to_check, &mut visited_folders, &mut all_warnings);
+        }
+
+        // Invalid symlinks are not stored in index, so it is used only when collecting files
+        let file_index = (use_file_index && collect == Collect::Files).then(|| FileIndex::load(&self.root_dirs, &mut all_warnings));
+        let mut new_file_index = FileIndex::default();
+        let scan_start = SystemTime::now();
+
         let mut file_results = Vec::new();
         // File traversal
         while let Some(current_file) = files_to_check.pop() {
@@ -251,6 +321,9 @@ where
             match (entry_type(file_type), collect) {
                 (EntryType::File, Collect::Files) => {
                     progress_handler.increase_items(1);
+                    if !current_file.parent().is_none_or(|parent| ownership_filter.allows_files_in_folder(parent)) {
+                        continue;
+                    }
                     process_file_in_file_mode_path_check(
                         &current_file,
                         &metadata,
@@ -261,6 +334,9 @@ where
                         &directories,
                         minimal_file_size,
                         maximal_file_size,
+                        minimal_modified_date,
+                        maximal_modified_date,
+                        ownership_filter,
                     );
                 }
                 (EntryType::File, Collect::InvalidSymlinks) => {
@@ -268,6 +344,9 @@ where
                 }
                 (EntryType::Symlink, Collect::InvalidSymlinks) => {
                     progress_handler.increase_items(1);
+                    if !current_file.parent().is_none_or(|parent| ownership_filter.allows_files_in_folder(parent)) {
+                        continue;
+                    }
                     process_symlink_in_symlink_mode_path_check(&current_file, &metadata, &mut all_warnings, &mut file_results, &extensions, &excluded_items);
                 }
                 (EntryType::Symlink | EntryType::Dir | EntryType::Other, _) => {
@@ -277,27 +356,83 @@ where
         }
         file_results.sort_by_cached_key(|fe| fe.path.to_string_lossy().to_string());
         for fe in file_results {
-            let key = (self.group_by)(&fe);
-            grouped_file_entries.entry(key).or_default().push(fe);
+            add_entry((self.group_by)(&fe), fe);
         }
 
         // Folder traversal
         while !folders_to_check.is_empty() {
             if check_if_stop_received(&stop_flag) {
                 progress_handler.join_thread();
-                return DirTraversalResult::Stopped;
+                return None;
             }
 
             let segments: Vec<_> = folders_to_check
                 .into_par_iter()
                 .with_max_len(2) // Avoiding checking too many folders in batch
-                .map(|current_folder| {
+                .map(|(current_folder, parent_ignore_rules)| {
                     let mut dir_result = Vec::new();
                     let mut warnings = Vec::new();
                     let mut fe_result = Vec::new();
 
+                    let ignore_rules = if use_ignore_files {
+                        IgnoreRules::for_folder(parent_ignore_rules.as_ref(), &current_folder, &mut warnings)
+                    } else {
+                        None
+                    };
+                    // Folders are still visited, because their subfolders may be writable
+                    let files_allowed = ownership_filter.allows_files_in_folder(&current_folder);
+
+                    if let Some(file_index) = &file_index {
+                        let Some((indexed_directory, store_in_index)) = read_dir_with_file_index(&current_folder, file_index, &mut warnings) else {
+                            return Some((Vec::new(), warnings, fe_result, None));
+                        };
+                        if check_if_stop_received(&stop_flag) {
+                            return None;
+                        }
+                        for folder_name in &indexed_directory.folders {
+                            process_dir_in_file_symlink_mode(
+                                recursive_search,
+                                current_folder.join(folder_name),
+                                &directories,
+                                &mut dir_result,
+                                &mut warnings,
+                                &excluded_items,
+                            );
+                        }
+                        if follow_symlinks {
+                            for symlink_name in &indexed_directory.symlinks {
+                                process_symlink_in_follow_mode(
+                                    recursive_search,
+                                    current_folder.join(symlink_name),
+                                    &directories,
+                                    &mut dir_result,
+                                    &mut warnings,
+                                    &excluded_items,
+                                );
+                            }
+                        }
+                        for indexed_file in indexed_directory.files.iter().filter(|_| files_allowed) {
+                            process_file_in_index_mode(
+                                &current_folder,
+                                indexed_file,
+                                &mut fe_result,
+                                &extensions,
+                                &directories,
+                                &excluded_items,
+                                minimal_file_size,
+                                maximal_file_size,
+                                minimal_modified_date,
+                                maximal_modified_date,
+                                ownership_filter,
+                            );
+                        }
+                        progress_handler.increase_items(indexed_directory.files.len());
+                        let dir_result = filter_ignored_items(dir_result, &mut fe_result, ignore_rules.as_ref());
+                        return Some((dir_result, warnings, fe_result, store_in_index.then_some((current_folder, indexed_directory))));
+                    }
+
                     let Some(read_dir) = common_read_dir(&current_folder, &mut warnings) else {
-                        return Some((dir_result, warnings, fe_result));
+                        return Some((Vec::new(), warnings, fe_result, None));
                     };
 
                     let mut counter = 0;
@@ -314,10 +449,13 @@ where
 
                         match (entry_type(file_type), collect) {
                             (EntryType::Dir, Collect::Files | Collect::InvalidSymlinks) => {
-                                process_dir_in_file_symlink_mode(recursive_search, entry_data, &directories, &mut dir_result, &mut warnings, &excluded_items);
+                                process_dir_in_file_symlink_mode(recursive_search, entry_data.path(), &directories, &mut dir_result, &mut warnings, &excluded_items);
                             }
                             (EntryType::File, Collect::Files) => {
                                 counter += 1;
+                                if !files_allowed {
+                                    continue;
+                                }
                                 process_file_in_file_mode(
                                     entry_data,
                                     &mut warnings,
@@ -327,6 +465,9 @@ where
                                     &excluded_items,
                                     minimal_file_size,
                                     maximal_file_size,
+                                    minimal_modified_date,
+                                    maximal_modified_date,
+                                    ownership_filter,
                                 );
                             }
                             (EntryType::File, Collect::InvalidSymlinks) => {
@@ -334,9 +475,17 @@ where
                             }
                             (EntryType::Symlink, Collect::InvalidSymlinks) => {
                                 counter += 1;
+                                if !files_allowed {
+                                    continue;
+                                }
                                 process_symlink_in_symlink_mode(entry_data, &mut warnings, &mut fe_result, &extensions, &directories, &excluded_items);
                             }
-                            (EntryType::Symlink, Collect::Files) | (EntryType::Other, _) => {
+                            (EntryType::Symlink, Collect::Files) => {
+                                if follow_symlinks {
+                                    process_symlink_in_follow_mode(recursive_search, entry_data.path(), &directories, &mut dir_result, &mut warnings, &excluded_items);
+                                }
+                            }
+                            (EntryType::Other, _) => {
                                 // nothing to do
                             }
                         }
@@ -345,37 +494,54 @@ where
                         // Increase counter in batch, because usually it may be slow to add multiple times atomic value
                         progress_handler.increase_items(counter);
                     }
-                    Some((dir_result, warnings, fe_result))
+                    let dir_result = filter_ignored_items(dir_result, &mut fe_result, ignore_rules.as_ref());
+                    Some((dir_result, warnings, fe_result, None))
                 })
                 .while_some()
                 .collect();
 
-            let required_size = segments.iter().map(|(segment, _, _)| segment.len()).sum::<usize>();
+            let required_size = segments.iter().map(|(segment, _, _, _)| segment.len()).sum::<usize>();
             folders_to_check = Vec::with_capacity(required_size);
 
             // Process collected data
-            for (segment, warnings, mut fe_result) in segments {
+            for (segment, warnings, mut fe_result, indexed_directory) in segments {
                 folders_to_check.extend(segment);
                 all_warnings.extend(warnings);
+                if let Some((path, indexed_directory)) = indexed_directory {
+                    new_file_index.insert(path, indexed_directory, scan_start);
+                }
                 fe_result.sort_by_cached_key(|fe| fe.path.to_string_lossy().to_string());
                 for fe in fe_result {
-                    let key = (self.group_by)(&fe);
-                    grouped_file_entries.entry(key).or_default().push(fe);
+                    add_entry((self.group_by)(&fe), fe);
                 }
             }
+            if follow_symlinks {
+                folders_to_check = filter_visited_folders(folders_to_check, &mut visited_folders, &mut all_warnings);
+            }
         }
 
         progress_handler.join_thread();
 
-        debug!("Collected {} files", grouped_file_entries.values().map(Vec::len).sum::<usize>());
-
-        match collect {
-            Collect::Files | Collect::InvalidSymlinks => DirTraversalResult::SuccessFiles {
-                grouped_file_entries,
-                warnings: all_warnings,
-            },
+        // Directories not visited in this scan(e.g. removed or excluded) are dropped from index
+        if file_index.is_some() {
+            debug!("Saving {} directories to file index", new_file_index.len());
+            new_file_index.save(&self.root_dirs, &mut all_warnings);
         }
+
+        Some(all_warnings)
+    }
+}
+
+// Ignored folders are not visited at all, so files inside them cannot be re-included, like in git
+fn filter_ignored_items(dir_result: Vec<PathBuf>, fe_result: &mut Vec<FileEntry>, ignore_rules: Option<&Arc<IgnoreRules>>) -> Vec<(PathBuf, Option<Arc<IgnoreRules>>)> {
+    if let Some(ignore_rules) = ignore_rules {
+        fe_result.retain(|fe| !ignore_rules.is_ignored(&fe.path, false));
     }
+    dir_result
+        .into_iter()
+        .filter(|dir| ignore_rules.is_none_or(|ignore_rules| !ignore_rules.is_ignored(dir, true)))
+        .map(|dir| (dir, ignore_rules.cloned()))
+        .collect()
 }
 
 fn process_file_in_file_mode(
@@ -387,6 +553,9 @@ fn process_file_in_file_mode(
     excluded_items: &ExcludedItems,
     minimal_file_size: u64,
     maximal_file_size: u64,
+    minimal_modified_date: u64,
+    maximal_modified_date: u64,
+    ownership_filter: OwnershipFilter,
 ) {
     if !extensions.check_if_entry_have_valid_extension(&entry_data.file_name()) {
         return;
@@ -417,11 +586,20 @@ fn process_file_in_file_mode(
         return;
     };
 
-    if (minimal_file_size..=maximal_file_size).contains(&metadata.len()) {
+    if !(minimal_file_size..=maximal_file_size).contains(&metadata.len()) {
+        return;
+    }
+
+    if !ownership_filter.matches_metadata(&metadata) {
+        return;
+    }
+
+    let modified_date = get_modified_time(&metadata, warnings, &current_file_name, false);
+    if (minimal_modified_date..=maximal_modified_date).contains(&modified_date) {
         // Creating new file entry
         let fe: FileEntry = FileEntry {
             size: metadata.len(),
-            modified_date: get_modified_time(&metadata, warnings, &current_file_name, false),
+            modified_date,
             path: current_file_name,
         };
 
@@ -440,6 +618,9 @@ fn process_file_in_file_mode_path_check(
     directories: &Directories,
     minimal_file_size: u64,
     maximal_file_size: u64,
+    minimal_modified_date: u64,
+    maximal_modified_date: u64,
+    ownership_filter: OwnershipFilter,
 ) {
     let Some(file_name) = path.file_name() else {
         return;
@@ -459,11 +640,20 @@ fn process_file_in_file_mode_path_check(
         return;
     }
 
-    if (minimal_file_size..=maximal_file_size).contains(&metadata.len()) {
+    if !(minimal_file_size..=maximal_file_size).contains(&metadata.len()) {
+        return;
+    }
+
+    if !ownership_filter.matches_metadata(metadata) {
+        return;
+    }
+
+    let modified_date = get_modified_time(metadata, warnings, path, false);
+    if (minimal_modified_date..=maximal_modified_date).contains(&modified_date) {
         // Creating new file entry
         let fe: FileEntry = FileEntry {
             size: metadata.len(),
-            modified_date: get_modified_time(metadata, warnings, path, false),
+            modified_date,
             path: path.to_path_buf(),
         };
 
@@ -474,7 +664,7 @@ fn process_file_in_file_mode_path_check(
 #[cfg_attr(target_family = "windows", expect(clippy::needless_pass_by_ref_mut))]
 fn process_dir_in_file_symlink_mode(
     recursive_search: bool,
-    entry_data: &DirEntry,
+    dir_path: PathBuf,
     directories: &Directories,
     dir_result: &mut Vec<PathBuf>,
     warnings: &mut Vec<String>,
@@ -484,7 +674,6 @@ fn process_dir_in_file_symlink_mode(
         return;
     }
 
-    let dir_path = entry_data.path();
     if directories.is_excluded_dir(&dir_path) {
         return;
     }
@@ -508,6 +697,151 @@ fn process_dir_in_file_symlink_mode(
     dir_result.push(dir_path);
 }
 
+// Files inside linked folder are reported with paths containing link, so removing them removes real files
+fn process_symlink_in_follow_mode(
+    recursive_search: bool,
+    symlink_path: PathBuf,
+    directories: &Directories,
+    dir_result: &mut Vec<PathBuf>,
+    warnings: &mut Vec<String>,
+    excluded_items: &ExcludedItems,
+) {
+    // Broken links and links to files are silently skipped
+    if fs::metadata(&symlink_path).is_ok_and(|metadata| metadata.is_dir()) {
+        process_dir_in_file_symlink_mode(recursive_search, symlink_path, directories, dir_result, warnings, excluded_items);
+    }
+}
+
+#[cfg(target_family = "unix")]
+type FolderId = (u64, u64);
+#[cfg(not(target_family = "unix"))]
+type FolderId = PathBuf;
+
+#[cfg(target_family = "unix")]
+fn get_folder_id(folder: &Path) -> Option<FolderId> {
+    fs::metadata(folder).ok().map(|metadata| (metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(target_family = "unix"))]
+fn get_folder_id(folder: &Path) -> Option<FolderId> {
+    fs::canonicalize(folder).ok()
+}
+
+// Removes folders which were already visited, folders which cannot be identified are still visited, to report errors when reading them
+// Folders are checked in path order, so when several links lead to same folder, the one with smallest path is always visited
+// Skipped paths are links(or paths containing them) leading back to visited folder, so they are reported
+fn filter_visited_folders<T>(mut folders: Vec<(PathBuf, T)>, visited_folders: &mut HashSet<FolderId>, warnings: &mut Vec<String>) -> Vec<(PathBuf, T)> {
+    folders.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+    folders.retain(|(folder, _)| {
+        let Some(folder_id) = get_folder_id(folder) else {
+            return true;
+        };
+        let not_visited = visited_folders.insert(folder_id);
+        if !not_visited {
+            warnings.push(flc!("core_folder_already_visited", path = folder.to_string_lossy().to_string()));
+        }
+        not_visited
+    });
+    folders
+}
+
+// Same as process_file_in_file_mode, but file name comes from file index.
+// Files may be changed in place without modifying folder, so their metadata is always read from disk.
+// Files cannot be mount points, so checking other filesystems is needed only for folders
+// Metadata of file is taken from index, so file is not read from disk at all
+fn process_file_in_index_mode(
+    current_folder: &Path,
+    indexed_file: &IndexedFile,
+    fe_result: &mut Vec<FileEntry>,
+    extensions: &Extensions,
+    directories: &Directories,
+    excluded_items: &ExcludedItems,
+    minimal_file_size: u64,
+    maximal_file_size: u64,
+    minimal_modified_date: u64,
+    maximal_modified_date: u64,
+    ownership_filter: OwnershipFilter,
+) {
+    if !extensions.check_if_entry_have_valid_extension(&indexed_file.name) {
+        return;
+    }
+
+    let current_file_name = current_folder.join(&indexed_file.name);
+    if excluded_items.is_excluded(&current_file_name) {
+        return;
+    }
+
+    if directories.is_excluded_file(&current_file_name) {
+        return;
+    }
+
+    if !(minimal_file_size..=maximal_file_size).contains(&indexed_file.size) {
+        return;
+    }
+
+    if !ownership_filter.matches(indexed_file.uid, indexed_file.gid, indexed_file.mode) {
+        return;
+    }
+
+    if (minimal_modified_date..=maximal_modified_date).contains(&indexed_file.modified_date) {
+        fe_result.push(FileEntry {
+            size: indexed_file.size,
+            modified_date: indexed_file.modified_date,
+            path: current_file_name,
+        });
+    }
+}
+
+// Returns content of folder from file index, if folder was not modified since previous scan, otherwise reads it from disk.
+// Second value tells if content can be stored in index - folders read with errors, are read again in next scan.
+fn read_dir_with_file_index(current_folder: &Path, file_index: &FileIndex, warnings: &mut Vec<String>) -> Option<(Arc<IndexedDirectory>, bool)> {
+    let modified = get_directory_modification(current_folder);
+    if let Some(modified) = modified
+        && let Some(indexed_directory) = file_index.get_unchanged(current_folder, modified)
+    {
+        return Some((indexed_directory, true));
+    }
+
+    let read_dir = common_read_dir(current_folder, warnings)?;
+    let mut indexed_directory = IndexedDirectory {
+        modified: modified.unwrap_or_default(),
+        ..Default::default()
+    };
+    let mut complete = modified.is_some();
+    for entry in &read_dir {
+        let Some(entry_data) = common_get_entry_data(entry, warnings, current_folder) else {
+            complete = false;
+            continue;
+        };
+        let Ok(file_type) = entry_data.file_type() else {
+            complete = false;
+            continue;
+        };
+        match entry_type(file_type) {
+            EntryType::Dir => indexed_directory.folders.push(entry_data.file_name()),
+            EntryType::File => {
+                let path = entry_data.path();
+                let Some(metadata) = common_get_metadata_dir(entry_data, warnings, &path) else {
+                    complete = false;
+                    continue;
+                };
+                let (uid, gid, mode) = get_file_ownership(&metadata);
+                indexed_directory.files.push(IndexedFile {
+                    name: entry_data.file_name(),
+                    size: metadata.len(),
+                    modified_date: get_modified_time(&metadata, warnings, &path, false),
+                    uid,
+                    gid,
+                    mode,
+                });
+            }
+            EntryType::Symlink => indexed_directory.symlinks.push(entry_data.file_name()),
+            EntryType::Other => {}
+        }
+    }
+    Some((Arc::new(indexed_directory), complete))
+}
+
 fn process_symlink_in_symlink_mode(
     entry_data: &DirEntry,
     warnings: &mut Vec<String>,
@@ -796,6 +1130,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_traversal_of_listed_files_does_not_walk_directories() -> io::Result<()> {
+        let dir = tempfile::Builder::new().tempdir()?;
+        let dir_path = normalize_path(dir.path());
+        create_files(&dir_path)?;
+        let sub_dir = dir_path.join("sub");
+        fs::create_dir(&sub_dir)?;
+        let listed_file = sub_dir.join("listed");
+        fs::write(&listed_file, CONTENT)?;
+
+        let mut common_data = CommonToolData::new(ToolType::SimilarImages);
+        common_data.set_listed_files(vec![dir_path, sub_dir, listed_file.clone()]);
+        common_data.set_minimal_file_size(0);
+        common_data
+            .directories
+            .optimize_directories(true, false)
+            .expect("Listed files alone are enough to start scan");
+
+        let DirTraversalResult::SuccessFiles { grouped_file_entries, .. } = DirTraversalBuilder::new()
+            .group_by(|_fe| ())
+            .stop_flag(&Arc::default())
+            .common_data(&common_data)
+            .build()
+            .run()
+        else {
+            panic!("Expect SuccessFiles.");
+        };
+        let found: Vec<_> = grouped_file_entries.into_values().flatten().map(|fe| fe.path).collect();
+        assert_eq!(found, vec![listed_file]);
+        Ok(())
+    }
+
     fn create_temp_structure(dir: &Path) -> io::Result<(PathBuf, PathBuf, PathBuf)> {
         let global_file = dir.join("global_file.txt");
         let other_dir = dir.join("other_file");
@@ -1041,4 +1407,197 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_traversal_modified_date_filter() -> io::Result<()> {
+        let dir = tempfile::Builder::new().tempdir()?;
+        let dir_path = normalize_path(dir.path());
+        create_temp_structure(&dir_path)?;
+        let secs = NOW.duration_since(SystemTime::UNIX_EPOCH).expect("Cannot fail calculating duration since epoch").as_secs();
+
+        let mut common_data = CommonToolData::new(ToolType::Duplicate);
+        common_data.set_included_paths(vec![dir_path]);
+        common_data.set_minimal_modified_date(secs);
+        common_data.set_maximal_modified_date(secs);
+        assert_eq!(run_traversal(&common_data).len(), 2);
+
+        common_data.set_minimal_modified_date(secs + 1);
+        assert!(run_traversal(&common_data).is_empty());
+
+        common_data.set_minimal_modified_date(0);
+        common_data.set_maximal_modified_date(secs - 1);
+        assert!(run_traversal(&common_data).is_empty());
+        Ok(())
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_traversal_ownership_filter() -> io::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::Builder::new().tempdir()?;
+        let dir_path = normalize_path(dir.path());
+        let (global_file, other_file, _) = create_temp_structure(&dir_path)?;
+        fs::set_permissions(&global_file, fs::Permissions::from_mode(0o600))?;
+        fs::set_permissions(&other_file, fs::Permissions::from_mode(0o644))?;
+        let uid = fs::metadata(&global_file)?.uid();
+
+        let mut common_data = CommonToolData::new(ToolType::Duplicate);
+        common_data.set_included_paths(vec![dir_path]);
+        common_data.set_ownership_filter(OwnershipFilter {
+            uid: Some(uid),
+            skip_not_deletable: true,
+            ..Default::default()
+        });
+        assert_eq!(run_traversal(&common_data).len(), 2);
+
+        common_data.set_ownership_filter(OwnershipFilter {
+            required_mode: Some(0o044),
+            ..Default::default()
+        });
+        let paths: Vec<_> = run_traversal(&common_data).into_iter().map(|fe| fe.path).collect();
+        assert_eq!(paths, vec![other_file]);
+
+        common_data.set_ownership_filter(OwnershipFilter {
+            uid: Some(uid.wrapping_add(1)),
+            ..Default::default()
+        });
+        assert!(run_traversal(&common_data).is_empty());
+        Ok(())
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_traversal_follow_symlinks() -> io::Result<()> {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::Builder::new().tempdir()?;
+        let other_dir = tempfile::Builder::new().tempdir()?;
+        let dir_path = normalize_path(dir.path());
+        let other_dir_path = normalize_path(other_dir.path());
+        let media_dir = dir_path.join("media");
+        fs::create_dir(&media_dir)?;
+        fs::write(dir_path.join("local.txt"), b"local")?;
+        fs::write(other_dir_path.join("linked.txt"), b"linked")?;
+        symlink(&other_dir_path, media_dir.join("disk_1"))?;
+        symlink(&other_dir_path, media_dir.join("disk_1_again"))?;
+        symlink(&dir_path, media_dir.join("loop"))?;
+        symlink(other_dir_path.join("linked.txt"), dir_path.join("file_link.txt"))?;
+
+        let mut common_data = CommonToolData::new(ToolType::Duplicate);
+        common_data.set_included_paths(vec![dir_path.clone()]);
+        assert_eq!(run_traversal(&common_data).len(), 1);
+
+        common_data.set_follow_symlinks(true);
+        let DirTraversalResult::SuccessFiles { grouped_file_entries, warnings } = DirTraversalBuilder::new()
+            .group_by(|_fe| ())
+            .stop_flag(&Arc::default())
+            .common_data(&common_data)
+            .build()
+            .run()
+        else {
+            panic!("Expect SuccessFiles.");
+        };
+        let mut paths: Vec<_> = grouped_file_entries.into_values().flatten().map(|fe| fe.path).collect();
+        paths.sort();
+        // Link with smallest path is always visited, others are reported
+        assert_eq!(paths, vec![dir_path.join("local.txt"), media_dir.join("disk_1").join("linked.txt")]);
+        assert_eq!(
+            warnings,
+            vec![
+                flc!("core_folder_already_visited", path = media_dir.join("disk_1_again").to_string_lossy().to_string()),
+                flc!("core_folder_already_visited", path = media_dir.join("loop").to_string_lossy().to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_traversal_with_ignore_files() -> io::Result<()> {
+        let dir = tempfile::Builder::new().tempdir()?;
+        let dir_path = normalize_path(dir.path());
+        let (global_file, other_file, other_dir) = create_temp_structure(&dir_path)?;
+        let build_dir = dir_path.join("build");
+        fs::create_dir(&build_dir)?;
+        fs::write(build_dir.join("output.txt"), b"output")?;
+        fs::write(dir_path.join(".gitignore"), "*.txt\nbuild/\n")?;
+        fs::write(other_dir.join(".czkawkaignore"), "!other_file.txt\n")?;
+
+        let mut common_data = CommonToolData::new(ToolType::Duplicate);
+        common_data.set_included_paths(vec![dir_path.clone()]);
+        assert_eq!(run_traversal(&common_data).len(), 5);
+
+        common_data.set_use_ignore_files(true);
+        let mut paths: Vec<_> = run_traversal(&common_data).into_iter().map(|fe| fe.path).collect();
+        paths.sort();
+        assert_eq!(paths, vec![dir_path.join(".gitignore"), other_dir.join(".czkawkaignore"), other_file]);
+        assert!(!paths.contains(&global_file));
+        Ok(())
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_traversal_with_file_index() -> io::Result<()> {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let temp_cache = tempfile::TempDir::new().expect("Failed to create temp cache dir");
+            let temp_config = tempfile::TempDir::new().expect("Failed to create temp config dir");
+            crate::common::config_cache_path::set_config_cache_path_test(temp_cache.path().to_path_buf(), temp_config.path().to_path_buf());
+            std::mem::forget(temp_cache);
+            std::mem::forget(temp_config);
+        });
+
+        let dir = tempfile::Builder::new().tempdir()?;
+        let dir_path = normalize_path(dir.path());
+        let (global_file, other_file, other_dir) = create_temp_structure(&dir_path)?;
+        // Recently modified folders are not stored in index
+        for folder in [&dir_path, &other_dir] {
+            File::open(folder)?.set_modified(*NOW)?;
+        }
+
+        let mut common_data = CommonToolData::new(ToolType::Duplicate);
+        common_data.set_included_paths(vec![dir_path]);
+        common_data.set_use_file_index(true);
+        assert_eq!(run_traversal(&common_data).len(), 2);
+
+        // Folder with unchanged modification date is not read again and metadata of its files is taken from index
+        let not_indexed_file = other_dir.join("not_indexed.txt");
+        fs::write(&not_indexed_file, b"new")?;
+        fs::write(&other_file, b"changed in place")?;
+        File::open(&other_file)?.set_modified(*NOW)?;
+        File::open(&other_dir)?.set_modified(*NOW)?;
+        let files = run_traversal(&common_data);
+        assert!(!files.iter().any(|fe| fe.path == not_indexed_file));
+        assert_eq!(files.iter().find(|fe| fe.path == other_file).map(|fe| fe.size), Some(10));
+
+        // Files changed in place are noticed, when folder is modified
+        File::open(&other_dir)?.set_modified(*NOW + Duration::from_secs(1))?;
+        let files = run_traversal(&common_data);
+        assert_eq!(files.iter().find(|fe| fe.path == other_file).map(|fe| fe.size), Some(16));
+
+        // Folder with recently modified file is not stored in index, because file may be still written
+        fs::write(&other_file, b"recently changed")?;
+        File::open(&other_dir)?.set_modified(*NOW + Duration::from_secs(2))?;
+        assert_eq!(run_traversal(&common_data).len(), 3);
+        fs::write(&other_file, b"written again")?;
+        let files = run_traversal(&common_data);
+        assert_eq!(files.iter().find(|fe| fe.path == other_file).map(|fe| fe.size), Some(13));
+
+        // Filters are applied also to files from index
+        common_data.set_minimal_file_size(100);
+        assert!(run_traversal(&common_data).is_empty());
+        common_data.set_minimal_file_size(0);
+
+        fs::remove_file(&other_file)?;
+        fs::remove_file(&not_indexed_file)?;
+        File::open(&other_dir)?.set_modified(*NOW + Duration::from_secs(4))?;
+        let files = run_traversal(&common_data);
+        assert_eq!(files.iter().map(|fe| &fe.path).collect::<Vec<_>>(), vec![&global_file]);
+
+        common_data.set_use_file_index(false);
+        fs::write(other_dir.join("new_file.txt"), b"new")?;
+        File::open(&other_dir)?.set_modified(*NOW + Duration::from_secs(4))?;
+        assert_eq!(run_traversal(&common_data).len(), 2);
+        Ok(())
+    }
 }
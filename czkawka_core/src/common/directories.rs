@@ -14,6 +14,8 @@ pub struct Directories {
     pub(crate) included_files: Vec<PathBuf>,
     pub(crate) excluded_files: Vec<PathBuf>,
     pub(crate) reference_files: Vec<PathBuf>,
+    // Paths read from file list, scanned as single files without walking into directories
+    pub(crate) listed_files: Vec<PathBuf>,
 
     pub(crate) original_included_paths: Vec<PathBuf>,
     pub(crate) original_excluded_paths: Vec<PathBuf>,
@@ -43,6 +45,26 @@ impl Directories {
         self.process_paths(included_paths, false, false)
     }
 
+    // Lists may contain millions of entries, so paths are only made absolute, without canonicalizing or checking if they exist
+    pub(crate) fn set_listed_files(&mut self, listed_files: Vec<PathBuf>) -> Messages {
+        let mut messages = Messages::new();
+        self.listed_files = listed_files
+            .into_iter()
+            .filter_map(|path| match std::path::absolute(&path) {
+                Ok(path) => {
+                    #[cfg(target_family = "windows")]
+                    let path = crate::common::normalize_windows_path(&path);
+                    Some(path)
+                }
+                Err(e) => {
+                    messages.warnings.push(format!("Cannot get absolute path of \"{}\", reason {e}", path.to_string_lossy()));
+                    None
+                }
+            })
+            .collect();
+        messages
+    }
+
     pub(crate) fn set_excluded_paths(&mut self, excluded_paths: Vec<PathBuf>) -> Messages {
         self.excluded_files = Vec::new();
         self.excluded_directories = Vec::new();
@@ -87,7 +109,7 @@ impl Directories {
         messages
     }
 
-    fn canonicalize_and_clear_path(path: &Path, is_excluded: bool) -> (Option<PathBuf>, Messages) {
+    pub(crate) fn canonicalize_and_clear_path(path: &Path, is_excluded: bool) -> (Option<PathBuf>, Messages) {
         let mut messages = Messages::new();
         let mut path = path.to_path_buf();
         if !path.exists() {
@@ -132,12 +154,12 @@ impl Directories {
     pub(crate) fn optimize_directories(&mut self, recursive_search: bool, skip_exist_check: bool) -> Result<Messages, Messages> {
         let mut messages: Messages = Messages::new();
 
-        if self.original_included_paths.is_empty() {
+        if self.original_included_paths.is_empty() && self.listed_files.is_empty() {
             messages.critical = Some(flc!("core_cannot_start_scan_no_included_paths"));
             return Err(messages);
         }
 
-        if self.included_directories.is_empty() && self.included_files.is_empty() {
+        if self.included_directories.is_empty() && self.included_files.is_empty() && self.listed_files.is_empty() {
             messages.critical = Some(flc!("core_skip_exist_check_all_included_paths_nonexistent"));
             return Err(messages);
         }
@@ -150,6 +172,7 @@ impl Directories {
             &mut self.included_files,
             &mut self.excluded_files,
             &mut self.reference_files,
+            &mut self.listed_files,
         ] {
             items.sort_unstable();
             items.dedup();
@@ -176,6 +199,21 @@ impl Directories {
             kk.retain(|id| !self.included_directories.iter().any(|ed| id.starts_with(ed)));
         }
 
+        // Listed files inside included directories would be found twice
+        // Included directories are usually few, so this is cheap even for long lists
+        if !self.included_directories.is_empty() {
+            let included_directories = &self.included_directories;
+            self.listed_files.retain(|file| {
+                !included_directories.iter().any(|dir| {
+                    if recursive_search {
+                        file.starts_with(dir)
+                    } else {
+                        file.parent() == Some(dir.as_path())
+                    }
+                })
+            });
+        }
+
         // Also check if files are not excluded directly
         {
             let kk = &mut self.included_files;
@@ -198,7 +236,10 @@ impl Directories {
         // So first, removing included files, that are inside excluded directories
         // So this will allow to remove excluded directories outside included directories
         self.included_files.retain(|ifile| !self.excluded_directories.iter().any(|ed| ifile.starts_with(ed)));
-        self.excluded_directories.retain(|ed| self.included_directories.iter().any(|id| ed.starts_with(id)));
+        // Listed files may be anywhere, so then excluded directories are always kept
+        if self.listed_files.is_empty() {
+            self.excluded_directories.retain(|ed| self.included_directories.iter().any(|id| ed.starts_with(id)));
+        }
 
         // Selecting Reference folders
         {
@@ -230,12 +271,12 @@ impl Directories {
             }
         }
 
-        if self.included_directories.is_empty() && self.included_files.is_empty() {
+        if self.included_directories.is_empty() && self.included_files.is_empty() && self.listed_files.is_empty() {
             messages.critical = Some(flc!("core_missing_no_chosen_included_path"));
             return Err(messages);
         }
 
-        if self.reference_directories == self.included_directories && self.included_files == self.reference_files {
+        if self.reference_directories == self.included_directories && self.included_files == self.reference_files && self.listed_files.is_empty() {
             messages.critical = Some(flc!("core_reference_included_paths_same"));
             return Err(messages);
         }
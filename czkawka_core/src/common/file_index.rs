@@ -0,0 +1,129 @@
+// Persistent index of directories content, used to skip reading directories and metadata of their files, that were not modified since previous scan.
+// Directory modification date changes only when its entries are created, removed or renamed, but not when files are changed in place,
+// so such changes are noticed only after folder is modified.
+// Index contains all files and folders, not only ones matching current filters, so it can be shared between tools.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bincode::Options;
+use fun_time::fun_time;
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::common::config_cache_path::{get_config_cache_path, open_cache_folder};
+use crate::common::fs_ops::{lock_file_beside, replace_file_content};
+use crate::flc;
+
+pub(crate) const FILE_INDEX_VERSION: u8 = 104;
+
+const MEMORY_LIMIT: u64 = 8 * 1024 * 1024 * 1024;
+// Directories modified shortly before scan may be modified again within same timestamp granularity (e.g. 2s on FAT), so they are not trusted
+const MODIFICATION_SAFETY_MARGIN: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct IndexedFile {
+    pub(crate) name: OsString,
+    pub(crate) size: u64,
+    pub(crate) modified_date: u64,
+    // Owner, group and permission bits, used by ownership filter
+    pub(crate) uid: u32,
+    pub(crate) gid: u32,
+    pub(crate) mode: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct IndexedDirectory {
+    pub(crate) modified: Duration,
+    pub(crate) files: Vec<IndexedFile>,
+    pub(crate) folders: Vec<OsString>,
+    // Targets of symlinks may change without modifying folder, so they are resolved in every scan
+    pub(crate) symlinks: Vec<OsString>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct FileIndex {
+    directories: HashMap<PathBuf, Arc<IndexedDirectory>>,
+}
+
+pub(crate) fn get_directory_modification(path: &Path) -> Option<Duration> {
+    fs::metadata(path).ok()?.modified().ok()?.duration_since(UNIX_EPOCH).ok()
+}
+
+fn get_file_index_file_name(root: &Path) -> String {
+    let root_hash = blake3::hash(root.to_string_lossy().as_bytes()).to_hex();
+    format!("file_index_{root_hash}_{FILE_INDEX_VERSION}.bin")
+}
+
+impl FileIndex {
+    #[fun_time(message = "load_file_index", level = "debug")]
+    pub(crate) fn load(roots: &[PathBuf], warnings: &mut Vec<String>) -> Self {
+        let mut directories = HashMap::new();
+        for root in roots {
+            let Some(((Some(file_handler), index_file), _)) = open_cache_folder(&get_file_index_file_name(root), false, false, warnings) else {
+                debug!("No file index for \"{}\"", root.to_string_lossy());
+                continue;
+            };
+            let options = bincode::DefaultOptions::new().with_limit(MEMORY_LIMIT);
+            match options.deserialize_from::<_, Vec<(PathBuf, IndexedDirectory)>>(BufReader::new(file_handler)) {
+                Ok(loaded) => {
+                    debug!("Loaded {} directories from file index \"{}\"", loaded.len(), index_file.to_string_lossy());
+                    directories.extend(loaded.into_iter().map(|(path, directory)| (path, Arc::new(directory))));
+                }
+                Err(e) => warnings.push(flc!("core_failed_to_load_data_from_cache", file = index_file.to_string_lossy(), reason = e.to_string())),
+            }
+        }
+        Self { directories }
+    }
+
+    // Each root has its own index file, so scanning only part of folders, does not remove index of other ones
+    #[fun_time(message = "save_file_index", level = "debug")]
+    pub(crate) fn save(&self, roots: &[PathBuf], warnings: &mut Vec<String>) {
+        for root in roots {
+            let root_directories: Vec<(&PathBuf, &IndexedDirectory)> = self
+                .directories
+                .iter()
+                .filter(|(path, _)| path.starts_with(root))
+                .map(|(path, directory)| (path, directory.as_ref()))
+                .collect();
+            let Some(config_cache_path) = get_config_cache_path() else {
+                return;
+            };
+            let index_file = config_cache_path.cache_folder.join(get_file_index_file_name(root));
+            // Same root may be scanned at the same time by other process, so index is replaced at once and never left half written
+            let options = bincode::DefaultOptions::new().with_limit(MEMORY_LIMIT);
+            let saved = options
+                .serialize(&root_directories)
+                .map_err(|e| e.to_string())
+                .and_then(|content| lock_file_beside(&index_file).and_then(|_lock| replace_file_content(&index_file, content).map_err(|e| e.to_string())));
+            if let Err(e) = saved {
+                warnings.push(flc!("core_failed_to_write_data_to_cache", file = index_file.to_string_lossy(), reason = e));
+                continue;
+            }
+            debug!("Saved {} directories to file index \"{}\"", root_directories.len(), index_file.to_string_lossy());
+        }
+    }
+
+    // Returns indexed content of folder, only if it was not modified since it was indexed
+    pub(crate) fn get_unchanged(&self, path: &Path, modified: Duration) -> Option<Arc<IndexedDirectory>> {
+        self.directories.get(path).filter(|directory| directory.modified == modified).cloned()
+    }
+
+    // Files modified shortly before scan may be still written, so folders containing them are read again in next scan
+    pub(crate) fn insert(&mut self, path: PathBuf, directory: Arc<IndexedDirectory>, scan_start: SystemTime) {
+        let is_recent = |modified: Duration| UNIX_EPOCH + modified + MODIFICATION_SAFETY_MARGIN >= scan_start;
+        let recently_modified = is_recent(directory.modified) || directory.files.iter().any(|file| is_recent(Duration::from_secs(file.modified_date)));
+        if !recently_modified {
+            self.directories.insert(path, directory);
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.directories.len()
+    }
+}
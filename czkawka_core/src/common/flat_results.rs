@@ -0,0 +1,420 @@
+// Flat exports of results, one row per item, for spreadsheets, databases or jq pipelines.
+// Every tool describes layout of its results, inside which every list of objects with "path" is a group,
+// and pair of object and list of objects is a group with reference item.
+// Rows keep only id of their group, so readers don't need to know nested layout used by each tool.
+
+use std::io::Write;
+
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultsFormat {
+    JsonPretty,
+    JsonCompact,
+    Csv,
+    // Newline delimited JSON, one item per line
+    Ndjson,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlatLayout {
+    // Single list of items, which are not similar to each other
+    Items,
+    // Groups of similar items, possibly inside maps, whose keys (e.g. size, hash or name) are group keys
+    Groups,
+    // Object with named lists of groups, e.g. "moved_files" in directory comparison
+    // First item of groups from listed sections is not reference, even if it is saved separately
+    Sections { without_references: &'static [&'static str] },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlatResultEntry {
+    // Simple tools save single list of items, which are not grouped
+    pub group_id: Option<usize>,
+    // Key of map, in which group was found, e.g. name of files or their size
+    pub group_key: Option<String>,
+    // Name of list, in which item was found, e.g. "moved_files" in directory comparison
+    pub section: Option<String>,
+    pub is_reference: bool,
+    pub fields: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlatResults {
+    pub entries: Vec<FlatResultEntry>,
+    pub has_group_keys: bool,
+    pub has_sections: bool,
+    pub has_references: bool,
+}
+
+impl FlatResults {
+    pub fn from_json(value: &Value, layout: FlatLayout) -> Self {
+        let mut entries = Vec::new();
+        let mut collector = GroupCollector {
+            next_group_id: 1,
+            entries: &mut entries,
+        };
+        match layout {
+            FlatLayout::Items => {
+                if let Some(items) = value.as_array().and_then(|values| entry_fields(values, true)) {
+                    collector.entries.extend(items.into_iter().map(|fields| FlatResultEntry { fields, ..Default::default() }));
+                }
+            }
+            FlatLayout::Groups => collector.collect(value, None, None, true),
+            FlatLayout::Sections { without_references } => {
+                for (section, value) in value.as_object().into_iter().flatten() {
+                    collector.collect(value, Some(section), None, !without_references.contains(&section.as_str()));
+                }
+            }
+        }
+        Self {
+            has_group_keys: entries.iter().any(|entry| entry.group_key.is_some()),
+            has_sections: entries.iter().any(|entry| entry.section.is_some()),
+            has_references: entries.iter().any(|entry| entry.is_reference),
+            entries,
+        }
+    }
+
+    // Path first, then other fields in order of their first occurrence, nested lists and objects are skipped
+    fn columns(&self) -> Vec<&str> {
+        let mut columns: Vec<&str> = vec!["path"];
+        for entry in &self.entries {
+            for (key, value) in &entry.fields {
+                if !value.is_array() && !value.is_object() && !columns.contains(&key.as_str()) {
+                    columns.push(key);
+                }
+            }
+        }
+        columns
+    }
+
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let columns = self.columns();
+        let mut header = vec!["group_id"];
+        if self.has_group_keys {
+            header.push("group_key");
+        }
+        if self.has_sections {
+            header.push("section");
+        }
+        if self.has_references {
+            header.push("reference");
+        }
+        header.extend(&columns);
+        writeln!(writer, "{}", header.into_iter().map(csv_escape).collect::<Vec<_>>().join(","))?;
+
+        for entry in &self.entries {
+            let mut row = vec![entry.group_id.map(|id| id.to_string()).unwrap_or_default()];
+            if self.has_group_keys {
+                row.push(csv_escape(&csv_text(entry.group_key.as_deref().unwrap_or_default())));
+            }
+            if self.has_sections {
+                row.push(csv_escape(entry.section.as_deref().unwrap_or_default()));
+            }
+            if self.has_references {
+                row.push(entry.is_reference.to_string());
+            }
+            row.extend(columns.iter().map(|column| csv_escape(&csv_value(entry.fields.get(*column)))));
+            writeln!(writer, "{}", row.join(","))?;
+        }
+        Ok(())
+    }
+
+    pub fn write_ndjson<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        for entry in &self.entries {
+            let mut object = Map::new();
+            object.insert("group_id".to_string(), entry.group_id.map_or(Value::Null, Value::from));
+            if self.has_group_keys {
+                object.insert("group_key".to_string(), entry.group_key.clone().map_or(Value::Null, Value::from));
+            }
+            if self.has_sections {
+                object.insert("section".to_string(), entry.section.clone().map_or(Value::Null, Value::from));
+            }
+            if self.has_references {
+                object.insert("reference".to_string(), Value::Bool(entry.is_reference));
+            }
+            for (key, value) in &entry.fields {
+                object.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+            serde_json::to_writer(&mut *writer, &Value::Object(object))?;
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
+
+fn entry_field(value: &Value, allow_path_strings: bool) -> Option<Map<String, Value>> {
+    match value {
+        Value::String(path) if allow_path_strings => Some(Map::from_iter([("path".to_string(), Value::String(path.clone()))])),
+        Value::Object(object) if object.get("path").is_some_and(Value::is_string) => Some(object.clone()),
+        _ => None,
+    }
+}
+
+fn entry_fields(values: &[Value], allow_path_strings: bool) -> Option<Vec<Map<String, Value>>> {
+    if values.is_empty() {
+        return None;
+    }
+    values.iter().map(|value| entry_field(value, allow_path_strings)).collect()
+}
+
+// Element of list, which is not item itself, but contains items, e.g. changed file with its version from both compared folders
+fn nested_entries(object: &Map<String, Value>) -> Option<Vec<Map<String, Value>>> {
+    let mut items = Vec::new();
+    for value in object.values() {
+        if let Some(item) = entry_field(value, false) {
+            items.push(item);
+        } else if let Some(nested) = value.as_array().and_then(|values| entry_fields(values, false)) {
+            items.extend(nested);
+        }
+    }
+    (!items.is_empty()).then_some(items)
+}
+
+struct GroupCollector<'a> {
+    next_group_id: usize,
+    entries: &'a mut Vec<FlatResultEntry>,
+}
+
+impl GroupCollector<'_> {
+    fn push_group(&mut self, items: Vec<Map<String, Value>>, reference: Option<Map<String, Value>>, section: Option<&str>, group_key: Option<&str>) {
+        let group_id = self.next_group_id;
+        self.next_group_id += 1;
+        let reference = reference.map(|fields| (fields, true));
+        for (fields, is_reference) in reference.into_iter().chain(items.into_iter().map(|fields| (fields, false))) {
+            self.entries.push(FlatResultEntry {
+                group_id: Some(group_id),
+                group_key: group_key.map(str::to_string),
+                section: section.map(str::to_string),
+                is_reference,
+                fields,
+            });
+        }
+    }
+
+    fn collect(&mut self, value: &Value, section: Option<&str>, group_key: Option<&str>, allow_references: bool) {
+        match value {
+            Value::Array(values) => {
+                if let [reference, items] = values.as_slice()
+                    && let Some(reference) = entry_field(reference, false)
+                    && let Some(items) = items.as_array().and_then(|items| entry_fields(items, false))
+                {
+                    if allow_references {
+                        self.push_group(items, Some(reference), section, group_key);
+                    } else {
+                        self.push_group(std::iter::once(reference).chain(items).collect(), None, section, group_key);
+                    }
+                } else if let Some(items) = entry_fields(values, false) {
+                    self.push_group(items, None, section, group_key);
+                } else {
+                    for value in values {
+                        if let Some(items) = value.as_object().and_then(nested_entries) {
+                            self.push_group(items, None, section, group_key);
+                        } else {
+                            self.collect(value, section, group_key, allow_references);
+                        }
+                    }
+                }
+            }
+            Value::Object(object) => {
+                for (key, value) in object {
+                    self.collect(value, section, Some(key), allow_references);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn csv_value(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => csv_text(text),
+        Some(value) => value.to_string(),
+    }
+}
+
+// Spreadsheets run cells starting with these characters as formulas, e.g. file named "=HYPERLINK(...).txt"
+fn csv_text(text: &str) -> String {
+    if text.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{text}")
+    } else {
+        text.to_string()
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn to_string(results: &FlatResults, format: ResultsFormat) -> String {
+        let mut output = Vec::new();
+        match format {
+            ResultsFormat::Csv => results.write_csv(&mut output).unwrap(),
+            ResultsFormat::Ndjson => results.write_ndjson(&mut output).unwrap(),
+            ResultsFormat::JsonPretty | ResultsFormat::JsonCompact => unreachable!(),
+        }
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_flat_results_of_grouped_tools() {
+        // Duplicates by hash, grouped by size, with reference group
+        let value = json!({
+            "10": [
+                [{"path": "/a/1.txt", "size": 10, "hash": "ab"}, {"path": "/a/2,\"x\".txt", "size": 10, "hash": "ab"}],
+                [{"path": "/a/3.txt", "size": 10, "hash": "cd"}, {"path": "/a/4.txt", "size": 10, "hash": "cd"}]
+            ],
+            "20": [[{"path": "/ref/5.txt", "size": 20}, [{"path": "/a/6.txt", "size": 20, "vector": [1, 2]}]]]
+        });
+        let results = FlatResults::from_json(&value, FlatLayout::Groups);
+        assert_eq!(results.entries.len(), 6);
+        assert!(!results.has_sections);
+        assert_eq!(
+            to_string(&results, ResultsFormat::Csv),
+            "group_id,group_key,reference,path,hash,size\n\
+             1,10,false,/a/1.txt,ab,10\n\
+             1,10,false,\"/a/2,\"\"x\"\".txt\",ab,10\n\
+             2,10,false,/a/3.txt,cd,10\n\
+             2,10,false,/a/4.txt,cd,10\n\
+             3,20,true,/ref/5.txt,,20\n\
+             3,20,false,/a/6.txt,,20\n"
+        );
+        let lines: Vec<Value> = to_string(&results, ResultsFormat::Ndjson).lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(
+            lines[5],
+            json!({"group_id": 3, "group_key": "20", "reference": false, "path": "/a/6.txt", "size": 20, "vector": [1, 2]})
+        );
+    }
+
+    #[test]
+    fn test_flat_results_of_duplicates_by_name() {
+        // Names of files are keys of map, they must not be treated as sections
+        let value = json!({
+            "photo.jpg": [{"path": "/a/photo.jpg", "size": 1}, {"path": "/b/photo.jpg", "size": 2}],
+            "=1+1.txt": [[{"path": "/ref/=1+1.txt", "size": 3}, [{"path": "/a/=1+1.txt", "size": 3}]]]
+        });
+        let results = FlatResults::from_json(&value, FlatLayout::Groups);
+        assert!(!results.has_sections);
+        assert!(results.entries.iter().all(|entry| entry.section.is_none()));
+        assert_eq!(
+            to_string(&results, ResultsFormat::Csv),
+            "group_id,group_key,reference,path,size\n\
+             1,'=1+1.txt,true,/ref/=1+1.txt,3\n\
+             1,'=1+1.txt,false,/a/=1+1.txt,3\n\
+             2,photo.jpg,false,/a/photo.jpg,1\n\
+             2,photo.jpg,false,/b/photo.jpg,2\n"
+        );
+        let lines: Vec<Value> = to_string(&results, ResultsFormat::Ndjson).lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(
+            lines[2],
+            json!({"group_id": 2, "group_key": "photo.jpg", "reference": false, "path": "/a/photo.jpg", "size": 1})
+        );
+    }
+
+    #[test]
+    fn test_flat_results_of_similar_images_and_videos() {
+        // Similar images and videos are saved as list of groups, without any keys
+        let value = json!([
+            [{"path": "/a/1.png", "size": 1, "similarity": 0}, {"path": "/a/2.png", "size": 2, "similarity": 3}],
+            [{"path": "/a/3.png", "size": 3, "similarity": 0}, {"path": "/a/4.png", "size": 4, "similarity": 1}]
+        ]);
+        let results = FlatResults::from_json(&value, FlatLayout::Groups);
+        assert!(!results.has_group_keys);
+        assert!(!results.has_references);
+        assert_eq!(
+            to_string(&results, ResultsFormat::Csv),
+            "group_id,path,similarity,size\n1,/a/1.png,0,1\n1,/a/2.png,3,2\n2,/a/3.png,0,3\n2,/a/4.png,1,4\n"
+        );
+
+        // Similar videos with reference folders
+        let value = json!([
+            [{"path": "/ref/1.mp4", "size": 1}, [{"path": "/a/1.mp4", "size": 1}, {"path": "/a/2.mp4", "size": 2}]],
+            [{"path": "/ref/3.mp4", "size": 3}, [{"path": "/a/3.mp4", "size": 3}]]
+        ]);
+        let results = FlatResults::from_json(&value, FlatLayout::Groups);
+        assert!(!results.has_group_keys);
+        assert_eq!(
+            to_string(&results, ResultsFormat::Csv),
+            "group_id,reference,path,size\n\
+             1,true,/ref/1.mp4,1\n\
+             1,false,/a/1.mp4,1\n\
+             1,false,/a/2.mp4,2\n\
+             2,true,/ref/3.mp4,3\n\
+             2,false,/a/3.mp4,3\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_escapes_formulas() {
+        let value = json!(["=HYPERLINK(\"http://x\")", "+1", "-1", "@SUM(A1)", "\tcmd", "/a/=b", "normal"]);
+        let results = FlatResults::from_json(&value, FlatLayout::Items);
+        assert_eq!(
+            to_string(&results, ResultsFormat::Csv),
+            "group_id,path\n\
+             ,\"'=HYPERLINK(\"\"http://x\"\")\"\n\
+             ,'+1\n\
+             ,'-1\n\
+             ,'@SUM(A1)\n\
+             ,'\tcmd\n\
+             ,/a/=b\n\
+             ,normal\n"
+        );
+        // NDJSON is not opened by spreadsheets, so values are saved unchanged
+        assert!(to_string(&results, ResultsFormat::Ndjson).starts_with("{\"group_id\":null,\"path\":\"=HYPERLINK"));
+    }
+
+    #[test]
+    fn test_flat_results_of_simple_tools_and_sections() {
+        let results = FlatResults::from_json(&json!(["/empty/1", "/empty/2"]), FlatLayout::Items);
+        assert_eq!(to_string(&results, ResultsFormat::Csv), "group_id,path\n,/empty/1\n,/empty/2\n");
+        assert_eq!(
+            to_string(&results, ResultsFormat::Ndjson),
+            "{\"group_id\":null,\"path\":\"/empty/1\"}\n{\"group_id\":null,\"path\":\"/empty/2\"}\n"
+        );
+
+        // Directory comparison
+        let value = json!({
+            "first_directory": "/first",
+            "files_only_in_first": [{"path": "/first/a", "size": 1}],
+            "changed_files": [{"relative_path": "b", "first_file": {"path": "/first/b", "size": 2}, "second_file": {"path": "/second/b", "size": 3}}],
+            "moved_files": []
+        });
+        let results = FlatResults::from_json(&value, FlatLayout::Sections { without_references: &[] });
+        assert!(results.has_sections);
+        assert!(!results.has_group_keys);
+        assert!(!results.has_references);
+        assert_eq!(
+            to_string(&results, ResultsFormat::Csv),
+            "group_id,section,path,size\n1,changed_files,/first/b,2\n1,changed_files,/second/b,3\n2,files_only_in_first,/first/a,1\n"
+        );
+
+        // Subset folder is saved before folders containing it, but it is not reference
+        let value = json!({
+            "duplicated_folders": [[{"path": "/ref/a"}, [{"path": "/b"}]]],
+            "subset_folders": [[{"path": "/c"}, [{"path": "/d"}]]]
+        });
+        let results = FlatResults::from_json(
+            &value,
+            FlatLayout::Sections {
+                without_references: &["subset_folders"],
+            },
+        );
+        assert_eq!(
+            to_string(&results, ResultsFormat::Csv),
+            "group_id,section,reference,path\n1,duplicated_folders,true,/ref/a\n1,duplicated_folders,false,/b\n2,subset_folders,false,/c\n2,subset_folders,false,/d\n"
+        );
+        assert!(FlatResults::from_json(&json!([]), FlatLayout::Items).entries.is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 use std::io::Error;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use std::{fs, io};
 
 use crate::flc;
@@ -155,37 +155,126 @@ pub fn remove_single_file<P: AsRef<Path>>(full_path: P, remove_to_trash: bool) -
 ///
 /// Note: if used on Android or iOS platforms, ensure `remove_to_trash` is false, as trash is not supported
 /// and will always return an [`Error`].
-pub fn remove_single_folder(full_path: &str, remove_to_trash: bool) -> Result<(), String> {
+pub fn remove_single_folder<P: AsRef<Path>>(full_path: P, remove_to_trash: bool) -> Result<(), String> {
     if remove_to_trash {
-        if let Err(e) = trash_delete(full_path) {
-            return Err(flc!("core_error_moving_to_trash", file = full_path, error = e));
+        if let Err(e) = trash_delete(&full_path) {
+            return Err(flc!("core_error_moving_to_trash", file = full_path.as_ref().to_string_lossy().to_string(), error = e));
         }
     } else {
-        if let Err(e) = fs::remove_dir_all(full_path) {
-            return Err(flc!("core_error_removing", file = full_path, error = e.to_string()));
+        if let Err(e) = fs::remove_dir_all(&full_path) {
+            return Err(flc!("core_error_removing", file = full_path.as_ref().to_string_lossy().to_string(), error = e.to_string()));
         }
     }
     Ok(())
 }
 
+// Recreates whole path of item inside folder, so items with same names from different folders do not collide
+pub(crate) fn path_inside_folder(folder: &Path, path: &Path) -> PathBuf {
+    folder.join(path.components().filter(|component| matches!(component, Component::Normal(_))).collect::<PathBuf>())
+}
+
+// Rename fails when folder is on other device, so items are copied and removed instead
+pub(crate) fn move_item(source: &Path, destination: &Path) -> Result<(), String> {
+    if fs::symlink_metadata(destination).is_ok() {
+        return Err(flc!("core_destination_already_exists", path = destination.to_string_lossy().to_string()));
+    }
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    if fs::rename(source, destination).is_ok() {
+        return Ok(());
+    }
+
+    if let Err(e) = copy_tree(source, destination) {
+        // Partially copied item is useless, original is still untouched
+        let _ = remove_tree(destination);
+        return Err(flc!("core_cannot_copy_item", reason = e.to_string()));
+    }
+    remove_tree(source).map_err(|e| flc!("core_copied_item_not_removed", path = source.to_string_lossy().to_string(), reason = e.to_string()))
+}
+
+// Symlinks are copied as symlinks, so folder tree is never copied through them
+fn copy_tree(source: &Path, destination: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(source)?;
+    if metadata.file_type().is_symlink() {
+        copy_symlink(source, destination)
+    } else if metadata.is_dir() {
+        fs::create_dir(destination)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_tree(&entry.path(), &destination.join(entry.file_name()))?;
+        }
+        // Copying content changes modification date of folder, so it is set at the end
+        filetime::set_file_mtime(destination, filetime::FileTime::from_last_modification_time(&metadata))
+    } else {
+        fs::copy(source, destination)?;
+        filetime::set_file_mtime(destination, filetime::FileTime::from_last_modification_time(&metadata))
+    }
+}
+
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    let target = fs::read_link(source)?;
+    #[cfg(target_family = "unix")]
+    {
+        std::os::unix::fs::symlink(target, destination)
+    }
+    #[cfg(target_family = "windows")]
+    {
+        if fs::metadata(source).is_ok_and(|metadata| metadata.is_dir()) {
+            std::os::windows::fs::symlink_dir(target, destination)
+        } else {
+            std::os::windows::fs::symlink_file(target, destination)
+        }
+    }
+    #[cfg(not(any(target_family = "unix", target_family = "windows")))]
+    {
+        let _ = (target, destination);
+        Err(Error::new(io::ErrorKind::Unsupported, "Soft links are not supported on this platform"))
+    }
+}
+
+fn remove_tree(path: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+pub(crate) fn path_with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_os_string();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+// Files like journals are replaced as a whole, so instead of them, separate lock file is locked by every process and thread using them.
+// Lock is released when returned file is dropped.
+pub(crate) fn lock_file_beside(path: &Path) -> Result<fs::File, String> {
+    let lock_path = path_with_suffix(path, ".lock");
+    let lock_file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .and_then(|lock_file| lock_file.lock().map(|()| lock_file));
+    lock_file.map_err(|e| flc!("core_cannot_lock_file", path = lock_path.to_string_lossy().to_string(), reason = e.to_string()))
+}
+
+// Content is saved to temporary file first, so file is never left half written
+pub(crate) fn replace_file_content(path: &Path, content: impl AsRef<[u8]>) -> io::Result<()> {
+    let temp_path = path_with_suffix(path, ".tmp");
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
+
 // Function to create hardlink, when destination exists
 // This is always true in this app, because creating hardlink, to newly created file is pointless
 pub fn make_hard_link<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<()> {
     let src = src.as_ref();
     let dst = dst.as_ref();
-    let dst_dir = dst.parent().ok_or_else(|| Error::other("No parent"))?;
-    let mut temp;
-    let mut attempts = MAX_SYMLINK_HARDLINK_ATTEMPTS;
-    loop {
-        temp = dst_dir.join(format!("{}.czkawka_tmp", rand::random::<u128>()));
-        if !temp.exists() {
-            break;
-        }
-        attempts -= 1;
-        if attempts == 0 {
-            return Err(Error::other("Cannot choose temporary file for hardlink creation"));
-        }
-    }
+    let temp = choose_temporary_path(dst, "hardlink")?;
     fs::rename(dst, temp.as_path())?;
     match fs::hard_link(src, dst) {
         Ok(()) => {
@@ -211,23 +300,113 @@ fn describe_hardlink_error(e: io::Error, dst: &Path) -> io::Error {
     }
 }
 
-#[cfg(any(target_family = "unix", target_family = "windows"))]
-pub fn make_file_symlink<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<()> {
-    let src = src.as_ref();
-    let dst = dst.as_ref();
+// Picks a free name next to `dst`, used to hold one of the files while it is being replaced
+fn choose_temporary_path(dst: &Path, operation: &str) -> io::Result<PathBuf> {
     let dst_dir = dst.parent().ok_or_else(|| Error::other("No parent"))?;
-    let mut temp;
-    let mut attempts = MAX_SYMLINK_HARDLINK_ATTEMPTS;
-    loop {
-        temp = dst_dir.join(format!("{}.czkawka_tmp", rand::random::<u128>()));
+    for _ in 0..MAX_SYMLINK_HARDLINK_ATTEMPTS {
+        let temp = dst_dir.join(format!("{}.czkawka_tmp", rand::random::<u128>()));
         if !temp.exists() {
-            break;
+            return Ok(temp);
         }
-        attempts -= 1;
-        if attempts == 0 {
-            return Err(Error::other("Cannot choose temporary file for symlink creation"));
+    }
+    Err(Error::other(format!("Cannot choose temporary file for {operation} creation")))
+}
+
+// Replaces `dst` with a copy-on-write clone of `src` - both files share the same data blocks,
+// but unlike hardlinks each keeps its own inode, so editing one copy never changes the other.
+// The clone is created next to `dst` and gets its permissions, owner and modification time
+// before being renamed over it, so on any failure `dst` is left untouched.
+pub fn make_reflink<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<()> {
+    let src = src.as_ref();
+    let dst = dst.as_ref();
+    let dst_metadata = fs::metadata(dst)?;
+    if fs::metadata(src)?.len() != dst_metadata.len() {
+        return Err(Error::other(flc!(
+            "core_reflink_size_mismatch",
+            file = dst.to_string_lossy().to_string(),
+            original = src.to_string_lossy().to_string()
+        )));
+    }
+
+    let temp = choose_temporary_path(dst, "reflink")?;
+    let result = clone_file(src, &temp)
+        .and_then(|()| copy_file_metadata(&dst_metadata, &temp))
+        .and_then(|()| fs::rename(&temp, dst));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp);
+        return Err(describe_reflink_error(e, dst));
+    }
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn clone_file(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let src_file = fs::File::open(src)?;
+    let dst_file = fs::OpenOptions::new().write(true).create_new(true).open(dst)?;
+    // SAFETY: both descriptors belong to files that stay open until the end of this function
+    let res = unsafe { libc::ioctl(dst_file.as_raw_fd(), libc::FICLONE, src_file.as_raw_fd()) };
+    if res == -1 {
+        return Err(Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn clone_file(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let src_c = CString::new(src.as_os_str().as_bytes()).map_err(Error::other)?;
+    let dst_c = CString::new(dst.as_os_str().as_bytes()).map_err(Error::other)?;
+    // SAFETY: both pointers come from CStrings that outlive the call
+    let res = unsafe { libc::clonefile(src_c.as_ptr(), dst_c.as_ptr(), 0) };
+    if res == -1 {
+        return Err(Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+fn clone_file(_src: &Path, _dst: &Path) -> io::Result<()> {
+    Err(Error::new(io::ErrorKind::Unsupported, "Reflinks are not supported on this platform"))
+}
+
+fn copy_file_metadata(metadata: &fs::Metadata, path: &Path) -> io::Result<()> {
+    #[cfg(target_family = "unix")]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let current = fs::metadata(path)?;
+        if current.uid() != metadata.uid() || current.gid() != metadata.gid() {
+            std::os::unix::fs::chown(path, Some(metadata.uid()), Some(metadata.gid()))?;
         }
     }
+    filetime::set_file_times(
+        path,
+        filetime::FileTime::from_last_access_time(metadata),
+        filetime::FileTime::from_last_modification_time(metadata),
+    )?;
+    fs::set_permissions(path, metadata.permissions())
+}
+
+// Most filesystems (ext4, NTFS, network mounts) can't share blocks between files and report it
+// with a generic "Operation not supported" or "Invalid argument", so name the real cause.
+fn describe_reflink_error(e: io::Error, dst: &Path) -> io::Error {
+    if matches!(e.kind(), io::ErrorKind::Unsupported | io::ErrorKind::CrossesDevices | io::ErrorKind::InvalidInput) {
+        let kind = e.kind();
+        let message = flc!("core_reflink_unsupported_filesystem", path = dst.to_string_lossy().to_string(), reason = e.to_string());
+        Error::new(kind, message)
+    } else {
+        e
+    }
+}
+
+#[cfg(any(target_family = "unix", target_family = "windows"))]
+pub fn make_file_symlink<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<()> {
+    let src = src.as_ref();
+    let dst = dst.as_ref();
+    let temp = choose_temporary_path(dst, "symlink")?;
     fs::rename(dst, temp.as_path())?;
     let result: Result<_, _>;
     #[cfg(target_family = "unix")]
@@ -287,4 +466,94 @@ mod tests {
         assert_eq!(described.kind(), ErrorKind::PermissionDenied);
         assert_eq!(described.to_string(), "Permission denied");
     }
+
+    #[test]
+    fn make_reflink_rejects_files_with_different_size() {
+        let dir = tempfile::Builder::new().tempdir().expect("Failed to create temp dir");
+        let (src, dst) = (dir.path().join("a"), dir.path().join("b"));
+        fs::write(&src, b"abc").expect("Failed to write src");
+        fs::write(&dst, b"abcd").expect("Failed to write dst");
+
+        assert!(make_reflink(&src, &dst).is_err());
+        assert_eq!(fs::read(&dst).expect("Failed to read dst"), b"abcd");
+    }
+
+    #[test]
+    fn make_reflink_keeps_destination_when_cloning_is_not_possible() {
+        let dir = tempfile::Builder::new().tempdir().expect("Failed to create temp dir");
+        let (src, dst) = (dir.path().join("a"), dir.path().join("b"));
+        fs::write(&src, b"same").expect("Failed to write src");
+        fs::write(&dst, b"same").expect("Failed to write dst");
+
+        // Depending on filesystem, reflink may be supported or not, but in both cases destination must stay readable and identical
+        let _ = make_reflink(&src, &dst);
+
+        assert_eq!(fs::read(&dst).expect("Failed to read dst"), b"same");
+        assert_eq!(fs::read_dir(dir.path()).expect("Failed to read dir").count(), 2);
+    }
+
+    #[test]
+    fn describe_reflink_error_explains_unsupported_filesystem() {
+        let raw = Error::new(ErrorKind::Unsupported, "Operation not supported");
+        let described = describe_reflink_error(raw, Path::new("/mnt/ext4/b"));
+
+        assert_eq!(described.kind(), ErrorKind::Unsupported);
+        let message = described.to_string();
+        assert!(message.contains("/mnt/ext4/b"), "missing path in: {message}");
+        assert!(message.contains("Operation not supported"), "missing original reason in: {message}");
+    }
+
+    // Used when folder cannot be renamed, because it is moved to other device
+    #[test]
+    fn copy_tree_copies_folders_with_symlinks_and_dates() {
+        let dir = tempfile::Builder::new().tempdir().expect("Failed to create temp dir");
+        let (src, dst) = (dir.path().join("src"), dir.path().join("dst"));
+        fs::create_dir_all(src.join("nested")).expect("Failed to create src");
+        fs::write(src.join("nested").join("file.txt"), b"content").expect("Failed to write file");
+        let old_time = filetime::FileTime::from_unix_time(1_000_000, 0);
+        filetime::set_file_mtime(src.join("nested").join("file.txt"), old_time).expect("Failed to set file date");
+        filetime::set_file_mtime(src.join("nested"), old_time).expect("Failed to set folder date");
+        #[cfg(target_family = "unix")]
+        std::os::unix::fs::symlink("nested", src.join("link")).expect("Failed to create symlink");
+
+        copy_tree(&src, &dst).expect("Failed to copy tree");
+        remove_tree(&src).expect("Failed to remove src");
+
+        assert!(!src.exists());
+        assert_eq!(fs::read(dst.join("nested").join("file.txt")).expect("Failed to read copied file"), b"content");
+        for path in [dst.join("nested"), dst.join("nested").join("file.txt")] {
+            let metadata = fs::metadata(&path).expect("Failed to read copied metadata");
+            assert_eq!(filetime::FileTime::from_last_modification_time(&metadata), old_time);
+        }
+        #[cfg(target_family = "unix")]
+        assert_eq!(fs::read_link(dst.join("link")).expect("Symlink was not copied"), PathBuf::from("nested"));
+    }
+
+    #[test]
+    fn move_item_never_overwrites_destination() {
+        let dir = tempfile::Builder::new().tempdir().expect("Failed to create temp dir");
+        let (src, dst) = (dir.path().join("a"), dir.path().join("b"));
+        fs::write(&src, b"src").expect("Failed to write src");
+        fs::write(&dst, b"dst").expect("Failed to write dst");
+
+        move_item(&src, &dst).unwrap_err();
+        assert_eq!(fs::read(&dst).expect("Failed to read dst"), b"dst");
+        assert!(src.exists());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn remove_single_folder_removes_folder_with_non_utf8_name() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = tempfile::Builder::new().tempdir().expect("Failed to create temp dir");
+        let folder = dir.path().join(std::ffi::OsStr::from_bytes(b"folder\xff"));
+        let lossy_folder = dir.path().join("folder\u{FFFD}");
+        fs::create_dir_all(folder.join("nested")).expect("Failed to create folder");
+        fs::create_dir(&lossy_folder).expect("Failed to create folder with lossy name");
+
+        remove_single_folder(&folder, false).expect("Failed to remove folder");
+        assert!(!folder.exists());
+        assert!(lossy_folder.exists());
+    }
 }
@@ -0,0 +1,203 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::common::model::{HashKey, HashType};
+use crate::flc;
+
+// Only these hashes have standard tools (`sha256sum`, `b3sum`, `b3sum --keyed`) which can check manifest
+pub fn is_hash_type_supported_in_manifest(hash_type: HashType) -> bool {
+    matches!(hash_type, HashType::Blake3 | HashType::Blake3Keyed(_) | HashType::Sha256)
+}
+
+// Key file contains exactly 32 raw bytes, the same as key passed to `b3sum --keyed` on standard input
+pub fn load_hash_key(file_name: &Path) -> Result<HashKey, String> {
+    let content = std::fs::read(file_name).map_err(|e| flc!("core_cannot_open_hash_key", file = file_name.to_string_lossy(), reason = e.to_string()))?;
+    let key = content
+        .try_into()
+        .map_err(|content: Vec<u8>| flc!("core_invalid_hash_key_length", file = file_name.to_string_lossy(), length = content.len()))?;
+    Ok(HashKey(key))
+}
+
+pub fn save_hash_manifest<P: AsRef<Path>, H: AsRef<str>>(file_name: &str, entries: impl IntoIterator<Item = (P, H)>) -> std::io::Result<()> {
+    let file_handler = File::create(file_name)?;
+    let mut writer = BufWriter::new(file_handler);
+    write_hash_manifest(&mut writer, entries)?;
+    writer.flush()
+}
+
+// Writes lines in format "<hash>  <path>", the same as `sha256sum` and `b3sum` produce
+pub fn write_hash_manifest<W: Write, P: AsRef<Path>, H: AsRef<str>>(writer: &mut W, entries: impl IntoIterator<Item = (P, H)>) -> std::io::Result<()> {
+    for (path, hash) in entries {
+        let path = path.as_ref().to_string_lossy();
+        let hash = hash.as_ref();
+        // Same escaping as in coreutils - line with escaped path starts with backslash
+        if path.contains(['\\', '\n', '\r']) {
+            let escaped_path = path.replace('\\', "\\\\").replace('\n', "\\n").replace('\r', "\\r");
+            writeln!(writer, "\\{hash}  {escaped_path}")?;
+        } else {
+            writeln!(writer, "{hash}  {path}")?;
+        }
+    }
+    Ok(())
+}
+
+// Loads manifest created by czkawka, `sha256sum` or `b3sum`
+// Relative paths are resolved against folder in which manifest is placed
+pub fn load_hash_manifest(file_name: &Path) -> Result<(Vec<(PathBuf, String)>, Vec<String>), String> {
+    let content = std::fs::read_to_string(file_name).map_err(|e| flc!("core_cannot_open_hash_manifest", file = file_name.to_string_lossy(), reason = e.to_string()))?;
+    let (mut entries, warnings) = parse_hash_manifest(&content);
+
+    let base_folder = file_name.parent().unwrap_or_else(|| Path::new(""));
+    for (path, _hash) in &mut entries {
+        if path.is_relative() {
+            *path = base_folder.join(&*path);
+        }
+    }
+    Ok((entries, warnings))
+}
+
+// Returns entries with lowercase hashes and warnings about lines that could not be parsed
+pub fn parse_hash_manifest(content: &str) -> (Vec<(PathBuf, String)>, Vec<String>) {
+    let mut entries = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (idx, line) in content.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match parse_hash_manifest_line(line) {
+            Some(entry) => entries.push(entry),
+            None => warnings.push(flc!("core_invalid_hash_manifest_line", line = (idx + 1).to_string())),
+        }
+    }
+
+    (entries, warnings)
+}
+
+// Line looks like "<hash>  <path>" or "<hash> *<path>" (binary mode), escaped lines start with backslash
+fn parse_hash_manifest_line(line: &str) -> Option<(PathBuf, String)> {
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let (hash, path) = line.split_once(' ')?;
+    let path = path.strip_prefix([' ', '*'])?;
+    if hash.is_empty() || path.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let path = if escaped { unescape_path(path)? } else { path.to_string() };
+    Some((PathBuf::from(path), hash.to_ascii_lowercase()))
+}
+
+fn unescape_path(path: &str) -> Option<String> {
+    let mut result = String::with_capacity(path.len());
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '\\' => result.push('\\'),
+            'n' => result.push('\n'),
+            'r' => result.push('\r'),
+            _ => return None,
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn test_write_hash_manifest() {
+        let entries = [
+            (PathBuf::from("/home/user/file.txt"), "abcd".to_string()),
+            (PathBuf::from("/home/user/new\nline.txt"), "ef01".to_string()),
+            (PathBuf::from("/home/user/back\\slash.txt"), "2345".to_string()),
+        ];
+        let mut buffer = Vec::new();
+        write_hash_manifest(&mut buffer, entries.iter().map(|(path, hash)| (path.as_path(), hash.as_str()))).unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "abcd  /home/user/file.txt\n\\ef01  /home/user/new\\nline.txt\n\\2345  /home/user/back\\\\slash.txt\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_hash_manifest() {
+        let content = "# comment\nABCD  /home/user/file.txt\nef01 *relative/binary.bin\n\\2345  /home/user/new\\nline\\\\.txt\n\nnot a manifest line\nzz  /file\n";
+        let (entries, warnings) = parse_hash_manifest(content);
+
+        assert_eq!(
+            entries,
+            vec![
+                (PathBuf::from("/home/user/file.txt"), "abcd".to_string()),
+                (PathBuf::from("relative/binary.bin"), "ef01".to_string()),
+                (PathBuf::from("/home/user/new\nline\\.txt"), "2345".to_string()),
+            ]
+        );
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_written_manifest_can_be_parsed() {
+        let entries = [
+            (PathBuf::from("/home/user/file.txt"), "abcd".to_string()),
+            (PathBuf::from("/home/user/new\nline\r.txt"), "ef01".to_string()),
+            (PathBuf::from("/home/user/back\\slash  two spaces.txt"), "2345".to_string()),
+        ];
+        let mut buffer = Vec::new();
+        write_hash_manifest(&mut buffer, entries.iter().map(|(path, hash)| (path.as_path(), hash.as_str()))).unwrap();
+
+        let (parsed, warnings) = parse_hash_manifest(&String::from_utf8(buffer).unwrap());
+        assert!(warnings.is_empty());
+        assert_eq!(parsed, entries);
+    }
+
+    #[test]
+    fn test_load_hash_manifest_resolves_relative_paths() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let manifest = temp_dir.path().join("checksums.b3");
+        std::fs::write(&manifest, "abcd  file.txt\nef01  /absolute/file.txt\n").unwrap();
+
+        let (entries, warnings) = load_hash_manifest(&manifest).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(entries[0].0, temp_dir.path().join("file.txt"));
+        assert_eq!(entries[1].0, PathBuf::from("/absolute/file.txt"));
+
+        load_hash_manifest(&temp_dir.path().join("not_existing.b3")).unwrap_err();
+    }
+
+    #[test]
+    fn test_load_hash_key() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let key_file = temp_dir.path().join("key.bin");
+        std::fs::write(&key_file, [7u8; 32]).unwrap();
+        let key = load_hash_key(&key_file).unwrap();
+
+        let mut hasher = HashType::Blake3Keyed(key).hasher();
+        hasher.update(b"abc");
+        assert_eq!(hasher.finalize(), blake3::keyed_hash(&[7u8; 32], b"abc").to_hex().to_string());
+
+        std::fs::write(&key_file, [7u8; 33]).unwrap();
+        load_hash_key(&key_file).unwrap_err();
+        load_hash_key(&temp_dir.path().join("not_existing.bin")).unwrap_err();
+    }
+
+    #[test]
+    fn test_supported_hash_types() {
+        assert!(is_hash_type_supported_in_manifest(HashType::Blake3));
+        assert!(is_hash_type_supported_in_manifest(HashType::Sha256));
+        assert!(is_hash_type_supported_in_manifest(HashType::Blake3Keyed(HashKey([0; 32]))));
+        assert!(!is_hash_type_supported_in_manifest(HashType::Crc32));
+        assert!(!is_hash_type_supported_in_manifest(HashType::Xxh3));
+    }
+}
@@ -0,0 +1,111 @@
+// Support for ignore files(with gitignore syntax) placed inside scanned directories.
+// Rules from ignore file are applied to directory in which it is placed and to all its subdirectories.
+// Like in git, rules from deeper directories take precedence over rules from parent directories
+// and content of ignored directory cannot be re-included by negated patterns.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use crate::flc;
+
+pub const IGNORE_FILE_NAMES: &[&str] = &[".gitignore", ".czkawkaignore"];
+
+#[derive(Debug)]
+pub(crate) struct IgnoreRules {
+    matcher: Gitignore,
+    parent: Option<Arc<Self>>,
+}
+
+impl IgnoreRules {
+    // Returns rules that should be applied to content of folder - rules of parent folders extended by ignore files found in it
+    pub(crate) fn for_folder(parent: Option<&Arc<Self>>, folder: &Path, warnings: &mut Vec<String>) -> Option<Arc<Self>> {
+        let mut builder = GitignoreBuilder::new(folder);
+        let mut found_ignore_file = false;
+        for ignore_file_name in IGNORE_FILE_NAMES {
+            let ignore_file = folder.join(ignore_file_name);
+            if !ignore_file.is_file() {
+                continue;
+            }
+            found_ignore_file = true;
+            // Invalid lines are reported, but all remaining valid ones are still used
+            if let Some(e) = builder.add(&ignore_file) {
+                warnings.push(flc!("core_cannot_parse_ignore_file", file = ignore_file.to_string_lossy(), reason = e.to_string()));
+            }
+        }
+
+        if !found_ignore_file {
+            return parent.cloned();
+        }
+
+        match builder.build() {
+            Ok(matcher) if !matcher.is_empty() => Some(Arc::new(Self { matcher, parent: parent.cloned() })),
+            Ok(_) => parent.cloned(),
+            Err(e) => {
+                warnings.push(flc!("core_cannot_parse_ignore_file", file = folder.to_string_lossy(), reason = e.to_string()));
+                parent.cloned()
+            }
+        }
+    }
+
+    pub(crate) fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let mut rules = Some(self);
+        while let Some(current_rules) = rules {
+            match current_rules.matcher.matched(path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => rules = current_rules.parent.as_deref(),
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_ignore_rules_nested_folders() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let root = temp_dir.path();
+        let sub = root.join("sub");
+        fs::create_dir(&sub).expect("Failed to create dir");
+        fs::write(root.join(".gitignore"), "*.log\n/build\ntarget/\n").expect("Failed to write file");
+        fs::write(sub.join(".czkawkaignore"), "!keep.log\n").expect("Failed to write file");
+
+        let mut warnings = Vec::new();
+        let root_rules = IgnoreRules::for_folder(None, root, &mut warnings).expect("Rules should be loaded");
+        let sub_rules = IgnoreRules::for_folder(Some(&root_rules), &sub, &mut warnings).expect("Rules should be loaded");
+        assert!(warnings.is_empty());
+
+        assert!(root_rules.is_ignored(&root.join("a.log"), false));
+        assert!(root_rules.is_ignored(&root.join("build"), true));
+        assert!(root_rules.is_ignored(&root.join("target"), true));
+        assert!(!root_rules.is_ignored(&root.join("target"), false));
+        assert!(!root_rules.is_ignored(&root.join("a.txt"), false));
+
+        // Anchored pattern matches only in folder with ignore file
+        assert!(!sub_rules.is_ignored(&sub.join("build"), true));
+        assert!(sub_rules.is_ignored(&sub.join("target"), true));
+        assert!(sub_rules.is_ignored(&sub.join("other.log"), false));
+        assert!(!sub_rules.is_ignored(&sub.join("keep.log"), false));
+    }
+
+    #[test]
+    fn test_ignore_rules_without_ignore_files() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let mut warnings = Vec::new();
+        assert!(IgnoreRules::for_folder(None, temp_dir.path(), &mut warnings).is_none());
+
+        fs::write(temp_dir.path().join(".gitignore"), "# only comment\n").expect("Failed to write file");
+        assert!(IgnoreRules::for_folder(None, temp_dir.path(), &mut warnings).is_none());
+        assert!(warnings.is_empty());
+    }
+}
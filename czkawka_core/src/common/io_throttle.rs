@@ -0,0 +1,155 @@
+// Limiting of read speed and priority of threads reading files, to not slow down other programs during scan.
+// Settings are global, so GUI may change them while scan is running - they are checked inside reading loops.
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::debug;
+
+// Bytes per second, 0 - no limit
+static READ_SPEED_LIMIT: AtomicU64 = AtomicU64::new(0);
+static IDLE_PRIORITY: AtomicBool = AtomicBool::new(false);
+// Time at which next read may start, shared by all threads, so limit is applied to whole scan
+static NEXT_READ_TIME: LazyLock<Mutex<Instant>> = LazyLock::new(|| Mutex::new(Instant::now()));
+
+// Short sleeps allow to react quickly to changed limit
+const MAX_SLEEP_STEP: Duration = Duration::from_millis(50);
+// Time not used for reading, that may be used later for burst of reads
+const MAX_BURST_TIME: Duration = Duration::from_millis(500);
+
+thread_local! {
+    static THREAD_IDLE_PRIORITY: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn set_read_speed_limit(bytes_per_second: u64) {
+    let previous = READ_SPEED_LIMIT.swap(bytes_per_second, Ordering::Relaxed);
+    if previous != bytes_per_second {
+        debug!("Read speed limit set to {bytes_per_second} B/s (0 - no limit)");
+    }
+}
+
+pub fn get_read_speed_limit() -> u64 {
+    READ_SPEED_LIMIT.load(Ordering::Relaxed)
+}
+
+// Lowers CPU(nice) and IO(ioprio, only Linux) priority of threads reading files
+// Disabling it restores IO priority, but raising nice back requires CAP_SYS_NICE(or high enough RLIMIT_NICE),
+// so usually threads which already read files keep lowest CPU priority until process ends
+pub fn set_idle_priority(idle_priority: bool) {
+    let previous = IDLE_PRIORITY.swap(idle_priority, Ordering::Relaxed);
+    if previous != idle_priority {
+        debug!("Idle priority of reading threads set to {idle_priority}");
+    }
+}
+
+pub fn get_idle_priority() -> bool {
+    IDLE_PRIORITY.load(Ordering::Relaxed)
+}
+
+// Should be called after every read of file content, blocks thread when reads are faster than limit
+pub(crate) fn throttle_read(bytes: u64) {
+    update_thread_priority();
+
+    let limit = get_read_speed_limit();
+    if limit == 0 || bytes == 0 {
+        return;
+    }
+
+    let read_duration = Duration::from_secs_f64(bytes as f64 / limit as f64);
+    let wait_until = {
+        let mut next_read_time = NEXT_READ_TIME.lock().expect("Cannot fail");
+        let now = Instant::now();
+        let start = (*next_read_time).max(now.checked_sub(MAX_BURST_TIME).unwrap_or(now));
+        *next_read_time = start + read_duration;
+        *next_read_time
+    };
+
+    while let Some(remaining) = wait_until.checked_duration_since(Instant::now()) {
+        if remaining.is_zero() || get_read_speed_limit() != limit {
+            break;
+        }
+        thread::sleep(remaining.min(MAX_SLEEP_STEP));
+    }
+}
+
+fn update_thread_priority() {
+    let idle_priority = get_idle_priority();
+    THREAD_IDLE_PRIORITY.with(|thread_idle_priority| {
+        if thread_idle_priority.get() != idle_priority {
+            set_current_thread_priority(idle_priority);
+            thread_idle_priority.set(idle_priority);
+        }
+    });
+}
+
+// On Linux nice and ioprio are set per thread, on other Unix systems nice applies to whole process
+#[cfg(target_family = "unix")]
+fn set_current_thread_priority(idle_priority: bool) {
+    const NICE_IDLE: libc::c_int = 19;
+    const NICE_NORMAL: libc::c_int = 0;
+
+    let nice = if idle_priority { NICE_IDLE } else { NICE_NORMAL };
+    // SAFETY: setpriority only changes priority of current thread/process and doesn't touch memory
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) };
+    // Increasing priority requires CAP_SYS_NICE or RLIMIT_NICE allowing it, so thread may stay with nice 19 after disabling idle priority
+    if result != 0 {
+        debug!("Cannot set nice value to {nice} - {}", std::io::Error::last_os_error());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+        const CURRENT_THREAD: libc::c_long = 0;
+        const IOPRIO_CLASS_SHIFT: libc::c_long = 13;
+        const IOPRIO_CLASS_BE: libc::c_long = 2;
+        const IOPRIO_CLASS_IDLE: libc::c_long = 3;
+        const IOPRIO_BE_NORMAL: libc::c_long = 4;
+
+        let ioprio = if idle_priority {
+            IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT
+        } else {
+            (IOPRIO_CLASS_BE << IOPRIO_CLASS_SHIFT) | IOPRIO_BE_NORMAL
+        };
+        // SAFETY: ioprio_set with who = 0 changes only IO priority of current thread and doesn't touch memory
+        let result = unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, CURRENT_THREAD, ioprio) };
+        if result != 0 {
+            debug!("Cannot set IO priority - {}", std::io::Error::last_os_error());
+        }
+    }
+}
+
+#[cfg(not(target_family = "unix"))]
+fn set_current_thread_priority(_idle_priority: bool) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Limit is global, so it is restored even when assertion fails, to not slow down other tests
+    struct RestoreReadSpeedLimit(u64);
+
+    impl Drop for RestoreReadSpeedLimit {
+        fn drop(&mut self) {
+            set_read_speed_limit(self.0);
+        }
+    }
+
+    #[test]
+    fn test_throttle_read() {
+        let _restore = RestoreReadSpeedLimit(get_read_speed_limit());
+        set_read_speed_limit(10 * 1024 * 1024);
+        let start = Instant::now();
+        // Burst time is available only after being idle, so first reads are limited after it is used
+        for _ in 0..10 {
+            throttle_read(1024 * 1024);
+        }
+        set_read_speed_limit(0);
+        assert!(start.elapsed() >= Duration::from_millis(400), "Elapsed {:?}", start.elapsed());
+
+        let start = Instant::now();
+        throttle_read(100 * 1024 * 1024);
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,234 @@
+// Rules choosing item kept in each group of duplicates or similar files
+// Rules are checked in order, next rule is used only when items are equal according to all previous ones
+use std::cmp::Ordering;
+use std::fmt;
+use std::path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::common::tool_data::DeleteMethod;
+use crate::common::traits::ResultEntry;
+
+pub const KEEP_RULES_HELP: &str = "path:FOLDER, shortest-path, longest-path, resolution, bitrate, no-copy, oldest, newest, biggest, smallest";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeepRule {
+    PathInside(PathBuf),
+    ShortestPath,
+    LongestPath,
+    HighestResolution,
+    HighestBitrate,
+    WithoutCopyMarkers,
+    Oldest,
+    Newest,
+    Biggest,
+    Smallest,
+}
+
+// Properties, which are known only for some tools, for other ones all items are equal
+pub trait KeepRuleEntry: ResultEntry {
+    fn get_resolution(&self) -> Option<u64> {
+        None
+    }
+    fn get_bitrate(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl FromStr for KeepRule {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let src = src.trim();
+        if let Some(folder) = src.strip_prefix("path:") {
+            if folder.is_empty() {
+                return Err("Keep rule \"path:\" requires folder".to_string());
+            }
+            // Paths of found items are absolute, so relative folder would never match any of them
+            let folder = PathBuf::from(folder);
+            return Ok(Self::PathInside(path::absolute(&folder).unwrap_or(folder)));
+        }
+        match src.to_ascii_lowercase().as_str() {
+            "shortest-path" => Ok(Self::ShortestPath),
+            "longest-path" => Ok(Self::LongestPath),
+            "resolution" => Ok(Self::HighestResolution),
+            "bitrate" => Ok(Self::HighestBitrate),
+            "no-copy" => Ok(Self::WithoutCopyMarkers),
+            "oldest" => Ok(Self::Oldest),
+            "newest" => Ok(Self::Newest),
+            "biggest" => Ok(Self::Biggest),
+            "smallest" => Ok(Self::Smallest),
+            _ => Err(format!("Couldn't parse keep rule \"{src}\" (allowed: {KEEP_RULES_HELP})")),
+        }
+    }
+}
+
+impl fmt::Display for KeepRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PathInside(folder) => write!(f, "path:{}", folder.to_string_lossy()),
+            Self::ShortestPath => write!(f, "shortest-path"),
+            Self::LongestPath => write!(f, "longest-path"),
+            Self::HighestResolution => write!(f, "resolution"),
+            Self::HighestBitrate => write!(f, "bitrate"),
+            Self::WithoutCopyMarkers => write!(f, "no-copy"),
+            Self::Oldest => write!(f, "oldest"),
+            Self::Newest => write!(f, "newest"),
+            Self::Biggest => write!(f, "biggest"),
+            Self::Smallest => write!(f, "smallest"),
+        }
+    }
+}
+
+impl KeepRule {
+    // Rule matching item kept by delete method, used when all given rules consider items equal
+    // Hardlinks and reflinks use the oldest file as source
+    pub fn from_delete_method(delete_method: DeleteMethod) -> Option<Self> {
+        match delete_method {
+            DeleteMethod::AllExceptNewest => Some(Self::Newest),
+            DeleteMethod::AllExceptOldest | DeleteMethod::HardLink | DeleteMethod::Reflink => Some(Self::Oldest),
+            DeleteMethod::AllExceptBiggest => Some(Self::Biggest),
+            DeleteMethod::AllExceptSmallest => Some(Self::Smallest),
+            DeleteMethod::None | DeleteMethod::Delete | DeleteMethod::OneOldest | DeleteMethod::OneNewest | DeleteMethod::OneBiggest | DeleteMethod::OneSmallest => None,
+        }
+    }
+
+    // Less means that first item is preferred
+    fn compare<T: KeepRuleEntry>(&self, first: &T, second: &T) -> Ordering {
+        match self {
+            Self::PathInside(folder) => second.get_path().starts_with(folder).cmp(&first.get_path().starts_with(folder)),
+            Self::ShortestPath => path_length(first.get_path()).cmp(&path_length(second.get_path())),
+            Self::LongestPath => path_length(second.get_path()).cmp(&path_length(first.get_path())),
+            Self::HighestResolution => second.get_resolution().cmp(&first.get_resolution()),
+            Self::HighestBitrate => second.get_bitrate().cmp(&first.get_bitrate()),
+            Self::WithoutCopyMarkers => has_copy_marker(first.get_path()).cmp(&has_copy_marker(second.get_path())),
+            Self::Oldest => first.get_modified_date().cmp(&second.get_modified_date()),
+            Self::Newest => second.get_modified_date().cmp(&first.get_modified_date()),
+            Self::Biggest => second.get_size().cmp(&first.get_size()),
+            Self::Smallest => first.get_size().cmp(&second.get_size()),
+        }
+    }
+}
+
+// Rules separated by semicolons, e.g. "path:/srv/originals; no-copy; oldest"
+pub fn parse_keep_rules(src: &str) -> Result<Vec<KeepRule>, String> {
+    src.split(';').filter(|rule| !rule.trim().is_empty()).map(KeepRule::from_str).collect()
+}
+
+pub fn keep_rules_to_string(rules: &[KeepRule]) -> String {
+    rules.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
+fn path_length(path: &Path) -> usize {
+    path.as_os_str().len()
+}
+
+// Names like "photo - Copy.jpg", "photo_copy.jpg", "Copy of photo.jpg" or "photo(1).jpg" are usually created by file managers or browsers
+// Only these exact markers are checked, because "copy" is also part of names like "copyright.txt"
+fn has_copy_marker(path: &Path) -> bool {
+    let Some(stem) = path.file_stem() else {
+        return false;
+    };
+    let stem = stem.to_string_lossy().to_lowercase();
+    if stem.starts_with("copy of ") {
+        return true;
+    }
+    let Some(name) = strip_copy_number(&stem) else {
+        return stem.ends_with(" - copy") || stem.ends_with("_copy");
+    };
+    // Name without number, e.g. "(1).jpg", is not a copy of anything
+    !name.trim_end().is_empty()
+}
+
+// Returns name without number in brackets at the end, e.g. "photo" for "photo (12)"
+fn strip_copy_number(stem: &str) -> Option<&str> {
+    let (name, number) = stem.trim_end().strip_suffix(')')?.rsplit_once('(')?;
+    (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit())).then_some(name)
+}
+
+// Index of item, which should be kept, the first one is chosen when items are equal according to all rules
+pub fn choose_item_to_keep<T: KeepRuleEntry>(rules: &[KeepRule], items: &[T]) -> Option<usize> {
+    items
+        .iter()
+        .enumerate()
+        .min_by(|(_, first), (_, second)| {
+            rules
+                .iter()
+                .map(|rule| rule.compare(*first, *second))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        })
+        .map(|(idx, _)| idx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::model::FileEntry;
+
+    fn entry(path: &str, size: u64, modified_date: u64) -> FileEntry {
+        FileEntry {
+            path: PathBuf::from(path),
+            size,
+            modified_date,
+        }
+    }
+
+    #[test]
+    fn test_parse_keep_rules() {
+        let rules = parse_keep_rules("path:/srv/originals; no-copy;OLDEST;").unwrap();
+        assert_eq!(
+            rules,
+            vec![KeepRule::PathInside(PathBuf::from("/srv/originals")), KeepRule::WithoutCopyMarkers, KeepRule::Oldest]
+        );
+        assert_eq!(keep_rules_to_string(&rules), "path:/srv/originals; no-copy; oldest");
+        parse_keep_rules("path:").unwrap_err();
+
+        // Relative folder is resolved against current folder, so it can match absolute paths of found items
+        let rules = parse_keep_rules("path:originals").unwrap();
+        let expected_folder = std::env::current_dir().unwrap().join("originals");
+        assert_eq!(rules, vec![KeepRule::PathInside(expected_folder.clone())]);
+        let items = vec![entry("/tmp/photo.jpg", 10, 1), entry(&expected_folder.join("photo.jpg").to_string_lossy(), 10, 1)];
+        assert_eq!(choose_item_to_keep(&rules, &items), Some(1));
+        parse_keep_rules("oldest; unknown").unwrap_err();
+    }
+
+    #[test]
+    fn test_has_copy_marker() {
+        assert!(has_copy_marker(Path::new("/a/photo - Copy.jpg")));
+        assert!(has_copy_marker(Path::new("/a/photo_copy.jpg")));
+        assert!(has_copy_marker(Path::new("/a/photo (12).jpg")));
+        assert!(has_copy_marker(Path::new("/a/photo - Copy (2).jpg")));
+        assert!(has_copy_marker(Path::new("/a/Copy of photo.jpg")));
+        assert!(!has_copy_marker(Path::new("/a/photo.jpg")));
+        assert!(!has_copy_marker(Path::new("/a/photo (old).jpg")));
+        assert!(!has_copy_marker(Path::new("/a/()")));
+        assert!(!has_copy_marker(Path::new("/a/(1).jpg")));
+
+        // Names only containing "copy" are not copies
+        assert!(!has_copy_marker(Path::new("/a/copyright.txt")));
+        assert!(!has_copy_marker(Path::new("/a/photocopy.pdf")));
+        assert!(!has_copy_marker(Path::new("/a/copy_of_contract_signed.pdf")));
+        assert!(!has_copy_marker(Path::new("/a/copy.txt")));
+    }
+
+    #[test]
+    fn test_choose_item_to_keep() {
+        let items = vec![
+            entry("/home/downloads/photo(1).jpg", 10, 1),
+            entry("/srv/originals/photo - copy.jpg", 10, 3),
+            entry("/srv/originals/photo.jpg", 10, 2),
+            entry("/srv/originals/2020/photo.jpg", 10, 1),
+        ];
+        let rules = parse_keep_rules("path:/srv/originals; no-copy; newest").unwrap();
+        assert_eq!(choose_item_to_keep(&rules, &items), Some(2));
+        let rules = parse_keep_rules("no-copy; oldest").unwrap();
+        assert_eq!(choose_item_to_keep(&rules, &items), Some(3));
+        let rules = parse_keep_rules("longest-path").unwrap();
+        assert_eq!(choose_item_to_keep(&rules, &items), Some(1));
+
+        // Equal items, so the first one is kept
+        assert_eq!(choose_item_to_keep(&[KeepRule::HighestResolution, KeepRule::Biggest], &items), Some(0));
+        assert_eq!(choose_item_to_keep::<FileEntry>(&[KeepRule::Oldest], &[]), None);
+    }
+}
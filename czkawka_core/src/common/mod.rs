@@ -1,22 +1,37 @@
+pub mod archive;
 pub mod audio_fingerprint;
 pub mod basic_gui_cli;
 pub mod build_runtime_info;
 pub mod cache;
+pub mod checkpoint;
 pub mod config_cache_path;
 pub mod consts;
+pub mod device_io;
 pub mod dir_traversal;
 pub mod directories;
 pub mod extensions;
 pub mod ffmpeg_utils;
+pub mod file_index;
+pub mod flat_results;
+pub mod hash_manifest;
+pub mod ignore_files;
 pub mod image;
+pub mod io_throttle;
 pub mod items;
+pub mod keep_rules;
 pub mod logger;
 pub mod model;
+pub mod ownership;
+pub mod path_list;
 pub mod process_utils;
 pub mod progress_data;
 pub mod progress_stop_handler;
+pub mod quarantine;
+pub mod saved_results;
+pub mod spill_store;
 pub mod tool_data;
 pub mod traits;
+pub mod undo_journal;
 pub mod video_utils;
 
 pub mod deletion;
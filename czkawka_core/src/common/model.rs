@@ -3,12 +3,15 @@ use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 use xxhash_rust::xxh3::Xxh3;
 
+use crate::common::flat_results::FlatLayout;
+use crate::common::keep_rules::KeepRuleEntry;
 use crate::common::traits::ResultEntry;
 use crate::tools::duplicate::MyHasher;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Deserialize, Serialize)]
 pub enum ToolType {
     Duplicate,
+    DuplicateFolders,
     EmptyFolders,
     EmptyFiles,
     InvalidSymlinks,
@@ -22,13 +25,37 @@ pub enum ToolType {
     TemporaryFiles,
     ExifRemover,
     VideoOptimizer,
+    IntegrityChecker,
+    DirectoryComparison,
     #[default]
     None,
 }
 
 impl ToolType {
     pub fn may_use_reference_paths(self) -> bool {
-        matches!(self, Self::Duplicate | Self::SameMusic | Self::SimilarImages | Self::SimilarVideos)
+        matches!(self, Self::Duplicate | Self::DuplicateFolders | Self::SameMusic | Self::SimilarImages | Self::SimilarVideos)
+    }
+
+    // Describes how groups and sections are stored in saved results, used by flat exports and when loading saved results
+    pub fn flat_layout(self) -> FlatLayout {
+        match self {
+            Self::Duplicate | Self::SameMusic | Self::SimilarImages | Self::SimilarVideos => FlatLayout::Groups,
+            Self::DuplicateFolders => FlatLayout::Sections {
+                without_references: &["subset_folders"],
+            },
+            Self::IntegrityChecker | Self::DirectoryComparison => FlatLayout::Sections { without_references: &[] },
+            Self::EmptyFolders
+            | Self::EmptyFiles
+            | Self::InvalidSymlinks
+            | Self::BrokenFiles
+            | Self::BadExtensions
+            | Self::BadNames
+            | Self::BigFile
+            | Self::TemporaryFiles
+            | Self::ExifRemover
+            | Self::VideoOptimizer
+            | Self::None => FlatLayout::Items,
+        }
     }
 }
 
@@ -40,6 +67,8 @@ pub enum CheckingMethod {
     SizeName,
     Size,
     Hash,
+    ContentChunks,
+    SampledHash,
     AudioTags,
     AudioContent,
     VideoAudioContent,
@@ -53,6 +82,8 @@ pub struct FileEntry {
     pub modified_date: u64,
 }
 
+impl KeepRuleEntry for FileEntry {}
+
 impl ResultEntry for FileEntry {
     fn get_path(&self) -> &Path {
         &self.path
@@ -71,14 +102,43 @@ pub enum HashType {
     Blake3,
     Crc32,
     Xxh3,
+    Sha256,
+    // BLAKE3 with secret 32 byte key, the same as `b3sum --keyed`
+    Blake3Keyed(HashKey),
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct HashKey(pub [u8; 32]);
+
+// Key must not be visible in logs or names of cache files, so only its short fingerprint is printed
+impl std::fmt::Debug for HashKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.fingerprint())
+    }
+}
+
+impl HashKey {
+    pub fn fingerprint(&self) -> String {
+        blake3::hash(&self.0).to_hex().chars().take(16).collect()
+    }
 }
 
 impl HashType {
+    // Used in names of cache files, hashes calculated with different keys are kept in different files
+    pub fn cache_name(self) -> String {
+        match self {
+            Self::Blake3Keyed(key) => format!("Blake3Keyed_{}", key.fingerprint()),
+            _ => format!("{self:?}"),
+        }
+    }
+
     pub(crate) fn hasher(self) -> Box<dyn MyHasher> {
         match self {
             Self::Blake3 => Box::new(blake3::Hasher::new()),
+            Self::Blake3Keyed(key) => Box::new(blake3::Hasher::new_keyed(&key.0)),
             Self::Crc32 => Box::new(crc32fast::Hasher::new()),
             Self::Xxh3 => Box::new(Xxh3::new()),
+            Self::Sha256 => Box::new(sha2::Sha256::default()),
         }
     }
 }
@@ -114,11 +174,24 @@ mod tests {
         let blake3_hasher = HashType::Blake3.hasher();
         let crc32_hasher = HashType::Crc32.hasher();
         let xxh3_hasher = HashType::Xxh3.hasher();
+        let sha256_hasher = HashType::Sha256.hasher();
+        let blake3_keyed_hasher = HashType::Blake3Keyed(HashKey([1; 32])).hasher();
 
         // Just verify they can be created
         assert!(std::mem::size_of_val(&blake3_hasher) > 0);
         assert!(std::mem::size_of_val(&crc32_hasher) > 0);
         assert!(std::mem::size_of_val(&xxh3_hasher) > 0);
+        assert!(std::mem::size_of_val(&sha256_hasher) > 0);
+        assert!(std::mem::size_of_val(&blake3_keyed_hasher) > 0);
+    }
+
+    #[test]
+    fn test_hash_key_is_not_visible_in_cache_name() {
+        let hash_type = HashType::Blake3Keyed(HashKey([0xAB; 32]));
+        assert!(!hash_type.cache_name().contains("171,"));
+        assert!(!format!("{hash_type:?}").contains("171,"));
+        assert_ne!(hash_type.cache_name(), HashType::Blake3Keyed(HashKey([0xAC; 32])).cache_name());
+        assert_eq!(HashType::Sha256.cache_name(), "Sha256");
     }
 
     #[test]
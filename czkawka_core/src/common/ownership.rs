@@ -0,0 +1,164 @@
+// Filtering files by owner, group and permissions - used only on Unix-like systems, on other systems filters are ignored
+#[cfg(target_family = "unix")]
+use std::ffi::CString;
+use std::fs::Metadata;
+#[cfg(target_family = "unix")]
+use std::os::unix::ffi::OsStrExt;
+#[cfg(target_family = "unix")]
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OwnershipFilter {
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+    // All of these permission bits must be set, like in `find -perm -MODE`
+    pub required_mode: Option<u32>,
+    // Files inside folders without write permission cannot be deleted or moved by current user
+    pub skip_not_deletable: bool,
+}
+
+impl OwnershipFilter {
+    pub fn is_active(&self) -> bool {
+        self.uid.is_some() || self.gid.is_some() || self.required_mode.is_some() || self.skip_not_deletable
+    }
+
+    pub(crate) fn matches(&self, uid: u32, gid: u32, mode: u32) -> bool {
+        self.uid.is_none_or(|required_uid| required_uid == uid)
+            && self.gid.is_none_or(|required_gid| required_gid == gid)
+            && self.required_mode.is_none_or(|required_mode| mode & required_mode == required_mode)
+    }
+
+    pub(crate) fn matches_metadata(&self, metadata: &Metadata) -> bool {
+        let (uid, gid, mode) = get_file_ownership(metadata);
+        self.matches(uid, gid, mode)
+    }
+
+    pub(crate) fn allows_files_in_folder(&self, folder: &Path) -> bool {
+        !self.skip_not_deletable || can_modify_folder_content(folder)
+    }
+}
+
+// Lists only filters which are set, e.g. "owner uid 1000, permissions containing 644"
+impl std::fmt::Display for OwnershipFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut filters = Vec::new();
+        if let Some(uid) = self.uid {
+            filters.push(format!("owner uid {uid}"));
+        }
+        if let Some(gid) = self.gid {
+            filters.push(format!("group gid {gid}"));
+        }
+        if let Some(required_mode) = self.required_mode {
+            filters.push(format!("permissions containing {required_mode:o}"));
+        }
+        if self.skip_not_deletable {
+            filters.push("deletable by current user".to_string());
+        }
+        write!(f, "{}", filters.join(", "))
+    }
+}
+
+// Owner, group and permission bits of file, on non-Unix systems always zeros, which are never checked there
+#[cfg(target_family = "unix")]
+pub(crate) fn get_file_ownership(metadata: &Metadata) -> (u32, u32, u32) {
+    (metadata.uid(), metadata.gid(), metadata.mode())
+}
+
+#[cfg(not(target_family = "unix"))]
+pub(crate) fn get_file_ownership(_metadata: &Metadata) -> (u32, u32, u32) {
+    (0, 0, 0)
+}
+
+#[cfg(target_family = "unix")]
+fn can_modify_folder_content(folder: &Path) -> bool {
+    let Ok(folder_c) = CString::new(folder.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // access respects ACLs and root privileges, which would be hard to check only by permission bits
+    // SAFETY: pointer comes from CString that outlives the call
+    unsafe { libc::access(folder_c.as_ptr(), libc::W_OK | libc::X_OK) == 0 }
+}
+
+#[cfg(not(target_family = "unix"))]
+fn can_modify_folder_content(_folder: &Path) -> bool {
+    true
+}
+
+// Accepts numeric id or name of user
+#[cfg(target_family = "unix")]
+pub fn resolve_user(user: &str) -> Option<u32> {
+    if let Ok(uid) = user.parse::<u32>() {
+        return Some(uid);
+    }
+    let user_c = CString::new(user).ok()?;
+    // SAFETY: pointer comes from CString that outlives the call, returned struct is read before any other getpw* call in this thread
+    let passwd = unsafe { libc::getpwnam(user_c.as_ptr()) };
+    // SAFETY: getpwnam returns null or pointer to valid passwd struct
+    unsafe { passwd.as_ref() }.map(|passwd| passwd.pw_uid)
+}
+
+// Accepts numeric id or name of group
+#[cfg(target_family = "unix")]
+pub fn resolve_group(group: &str) -> Option<u32> {
+    if let Ok(gid) = group.parse::<u32>() {
+        return Some(gid);
+    }
+    let group_c = CString::new(group).ok()?;
+    // SAFETY: pointer comes from CString that outlives the call, returned struct is read before any other getgr* call in this thread
+    let group = unsafe { libc::getgrnam(group_c.as_ptr()) };
+    // SAFETY: getgrnam returns null or pointer to valid group struct
+    unsafe { group.as_ref() }.map(|group| group.gr_gid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ownership_filter_matches() {
+        let filter = OwnershipFilter::default();
+        assert!(!filter.is_active());
+        assert!(filter.matches(1000, 1000, 0o644));
+
+        let filter = OwnershipFilter {
+            uid: Some(1000),
+            gid: None,
+            required_mode: Some(0o060),
+            skip_not_deletable: false,
+        };
+        assert!(filter.is_active());
+        assert!(filter.matches(1000, 5, 0o664));
+        assert!(!filter.matches(1000, 5, 0o644));
+        assert!(!filter.matches(0, 5, 0o664));
+        assert_eq!(filter.to_string(), "owner uid 1000, permissions containing 60");
+
+        let filter = OwnershipFilter {
+            gid: Some(100),
+            skip_not_deletable: true,
+            ..Default::default()
+        };
+        assert_eq!(filter.to_string(), "group gid 100, deletable by current user");
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_resolve_user_and_group() {
+        assert_eq!(resolve_user("0"), Some(0));
+        assert_eq!(resolve_user("root"), Some(0));
+        assert_eq!(resolve_group("0"), Some(0));
+        assert_eq!(resolve_user("czkawka_not_existing_user"), None);
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_skip_not_deletable() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+        let filter = OwnershipFilter {
+            skip_not_deletable: true,
+            ..Default::default()
+        };
+        assert!(filter.allows_files_in_folder(temp_dir.path()));
+        assert!(!filter.allows_files_in_folder(&temp_dir.path().join("not_existing")));
+    }
+}
@@ -0,0 +1,56 @@
+// Reading of path lists produced by other tools e.g. `find -print0`, `fd` or `git ls-files -z`
+// Paths from such list should be used as included paths, so they are filtered like any other scanned file
+use std::io;
+use std::io::Read;
+use std::path::PathBuf;
+
+// When list contains NUL character, it is used as separator, otherwise paths are separated by new lines
+pub fn parse_path_list(content: &[u8]) -> Vec<PathBuf> {
+    let separator = if content.contains(&b'\0') { b'\0' } else { b'\n' };
+
+    content
+        .split(|&c| c == separator)
+        .map(|path| if separator == b'\n' { path.strip_suffix(b"\r").unwrap_or(path) } else { path })
+        .filter(|path| !path.is_empty())
+        .map(path_from_bytes)
+        .collect()
+}
+
+pub fn read_path_list<R: Read>(mut reader: R) -> io::Result<Vec<PathBuf>> {
+    let mut content = Vec::new();
+    reader.read_to_end(&mut content)?;
+    Ok(parse_path_list(&content))
+}
+
+#[cfg(target_family = "unix")]
+fn path_from_bytes(path: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(path))
+}
+
+#[cfg(not(target_family = "unix"))]
+fn path_from_bytes(path: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(path).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path_list_new_lines() {
+        let paths = parse_path_list(b"/a/b.txt\r\n./c d.txt\n\n/e\n");
+        assert_eq!(paths, vec![PathBuf::from("/a/b.txt"), PathBuf::from("./c d.txt"), PathBuf::from("/e")]);
+    }
+
+    #[test]
+    fn test_parse_path_list_nul_separated() {
+        let paths = parse_path_list(b"/a/new\nline.txt\0/b.txt\0");
+        assert_eq!(paths, vec![PathBuf::from("/a/new\nline.txt"), PathBuf::from("/b.txt")]);
+    }
+
+    #[test]
+    fn test_read_path_list_empty() {
+        assert!(read_path_list(&b""[..]).expect("Reading from slice cannot fail").is_empty());
+    }
+}
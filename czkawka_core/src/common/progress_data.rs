@@ -15,6 +15,27 @@ pub enum DuplicateStage {
     LoadingPreHashCache(CacheLoadPhase),
     PreHashing,
     SavingPreHashCache,
+    LoadingHashCache(CacheLoadPhase),
+    FullHashing,
+    SampledHashing,
+    SavingHashCache,
+    VerifyingFiles,
+    CalculatingChunks,
+    ComparingChunks,
+    ReadingArchives,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DuplicateFoldersStage {
+    LoadingHashCache(CacheLoadPhase),
+    FullHashing,
+    SavingHashCache,
+    ComparingFolders,
+    VerifyingFolders,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DirectoryComparisonStage {
     LoadingHashCache(CacheLoadPhase),
     FullHashing,
     SavingHashCache,
@@ -85,11 +106,14 @@ pub enum ToolStage {
     RenamingFiles,
     MovingFiles,
     HardlinkingFiles,
+    ReflinkingFiles,
     SymlinkingFiles,
     OptimizingVideos,
     CleaningExif,
 
     Duplicate(DuplicateStage),
+    DuplicateFolders(DuplicateFoldersStage),
+    DirectoryComparison(DirectoryComparisonStage),
     SameMusic(SameMusicMode, SameMusicStage),
     SimilarImages(SimilarImagesStage),
     SimilarVideos(SimilarVideosMode, SimilarVideosStage),
@@ -100,13 +124,21 @@ pub enum ToolStage {
     BadExtensionsChecking,
     BadNamesChecking,
     EmptyFilesCheckingContent,
+    IntegrityChecking,
 }
 
 impl ToolStage {
     pub fn is_special_non_tool_stage(self) -> bool {
         matches!(
             self,
-            Self::DeletingFiles | Self::RenamingFiles | Self::MovingFiles | Self::HardlinkingFiles | Self::SymlinkingFiles | Self::OptimizingVideos | Self::CleaningExif
+            Self::DeletingFiles
+                | Self::RenamingFiles
+                | Self::MovingFiles
+                | Self::HardlinkingFiles
+                | Self::ReflinkingFiles
+                | Self::SymlinkingFiles
+                | Self::OptimizingVideos
+                | Self::CleaningExif
         )
     }
 
@@ -118,6 +150,8 @@ impl ToolStage {
         matches!(
             self,
             Self::Duplicate(DuplicateStage::LoadingPreHashCache(_) | DuplicateStage::LoadingHashCache(_))
+                | Self::DuplicateFolders(DuplicateFoldersStage::LoadingHashCache(_))
+                | Self::DirectoryComparison(DirectoryComparisonStage::LoadingHashCache(_))
                 | Self::SameMusic(_, SameMusicStage::LoadingTagsCache(_) | SameMusicStage::LoadingFingerprintCache(_))
                 | Self::ExifRemover(ExifRemoverStage::LoadingCache(_))
                 | Self::SimilarVideos(_, SimilarVideosStage::LoadingAudioCache(_))
@@ -128,6 +162,8 @@ impl ToolStage {
         matches!(
             self,
             Self::Duplicate(DuplicateStage::SavingPreHashCache | DuplicateStage::SavingHashCache)
+                | Self::DuplicateFolders(DuplicateFoldersStage::SavingHashCache)
+                | Self::DirectoryComparison(DirectoryComparisonStage::SavingHashCache)
                 | Self::SameMusic(_, SameMusicStage::SavingTagsCache | SameMusicStage::SavingFingerprintCache)
                 | Self::ExifRemover(ExifRemoverStage::SavingCache)
                 | Self::SimilarVideos(_, SimilarVideosStage::SavingAudioCache)
@@ -145,6 +181,8 @@ impl ToolStage {
                         | DuplicateStage::SavingPreHashCache
                         | DuplicateStage::SavingHashCache,
                 )
+                | Self::DuplicateFolders(DuplicateFoldersStage::LoadingHashCache(CacheLoadPhase::Loading) | DuplicateFoldersStage::SavingHashCache)
+                | Self::DirectoryComparison(DirectoryComparisonStage::LoadingHashCache(CacheLoadPhase::Loading) | DirectoryComparisonStage::SavingHashCache)
                 | Self::SameMusic(
                     _,
                     SameMusicStage::LoadingTagsCache(CacheLoadPhase::Loading)
@@ -160,13 +198,17 @@ impl ToolStage {
     pub fn uses_bytes(self) -> bool {
         matches!(
             self,
-            Self::Duplicate(DuplicateStage::PreHashing | DuplicateStage::FullHashing)
+            Self::Duplicate(
+                DuplicateStage::PreHashing | DuplicateStage::FullHashing | DuplicateStage::SampledHashing | DuplicateStage::VerifyingFiles | DuplicateStage::CalculatingChunks
+            ) | Self::DuplicateFolders(DuplicateFoldersStage::FullHashing | DuplicateFoldersStage::VerifyingFolders)
+                | Self::DirectoryComparison(DirectoryComparisonStage::FullHashing)
                 | Self::SimilarImages(SimilarImagesStage::CalculatingHashes)
                 | Self::SameMusic(_, SameMusicStage::CalculatingFingerprints)
                 | Self::SimilarVideos(_, SimilarVideosStage::CalculatingAudioFingerprints)
                 | Self::ExifRemover(ExifRemoverStage::ExtractingTags)
                 | Self::BrokenFilesChecking
                 | Self::EmptyFilesCheckingContent
+                | Self::IntegrityChecking
                 | Self::VideoOptimizer(VideoOptimizerStage::ProcessingVideos)
         )
     }
@@ -179,18 +221,34 @@ impl ToolStage {
             | Self::RenamingFiles
             | Self::MovingFiles
             | Self::HardlinkingFiles
+            | Self::ReflinkingFiles
             | Self::SymlinkingFiles
             | Self::OptimizingVideos
             | Self::CleaningExif => 0,
 
             Self::Duplicate(s) => match s {
-                DuplicateStage::HidingHardLinks => 1,
-                DuplicateStage::LoadingPreHashCache(_) => 2,
+                DuplicateStage::HidingHardLinks | DuplicateStage::CalculatingChunks | DuplicateStage::ReadingArchives => 1,
+                DuplicateStage::LoadingPreHashCache(_) | DuplicateStage::ComparingChunks => 2,
                 DuplicateStage::PreHashing => 3,
                 DuplicateStage::SavingPreHashCache => 4,
                 DuplicateStage::LoadingHashCache(_) => 5,
-                DuplicateStage::FullHashing => 6,
+                DuplicateStage::FullHashing | DuplicateStage::SampledHashing => 6,
                 DuplicateStage::SavingHashCache => 7,
+                DuplicateStage::VerifyingFiles => 8,
+            },
+
+            Self::DuplicateFolders(s) => match s {
+                DuplicateFoldersStage::LoadingHashCache(_) => 1,
+                DuplicateFoldersStage::FullHashing => 2,
+                DuplicateFoldersStage::SavingHashCache => 3,
+                DuplicateFoldersStage::ComparingFolders => 4,
+                DuplicateFoldersStage::VerifyingFolders => 5,
+            },
+
+            Self::DirectoryComparison(s) => match s {
+                DirectoryComparisonStage::LoadingHashCache(_) => 1,
+                DirectoryComparisonStage::FullHashing => 2,
+                DirectoryComparisonStage::SavingHashCache => 3,
             },
 
             Self::SameMusic(mode, s) => match (mode, s) {
@@ -236,7 +294,7 @@ impl ToolStage {
                 VideoOptimizerStage::CreatingThumbnails => 2,
             },
 
-            Self::BrokenFilesChecking | Self::BadExtensionsChecking | Self::BadNamesChecking | Self::EmptyFilesCheckingContent => 1,
+            Self::BrokenFilesChecking | Self::BadExtensionsChecking | Self::BadNamesChecking | Self::EmptyFilesCheckingContent | Self::IntegrityChecking => 1,
         }
     }
 
@@ -248,15 +306,18 @@ impl ToolStage {
             | Self::RenamingFiles
             | Self::MovingFiles
             | Self::HardlinkingFiles
+            | Self::ReflinkingFiles
             | Self::SymlinkingFiles
             | Self::OptimizingVideos
             | Self::CleaningExif => 0,
-            Self::Duplicate(_) | Self::SameMusic(SameMusicMode::AudioContent, _) => 7,
+            Self::Duplicate(DuplicateStage::CalculatingChunks | DuplicateStage::ComparingChunks) | Self::VideoOptimizer(_) => 2,
+            Self::Duplicate(_) => 8,
+            Self::SameMusic(SameMusicMode::AudioContent, _) => 7,
             Self::SameMusic(SameMusicMode::AudioTags, _) => 4,
-            Self::SimilarImages(_) | Self::ExifRemover(_) | Self::SimilarVideos(SimilarVideosMode::VisualHash, _) => 3,
+            Self::DuplicateFolders(_) => 5,
+            Self::SimilarImages(_) | Self::ExifRemover(_) | Self::SimilarVideos(SimilarVideosMode::VisualHash, _) | Self::DirectoryComparison(_) => 3,
             Self::SimilarVideos(SimilarVideosMode::AudioContent, _) => 6,
-            Self::VideoOptimizer(_) => 2,
-            Self::BrokenFilesChecking | Self::BadExtensionsChecking | Self::BadNamesChecking | Self::EmptyFilesCheckingContent => 1,
+            Self::BrokenFilesChecking | Self::BadExtensionsChecking | Self::BadNamesChecking | Self::EmptyFilesCheckingContent | Self::IntegrityChecking => 1,
         }
     }
 }
@@ -365,8 +426,12 @@ impl ProgressData {
             // Cache load/save (granular per cache kind, indeterminate)
             ToolStage::Duplicate(DuplicateStage::LoadingPreHashCache(CacheLoadPhase::Loading)) => flc!("stage_loading_prehash_cache"),
             ToolStage::Duplicate(DuplicateStage::SavingPreHashCache) => flc!("stage_saving_prehash_cache"),
-            ToolStage::Duplicate(DuplicateStage::LoadingHashCache(CacheLoadPhase::Loading)) => flc!("stage_loading_hash_cache"),
-            ToolStage::Duplicate(DuplicateStage::SavingHashCache) => flc!("stage_saving_hash_cache"),
+            ToolStage::Duplicate(DuplicateStage::LoadingHashCache(CacheLoadPhase::Loading))
+            | ToolStage::DuplicateFolders(DuplicateFoldersStage::LoadingHashCache(CacheLoadPhase::Loading))
+            | ToolStage::DirectoryComparison(DirectoryComparisonStage::LoadingHashCache(CacheLoadPhase::Loading)) => flc!("stage_loading_hash_cache"),
+            ToolStage::Duplicate(DuplicateStage::SavingHashCache)
+            | ToolStage::DuplicateFolders(DuplicateFoldersStage::SavingHashCache)
+            | ToolStage::DirectoryComparison(DirectoryComparisonStage::SavingHashCache) => flc!("stage_saving_hash_cache"),
             ToolStage::SameMusic(_, SameMusicStage::LoadingTagsCache(CacheLoadPhase::Loading)) => flc!("stage_loading_tags_cache"),
             ToolStage::SameMusic(_, SameMusicStage::SavingTagsCache) => flc!("stage_saving_tags_cache"),
             ToolStage::SameMusic(_, SameMusicStage::LoadingFingerprintCache(CacheLoadPhase::Loading))
@@ -383,6 +448,8 @@ impl ProgressData {
                 _,
                 SameMusicStage::LoadingTagsCache(CacheLoadPhase::FilteringOutdated) | SameMusicStage::LoadingFingerprintCache(CacheLoadPhase::FilteringOutdated),
             )
+            | ToolStage::DuplicateFolders(DuplicateFoldersStage::LoadingHashCache(CacheLoadPhase::FilteringOutdated))
+            | ToolStage::DirectoryComparison(DirectoryComparisonStage::LoadingHashCache(CacheLoadPhase::FilteringOutdated))
             | ToolStage::SimilarVideos(_, SimilarVideosStage::LoadingAudioCache(CacheLoadPhase::FilteringOutdated))
             | ToolStage::ExifRemover(ExifRemoverStage::LoadingCache(CacheLoadPhase::FilteringOutdated)) => flc!("stage_filtering_outdated_cache"),
 
@@ -391,7 +458,18 @@ impl ProgressData {
             | ToolStage::SimilarImages(SimilarImagesStage::HidingHardLinks)
             | ToolStage::SimilarVideos(_, SimilarVideosStage::HidingHardLinks) => flc!("stage_hiding_links", items_stats = items_stats),
             ToolStage::Duplicate(DuplicateStage::PreHashing) => flc!("stage_analyzed_partial_hash", items_stats = items_stats, size_stats = size_stats),
-            ToolStage::Duplicate(DuplicateStage::FullHashing) => flc!("stage_analyzed_full_hash", items_stats = items_stats, size_stats = size_stats),
+            ToolStage::Duplicate(DuplicateStage::FullHashing)
+            | ToolStage::DuplicateFolders(DuplicateFoldersStage::FullHashing)
+            | ToolStage::DirectoryComparison(DirectoryComparisonStage::FullHashing) => {
+                flc!("stage_analyzed_full_hash", items_stats = items_stats, size_stats = size_stats)
+            }
+            ToolStage::Duplicate(DuplicateStage::SampledHashing) => flc!("stage_analyzed_sampled_hash", items_stats = items_stats, size_stats = size_stats),
+            ToolStage::Duplicate(DuplicateStage::VerifyingFiles) => flc!("stage_verified_files", items_stats = items_stats, size_stats = size_stats),
+            ToolStage::Duplicate(DuplicateStage::CalculatingChunks) => flc!("stage_calculated_chunks", items_stats = items_stats, size_stats = size_stats),
+            ToolStage::Duplicate(DuplicateStage::ComparingChunks) => flc!("stage_compared_chunks", items_stats = items_stats),
+            ToolStage::Duplicate(DuplicateStage::ReadingArchives) => flc!("stage_read_archives", items_stats = items_stats),
+            ToolStage::DuplicateFolders(DuplicateFoldersStage::ComparingFolders) => flc!("stage_compared_folders", items_stats = items_stats),
+            ToolStage::DuplicateFolders(DuplicateFoldersStage::VerifyingFolders) => flc!("stage_verified_folders", items_stats = items_stats, size_stats = size_stats),
             ToolStage::SameMusic(_, SameMusicStage::ReadingTags) => flc!("stage_checked_tags", items_stats = items_stats),
             ToolStage::SameMusic(_, SameMusicStage::ComparingTags) => flc!("stage_compared_tags", items_stats = items_stats),
             ToolStage::SameMusic(_, SameMusicStage::CalculatingFingerprints) | ToolStage::SimilarVideos(_, SimilarVideosStage::CalculatingAudioFingerprints) => {
@@ -411,6 +489,7 @@ impl ProgressData {
             ToolStage::BadExtensionsChecking => flc!("stage_checked_files_bad_extensions", items_stats = items_stats),
             ToolStage::BadNamesChecking => flc!("stage_checked_files_bad_names", items_stats = items_stats),
             ToolStage::EmptyFilesCheckingContent => flc!("stage_checking_empty_files_content", items_stats = items_stats, size_stats = size_stats),
+            ToolStage::IntegrityChecking => flc!("stage_checked_files_integrity", items_stats = items_stats, size_stats = size_stats),
 
             // File operations
             ToolStage::DeletingFiles if has_size => flc!("stage_deleting_files", items_stats = items_stats, size_stats = size_stats),
@@ -420,6 +499,8 @@ impl ProgressData {
             ToolStage::MovingFiles => flc!("stage_moving_no_size_files", items_stats = items_stats),
             ToolStage::HardlinkingFiles if has_size => flc!("stage_hardlinking_files", items_stats = items_stats, size_stats = size_stats),
             ToolStage::HardlinkingFiles => flc!("stage_hardlinking_no_size_files", items_stats = items_stats),
+            ToolStage::ReflinkingFiles if has_size => flc!("stage_reflinking_files", items_stats = items_stats, size_stats = size_stats),
+            ToolStage::ReflinkingFiles => flc!("stage_reflinking_no_size_files", items_stats = items_stats),
             ToolStage::SymlinkingFiles if has_size => flc!("stage_symlinking_files", items_stats = items_stats, size_stats = size_stats),
             ToolStage::SymlinkingFiles => flc!("stage_symlinking_no_size_files", items_stats = items_stats),
             ToolStage::OptimizingVideos if has_size => flc!("stage_optimizing_videos", items_stats = items_stats, size_stats = size_stats),
@@ -437,7 +518,7 @@ mod tests {
     #[test]
     fn test_stage_indices() {
         assert_eq!(ToolStage::Duplicate(DuplicateStage::FullHashing).current_stage_idx(), 6);
-        assert_eq!(ToolStage::Duplicate(DuplicateStage::FullHashing).max_stage_idx(), 7);
+        assert_eq!(ToolStage::Duplicate(DuplicateStage::FullHashing).max_stage_idx(), 8);
         assert_eq!(
             ToolStage::SameMusic(SameMusicMode::AudioContent, SameMusicStage::ComparingFingerprints).current_stage_idx(),
             7
@@ -0,0 +1,340 @@
+// Quarantine is folder, to which removed items are moved instead of being deleted, e.g. on servers and network mounts, where trash is not available.
+// Items are kept with their whole original path recreated inside "items" subfolder, and every item is saved in manifest,
+// so it can be restored later or purged after its expiry time.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fs, io};
+
+use humansize::{BINARY, format_size};
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::common::fs_ops::{lock_file_beside, move_item, path_inside_folder, replace_file_content};
+use crate::flc;
+use crate::helpers::messages::Messages;
+
+const MANIFEST_FILE_NAME: &str = "manifest.jsonl";
+const ITEMS_FOLDER_NAME: &str = "items";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuarantineSettings {
+    pub folder: PathBuf,
+    // 0 - items never expire
+    pub expiry_days: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuarantineEntry {
+    pub original_path: PathBuf,
+    pub quarantine_path: PathBuf,
+    pub size: u64,
+    pub quarantined_at: u64,
+    pub expires_at: Option<u64>,
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+fn manifest_path(folder: &Path) -> PathBuf {
+    folder.join(MANIFEST_FILE_NAME)
+}
+
+// Same path may be quarantined multiple times, so older items are never overwritten
+fn free_quarantine_path(path: PathBuf) -> PathBuf {
+    if fs::symlink_metadata(&path).is_err() {
+        return path;
+    }
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    let extension = path.extension().map(|extension| extension.to_string_lossy().into_owned());
+    let mut counter: u64 = 1;
+    loop {
+        let candidate = path.with_file_name(match &extension {
+            Some(extension) => format!("{stem}({counter}).{extension}"),
+            None => format!("{stem}({counter})"),
+        });
+        if fs::symlink_metadata(&candidate).is_err() {
+            return candidate;
+        }
+        counter += 1;
+    }
+}
+
+pub struct Quarantine {
+    settings: QuarantineSettings,
+    // Held while appending, so entries from different threads are never mixed
+    manifest_lock: Mutex<()>,
+}
+
+impl Quarantine {
+    pub fn new(settings: QuarantineSettings) -> Self {
+        Self {
+            settings,
+            manifest_lock: Mutex::new(()),
+        }
+    }
+
+    // Manifest may be replaced by purge or restore in other process, so it is opened again for every entry
+    fn save_entry(&self, entry: &QuarantineEntry) -> Result<(), String> {
+        let manifest_path = manifest_path(&self.settings.folder);
+        let cannot_save = |reason: String| flc!("core_quarantine_manifest_cannot_save", path = manifest_path.to_string_lossy().to_string(), reason = reason);
+        let line = serde_json::to_string(entry).map_err(|e| cannot_save(e.to_string()))? + "\n";
+
+        let _manifest_lock = self.manifest_lock.lock().expect("Quarantine manifest lock is poisoned");
+        let _file_lock = lock_file_beside(&manifest_path)?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&manifest_path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| cannot_save(e.to_string()))
+    }
+
+    // Returns path of item inside quarantine
+    pub fn quarantine_item(&self, path: &Path, size: u64) -> Result<PathBuf, String> {
+        let items_folder = self.settings.folder.join(ITEMS_FOLDER_NAME);
+        if path.starts_with(&self.settings.folder) {
+            return Err(flc!("core_quarantine_item_inside_folder", path = path.to_string_lossy().to_string()));
+        }
+        let quarantine_path = free_quarantine_path(path_inside_folder(&items_folder, path));
+        move_item(path, &quarantine_path).map_err(|e| flc!("core_quarantine_cannot_move", path = path.to_string_lossy().to_string(), reason = e))?;
+
+        let quarantined_at = now();
+        let entry = QuarantineEntry {
+            original_path: path.to_path_buf(),
+            quarantine_path: quarantine_path.clone(),
+            size,
+            quarantined_at,
+            expires_at: (self.settings.expiry_days > 0).then(|| quarantined_at + u64::from(self.settings.expiry_days) * 24 * 60 * 60),
+        };
+        // Item without entry in manifest could be found only manually, so it is moved back
+        if let Err(e) = self.save_entry(&entry) {
+            let _ = move_item(&quarantine_path, path);
+            return Err(e);
+        }
+        Ok(quarantine_path)
+    }
+}
+
+pub fn load_manifest(folder: &Path) -> Result<Vec<QuarantineEntry>, String> {
+    let manifest_path = manifest_path(folder);
+    let _file_lock = lock_file_beside(&manifest_path)?;
+    load_manifest_entries(&manifest_path)
+}
+
+fn load_manifest_entries(manifest_path: &Path) -> Result<Vec<QuarantineEntry>, String> {
+    let file = match File::open(manifest_path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(flc!(
+                "core_quarantine_manifest_cannot_open",
+                path = manifest_path.to_string_lossy().to_string(),
+                reason = e.to_string()
+            ));
+        }
+    };
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| {
+            flc!(
+                "core_quarantine_manifest_cannot_read",
+                path = manifest_path.to_string_lossy().to_string(),
+                reason = e.to_string()
+            )
+        })?;
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => debug!("Skipping invalid quarantine manifest line - {e}"),
+        }
+    }
+    Ok(entries)
+}
+
+// Manifest is loaded again, because other items may be quarantined while purging or restoring items.
+// Entries of removed items and items which are no longer in quarantine are dropped.
+fn remove_manifest_entries(folder: &Path, removed_paths: &[PathBuf]) -> Result<(), String> {
+    let manifest_path = manifest_path(folder);
+    let cannot_save = |reason: String| flc!("core_quarantine_manifest_cannot_save", path = manifest_path.to_string_lossy().to_string(), reason = reason);
+    let _file_lock = lock_file_beside(&manifest_path)?;
+    let mut content = String::new();
+    for entry in load_manifest_entries(&manifest_path)? {
+        if removed_paths.contains(&entry.quarantine_path) || !is_still_quarantined(&entry) {
+            continue;
+        }
+        content.push_str(&serde_json::to_string(&entry).map_err(|e| cannot_save(e.to_string()))?);
+        content.push('\n');
+    }
+    replace_file_content(&manifest_path, &content).map_err(|e| cannot_save(e.to_string()))
+}
+
+// Folders created only to recreate original path of item are not needed anymore
+fn remove_empty_parents(folder: &Path, quarantine_path: &Path) {
+    let items_folder = folder.join(ITEMS_FOLDER_NAME);
+    let mut current = quarantine_path.parent();
+    while let Some(parent) = current {
+        if parent == items_folder || !parent.starts_with(&items_folder) || fs::remove_dir(parent).is_err() {
+            break;
+        }
+        current = parent.parent();
+    }
+}
+
+// Item may be moved out of quarantine manually or by undo, then its entry is no longer needed
+fn is_still_quarantined(entry: &QuarantineEntry) -> bool {
+    fs::symlink_metadata(&entry.quarantine_path).is_ok()
+}
+
+// Removes expired items, or all items when `all` is set
+pub fn purge_quarantine(folder: &Path, all: bool, dry_run: bool) -> Messages {
+    let mut messages = Messages::new();
+    let entries = match load_manifest(folder) {
+        Ok(entries) => entries,
+        Err(e) => {
+            messages.errors.push(e);
+            return messages;
+        }
+    };
+
+    let current_time = now();
+    let mut purged_paths = Vec::new();
+    let mut purged_size = 0;
+    for entry in entries {
+        if !is_still_quarantined(&entry) || (!all && entry.expires_at.is_none_or(|expires_at| expires_at > current_time)) {
+            continue;
+        }
+        if dry_run {
+            messages
+                .messages
+                .push(flc!("core_quarantine_would_purge", path = entry.original_path.to_string_lossy().to_string()));
+            continue;
+        }
+
+        let result = if entry.quarantine_path.is_dir() {
+            fs::remove_dir_all(&entry.quarantine_path)
+        } else {
+            fs::remove_file(&entry.quarantine_path)
+        };
+        match result {
+            Ok(()) => {
+                remove_empty_parents(folder, &entry.quarantine_path);
+                purged_size += entry.size;
+                purged_paths.push(entry.quarantine_path);
+            }
+            Err(e) => messages.errors.push(flc!(
+                "core_quarantine_cannot_purge",
+                path = entry.quarantine_path.to_string_lossy().to_string(),
+                reason = e.to_string()
+            )),
+        }
+    }
+
+    if !dry_run {
+        messages
+            .messages
+            .push(flc!("core_quarantine_purged", items = purged_paths.len(), size = format_size(purged_size, BINARY)));
+        if let Err(e) = remove_manifest_entries(folder, &purged_paths) {
+            messages.errors.push(e);
+        }
+    }
+    messages
+}
+
+// Moves items back to their original paths - all items, or only ones inside any of given paths
+pub fn restore_from_quarantine(folder: &Path, paths: &[PathBuf], dry_run: bool) -> Messages {
+    let mut messages = Messages::new();
+    let entries = match load_manifest(folder) {
+        Ok(entries) => entries,
+        Err(e) => {
+            messages.errors.push(e);
+            return messages;
+        }
+    };
+
+    let mut restored_paths = Vec::new();
+    for entry in entries {
+        if !is_still_quarantined(&entry) || (!paths.is_empty() && !paths.iter().any(|path| entry.original_path.starts_with(path))) {
+            continue;
+        }
+        if dry_run {
+            messages
+                .messages
+                .push(flc!("core_quarantine_would_restore", path = entry.original_path.to_string_lossy().to_string()));
+            continue;
+        }
+
+        match move_item(&entry.quarantine_path, &entry.original_path) {
+            Ok(()) => {
+                remove_empty_parents(folder, &entry.quarantine_path);
+                restored_paths.push(entry.quarantine_path);
+            }
+            Err(e) => messages
+                .errors
+                .push(flc!("core_quarantine_cannot_restore", path = entry.original_path.to_string_lossy().to_string(), reason = e)),
+        }
+    }
+
+    if !dry_run {
+        messages.messages.push(flc!("core_quarantine_restored", items = restored_paths.len()));
+        if let Err(e) = remove_manifest_entries(folder, &restored_paths) {
+            messages.errors.push(e);
+        }
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_quarantine_restore_and_purge() {
+        let temp_dir = TempDir::new().unwrap();
+        let quarantine_folder = temp_dir.path().join("quarantine");
+        let data_folder = temp_dir.path().join("data");
+        fs::create_dir_all(&data_folder).unwrap();
+        let first = data_folder.join("first.txt");
+        let second = data_folder.join("second.txt");
+
+        let quarantine = Quarantine::new(QuarantineSettings {
+            folder: quarantine_folder.clone(),
+            expiry_days: 0,
+        });
+        for path in [&first, &second] {
+            fs::write(path, "content").unwrap();
+            let quarantine_path = quarantine.quarantine_item(path, 7).unwrap();
+            assert!(!path.exists());
+            assert!(quarantine_path.starts_with(quarantine_folder.join(ITEMS_FOLDER_NAME)));
+            assert!(quarantine_path.ends_with(path.file_name().unwrap()));
+        }
+        // Same path quarantined again does not overwrite older item
+        fs::write(&first, "newer").unwrap();
+        let newer_path = quarantine.quarantine_item(&first, 5).unwrap();
+        assert!(newer_path.ends_with("first(1).txt"));
+        assert_eq!(load_manifest(&quarantine_folder).unwrap().len(), 3);
+
+        // Items without expiry are purged only when all items are purged
+        let messages = purge_quarantine(&quarantine_folder, false, false);
+        assert!(messages.errors.is_empty());
+        assert_eq!(load_manifest(&quarantine_folder).unwrap().len(), 3);
+
+        let messages = restore_from_quarantine(&quarantine_folder, std::slice::from_ref(&second), false);
+        assert!(messages.errors.is_empty(), "{:?}", messages.errors);
+        assert_eq!(fs::read_to_string(&second).unwrap(), "content");
+        assert_eq!(load_manifest(&quarantine_folder).unwrap().len(), 2);
+
+        let messages = purge_quarantine(&quarantine_folder, true, false);
+        assert!(messages.errors.is_empty(), "{:?}", messages.errors);
+        assert!(load_manifest(&quarantine_folder).unwrap().is_empty());
+        assert!(!first.exists());
+        assert_eq!(fs::read_dir(quarantine_folder.join(ITEMS_FOLDER_NAME)).unwrap().count(), 0);
+    }
+}
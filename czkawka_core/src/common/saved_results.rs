@@ -0,0 +1,528 @@
+// Results saved as JSON by any tool, loaded back, so found items can be reviewed and edited before acting on them.
+// Saved file contains type of tool, whose layout of results is used to find groups, same as in flat exports.
+// Before applying action, every item is checked again, so items modified after saving results are never touched.
+
+use std::fs;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, UNIX_EPOCH};
+
+use crossbeam_channel::Sender;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::common::deletion::{DeleteItemType, delete_advanced_elements_and_add_to_messages, delete_simple_elements_and_add_to_messages, select_items_to_remove};
+use crate::common::flat_results::{FlatLayout, FlatResults};
+use crate::common::fs_ops::{move_item, path_inside_folder};
+use crate::common::keep_rules::{KeepRule, KeepRuleEntry};
+use crate::common::make_file_symlink;
+use crate::common::model::{ToolType, WorkContinueStatus};
+use crate::common::progress_data::{ProgressData, ToolStage};
+use crate::common::progress_stop_handler::check_if_stop_received;
+use crate::common::quarantine::QuarantineSettings;
+use crate::common::tool_data::{CommonToolData, DeleteMethod};
+use crate::common::traits::ResultEntry;
+use crate::common::undo_journal::{JournalOperation, JournalRecorder};
+use crate::flc;
+use crate::helpers::delayed_sender::DelayedSender;
+use crate::helpers::messages::Messages;
+
+// Layout of results differs between tools, so it cannot be recognized from results alone
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SavedResultsFile<T> {
+    pub tool_type: ToolType,
+    pub results: T,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedResultEntry {
+    pub path: PathBuf,
+    // Empty folders are saved only as paths, so they have neither size nor modification date, files without them are skipped
+    pub size: Option<u64>,
+    pub modified_date: Option<u64>,
+    // Set when item is validated
+    pub is_folder: bool,
+}
+
+impl KeepRuleEntry for SavedResultEntry {}
+
+impl ResultEntry for SavedResultEntry {
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+    fn get_modified_date(&self) -> u64 {
+        self.modified_date.unwrap_or_default()
+    }
+    fn get_size(&self) -> u64 {
+        self.size.unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SavedResultGroup {
+    // Reference item is never removed, other items may be replaced by links to it
+    pub reference: Option<SavedResultEntry>,
+    pub items: Vec<SavedResultEntry>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SavedResults {
+    pub tool_type: ToolType,
+    // Simple tools, like empty files finder, save single list of items, which are not similar to each other
+    pub groups: Vec<SavedResultGroup>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApplyOperation {
+    Remove,
+    // Items are moved with their whole path recreated inside folder, so items with same names don't collide
+    MoveTo(PathBuf),
+    // Items are replaced by symlinks to item kept in their group
+    Symlink,
+}
+
+#[derive(Debug, Clone)]
+pub struct ApplyParameters {
+    // Chooses items of each group, on which operation is performed
+    pub delete_method: DeleteMethod,
+    pub operation: ApplyOperation,
+    pub dry_run: bool,
+    pub move_to_trash: bool,
+    pub quarantine: Option<QuarantineSettings>,
+    pub keep_rules: Vec<KeepRule>,
+}
+
+impl SavedResults {
+    pub fn load(file_name: &Path) -> Result<Self, String> {
+        let file = File::open(file_name).map_err(|e| flc!("core_saved_results_cannot_open", file = file_name.to_string_lossy().to_string(), reason = e.to_string()))?;
+        let saved: SavedResultsFile<Value> = serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| flc!("core_saved_results_cannot_load", file = file_name.to_string_lossy().to_string(), reason = e.to_string()))?;
+        Ok(Self::from_json(&saved.results, saved.tool_type))
+    }
+
+    pub fn from_json(value: &Value, tool_type: ToolType) -> Self {
+        let mut groups: Vec<SavedResultGroup> = Vec::new();
+        let mut last_group_id = None;
+        // Items of group are saved one after another, with reference item first
+        for entry in FlatResults::from_json(value, tool_type.flat_layout()).entries {
+            if groups.is_empty() || entry.group_id != last_group_id {
+                groups.push(SavedResultGroup::default());
+                last_group_id = entry.group_id;
+            }
+            let group = groups.last_mut().expect("Group was added above");
+            let item = parse_entry(&entry.fields);
+            if entry.is_reference {
+                group.reference = Some(item);
+            } else {
+                group.items.push(item);
+            }
+        }
+        Self { tool_type, groups }
+    }
+
+    fn grouped(&self) -> bool {
+        self.tool_type.flat_layout() != FlatLayout::Items
+    }
+
+    pub fn items_number(&self) -> usize {
+        self.groups.iter().map(|group| group.items.len() + usize::from(group.reference.is_some())).sum()
+    }
+
+    // Removes items, which no longer exist, were modified after saving results or cannot be checked
+    pub fn validate(&mut self, messages: &mut Messages) {
+        let grouped = self.grouped();
+        for group in &mut self.groups {
+            if let Some(reference) = &mut group.reference
+                && !validate_entry(reference, grouped, messages)
+            {
+                // Items cannot be replaced by links to changed reference
+                group.items.clear();
+            }
+            group.items.retain_mut(|item| validate_entry(item, grouped, messages));
+        }
+        self.groups.retain(|group| {
+            if grouped {
+                group.items.len() + usize::from(group.reference.is_some()) > 1
+            } else {
+                !group.items.is_empty()
+            }
+        });
+    }
+
+    pub fn apply(self, params: &ApplyParameters, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> Messages {
+        let mut messages = Messages::new();
+        // Items from sections, e.g. files only in one of compared folders, are not duplicates of each other
+        if matches!(self.tool_type.flat_layout(), FlatLayout::Sections { .. }) {
+            messages.errors.push(flc!("core_saved_results_unsupported_tool"));
+            return messages;
+        }
+        if params.delete_method == DeleteMethod::None {
+            return messages;
+        }
+        if !self.grouped() && (params.delete_method != DeleteMethod::Delete || params.operation == ApplyOperation::Symlink) {
+            messages.errors.push(flc!("core_saved_results_not_grouped"));
+            return messages;
+        }
+        if matches!(params.delete_method, DeleteMethod::HardLink | DeleteMethod::Reflink | DeleteMethod::Delete) && params.operation == ApplyOperation::Symlink {
+            messages.errors.push(flc!("core_saved_results_symlink_requires_kept_item"));
+            return messages;
+        }
+        if matches!(params.delete_method, DeleteMethod::HardLink | DeleteMethod::Reflink) && params.operation != ApplyOperation::Remove {
+            messages.errors.push(flc!("core_saved_results_links_cannot_be_moved"));
+            return messages;
+        }
+
+        match &params.operation {
+            ApplyOperation::Remove => self.remove(params, stop_flag, progress_sender, &mut messages),
+            ApplyOperation::MoveTo(folder) => self.move_or_symlink(params, Some(folder), stop_flag, progress_sender, &mut messages),
+            ApplyOperation::Symlink => self.move_or_symlink(params, None, stop_flag, progress_sender, &mut messages),
+        }
+        messages
+    }
+
+    fn remove(self, params: &ApplyParameters, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>, messages: &mut Messages) {
+        let mut cd = CommonToolData::new(self.tool_type);
+        cd.delete_method = params.delete_method;
+        cd.dry_run = params.dry_run;
+        cd.move_to_trash = params.move_to_trash;
+        cd.quarantine = params.quarantine.clone();
+        cd.keep_rules = params.keep_rules.clone();
+
+        if self.grouped() {
+            let (referenced, not_referenced): (Vec<_>, Vec<_>) = self.groups.into_iter().partition(|group| group.reference.is_some());
+            for (use_reference_folders, groups) in [(true, referenced), (false, not_referenced)] {
+                if groups.is_empty() {
+                    continue;
+                }
+                let groups: Vec<Vec<SavedResultEntry>> = groups.into_iter().map(|group| group.reference.into_iter().chain(group.items).collect()).collect();
+                cd.use_reference_folders = use_reference_folders;
+                if delete_advanced_elements_and_add_to_messages(&mut cd, stop_flag, progress_sender, groups) == WorkContinueStatus::Stop {
+                    break;
+                }
+            }
+        } else {
+            // Only empty folders are saved in single list, so folders are removed only when they are still empty
+            let (folders, files): (Vec<_>, Vec<_>) = self.groups.into_iter().flat_map(|group| group.items).partition(|item| item.is_folder);
+            if delete_simple_elements_and_add_to_messages(&mut cd, stop_flag, progress_sender, DeleteItemType::DeletingFiles(files)) == WorkContinueStatus::Continue {
+                let _ = delete_simple_elements_and_add_to_messages(&mut cd, stop_flag, progress_sender, DeleteItemType::DeletingFolders(folders));
+            }
+        }
+        messages.extend_with_another_messages(cd.text_messages);
+    }
+
+    // Without folder, items are replaced by symlinks
+    fn move_or_symlink(
+        self,
+        params: &ApplyParameters,
+        target_folder: Option<&PathBuf>,
+        stop_flag: &Arc<AtomicBool>,
+        progress_sender: Option<&Sender<ProgressData>>,
+        messages: &mut Messages,
+    ) {
+        let grouped = self.grouped();
+        let operations: Vec<(SavedResultEntry, PathBuf)> = self
+            .groups
+            .into_iter()
+            .flat_map(|group| {
+                let use_reference_folders = group.reference.is_some();
+                let all_items: Vec<SavedResultEntry> = group.reference.into_iter().chain(group.items).collect();
+                let selected_items = if grouped {
+                    select_items_to_remove(params.delete_method, &params.keep_rules, use_reference_folders, all_items.clone())
+                } else {
+                    all_items.clone()
+                };
+                let kept_item = all_items.into_iter().find(|item| !selected_items.contains(item));
+                selected_items
+                    .into_iter()
+                    .filter_map(|item| {
+                        let destination = match target_folder {
+                            Some(folder) => path_inside_folder(folder, &item.path),
+                            None => kept_item.as_ref()?.path.clone(),
+                        };
+                        Some((item, destination))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let stage = if target_folder.is_some() { ToolStage::MovingFiles } else { ToolStage::SymlinkingFiles };
+        let mut progress = ProgressData::new(stage, operations.len(), operations.iter().map(|(item, _destination)| item.get_size()).sum());
+        let delayed_sender = progress_sender.map(|sender| DelayedSender::new(sender.clone(), Duration::from_millis(200)));
+
+        let journal = JournalRecorder::new();
+        for (item, destination) in operations {
+            if check_if_stop_received(stop_flag) {
+                break;
+            }
+            progress.entries_checked += 1;
+            progress.bytes_checked += item.get_size();
+            if let Some(delayed_sender) = &delayed_sender {
+                delayed_sender.send(progress);
+            }
+
+            let result = if params.dry_run {
+                Ok(())
+            } else if target_folder.is_some() {
+                move_item(&item.path, &destination)
+            } else {
+                make_file_symlink(&destination, &item.path).map_err(|e| e.to_string())
+            };
+            let path = item.path.to_string_lossy().to_string();
+            let destination_str = destination.to_string_lossy().to_string();
+            match result {
+                Ok(()) if params.dry_run && target_folder.is_some() => messages.messages.push(flc!("core_saved_results_would_move", path = path, destination = destination_str)),
+                Ok(()) if params.dry_run => messages.messages.push(flc!("core_saved_results_would_symlink", path = path, destination = destination_str)),
+                Ok(()) if target_folder.is_some() => journal.record(JournalOperation::Move, &item.path, Some(&destination), item.get_size()),
+                Ok(()) => journal.record(JournalOperation::Symlink, &item.path, Some(&destination), item.get_size()),
+                Err(e) if target_folder.is_some() => messages
+                    .errors
+                    .push(flc!("core_saved_results_failed_move", path = path, destination = destination_str, reason = e)),
+                Err(e) => messages
+                    .errors
+                    .push(flc!("core_saved_results_failed_symlink", path = path, destination = destination_str, reason = e)),
+            }
+        }
+    }
+}
+
+// Flat results contain only objects with path, and paths saved as strings
+fn parse_entry(fields: &Map<String, Value>) -> SavedResultEntry {
+    SavedResultEntry {
+        path: PathBuf::from(fields.get("path").and_then(Value::as_str).unwrap_or_default()),
+        size: fields.get("size").and_then(Value::as_u64),
+        modified_date: fields.get("modified_date").and_then(Value::as_u64),
+        is_folder: false,
+    }
+}
+
+fn validate_entry(entry: &mut SavedResultEntry, grouped: bool, messages: &mut Messages) -> bool {
+    let path = entry.path.to_string_lossy().to_string();
+    let Ok(metadata) = fs::symlink_metadata(&entry.path) else {
+        messages.warnings.push(flc!("core_saved_results_item_missing", path = path));
+        return false;
+    };
+    entry.is_folder = metadata.is_dir();
+    if entry.is_folder {
+        // Empty folders are removed only when they are still empty, but duplicate folders would need to be hashed again
+        if grouped {
+            messages.warnings.push(flc!("core_saved_results_folder_in_group", path = path));
+            return false;
+        }
+        return true;
+    }
+    let (Some(size), Some(saved_modified_date)) = (entry.size, entry.modified_date) else {
+        messages.warnings.push(flc!("core_saved_results_item_without_metadata", path = path));
+        return false;
+    };
+    let modified_date = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |time| time.as_secs());
+    if size != metadata.len() || saved_modified_date != modified_date {
+        messages.warnings.push(flc!("core_saved_results_item_changed", path = path));
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn entry(path: &str) -> SavedResultEntry {
+        SavedResultEntry {
+            path: PathBuf::from(path),
+            size: Some(1),
+            modified_date: Some(2),
+            is_folder: false,
+        }
+    }
+
+    fn json_entry(path: &Path) -> Value {
+        let metadata = fs::metadata(path).unwrap();
+        let modified_date = metadata.modified().unwrap().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        json!({"path": path, "size": metadata.len(), "modified_date": modified_date, "hash": "abc"})
+    }
+
+    fn apply_parameters(delete_method: DeleteMethod, operation: ApplyOperation, dry_run: bool) -> ApplyParameters {
+        ApplyParameters {
+            delete_method,
+            operation,
+            dry_run,
+            move_to_trash: false,
+            quarantine: None,
+            keep_rules: Vec::new(),
+        }
+    }
+
+    fn create_files(temp_dir: &TempDir, names: &[&str]) -> Vec<PathBuf> {
+        names
+            .iter()
+            .map(|name| {
+                let path = temp_dir.path().join(name);
+                fs::write(&path, "data").unwrap();
+                path
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_from_json_finds_groups() {
+        let file = |path: &str| json!({"path": path, "size": 1, "modified_date": 2});
+
+        // Simple tools
+        let results = SavedResults::from_json(&json!([file("/a"), file("/b")]), ToolType::BigFile);
+        assert!(!results.grouped());
+        assert_eq!(
+            results.groups,
+            vec![SavedResultGroup {
+                reference: None,
+                items: vec![entry("/a"), entry("/b")]
+            }]
+        );
+
+        // Empty folders are saved as paths
+        let results = SavedResults::from_json(&json!(["/folder"]), ToolType::EmptyFolders);
+        assert_eq!(results.groups.first().map(|group| group.items.len()), Some(1));
+
+        // Duplicates grouped by size, with and without reference
+        let results = SavedResults::from_json(&json!({"10": [[file("/a"), file("/b")]], "20": [[file("/c"), [file("/d")]]]}), ToolType::Duplicate);
+        assert!(results.grouped());
+        assert_eq!(
+            results.groups,
+            vec![
+                SavedResultGroup {
+                    reference: None,
+                    items: vec![entry("/a"), entry("/b")]
+                },
+                SavedResultGroup {
+                    reference: Some(entry("/c")),
+                    items: vec![entry("/d")]
+                },
+            ]
+        );
+
+        // Similar images are saved as list of groups, which must not be read as list of items
+        let results = SavedResults::from_json(&json!([[file("/a"), file("/b")], [file("/c"), file("/d")]]), ToolType::SimilarImages);
+        assert_eq!(results.groups.len(), 2);
+        assert!(SavedResults::from_json(&json!([[file("/a"), file("/b")]]), ToolType::BigFile).groups.is_empty());
+    }
+
+    #[test]
+    fn test_apply_skips_changed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = create_files(&temp_dir, &["a", "b", "c"]);
+        let [a, b, c] = paths.as_slice() else { unreachable!() };
+        let results_json = json!({"4": [[json_entry(a), json_entry(b), json_entry(c)]]});
+        fs::write(c, "changed data").unwrap();
+
+        let mut results = SavedResults::from_json(&results_json, ToolType::Duplicate);
+        let mut messages = Messages::new();
+        results.validate(&mut messages);
+        assert_eq!(messages.warnings.len(), 1);
+        assert_eq!(results.items_number(), 2);
+
+        let params = apply_parameters(DeleteMethod::Delete, ApplyOperation::MoveTo(temp_dir.path().join("moved")), false);
+        let messages = results.apply(&params, &Arc::new(AtomicBool::new(false)), None);
+        assert_eq!(messages.errors, Vec::<String>::new());
+        assert!(!a.exists());
+        assert!(!b.exists());
+        assert!(c.exists());
+        assert!(path_inside_folder(&temp_dir.path().join("moved"), a).exists());
+    }
+
+    #[test]
+    fn test_validate_skips_items_which_cannot_be_checked() {
+        let temp_dir = TempDir::new().unwrap();
+        let [file, folder_a, folder_b] = ["file", "folder_a", "folder_b"].map(|name| temp_dir.path().join(name));
+        fs::write(&file, "data").unwrap();
+        fs::create_dir(&folder_a).unwrap();
+        fs::create_dir(&folder_b).unwrap();
+
+        // File listed only by path has nothing to compare with
+        let mut results = SavedResults::from_json(&json!([file]), ToolType::EmptyFolders);
+        let mut messages = Messages::new();
+        results.validate(&mut messages);
+        assert_eq!(results.items_number(), 0);
+        assert_eq!(messages.warnings.len(), 1);
+
+        // Empty folders are saved as paths and removed only when still empty
+        let mut results = SavedResults::from_json(&json!([folder_a]), ToolType::EmptyFolders);
+        let mut messages = Messages::new();
+        results.validate(&mut messages);
+        assert_eq!(results.items_number(), 1);
+        assert!(messages.warnings.is_empty());
+
+        // Folders in groups would need to be hashed again
+        let mut results = SavedResults::from_json(&json!({"0": [[json_entry(&folder_a), json_entry(&folder_b)]]}), ToolType::Duplicate);
+        let mut messages = Messages::new();
+        results.validate(&mut messages);
+        assert_eq!(results.items_number(), 0);
+        assert_eq!(messages.warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_apply_rejects_results_without_groups_or_items() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = create_files(&temp_dir, &["a", "b", "c"]);
+        let [a, b, c] = paths.as_slice() else { unreachable!() };
+
+        let comparison = json!({
+            "first_directory": temp_dir.path(),
+            "second_directory": temp_dir.path(),
+            "files_only_in_first": [json_entry(a)],
+            "changed_files": [{"relative_path": "b", "first_file": json_entry(b), "second_file": json_entry(c)}],
+            "moved_files": []
+        });
+        let integrity = json!({"corrupted_files": [json_entry(a)], "modified_files": [json_entry(b), json_entry(c)], "missing_files": [], "new_files": []});
+        let duplicate_folders = json!({"duplicated_folders": [[json_entry(a), json_entry(b)]], "subset_folders": []});
+
+        for (tool_type, results) in [
+            (ToolType::DirectoryComparison, comparison),
+            (ToolType::IntegrityChecker, integrity),
+            (ToolType::DuplicateFolders, duplicate_folders),
+        ] {
+            let results_file = temp_dir.path().join("results.json");
+            fs::write(&results_file, serde_json::to_string(&SavedResultsFile { tool_type, results }).unwrap()).unwrap();
+            let mut results = SavedResults::load(&results_file).unwrap();
+            results.validate(&mut Messages::new());
+
+            let params = apply_parameters(DeleteMethod::Delete, ApplyOperation::Remove, false);
+            let messages = results.apply(&params, &Arc::new(AtomicBool::new(false)), None);
+            assert_eq!(messages.errors, vec![flc!("core_saved_results_unsupported_tool")], "{tool_type:?}");
+            assert!(a.exists() && b.exists() && c.exists());
+        }
+
+        // Results saved without type of tool cannot be read with correct layout
+        let results_file = temp_dir.path().join("results.json");
+        fs::write(&results_file, json!({"4": [[json_entry(a), json_entry(b)]]}).to_string()).unwrap();
+        assert!(SavedResults::load(&results_file).unwrap_err().contains("missing field `tool_type`"));
+    }
+
+    #[test]
+    fn test_apply_reflinks_only_duplicates() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = create_files(&temp_dir, &["a", "b"]);
+        let [a, b] = paths.as_slice() else { unreachable!() };
+        let params = apply_parameters(DeleteMethod::Reflink, ApplyOperation::Remove, true);
+
+        let mut results = SavedResults::from_json(&json!({"4": [[json_entry(a), json_entry(b)]]}), ToolType::Duplicate);
+        results.validate(&mut Messages::new());
+        let messages = results.apply(&params, &Arc::new(AtomicBool::new(false)), None);
+        assert_eq!(messages.errors, Vec::<String>::new());
+        assert!(messages.messages.iter().any(|message| message.starts_with("Would reflink")), "{:?}", messages.messages);
+
+        // Similar images only look alike, so they cannot share content
+        let mut results = SavedResults::from_json(&json!([[json_entry(a), json_entry(b)]]), ToolType::SimilarImages);
+        results.validate(&mut Messages::new());
+        let messages = results.apply(&params, &Arc::new(AtomicBool::new(false)), None);
+        assert_eq!(messages.errors, vec![flc!("core_reflink_requires_hash")]);
+    }
+}
@@ -0,0 +1,305 @@
+// Groups entries, which may not fit into memory, e.g. files collected in scans of tens of millions of files.
+// Entries are kept in memory until reaching limit, then are sorted by key and saved to temporary file(run).
+// At the end, all runs are merged, so groups are read one by one in order of keys, same as from BTreeMap.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::env;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Seek, SeekFrom};
+use std::mem;
+use std::path::PathBuf;
+use std::vec;
+
+use log::debug;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::common::config_cache_path::get_config_cache_path;
+use crate::common::model::FileEntry;
+
+// Approximated size of entry in memory, used to decide when entries should be moved to disk
+pub(crate) trait MemoryUsage {
+    fn memory_usage(&self) -> usize;
+}
+
+impl MemoryUsage for FileEntry {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>() + self.path.as_os_str().len()
+    }
+}
+
+impl<T: MemoryUsage> MemoryUsage for Vec<T> {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>() + self.iter().map(MemoryUsage::memory_usage).sum::<usize>()
+    }
+}
+
+// Temporary folder is often mounted in RAM, so cache folder is preferred
+fn get_spill_folder() -> PathBuf {
+    get_config_cache_path().map_or_else(env::temp_dir, |config_cache_path| config_cache_path.cache_folder)
+}
+
+pub(crate) struct SpillStore<K, V> {
+    memory_limit: usize,
+    memory_usage: usize,
+    entries: Vec<(K, V)>,
+    // Files are removed by system when closed, number of entries in each is saved to know where run ends
+    runs: Vec<(File, usize)>,
+    error: Option<String>,
+}
+
+impl<K, V> SpillStore<K, V>
+where
+    K: Ord + Serialize + DeserializeOwned,
+    V: MemoryUsage + Serialize + DeserializeOwned,
+{
+    pub(crate) fn new(memory_limit: u64) -> Self {
+        Self {
+            memory_limit: usize::try_from(memory_limit).unwrap_or(usize::MAX),
+            memory_usage: 0,
+            entries: Vec::new(),
+            runs: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub(crate) fn push(&mut self, key: K, value: V) {
+        self.memory_usage += size_of::<K>() + value.memory_usage();
+        self.entries.push((key, value));
+        // After error, entries are kept in memory, which may use more memory than allowed, but not lose any data
+        if self.memory_usage >= self.memory_limit
+            && self.error.is_none()
+            && let Err(e) = self.save_run()
+        {
+            self.error = Some(e);
+        }
+    }
+
+    fn save_run(&mut self) -> Result<(), String> {
+        // Stable sort, so entries with same key are read in order of adding
+        self.entries.sort_by(|(key_a, _), (key_b, _)| key_a.cmp(key_b));
+
+        let spill_folder = get_spill_folder();
+        let file = tempfile::tempfile_in(&spill_folder).map_err(|e| format!("Cannot create temporary file in \"{}\" - {e}", spill_folder.to_string_lossy()))?;
+        let mut writer = BufWriter::new(file);
+        for entry in &self.entries {
+            bincode::serialize_into(&mut writer, entry).map_err(|e| format!("Cannot save entries to temporary file - {e}"))?;
+        }
+        let mut file = writer.into_inner().map_err(|e| format!("Cannot save entries to temporary file - {e}"))?;
+        file.seek(SeekFrom::Start(0)).map_err(|e| format!("Cannot save entries to temporary file - {e}"))?;
+
+        debug!("Saved {} entries({} bytes in memory) to temporary file", self.entries.len(), self.memory_usage);
+        self.runs.push((file, self.entries.len()));
+        self.entries = Vec::new();
+        self.memory_usage = 0;
+        Ok(())
+    }
+
+    pub(crate) fn into_groups(mut self) -> Result<SpilledGroups<K, V>, String> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        // Entries left in memory are also saved, to not keep them in memory together with groups read from runs
+        if !self.runs.is_empty() && !self.entries.is_empty() {
+            self.save_run()?;
+        }
+        self.entries.sort_by(|(key_a, _), (key_b, _)| key_a.cmp(key_b));
+
+        let mut sources: Vec<RunSource<K, V>> = self.runs.into_iter().map(|(file, entries)| RunSource::File(BufReader::new(file), entries)).collect();
+        sources.push(RunSource::Memory(mem::take(&mut self.entries).into_iter()));
+        Ok(SpilledGroups::new(sources))
+    }
+
+    // Unlike into_groups, store is kept, so groups may be read again
+    pub(crate) fn groups(&self) -> Result<SpilledGroups<K, V>, String>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        if let Some(e) = &self.error {
+            return Err(e.clone());
+        }
+        let mut sources = Vec::with_capacity(self.runs.len() + 1);
+        for (file, entries) in &self.runs {
+            let mut file = file.try_clone().map_err(|e| format!("Cannot read entries from temporary file - {e}"))?;
+            file.seek(SeekFrom::Start(0)).map_err(|e| format!("Cannot read entries from temporary file - {e}"))?;
+            sources.push(RunSource::File(BufReader::new(file), *entries));
+        }
+        let mut entries = self.entries.clone();
+        entries.sort_by(|(key_a, _), (key_b, _)| key_a.cmp(key_b));
+        sources.push(RunSource::Memory(entries.into_iter()));
+        Ok(SpilledGroups::new(sources))
+    }
+}
+
+enum RunSource<K, V> {
+    File(BufReader<File>, usize),
+    Memory(vec::IntoIter<(K, V)>),
+}
+
+impl<K: DeserializeOwned, V: DeserializeOwned> RunSource<K, V> {
+    fn next_entry(&mut self) -> Result<Option<(K, V)>, String> {
+        match self {
+            Self::File(_reader, 0) => Ok(None),
+            Self::File(reader, entries_left) => {
+                *entries_left -= 1;
+                bincode::deserialize_from(reader)
+                    .map(Some)
+                    .map_err(|e| format!("Cannot read entries from temporary file - {e}"))
+            }
+            Self::Memory(entries) => Ok(entries.next()),
+        }
+    }
+}
+
+// Merges sorted runs and returns entries grouped by key, in ascending order of keys
+pub(crate) struct SpilledGroups<K, V> {
+    // Smallest not returned key of each source, source index decides order of entries with same key
+    heads: BinaryHeap<Reverse<(K, usize)>>,
+    values: Vec<Option<V>>,
+    sources: Vec<RunSource<K, V>>,
+    error: Option<String>,
+}
+
+impl<K, V> SpilledGroups<K, V>
+where
+    K: Ord + DeserializeOwned,
+    V: DeserializeOwned,
+{
+    fn new(sources: Vec<RunSource<K, V>>) -> Self {
+        let mut groups = Self {
+            heads: BinaryHeap::new(),
+            values: sources.iter().map(|_| None).collect(),
+            sources,
+            error: None,
+        };
+        for idx in 0..groups.sources.len() {
+            groups.read_next(idx);
+        }
+        groups
+    }
+
+    fn read_next(&mut self, idx: usize) {
+        let Some(source) = self.sources.get_mut(idx) else {
+            return;
+        };
+        match source.next_entry() {
+            Ok(Some((key, value))) => {
+                self.heads.push(Reverse((key, idx)));
+                if let Some(slot) = self.values.get_mut(idx) {
+                    *slot = Some(value);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                self.error.get_or_insert(e);
+            }
+        }
+    }
+
+    fn take_value(&mut self, idx: usize) -> Option<V> {
+        let value = self.values.get_mut(idx).and_then(Option::take);
+        self.read_next(idx);
+        value
+    }
+
+    // Entries from run, that cannot be read, are skipped, so groups may be incomplete
+    pub(crate) fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+}
+
+impl<K, V> Iterator for SpilledGroups<K, V>
+where
+    K: Ord + DeserializeOwned,
+    V: DeserializeOwned,
+{
+    type Item = (K, Vec<V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((key, idx)) = self.heads.pop()?;
+        let mut values: Vec<V> = self.take_value(idx).into_iter().collect();
+        while self.heads.peek().is_some_and(|Reverse((next_key, _))| *next_key == key) {
+            if let Some(Reverse((_, idx))) = self.heads.pop() {
+                values.extend(self.take_value(idx));
+            }
+        }
+        Some((key, values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn file_entry(path: &str, size: u64) -> FileEntry {
+        FileEntry {
+            path: PathBuf::from(path),
+            size,
+            modified_date: 0,
+        }
+    }
+
+    #[test]
+    fn test_spill_store_groups_in_order() {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let temp_cache = tempfile::TempDir::new().expect("Failed to create temp cache dir");
+            let temp_config = tempfile::TempDir::new().expect("Failed to create temp config dir");
+            crate::common::config_cache_path::set_config_cache_path_test(temp_cache.path().to_path_buf(), temp_config.path().to_path_buf());
+            std::mem::forget(temp_cache);
+            std::mem::forget(temp_config);
+        });
+
+        let entries = [
+            file_entry("/a", 30),
+            file_entry("/b", 10),
+            file_entry("/c", 20),
+            file_entry("/d", 10),
+            file_entry("/e", 30),
+            file_entry("/f", 10),
+            file_entry("/g", 40),
+        ];
+        let expected = vec![
+            (10, vec![PathBuf::from("/b"), PathBuf::from("/d"), PathBuf::from("/f")]),
+            (20, vec![PathBuf::from("/c")]),
+            (30, vec![PathBuf::from("/a"), PathBuf::from("/e")]),
+            (40, vec![PathBuf::from("/g")]),
+        ];
+
+        // Limit 1 saves every entry to separate file, so order must be restored by merging
+        for memory_limit in [1, 200, u64::MAX] {
+            let mut store = SpillStore::new(memory_limit);
+            for entry in entries.clone() {
+                store.push(entry.size, entry);
+            }
+            let mut groups = store.into_groups().unwrap();
+            let result: Vec<(u64, Vec<PathBuf>)> = groups.by_ref().map(|(size, files)| (size, files.into_iter().map(|fe| fe.path).collect())).collect();
+            assert_eq!(result, expected, "memory limit {memory_limit}");
+            assert_eq!(groups.take_error(), None);
+        }
+    }
+
+    #[test]
+    fn test_spill_store_groups_read_again() {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let temp_cache = tempfile::TempDir::new().expect("Failed to create temp cache dir");
+            let temp_config = tempfile::TempDir::new().expect("Failed to create temp config dir");
+            crate::common::config_cache_path::set_config_cache_path_test(temp_cache.path().to_path_buf(), temp_config.path().to_path_buf());
+            std::mem::forget(temp_cache);
+            std::mem::forget(temp_config);
+        });
+
+        let mut store = SpillStore::new(1);
+        for entry in [file_entry("/b", 10), file_entry("/a", 20), file_entry("/c", 10)] {
+            store.push(entry.size, entry);
+        }
+        let read_groups = || -> Vec<(u64, usize)> { store.groups().unwrap().map(|(size, files)| (size, files.len())).collect() };
+        assert_eq!(read_groups(), vec![(10, 2), (20, 1)]);
+        assert_eq!(read_groups(), vec![(10, 2), (20, 1)]);
+    }
+}
@@ -6,11 +6,15 @@ use crossbeam_channel::Sender;
 
 use crate::common::deletion;
 pub use crate::common::deletion::{DeleteItemType, DeleteResult};
+pub use crate::common::device_io::DeviceIoLimits;
 use crate::common::directories::Directories;
 use crate::common::extensions::Extensions;
 use crate::common::items::ExcludedItems;
+use crate::common::keep_rules::{KeepRule, KeepRuleEntry};
 use crate::common::model::{CheckingMethod, ToolType, WorkContinueStatus};
+use crate::common::ownership::OwnershipFilter;
 use crate::common::progress_data::ProgressData;
+use crate::common::quarantine::QuarantineSettings;
 use crate::common::traits::ResultEntry;
 use crate::helpers::messages::Messages;
 
@@ -25,13 +29,23 @@ pub struct CommonToolData {
     pub(crate) delete_method: DeleteMethod,
     pub(crate) maximal_file_size: u64,
     pub(crate) minimal_file_size: u64,
+    pub(crate) minimal_modified_date: u64,
+    pub(crate) maximal_modified_date: u64,
+    pub(crate) ownership_filter: OwnershipFilter,
     pub(crate) stopped_search: bool,
     pub(crate) use_cache: bool,
+    pub(crate) use_file_index: bool,
+    pub(crate) resume: bool,
+    pub(crate) use_ignore_files: bool,
+    pub(crate) follow_symlinks: bool,
+    pub(crate) device_io_limits: DeviceIoLimits,
     pub(crate) delete_outdated_cache: bool,
     pub(crate) save_also_as_json: bool,
     pub(crate) use_reference_folders: bool,
     pub(crate) dry_run: bool,
     pub(crate) move_to_trash: bool,
+    pub(crate) quarantine: Option<QuarantineSettings>,
+    pub(crate) keep_rules: Vec<KeepRule>,
     pub(crate) hide_hard_links: bool,
 }
 
@@ -45,6 +59,7 @@ pub enum DeleteMethod {
     OneOldest,
     OneNewest,
     HardLink,
+    Reflink, // Replace identical files with copy-on-write clones, supported only on e.g. Btrfs/XFS
     AllExceptBiggest,
     AllExceptSmallest,
     OneBiggest,
@@ -63,13 +78,23 @@ impl CommonToolData {
             delete_method: DeleteMethod::None,
             maximal_file_size: u64::MAX,
             minimal_file_size: 0,
+            minimal_modified_date: 0,
+            maximal_modified_date: u64::MAX,
+            ownership_filter: OwnershipFilter::default(),
             stopped_search: false,
             use_cache: true,
+            use_file_index: false,
+            resume: false,
+            use_ignore_files: false,
+            follow_symlinks: false,
+            device_io_limits: DeviceIoLimits::default(),
             delete_outdated_cache: true,
             save_also_as_json: false,
             use_reference_folders: false,
             dry_run: false,
             move_to_trash: false,
+            quarantine: None,
+            keep_rules: Vec::new(),
             hide_hard_links: false,
         }
     }
@@ -117,6 +142,43 @@ pub trait CommonData {
         self.get_cd().use_cache
     }
 
+    fn set_use_file_index(&mut self, use_file_index: bool) {
+        self.get_cd_mut().use_file_index = use_file_index;
+    }
+    fn get_use_file_index(&self) -> bool {
+        self.get_cd().use_file_index
+    }
+
+    fn set_use_ignore_files(&mut self, use_ignore_files: bool) {
+        self.get_cd_mut().use_ignore_files = use_ignore_files;
+    }
+    fn get_use_ignore_files(&self) -> bool {
+        self.get_cd().use_ignore_files
+    }
+
+    // Continues interrupted scan from checkpoint, if it was created with same inputs and parameters
+    fn set_resume(&mut self, resume: bool) {
+        self.get_cd_mut().resume = resume;
+    }
+    fn get_resume(&self) -> bool {
+        self.get_cd().resume
+    }
+
+    fn set_follow_symlinks(&mut self, follow_symlinks: bool) {
+        self.get_cd_mut().follow_symlinks = follow_symlinks;
+    }
+    fn get_follow_symlinks(&self) -> bool {
+        self.get_cd().follow_symlinks
+    }
+
+    // Used in stages which read content of files e.g. hashing
+    fn set_device_io_limits(&mut self, device_io_limits: DeviceIoLimits) {
+        self.get_cd_mut().device_io_limits = device_io_limits;
+    }
+    fn get_device_io_limits(&self) -> DeviceIoLimits {
+        self.get_cd().device_io_limits
+    }
+
     fn set_delete_outdated_cache(&mut self, delete_outdated_cache: bool) {
         self.get_cd_mut().delete_outdated_cache = delete_outdated_cache;
     }
@@ -151,6 +213,29 @@ pub trait CommonData {
         self.get_cd().minimal_file_size
     }
 
+    // Dates are in seconds since UNIX epoch, files modified outside of range are skipped during traversal
+    fn set_minimal_modified_date(&mut self, minimal_modified_date: u64) {
+        self.get_cd_mut().minimal_modified_date = minimal_modified_date;
+    }
+    fn get_minimal_modified_date(&self) -> u64 {
+        self.get_cd().minimal_modified_date
+    }
+
+    fn set_maximal_modified_date(&mut self, maximal_modified_date: u64) {
+        self.get_cd_mut().maximal_modified_date = maximal_modified_date;
+    }
+    fn get_maximal_modified_date(&self) -> u64 {
+        self.get_cd().maximal_modified_date
+    }
+
+    // Checked only on Unix-like systems
+    fn set_ownership_filter(&mut self, ownership_filter: OwnershipFilter) {
+        self.get_cd_mut().ownership_filter = ownership_filter;
+    }
+    fn get_ownership_filter(&self) -> OwnershipFilter {
+        self.get_cd().ownership_filter
+    }
+
     #[cfg(target_family = "unix")]
     fn set_exclude_other_filesystems(&mut self, exclude_other_filesystems: bool) {
         self.get_cd_mut().directories.set_exclude_other_filesystems(exclude_other_filesystems);
@@ -201,11 +286,33 @@ pub trait CommonData {
         self.get_cd().move_to_trash
     }
 
+    // When set, removed items are moved to quarantine folder instead of being deleted
+    fn set_quarantine(&mut self, quarantine: Option<QuarantineSettings>) {
+        self.get_cd_mut().quarantine = quarantine;
+    }
+    fn get_quarantine(&self) -> Option<&QuarantineSettings> {
+        self.get_cd().quarantine.as_ref()
+    }
+
+    // Rules choosing item kept in each group, used by delete methods, which keep only one item
+    fn set_keep_rules(&mut self, keep_rules: Vec<KeepRule>) {
+        self.get_cd_mut().keep_rules = keep_rules;
+    }
+    fn get_keep_rules(&self) -> &[KeepRule] {
+        &self.get_cd().keep_rules
+    }
+
     fn set_included_paths(&mut self, included_paths: Vec<PathBuf>) {
         let messages = self.get_cd_mut().directories.set_included_paths(included_paths);
         self.get_cd_mut().text_messages.extend_with_another_messages(messages);
     }
 
+    // Paths from file list are scanned as files, directories among them are not walked
+    fn set_listed_files(&mut self, listed_files: Vec<PathBuf>) {
+        let messages = self.get_cd_mut().directories.set_listed_files(listed_files);
+        self.get_cd_mut().text_messages.extend_with_another_messages(messages);
+    }
+
     fn set_excluded_paths(&mut self, excluded_paths: Vec<PathBuf>) {
         let messages = self.get_cd_mut().directories.set_excluded_paths(excluded_paths);
         self.get_cd_mut().text_messages.extend_with_another_messages(messages);
@@ -266,7 +373,7 @@ pub trait CommonData {
         deletion::delete_simple_elements_and_add_to_messages(self.get_cd_mut(), stop_flag, progress_sender, delete_item_type)
     }
 
-    fn delete_advanced_elements_and_add_to_messages<T: ResultEntry + Sized + Send + Sync + Clone>(
+    fn delete_advanced_elements_and_add_to_messages<T: KeepRuleEntry + Sized + Send + Sync + Clone>(
         &mut self,
         stop_flag: &Arc<AtomicBool>,
         progress_sender: Option<&Sender<ProgressData>>,
@@ -292,6 +399,7 @@ pub trait CommonData {
         println!("Reference paths(before optimization) - {:?}", self.get_cd().directories.original_reference_paths);
         println!("Included directories(optimized) - {:?}", self.get_cd().directories.included_directories);
         println!("Included files(optimized) - {:?}", self.get_cd().directories.included_files);
+        println!("Listed files - {}", self.get_cd().directories.listed_files.len());
         println!("Excluded directories(optimized) - {:?}", self.get_cd().directories.excluded_directories);
         println!("Excluded files(optimized) - {:?}", self.get_cd().directories.excluded_files);
         println!("Reference directories(optimized) - {:?}", self.get_cd().directories.reference_directories);
@@ -303,13 +411,24 @@ pub trait CommonData {
         println!("Recursive search: {}", self.get_cd().recursive_search);
         println!("Maximal file size: {}", self.get_cd().maximal_file_size);
         println!("Minimal file size: {}", self.get_cd().minimal_file_size);
+        println!("Minimal modified date: {}", self.get_cd().minimal_modified_date);
+        println!("Maximal modified date: {}", self.get_cd().maximal_modified_date);
+        println!("Ownership filter: {:?}", self.get_cd().ownership_filter);
         println!("Stopped search: {}", self.get_cd().stopped_search);
         println!("Use cache: {}", self.get_cd().use_cache);
+        println!("Use file index: {}", self.get_cd().use_file_index);
+        println!("Use ignore files: {}", self.get_cd().use_ignore_files);
+        println!("Follow symlinks: {}", self.get_cd().follow_symlinks);
+        println!("Resume: {}", self.get_cd().resume);
+        println!("Device IO limits: {:?}", self.get_cd().device_io_limits);
         println!("Delete outdated cache: {}", self.get_cd().delete_outdated_cache);
         println!("Save also as json: {}", self.get_cd().save_also_as_json);
         println!("Delete method: {:?}", self.get_cd().delete_method);
         println!("Use reference folders: {}", self.get_cd().use_reference_folders);
         println!("Dry run: {}", self.get_cd().dry_run);
+        println!("Move to trash: {}", self.get_cd().move_to_trash);
+        println!("Quarantine: {:?}", self.get_cd().quarantine);
+        println!("Keep rules: {:?}", self.get_cd().keep_rules);
         println!("Hide hard links: {}", self.get_cd().hide_hard_links);
 
         println!("---------------DEBUG PRINT MESSAGES---------------");
@@ -8,8 +8,10 @@ use crossbeam_channel::Sender;
 use fun_time::fun_time;
 use serde::Serialize;
 
+use crate::common::flat_results::{FlatResults, ResultsFormat};
 use crate::common::model::WorkContinueStatus;
 use crate::common::progress_data::ProgressData;
+use crate::common::saved_results::SavedResultsFile;
 use crate::common::tool_data::CommonData;
 
 pub trait DebugPrint {
@@ -45,6 +47,15 @@ pub trait PrintResults: CommonData {
             )?;
         }
 
+        if !dirs.listed_files.is_empty() {
+            writeln!(writer, "Also searched {} paths read from file list", dirs.listed_files.len())?;
+        }
+
+        let ownership_filter = self.get_cd().ownership_filter;
+        if ownership_filter.is_active() {
+            writeln!(writer, "Only files with {ownership_filter}")?;
+        }
+
         Ok(())
     }
 
@@ -78,14 +89,38 @@ pub trait PrintResults: CommonData {
         self.write_results(writer)
     }
 
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()>;
+    fn save_results_to_file(&self, file_name: &str, format: ResultsFormat) -> std::io::Result<()>;
+
+    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
+        self.save_results_to_file(file_name, if pretty_print { ResultsFormat::JsonPretty } else { ResultsFormat::JsonCompact })
+    }
+
+    fn save_results_to_file_internal<T: Serialize + std::fmt::Debug>(&self, file_name: &str, item_to_serialize: &T, format: ResultsFormat) -> std::io::Result<()> {
+        // JSON keeps type of tool, so results can be loaded back and applied later
+        let saved_results = SavedResultsFile {
+            tool_type: self.get_cd().tool_type,
+            results: item_to_serialize,
+        };
+        match format {
+            ResultsFormat::JsonPretty => self.save_results_to_file_as_json_pretty(file_name, &saved_results),
+            ResultsFormat::JsonCompact => self.save_results_to_file_as_json_compact(file_name, &saved_results),
+            ResultsFormat::Csv | ResultsFormat::Ndjson => self.save_results_to_file_as_flat(file_name, item_to_serialize, format),
+        }
+    }
 
-    fn save_results_to_file_as_json_internal<T: Serialize + std::fmt::Debug>(&self, file_name: &str, item_to_serialize: &T, pretty_print: bool) -> std::io::Result<()> {
-        if pretty_print {
-            self.save_results_to_file_as_json_pretty(file_name, item_to_serialize)
+    // One row per item, with id of its group instead of nested layout of results
+    #[fun_time(message = "save_results_to_file_as_flat", level = "debug")]
+    fn save_results_to_file_as_flat<T: Serialize + std::fmt::Debug>(&self, file_name: &str, item_to_serialize: &T, format: ResultsFormat) -> std::io::Result<()> {
+        let flat_results = FlatResults::from_json(&serde_json::to_value(item_to_serialize)?, self.get_cd().tool_type.flat_layout());
+        let file_handler = File::create(file_name)?;
+        let mut writer = BufWriter::new(file_handler);
+        if format == ResultsFormat::Csv {
+            flat_results.write_csv(&mut writer)?;
         } else {
-            self.save_results_to_file_as_json_compact(file_name, item_to_serialize)
+            flat_results.write_ndjson(&mut writer)?;
         }
+        writer.flush()?;
+        Ok(())
     }
 
     #[fun_time(message = "save_results_to_file_as_json_pretty", level = "debug")]
@@ -109,6 +144,10 @@ pub trait PrintResults: CommonData {
         self.save_results_to_file_as_json(&pretty_name, true)?;
         let compact_name = format!("{folder}/{base_file_name}_compact.json");
         self.save_results_to_file_as_json(&compact_name, false)?;
+        let csv_name = format!("{folder}/{base_file_name}.csv");
+        self.save_results_to_file(&csv_name, ResultsFormat::Csv)?;
+        let ndjson_name = format!("{folder}/{base_file_name}.ndjson");
+        self.save_results_to_file(&ndjson_name, ResultsFormat::Ndjson)?;
         let txt_name = format!("{folder}/{base_file_name}.txt");
         self.print_results_to_file(&txt_name)?;
         Ok(())
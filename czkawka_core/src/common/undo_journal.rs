@@ -0,0 +1,466 @@
+// Journal of changes made to filesystem - every removed, trashed, moved, renamed or linked item is saved as one JSON line.
+// Items changed by one action(e.g. deleting selected files) share operation id, so last action can be reverted at once.
+// Permanently deleted files cannot be restored, but are still saved, to show user what was lost.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fs, io};
+
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+
+use crate::common::config_cache_path::try_get_config_cache_path;
+use crate::common::fs_ops::{lock_file_beside, move_item, path_with_suffix, replace_file_content};
+use crate::flc;
+use crate::helpers::messages::Messages;
+
+const JOURNAL_FILE_NAME: &str = "undo_journal.jsonl";
+// Only few last actions are kept, older cannot be reverted anyway, because files were most likely changed since then
+const MAX_JOURNAL_OPERATIONS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JournalOperation {
+    Delete,
+    Trash,
+    Move,
+    Rename,
+    Hardlink,
+    Symlink,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub operation_id: u64,
+    pub operation: JournalOperation,
+    pub original_path: PathBuf,
+    // Destination of move/rename or file which replaced original path by hardlink/symlink
+    pub new_path: Option<PathBuf>,
+    pub size: u64,
+    // Only for links, to check if content is still the same as content of replaced file
+    pub hash: Option<String>,
+    pub timestamp: u64,
+}
+
+fn get_journal_path() -> Option<PathBuf> {
+    try_get_config_cache_path().map(|config_cache_path| config_cache_path.config_folder.join(JOURNAL_FILE_NAME))
+}
+
+fn now() -> std::time::Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
+}
+
+fn calculate_file_hash(path: &Path) -> io::Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+fn load_entries(journal_path: &Path) -> Result<Vec<JournalEntry>, String> {
+    let file = match File::open(journal_path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(flc!(
+                "core_undo_journal_cannot_open",
+                path = journal_path.to_string_lossy().to_string(),
+                reason = e.to_string()
+            ));
+        }
+    };
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| flc!("core_undo_journal_cannot_read", path = journal_path.to_string_lossy().to_string(), reason = e.to_string()))?;
+        // Line may be incomplete, when app was killed while saving it
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => debug!("Skipping invalid undo journal line - {e}"),
+        }
+    }
+    Ok(entries)
+}
+
+fn save_entries(journal_path: &Path, entries: &[JournalEntry]) -> Result<(), String> {
+    let cannot_save = |reason: String| flc!("core_undo_journal_cannot_save", path = journal_path.to_string_lossy().to_string(), reason = reason);
+    let mut content = String::new();
+    for entry in entries {
+        content.push_str(&serde_json::to_string(entry).map_err(|e| cannot_save(e.to_string()))?);
+        content.push('\n');
+    }
+    replace_file_content(journal_path, &content).map_err(|e| cannot_save(e.to_string()))
+}
+
+fn operation_ids(entries: &[JournalEntry]) -> Vec<u64> {
+    let mut ids: Vec<u64> = Vec::new();
+    for entry in entries {
+        if !ids.contains(&entry.operation_id) {
+            ids.push(entry.operation_id);
+        }
+    }
+    ids
+}
+
+// Records items changed by one action, old actions are removed from journal when first item is recorded
+pub struct JournalRecorder {
+    journal_path: Option<PathBuf>,
+    operation_id: u64,
+    // Set after old actions are removed, lock is also held while appending, so entries from different threads are never mixed
+    trimmed: Mutex<bool>,
+    // Many files may be linked to same file, which is hashed only once, even when files are recorded from many threads
+    link_target_hashes: Mutex<HashMap<PathBuf, Arc<OnceLock<Option<String>>>>>,
+}
+
+impl Default for JournalRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JournalRecorder {
+    pub fn new() -> Self {
+        Self::with_journal_path(get_journal_path())
+    }
+
+    fn with_journal_path(journal_path: Option<PathBuf>) -> Self {
+        Self {
+            journal_path,
+            operation_id: u64::try_from(now().as_nanos()).unwrap_or(u64::MAX),
+            trimmed: Mutex::new(false),
+            link_target_hashes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn trim_journal(journal_path: &Path) -> Result<(), String> {
+        let entries = load_entries(journal_path)?;
+        let ids = operation_ids(&entries);
+        if ids.len() >= MAX_JOURNAL_OPERATIONS {
+            let removed_ids = ids.get(..=ids.len() - MAX_JOURNAL_OPERATIONS).unwrap_or_default();
+            let kept_entries: Vec<_> = entries.into_iter().filter(|entry| !removed_ids.contains(&entry.operation_id)).collect();
+            save_entries(journal_path, &kept_entries)?;
+        }
+        Ok(())
+    }
+
+    // Journal may be replaced by other process between entries, so it is opened again for every entry
+    fn append_line(journal_path: &Path, trimmed: &mut bool, line: &str) -> Result<(), String> {
+        let _lock = lock_file_beside(journal_path)?;
+        if !*trimmed {
+            Self::trim_journal(journal_path)?;
+            *trimmed = true;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(journal_path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| flc!("core_undo_journal_cannot_save", path = journal_path.to_string_lossy().to_string(), reason = e.to_string()))
+    }
+
+    fn link_target_hash(&self, target: &Path) -> Option<String> {
+        let hash = self
+            .link_target_hashes
+            .lock()
+            .expect("Undo journal lock is poisoned")
+            .entry(target.to_path_buf())
+            .or_default()
+            .clone();
+        // Other threads recording links to same target wait here, until hash is calculated
+        hash.get_or_init(|| match calculate_file_hash(target) {
+            Ok(hash) => Some(hash),
+            Err(e) => {
+                error!("Cannot calculate hash of \"{}\" for undo journal - {e}", target.to_string_lossy());
+                None
+            }
+        })
+        .clone()
+    }
+
+    // Failure to save entry is only logged, because change was already done and cannot be stopped
+    pub fn record(&self, operation: JournalOperation, original_path: &Path, new_path: Option<&Path>, size: u64) {
+        let Some(journal_path) = &self.journal_path else {
+            return;
+        };
+
+        let hash = match (operation, new_path) {
+            (JournalOperation::Hardlink | JournalOperation::Symlink, Some(new_path)) => self.link_target_hash(new_path),
+            _ => None,
+        };
+        let entry = JournalEntry {
+            operation_id: self.operation_id,
+            operation,
+            original_path: original_path.to_path_buf(),
+            new_path: new_path.map(Path::to_path_buf),
+            size,
+            hash,
+            timestamp: now().as_secs(),
+        };
+        let line = match serde_json::to_string(&entry) {
+            Ok(line) => line + "\n",
+            Err(e) => {
+                error!("Cannot serialize undo journal entry - {e}");
+                return;
+            }
+        };
+
+        let mut trimmed = self.trimmed.lock().expect("Undo journal lock is poisoned");
+        if let Err(e) = Self::append_line(journal_path, &mut trimmed, &line) {
+            error!("{e}");
+        }
+    }
+}
+
+// Returns entries of last recorded action, in order of recording
+pub fn get_last_operation() -> Result<Vec<JournalEntry>, String> {
+    get_journal_path().map_or_else(|| Ok(Vec::new()), |journal_path| get_last_operation_from(&journal_path))
+}
+
+fn get_last_operation_from(journal_path: &Path) -> Result<Vec<JournalEntry>, String> {
+    let entries = {
+        let _lock = lock_file_beside(journal_path)?;
+        load_entries(journal_path)?
+    };
+    let Some(last_id) = entries.last().map(|entry| entry.operation_id) else {
+        return Ok(Vec::new());
+    };
+    Ok(entries.into_iter().filter(|entry| entry.operation_id == last_id).collect())
+}
+
+// Reverts items changed by last action in reverse order and removes action from journal.
+// Items which cannot be reverted are reported and also removed, so they never block reverting older actions.
+pub fn undo_last_operation(dry_run: bool) -> Messages {
+    match get_journal_path() {
+        Some(journal_path) => undo_last_operation_from(&journal_path, dry_run),
+        None => Messages {
+            errors: vec![flc!("core_undo_journal_not_available")],
+            ..Messages::new()
+        },
+    }
+}
+
+fn undo_last_operation_from(journal_path: &Path, dry_run: bool) -> Messages {
+    let mut messages = Messages::new();
+    let last_entries = match get_last_operation_from(journal_path) {
+        Ok(last_entries) => last_entries,
+        Err(e) => {
+            messages.errors.push(e);
+            return messages;
+        }
+    };
+    let Some(last_id) = last_entries.first().map(|entry| entry.operation_id) else {
+        messages.messages.push(flc!("core_undo_journal_empty"));
+        return messages;
+    };
+
+    let mut reverted = 0;
+    for entry in last_entries.iter().rev() {
+        let path = entry.original_path.to_string_lossy().to_string();
+        let result = if entry.operation == JournalOperation::Delete {
+            messages.warnings.push(flc!("core_undo_permanently_deleted", path = path));
+            continue;
+        } else if dry_run {
+            Ok(())
+        } else {
+            undo_entry(entry)
+        };
+        match result {
+            Ok(()) if dry_run => messages.messages.push(flc!("core_undo_would_restore", path = path)),
+            Ok(()) => reverted += 1,
+            Err(e) => messages.errors.push(flc!("core_undo_failed_restore", path = path, reason = e)),
+        }
+    }
+
+    if !dry_run {
+        messages.messages.push(flc!("core_undo_restored", restored = reverted, all = last_entries.len()));
+        // Journal is loaded again, because other actions may be recorded while reverting items
+        let result = lock_file_beside(journal_path).and_then(|_lock| {
+            let other_entries: Vec<_> = load_entries(journal_path)?.into_iter().filter(|entry| entry.operation_id != last_id).collect();
+            save_entries(journal_path, &other_entries)
+        });
+        if let Err(e) = result {
+            messages.errors.push(e);
+        }
+    }
+    messages
+}
+
+fn undo_entry(entry: &JournalEntry) -> Result<(), String> {
+    let new_path = entry.new_path.as_deref();
+    match (entry.operation, new_path) {
+        (JournalOperation::Delete, _) => Err(flc!("core_undo_permanently_deleted", path = entry.original_path.to_string_lossy().to_string())),
+        (JournalOperation::Trash, _) => restore_from_trash(&entry.original_path),
+        (JournalOperation::Move | JournalOperation::Rename, Some(new_path)) => move_back(new_path, &entry.original_path),
+        (JournalOperation::Hardlink, Some(new_path)) => {
+            // Hardlinked files share content, so any of them may be used as source of copy
+            let source = if entry.original_path.is_file() { &entry.original_path } else { new_path };
+            split_into_copy(source, &entry.original_path, entry.hash.as_deref())
+        }
+        (JournalOperation::Symlink, Some(new_path)) => {
+            if fs::symlink_metadata(&entry.original_path).is_ok_and(|metadata| !metadata.file_type().is_symlink()) {
+                return Err(flc!("core_undo_not_symlink"));
+            }
+            split_into_copy(new_path, &entry.original_path, entry.hash.as_deref())
+        }
+        (JournalOperation::Move | JournalOperation::Rename | JournalOperation::Hardlink | JournalOperation::Symlink, None) => Err(flc!("core_undo_missing_new_path")),
+    }
+}
+
+fn move_back(new_path: &Path, original_path: &Path) -> Result<(), String> {
+    if fs::symlink_metadata(original_path).is_ok() {
+        return Err(flc!("core_undo_path_used"));
+    }
+    // Moved items may be whole folders, also on other device
+    move_item(new_path, original_path)
+}
+
+// Replaces link with real copy of content, only when content is still same as when link was created
+fn split_into_copy(source: &Path, destination: &Path, expected_hash: Option<&str>) -> Result<(), String> {
+    let hash = calculate_file_hash(source).map_err(|e| flc!("core_undo_content_not_available", reason = e.to_string()))?;
+    if expected_hash.is_some_and(|expected_hash| expected_hash != hash) {
+        return Err(flc!("core_undo_content_changed", path = source.to_string_lossy().to_string()));
+    }
+
+    let temp_path = path_with_suffix(destination, ".czkawka_undo");
+    fs::copy(source, &temp_path).map_err(|e| e.to_string())?;
+    fs::rename(&temp_path, destination).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        e.to_string()
+    })
+}
+
+#[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))))]
+fn restore_from_trash(original_path: &Path) -> Result<(), String> {
+    if fs::symlink_metadata(original_path).is_ok() {
+        return Err(flc!("core_undo_path_used"));
+    }
+    // Same path may be trashed multiple times, so most recent item is restored
+    let item = trash::os_limited::list()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|item| item.original_path() == original_path)
+        .max_by_key(|item| item.time_deleted)
+        .ok_or_else(|| flc!("core_undo_not_in_trash"))?;
+    trash::os_limited::restore_all([item]).map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(target_os = "windows", all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android")))))]
+fn restore_from_trash(_original_path: &Path) -> Result<(), String> {
+    Err(flc!("core_undo_trash_not_supported"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::common::make_hard_link;
+
+    #[test]
+    fn test_undo_last_operation() {
+        let temp_dir = tempfile::TempDir::new().expect("Cannot create temporary folder");
+        let journal_path = temp_dir.path().join(JOURNAL_FILE_NAME);
+        let original = temp_dir.path().join("original.txt");
+        let linked = temp_dir.path().join("linked.txt");
+        let renamed = temp_dir.path().join("renamed.txt");
+        fs::write(&original, "content").expect("Cannot create original file");
+        fs::write(&linked, "content").expect("Cannot create linked file");
+
+        let recorder = JournalRecorder::with_journal_path(Some(journal_path.clone()));
+        make_hard_link(&original, &linked).expect("Cannot create hardlink");
+        recorder.record(JournalOperation::Hardlink, &linked, Some(&original), 7);
+        fs::rename(&original, &renamed).expect("Cannot rename original file");
+        recorder.record(JournalOperation::Rename, &original, Some(&renamed), 7);
+        recorder.record(JournalOperation::Delete, &temp_dir.path().join("deleted.txt"), None, 3);
+
+        let last_operation = get_last_operation_from(&journal_path).expect("Journal must be readable");
+        assert_eq!(last_operation.len(), 3);
+        assert!(last_operation.iter().all(|entry| entry.operation_id == recorder.operation_id));
+
+        let dry_run_messages = undo_last_operation_from(&journal_path, true);
+        assert_eq!(dry_run_messages.messages.len(), 2);
+        assert!(renamed.exists());
+
+        let messages = undo_last_operation_from(&journal_path, false);
+        assert!(messages.errors.is_empty(), "{:?}", messages.errors);
+        assert_eq!(messages.warnings.len(), 1);
+        assert!(original.exists());
+        assert!(!renamed.exists());
+        // File is no longer hardlink, so changing it does not change other file
+        fs::write(&linked, "changed").expect("Cannot change restored file");
+        assert_eq!(fs::read_to_string(&original).expect("Cannot read original file"), "content");
+
+        assert!(get_last_operation_from(&journal_path).expect("Journal must be readable").is_empty());
+        // Journal is saved through temporary file, which must not be left
+        assert!(!path_with_suffix(&journal_path, ".tmp").exists());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_undo_move_of_folder_from_other_device() {
+        // Memory filesystem is used as other device, so folder cannot be just renamed back
+        let Ok(other_device_dir) = tempfile::TempDir::new_in("/dev/shm") else {
+            return;
+        };
+        let temp_dir = tempfile::TempDir::new().expect("Cannot create temporary folder");
+        let journal_path = temp_dir.path().join(JOURNAL_FILE_NAME);
+        let original = temp_dir.path().join("folder");
+        let moved = other_device_dir.path().join("folder");
+        fs::create_dir_all(original.join("nested")).expect("Cannot create folder");
+        fs::write(original.join("nested").join("file.txt"), "content").expect("Cannot create file");
+
+        let recorder = JournalRecorder::with_journal_path(Some(journal_path.clone()));
+        move_item(&original, &moved).expect("Cannot move folder");
+        recorder.record(JournalOperation::Move, &original, Some(&moved), 7);
+
+        let messages = undo_last_operation_from(&journal_path, false);
+        assert!(messages.errors.is_empty(), "{:?}", messages.errors);
+        assert_eq!(fs::read_to_string(original.join("nested").join("file.txt")).expect("Cannot read restored file"), "content");
+        assert!(!moved.exists());
+    }
+
+    #[test]
+    fn test_link_target_is_hashed_once() {
+        let temp_dir = tempfile::TempDir::new().expect("Cannot create temporary folder");
+        let journal_path = temp_dir.path().join(JOURNAL_FILE_NAME);
+        let original = temp_dir.path().join("original.txt");
+        fs::write(&original, "content").expect("Cannot create original file");
+
+        let recorder = JournalRecorder::with_journal_path(Some(journal_path.clone()));
+        recorder.record(JournalOperation::Hardlink, &temp_dir.path().join("a.txt"), Some(&original), 7);
+        // Changed content is not noticed, because hash of target is calculated only for first link
+        fs::write(&original, "changed").expect("Cannot change original file");
+        recorder.record(JournalOperation::Hardlink, &temp_dir.path().join("b.txt"), Some(&original), 7);
+
+        assert_eq!(recorder.link_target_hashes.lock().expect("Lock must not be poisoned").len(), 1);
+        let entries = get_last_operation_from(&journal_path).expect("Journal must be readable");
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|entry| entry.hash == entries.first().and_then(|first| first.hash.clone())));
+    }
+
+    #[test]
+    fn test_old_operations_are_removed() {
+        let temp_dir = tempfile::TempDir::new().expect("Cannot create temporary folder");
+        let journal_path = temp_dir.path().join(JOURNAL_FILE_NAME);
+        let recorders: Vec<_> = (0..=MAX_JOURNAL_OPERATIONS)
+            .map(|idx| {
+                let recorder = JournalRecorder::with_journal_path(Some(journal_path.clone()));
+                recorder.record(JournalOperation::Delete, &temp_dir.path().join(format!("{idx}.txt")), None, 1);
+                recorder
+            })
+            .collect();
+        let entries = load_entries(&journal_path).expect("Journal must be readable");
+        assert_eq!(operation_ids(&entries).len(), MAX_JOURNAL_OPERATIONS);
+        assert!(!entries.iter().any(|entry| entry.original_path.ends_with("0.txt")));
+
+        // Recorder which already trimmed journal still appends to journal replaced by other recorders
+        recorders
+            .get(1)
+            .expect("Recorders were created")
+            .record(JournalOperation::Delete, &temp_dir.path().join("late.txt"), None, 1);
+        let entries = load_entries(&journal_path).expect("Journal must be readable");
+        assert_eq!(operation_ids(&entries).len(), MAX_JOURNAL_OPERATIONS);
+        assert!(entries.iter().any(|entry| entry.original_path.ends_with("late.txt")));
+    }
+}
@@ -15,6 +15,7 @@ use crate::common::model::{FileEntry, ToolType, WorkContinueStatus};
 use crate::common::progress_data::{ProgressData, ToolStage};
 use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
 use crate::common::tool_data::CommonToolData;
+use crate::common::undo_journal::{JournalOperation, JournalRecorder};
 use crate::tools::bad_extensions::workarounds::{DISABLED_EXTENSIONS, WORKAROUNDS};
 use crate::tools::bad_extensions::{BadExtensions, BadExtensionsParameters, BadFileEntry, Info};
 
@@ -200,6 +201,7 @@ impl BadExtensions {
 
     #[fun_time(message = "fix_bad_extensions", level = "debug")]
     pub fn fix_bad_extensions(&mut self, _fix_params: super::BadExtensionsFixParams, stop_flag: &Arc<AtomicBool>) {
+        let journal = JournalRecorder::new();
         let warnings: Vec<_> = mem::take(&mut self.bad_extensions_files)
             .into_par_iter()
             .map(|entry| {
@@ -214,7 +216,10 @@ impl BadExtensions {
                 }
 
                 match std::fs::rename(&entry.path, &new_path) {
-                    Ok(()) => Some(None),
+                    Ok(()) => {
+                        journal.record(JournalOperation::Rename, &entry.path, Some(&new_path), entry.size);
+                        Some(None)
+                    }
                     Err(e) => Some(Some(format!("Failed to rename {:?} to {:?}: {}", entry.path, new_path, e))),
                 }
             })
@@ -6,6 +6,7 @@ use std::time::Instant;
 use crossbeam_channel::Sender;
 use fun_time::fun_time;
 
+use crate::common::flat_results::ResultsFormat;
 use crate::common::model::WorkContinueStatus;
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
@@ -83,8 +84,8 @@ impl PrintResults for BadExtensions {
         Ok(())
     }
 
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
-        self.save_results_to_file_as_json_internal(file_name, &self.bad_extensions_files, pretty_print)
+    fn save_results_to_file(&self, file_name: &str, format: ResultsFormat) -> std::io::Result<()> {
+        self.save_results_to_file_internal(file_name, &self.bad_extensions_files, format)
     }
 }
 
@@ -13,6 +13,7 @@ use crate::common::model::{ToolType, WorkContinueStatus};
 use crate::common::progress_data::{ProgressData, ToolStage};
 use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
 use crate::common::tool_data::CommonToolData;
+use crate::common::undo_journal::{JournalOperation, JournalRecorder};
 use crate::tools::bad_names::{BadNameEntry, BadNames, BadNamesParameters, Info, NameFixerParams, NameIssues};
 
 impl BadNames {
@@ -103,6 +104,7 @@ impl BadNames {
 
     #[fun_time(message = "fix_bad_names", level = "debug")]
     pub fn fix_bad_names(&mut self, _fix_params: NameFixerParams, stop_flag: &Arc<AtomicBool>) {
+        let journal = JournalRecorder::new();
         let warnings: Vec<_> = mem::take(&mut self.bad_names_files)
             .into_par_iter()
             .map(|entry| {
@@ -117,7 +119,10 @@ impl BadNames {
                 }
 
                 match fs::rename(&entry.path, &new_path) {
-                    Ok(()) => Some(None),
+                    Ok(()) => {
+                        journal.record(JournalOperation::Rename, &entry.path, Some(&new_path), entry.size);
+                        Some(None)
+                    }
                     Err(e) => Some(Some(format!("Failed to rename {:?} to {:?}: {}", entry.path, new_path, e))),
                 }
             })
@@ -7,6 +7,7 @@ use crossbeam_channel::Sender;
 use fun_time::fun_time;
 use humansize::{BINARY, format_size};
 
+use crate::common::flat_results::ResultsFormat;
 use crate::common::model::WorkContinueStatus;
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
@@ -67,8 +68,8 @@ impl PrintResults for BigFile {
         Ok(())
     }
 
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
-        self.save_results_to_file_as_json_internal(file_name, &self.big_files, pretty_print)
+    fn save_results_to_file(&self, file_name: &str, format: ResultsFormat) -> std::io::Result<()> {
+        self.save_results_to_file_internal(file_name, &self.big_files, format)
     }
 }
 
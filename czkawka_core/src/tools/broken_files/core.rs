@@ -13,12 +13,15 @@ use lopdf::Document;
 use rayon::prelude::*;
 
 use crate::common::cache::{CACHE_BROKEN_FILES_VERSION, load_and_split_cache_generalized_by_path, save_and_connect_cache_generalized_by_path};
+use crate::common::checkpoint::Checkpoint;
 use crate::common::consts::{
     AUDIO_FILES_CONTENT_EXTENSIONS, BZ2_FILES_EXTENSIONS, FONT_FILES_EXTENSIONS, GZ_FILES_EXTENSIONS, IMAGE_RS_BROKEN_FILES_EXTENSIONS, JSON_FILES_EXTENSIONS,
     PDF_FILES_EXTENSIONS, SEVENZ_FILES_EXTENSIONS, SVG_FILES_EXTENSIONS, TAR_FILES_EXTENSIONS, TOML_FILES_EXTENSIONS, VIDEO_FILES_EXTENSIONS, XML_FILES_EXTENSIONS,
     XZ_FILES_EXTENSIONS, YAML_FILES_EXTENSIONS, ZIP_FILES_EXTENSIONS, ZST_FILES_EXTENSIONS,
 };
+use crate::common::device_io::DeviceIoScheduler;
 use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
+use crate::common::io_throttle::throttle_read;
 use crate::common::model::{ToolType, WorkContinueStatus};
 use crate::common::process_utils::run_command_interruptible;
 use crate::common::progress_data::{ProgressData, ToolStage};
@@ -68,6 +71,19 @@ impl BrokenFiles {
         }
     }
 
+    pub(crate) fn get_checkpoint(&self) -> Checkpoint {
+        Checkpoint::new(&self.common_data, &self.params)
+    }
+
+    // Files collected by interrupted scan are used instead of searching them again
+    pub(crate) fn load_files_from_checkpoint(&mut self, checkpoint: &Checkpoint) -> bool {
+        let Some(files_to_check) = checkpoint.resume(&mut self.common_data) else {
+            return false;
+        };
+        self.files_to_check = files_to_check;
+        true
+    }
+
     fn check_broken_image(mut file_entry: BrokenEntry) -> BrokenEntry {
         let error = match image::open(&file_entry.path) {
             Ok(img) => {
@@ -510,7 +526,7 @@ impl BrokenFiles {
     }
 
     #[fun_time(message = "look_for_broken_files", level = "debug")]
-    pub(crate) fn look_for_broken_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+    pub(crate) fn look_for_broken_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>, checkpoint: &Checkpoint) -> WorkContinueStatus {
         if self.files_to_check.is_empty() {
             return WorkContinueStatus::Continue;
         }
@@ -519,6 +535,7 @@ impl BrokenFiles {
 
         let checked_types = self.params.checked_types;
         Self::move_cached_entries_missing_required_checks(&mut records_already_cached, &mut non_cached_files_to_check, checked_types);
+        checkpoint.move_partial_results_by_path(&mut records_already_cached, &mut non_cached_files_to_check);
 
         let progress_handler = prepare_thread_handler_common(
             progress_sender,
@@ -530,28 +547,40 @@ impl BrokenFiles {
         let non_cached_files_to_check = non_cached_files_to_check.into_iter().collect::<Vec<_>>();
 
         debug!("look_for_broken_files - started finding for broken files");
-        let mut vec_file_entry: Vec<BrokenEntry> = non_cached_files_to_check
-            .into_par_iter()
-            .with_max_len(3)
-            .map(|(_, file_entry)| {
-                if check_if_stop_received(stop_flag) {
-                    return None;
-                }
+        let device_scheduler = DeviceIoScheduler::new(self.common_data.device_io_limits);
+        let partial_results = checkpoint.partial_results();
+        let check_file = |(_, file_entry): (String, BrokenEntry)| {
+            if check_if_stop_received(stop_flag) {
+                return None;
+            }
 
-                let size = file_entry.size;
-                let res = Self::check_file(file_entry, stop_flag, checked_types);
+            let size = file_entry.size;
+            let res = Self::check_file(file_entry, stop_flag, checked_types);
+            if let Some(Some(entry)) = &res {
+                partial_results.add(entry);
+            }
+            throttle_read(size);
 
-                progress_handler.increase_items(1);
-                progress_handler.increase_size(size);
+            progress_handler.increase_items(1);
+            progress_handler.increase_size(size);
 
-                res
-            })
-            .while_some()
-            .flatten()
-            .collect::<Vec<BrokenEntry>>();
+            res
+        };
+        let mut vec_file_entry: Vec<BrokenEntry> = if device_scheduler.is_active() {
+            device_scheduler
+                .map_grouped_by_device(non_cached_files_to_check, |(_, file_entry)| file_entry.path.as_path(), check_file)
+                .into_iter()
+                .flatten()
+                .collect()
+        } else {
+            non_cached_files_to_check.into_par_iter().with_max_len(3).map(check_file).while_some().flatten().collect()
+        };
         debug!("look_for_broken_files - ended finding for broken files");
 
         progress_handler.join_thread();
+        if check_if_stop_received(stop_flag) {
+            partial_results.flush();
+        }
 
         // Just connect loaded results with already calculated
         vec_file_entry.extend(records_already_cached.into_values());
@@ -111,7 +111,7 @@ pub struct Info {
     pub scanning_time: Duration,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct BrokenFilesParameters {
     pub checked_types: CheckedTypes,
 }
@@ -6,8 +6,11 @@ use std::sync::atomic::AtomicBool;
 
 use tempfile::TempDir;
 
+use crate::common::consts::IMAGE_RS_BROKEN_FILES_EXTENSIONS;
+use crate::common::model::WorkContinueStatus;
 use crate::common::tool_data::CommonData;
 use crate::common::traits::Search;
+use crate::flc;
 use crate::tools::broken_files::{BrokenEntry, BrokenFiles, BrokenFilesParameters, CheckedTypes, CheckedTypesSingle};
 
 fn run_check(dir: &TempDir, checked_types: CheckedTypes) -> Vec<BrokenEntry> {
@@ -416,3 +419,47 @@ fn test_multiple_avif_valid_and_broken() {
     assert_eq!(results.len(), 1, "Only the corrupted AVIF should be detected");
     assert!(results[0].has_errors());
 }
+
+#[test]
+fn test_resumed_scan_uses_files_checked_before_interruption() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let temp_cache = TempDir::new().unwrap();
+        let temp_config = TempDir::new().unwrap();
+        crate::common::config_cache_path::set_config_cache_path_test(temp_cache.path().to_path_buf(), temp_config.path().to_path_buf());
+        std::mem::forget(temp_cache);
+        std::mem::forget(temp_config);
+    });
+
+    let temp_dir = TempDir::new().unwrap();
+    let source_image = get_test_resources_path().join("images").join("normal.jpg");
+    fs::copy(&source_image, temp_dir.path().join("checked.jpg")).unwrap();
+    fs::copy(&source_image, temp_dir.path().join("not_checked.jpg")).unwrap();
+
+    let create_finder = || {
+        let mut finder = BrokenFiles::new(BrokenFilesParameters::new(CheckedTypes::IMAGE));
+        finder.set_included_paths(vec![temp_dir.path().to_path_buf()]);
+        finder.set_recursive_search(true);
+        finder.set_use_cache(true);
+        finder.set_resume(true);
+        finder
+    };
+    let stop_flag = Arc::new(AtomicBool::new(false));
+
+    // Scan interrupted after checking first file, result is faked to be sure that file is not checked again
+    let mut finder = create_finder();
+    finder.prepare_items(Some(IMAGE_RS_BROKEN_FILES_EXTENSIONS)).unwrap();
+    assert_eq!(finder.check_files(&stop_flag, None), WorkContinueStatus::Continue);
+    let checkpoint = finder.get_checkpoint();
+    checkpoint.save_stage(&finder.files_to_check);
+    let mut checked = finder.files_to_check.values().find(|entry| entry.path.ends_with("checked.jpg")).cloned().unwrap();
+    checked.errors.insert(CheckedTypesSingle::Image, Some("Checked before interruption".to_string()));
+    checkpoint.partial_results().add(&checked);
+
+    let mut finder = create_finder();
+    finder.search(&stop_flag, None);
+    assert!(finder.get_text_messages().messages.contains(&flc!("core_resumed_from_checkpoint")));
+    let broken_files = finder.get_broken_files();
+    assert_eq!(broken_files.len(), 1);
+    assert!(broken_files[0].path.ends_with("checked.jpg"));
+}
@@ -12,8 +12,10 @@ use crate::common::consts::{
     XZ_FILES_EXTENSIONS, YAML_FILES_EXTENSIONS, ZIP_FILES_EXTENSIONS, ZST_FILES_EXTENSIONS,
 };
 use crate::common::ffmpeg_utils::check_if_ffprobe_ffmpeg_exists;
+use crate::common::flat_results::ResultsFormat;
 use crate::common::model::WorkContinueStatus;
 use crate::common::progress_data::ProgressData;
+use crate::common::progress_stop_handler::check_if_stop_received;
 use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
 use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
 use crate::flc;
@@ -81,14 +83,23 @@ impl Search for BrokenFiles {
             if self.prepare_items(Some(&extensions)).is_err() {
                 return;
             }
-            if self.check_files(stop_flag, progress_sender) == WorkContinueStatus::Stop {
-                self.common_data.stopped_search = true;
-                return;
+            // Collected files are saved in checkpoint, so interrupted scan may be resumed without searching them again
+            let checkpoint = self.get_checkpoint();
+            if !self.load_files_from_checkpoint(&checkpoint) {
+                if self.check_files(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                    self.common_data.stopped_search = true;
+                    return;
+                }
+                checkpoint.save_stage(&self.files_to_check);
             }
-            if self.look_for_broken_files(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+            if self.look_for_broken_files(stop_flag, progress_sender, &checkpoint) == WorkContinueStatus::Stop {
                 self.common_data.stopped_search = true;
                 return;
             }
+            // Checking shows partial results when stopped, so checkpoint is still needed to finish it later
+            if !check_if_stop_received(stop_flag) {
+                checkpoint.remove();
+            }
             if self.delete_files(stop_flag, progress_sender) == WorkContinueStatus::Stop {
                 self.common_data.stopped_search = true;
             }
@@ -127,8 +138,8 @@ impl PrintResults for BrokenFiles {
         Ok(())
     }
 
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
-        self.save_results_to_file_as_json_internal(file_name, &self.broken_files, pretty_print)
+    fn save_results_to_file(&self, file_name: &str, format: ResultsFormat) -> std::io::Result<()> {
+        self.save_results_to_file_internal(file_name, &self.broken_files, format)
     }
 }
 impl DeletingItems for BrokenFiles {
@@ -0,0 +1,276 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use log::debug;
+use rayon::prelude::*;
+
+use crate::common::cache::{load_and_split_cache_generalized_by_size, save_cache_to_file_generalized};
+use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
+use crate::common::model::{CheckingMethod, FileEntry, ToolType};
+use crate::common::progress_data::{CacheLoadPhase, DirectoryComparisonStage, ProgressData, ToolStage};
+use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
+use crate::common::tool_data::{CommonData, CommonToolData};
+use crate::flc;
+use crate::tools::directory_comparison::{ChangedFileEntry, DirectoryComparison, DirectoryComparisonParameters, Info, MovedFilesEntry};
+use crate::tools::duplicate::core::get_duplicate_cache_file;
+use crate::tools::duplicate::{DuplicateEntry, THREAD_BUFFER, hash_calculation};
+
+impl DirectoryComparison {
+    pub fn new(params: DirectoryComparisonParameters) -> Self {
+        Self {
+            common_data: CommonToolData::new(ToolType::DirectoryComparison),
+            information: Info::default(),
+            files_only_in_first: Vec::new(),
+            files_only_in_second: Vec::new(),
+            changed_files: Vec::new(),
+            moved_files: Vec::new(),
+            params,
+        }
+    }
+
+    // Compared directories are the only scanned paths, so relative paths of all found files can be calculated
+    #[fun_time(message = "prepare_compared_directories", level = "debug")]
+    pub(crate) fn prepare_compared_directories(&mut self) -> Option<(PathBuf, PathBuf)> {
+        let compared_directories = vec![self.params.first_directory.clone(), self.params.second_directory.clone()];
+        // Avoids duplicated warnings, when same paths were already set
+        if self.common_data.directories.original_included_paths != compared_directories {
+            self.set_included_paths(compared_directories);
+        }
+        self.common_data.directories.listed_files.clear();
+
+        let [first_directory, second_directory] = self.common_data.directories.included_directories.as_slice() else {
+            self.common_data.text_messages.critical = Some(flc!("core_compared_directories_must_exist"));
+            return None;
+        };
+        if first_directory.starts_with(second_directory) || second_directory.starts_with(first_directory) {
+            self.common_data.text_messages.critical = Some(flc!(
+                "core_compared_directories_nested",
+                first = first_directory.to_string_lossy(),
+                second = second_directory.to_string_lossy()
+            ));
+            return None;
+        }
+        Some((first_directory.clone(), second_directory.clone()))
+    }
+
+    #[fun_time(message = "collect_files", level = "debug")]
+    pub(crate) fn collect_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> Option<Vec<FileEntry>> {
+        let result = DirTraversalBuilder::new()
+            .common_data(&self.common_data)
+            .group_by(|_fe| ())
+            .checking_method(CheckingMethod::Hash)
+            .stop_flag(stop_flag)
+            .progress_sender(progress_sender)
+            .build()
+            .run();
+
+        match result {
+            DirTraversalResult::SuccessFiles { grouped_file_entries, warnings } => {
+                self.common_data.text_messages.warnings.extend(warnings);
+                Some(grouped_file_entries.into_values().flatten().collect())
+            }
+            DirTraversalResult::Stopped => None,
+        }
+    }
+
+    // Pairs files with same relative path, files with different size are changed without need to check their content.
+    // Returns pairs of files with same size, which must be compared by hash.
+    #[fun_time(message = "pair_files_by_relative_path", level = "debug")]
+    pub(crate) fn pair_files_by_relative_path(&mut self, files: Vec<FileEntry>, first_directory: &Path, second_directory: &Path) -> Vec<(PathBuf, FileEntry, FileEntry)> {
+        let mut first_files: BTreeMap<PathBuf, FileEntry> = BTreeMap::new();
+        let mut second_files: BTreeMap<PathBuf, FileEntry> = BTreeMap::new();
+        for file_entry in files {
+            if let Ok(relative_path) = file_entry.path.strip_prefix(first_directory) {
+                first_files.insert(relative_path.to_path_buf(), file_entry);
+            } else if let Ok(relative_path) = file_entry.path.strip_prefix(second_directory) {
+                second_files.insert(relative_path.to_path_buf(), file_entry);
+            }
+        }
+
+        let mut same_size_files = Vec::new();
+        for (relative_path, first_file) in first_files {
+            match second_files.remove(&relative_path) {
+                Some(second_file) if second_file.size == first_file.size => same_size_files.push((relative_path, first_file, second_file)),
+                Some(second_file) => self.changed_files.push(ChangedFileEntry {
+                    relative_path,
+                    first_file,
+                    second_file,
+                }),
+                None => self.files_only_in_first.push(first_file),
+            }
+        }
+        self.files_only_in_second = second_files.into_values().collect();
+
+        same_size_files
+    }
+
+    // Files which exist only in one directory can be moved only when other directory contains file with same size
+    pub(crate) fn get_possibly_moved_files(&self) -> Vec<FileEntry> {
+        let first_sizes: HashSet<u64> = self.files_only_in_first.iter().map(|fe| fe.size).collect();
+        let second_sizes: HashSet<u64> = self.files_only_in_second.iter().map(|fe| fe.size).collect();
+
+        self.files_only_in_first
+            .iter()
+            .filter(|fe| second_sizes.contains(&fe.size))
+            .chain(self.files_only_in_second.iter().filter(|fe| first_sizes.contains(&fe.size)))
+            .cloned()
+            .collect()
+    }
+
+    // Uses same cache as duplicate finder, so files already hashed there are not read again.
+    // Returns hashes of all files which could be read.
+    #[fun_time(message = "hash_files", level = "debug")]
+    pub(crate) fn hash_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>, files: Vec<FileEntry>) -> Option<HashMap<PathBuf, String>> {
+        let mut files_to_hash: BTreeMap<u64, Vec<DuplicateEntry>> = Default::default();
+        for file_entry in files {
+            files_to_hash.entry(file_entry.size).or_default().push(file_entry.into_duplicate_entry());
+        }
+
+        let progress_handler = prepare_thread_handler_common(
+            progress_sender,
+            ToolStage::DirectoryComparison(DirectoryComparisonStage::LoadingHashCache(CacheLoadPhase::Loading)),
+            0,
+            0,
+        );
+        let (loaded_hash_map, records_already_cached, non_cached_files_to_check) =
+            load_and_split_cache_generalized_by_size(&get_duplicate_cache_file(self.params.hash_type, false), self.common_data.use_cache, files_to_hash, self);
+        progress_handler.join_thread();
+        if check_if_stop_received(stop_flag) {
+            return None;
+        }
+
+        let progress_handler = prepare_thread_handler_common(
+            progress_sender,
+            ToolStage::DirectoryComparison(DirectoryComparisonStage::FullHashing),
+            non_cached_files_to_check.values().map(Vec::len).sum(),
+            non_cached_files_to_check.iter().map(|(size, items)| (*size) * items.len() as u64).sum::<u64>(),
+        );
+
+        let hash_type = self.params.hash_type;
+        let non_cached_files_to_check: Vec<DuplicateEntry> = non_cached_files_to_check.into_values().flatten().collect();
+        let hash_results: Vec<Result<DuplicateEntry, String>> = non_cached_files_to_check
+            .into_par_iter()
+            .map(|mut file_entry| {
+                if check_if_stop_received(stop_flag) {
+                    return None;
+                }
+                let result = THREAD_BUFFER.with_borrow_mut(|buffer| hash_calculation(buffer, &file_entry, hash_type, progress_handler.size_counter(), stop_flag));
+                progress_handler.increase_items(1);
+                match result {
+                    Ok(hash) => {
+                        file_entry.hash = hash?;
+                        Some(Ok(file_entry))
+                    }
+                    Err(e) => Some(Err(e)),
+                }
+            })
+            .while_some()
+            .collect();
+        progress_handler.join_thread();
+
+        let mut newly_hashed = Vec::new();
+        for result in hash_results {
+            match result {
+                Ok(file_entry) => newly_hashed.push(file_entry),
+                Err(e) => self.common_data.text_messages.warnings.push(e),
+            }
+        }
+        debug!("hash_files - calculated {} hashes", newly_hashed.len());
+
+        // Even if clicked stop, save already calculated hashes to cache
+        if self.common_data.use_cache {
+            let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::DirectoryComparison(DirectoryComparisonStage::SavingHashCache), 0, 0);
+            let mut all_results: BTreeMap<String, DuplicateEntry> = Default::default();
+            for file_entry in loaded_hash_map.into_values().flatten() {
+                all_results.insert(file_entry.path.to_string_lossy().to_string(), file_entry);
+            }
+            for file_entry in &newly_hashed {
+                all_results.insert(file_entry.path.to_string_lossy().to_string(), file_entry.clone());
+            }
+            let messages = save_cache_to_file_generalized(
+                &get_duplicate_cache_file(self.params.hash_type, false),
+                &all_results,
+                self.common_data.save_also_as_json,
+                self.params.minimal_cache_file_size,
+            );
+            self.get_text_messages_mut().extend_with_another_messages(messages);
+            progress_handler.join_thread();
+        }
+
+        if check_if_stop_received(stop_flag) {
+            return None;
+        }
+
+        Some(
+            newly_hashed
+                .into_iter()
+                .chain(records_already_cached.into_values().flatten())
+                .map(|file_entry| (file_entry.path, file_entry.hash))
+                .collect(),
+        )
+    }
+
+    // Files which could not be read are already reported as warnings, so they are not shown as changed or moved
+    #[fun_time(message = "compare_hashes", level = "debug")]
+    pub(crate) fn compare_hashes(&mut self, same_size_files: Vec<(PathBuf, FileEntry, FileEntry)>, hashes: &HashMap<PathBuf, String>) {
+        for (relative_path, first_file, second_file) in same_size_files {
+            match (hashes.get(&first_file.path), hashes.get(&second_file.path)) {
+                (Some(first_hash), Some(second_hash)) if first_hash == second_hash => self.information.number_of_identical_files += 1,
+                (Some(_), Some(_)) => self.changed_files.push(ChangedFileEntry {
+                    relative_path,
+                    first_file,
+                    second_file,
+                }),
+                _ => {}
+            }
+        }
+
+        // Files with same content, that exist only in one of directories, were moved or renamed
+        let mut files_by_hash: BTreeMap<(u64, String), (Vec<FileEntry>, Vec<FileEntry>)> = BTreeMap::new();
+        let mut files_only_in_first = Vec::new();
+        for file_entry in mem::take(&mut self.files_only_in_first) {
+            match hashes.get(&file_entry.path) {
+                Some(hash) => files_by_hash.entry((file_entry.size, hash.clone())).or_default().0.push(file_entry),
+                None => files_only_in_first.push(file_entry),
+            }
+        }
+        let mut files_only_in_second = Vec::new();
+        for file_entry in mem::take(&mut self.files_only_in_second) {
+            match hashes.get(&file_entry.path) {
+                Some(hash) => files_by_hash.entry((file_entry.size, hash.clone())).or_default().1.push(file_entry),
+                None => files_only_in_second.push(file_entry),
+            }
+        }
+        for ((size, hash), (first_files, second_files)) in files_by_hash {
+            if first_files.is_empty() || second_files.is_empty() {
+                files_only_in_first.extend(first_files);
+                files_only_in_second.extend(second_files);
+            } else {
+                self.moved_files.push(MovedFilesEntry {
+                    size,
+                    hash,
+                    first_files,
+                    second_files,
+                });
+            }
+        }
+
+        files_only_in_first.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+        files_only_in_second.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+        self.files_only_in_first = files_only_in_first;
+        self.files_only_in_second = files_only_in_second;
+        self.changed_files.sort_unstable_by(|a, b| a.relative_path.cmp(&b.relative_path));
+        self.moved_files
+            .sort_unstable_by(|a, b| a.first_files.first().map(|fe| &fe.path).cmp(&b.first_files.first().map(|fe| &fe.path)));
+
+        self.information.number_of_files_only_in_first = self.files_only_in_first.len();
+        self.information.number_of_files_only_in_second = self.files_only_in_second.len();
+        self.information.number_of_changed_files = self.changed_files.len();
+        self.information.number_of_moved_files = self.moved_files.len();
+    }
+}
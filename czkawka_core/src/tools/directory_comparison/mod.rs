@@ -0,0 +1,96 @@
+pub mod core;
+#[cfg(test)]
+mod tests;
+pub mod traits;
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::model::{FileEntry, HashType};
+use crate::common::tool_data::CommonToolData;
+
+// File which exists at same relative path in both directories, but has different content
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct ChangedFileEntry {
+    pub relative_path: PathBuf,
+    pub first_file: FileEntry,
+    pub second_file: FileEntry,
+}
+
+// Files with same content, which are placed at different relative paths in each directory
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct MovedFilesEntry {
+    pub size: u64,
+    pub hash: String,
+    pub first_files: Vec<FileEntry>,
+    pub second_files: Vec<FileEntry>,
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct Info {
+    pub number_of_identical_files: usize,
+    pub number_of_files_only_in_first: usize,
+    pub number_of_files_only_in_second: usize,
+    pub number_of_changed_files: usize,
+    pub number_of_moved_files: usize,
+    pub scanning_time: Duration,
+}
+
+#[derive(Clone)]
+pub struct DirectoryComparisonParameters {
+    // Usually source directory
+    pub first_directory: PathBuf,
+    // Usually backup or copy of first directory
+    pub second_directory: PathBuf,
+    pub hash_type: HashType,
+    pub minimal_cache_file_size: u64,
+}
+
+impl DirectoryComparisonParameters {
+    pub fn new(first_directory: PathBuf, second_directory: PathBuf, hash_type: HashType, minimal_cache_file_size: u64) -> Self {
+        Self {
+            first_directory,
+            second_directory,
+            hash_type,
+            minimal_cache_file_size,
+        }
+    }
+}
+
+pub struct DirectoryComparison {
+    common_data: CommonToolData,
+    information: Info,
+    files_only_in_first: Vec<FileEntry>,
+    files_only_in_second: Vec<FileEntry>,
+    changed_files: Vec<ChangedFileEntry>,
+    moved_files: Vec<MovedFilesEntry>,
+    params: DirectoryComparisonParameters,
+}
+
+impl DirectoryComparison {
+    pub fn get_params(&self) -> &DirectoryComparisonParameters {
+        &self.params
+    }
+
+    pub const fn get_information(&self) -> Info {
+        self.information
+    }
+
+    pub const fn get_files_only_in_first(&self) -> &Vec<FileEntry> {
+        &self.files_only_in_first
+    }
+
+    pub const fn get_files_only_in_second(&self) -> &Vec<FileEntry> {
+        &self.files_only_in_second
+    }
+
+    pub const fn get_changed_files(&self) -> &Vec<ChangedFileEntry> {
+        &self.changed_files
+    }
+
+    pub const fn get_moved_files(&self) -> &Vec<MovedFilesEntry> {
+        &self.moved_files
+    }
+}
@@ -0,0 +1,111 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use tempfile::TempDir;
+
+use crate::common::model::HashType;
+use crate::common::tool_data::CommonData;
+use crate::common::traits::{PrintResults, Search};
+use crate::tools::directory_comparison::{DirectoryComparison, DirectoryComparisonParameters};
+
+fn run_comparison(first_directory: &Path, second_directory: &Path) -> DirectoryComparison {
+    let mut comparison = DirectoryComparison::new(DirectoryComparisonParameters::new(
+        first_directory.to_path_buf(),
+        second_directory.to_path_buf(),
+        HashType::Blake3,
+        0,
+    ));
+    comparison.set_recursive_search(true);
+    comparison.set_use_cache(false);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    comparison.search(&stop_flag, None);
+    comparison
+}
+
+#[test]
+fn test_compare_directories_finds_all_kinds_of_differences() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first");
+    let second = temp_dir.path().join("second");
+    for dir in [&first, &second] {
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("identical.txt"), b"same content").unwrap();
+        fs::write(dir.join("sub").join("identical.txt"), b"other same content").unwrap();
+    }
+    // Same size, different content
+    fs::write(first.join("changed.txt"), b"content A").unwrap();
+    fs::write(second.join("changed.txt"), b"content B").unwrap();
+    // Different size
+    fs::write(first.join("resized.txt"), b"short").unwrap();
+    fs::write(second.join("resized.txt"), b"much longer").unwrap();
+    fs::write(first.join("only_first.txt"), b"first only").unwrap();
+    fs::write(second.join("only_second.txt"), b"second only!").unwrap();
+    fs::write(first.join("moved.txt"), b"moved content").unwrap();
+    fs::write(second.join("sub").join("renamed.txt"), b"moved content").unwrap();
+
+    let comparison = run_comparison(&first, &second);
+    assert!(comparison.get_text_messages().critical.is_none());
+    let info = comparison.get_information();
+    assert_eq!(info.number_of_identical_files, 2);
+
+    assert_eq!(comparison.get_files_only_in_first().len(), 1);
+    assert_eq!(comparison.get_files_only_in_first()[0].path, first.join("only_first.txt"));
+    assert_eq!(comparison.get_files_only_in_second().len(), 1);
+    assert_eq!(comparison.get_files_only_in_second()[0].path, second.join("only_second.txt"));
+
+    let changed: Vec<_> = comparison.get_changed_files().iter().map(|entry| entry.relative_path.clone()).collect();
+    assert_eq!(changed, vec![Path::new("changed.txt").to_path_buf(), Path::new("resized.txt").to_path_buf()]);
+
+    assert_eq!(comparison.get_moved_files().len(), 1);
+    let moved = &comparison.get_moved_files()[0];
+    assert_eq!(moved.first_files.len(), 1);
+    assert_eq!(moved.first_files[0].path, first.join("moved.txt"));
+    assert_eq!(moved.second_files.len(), 1);
+    assert_eq!(moved.second_files[0].path, second.join("sub").join("renamed.txt"));
+    assert!(comparison.found_any_items());
+
+    let mut output = Vec::new();
+    comparison.write_results(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("Files only in first directory"));
+    assert!(output.contains("Moved files"));
+}
+
+#[test]
+fn test_compare_identical_directories() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first");
+    let second = temp_dir.path().join("second");
+    for dir in [&first, &second] {
+        fs::create_dir(dir).unwrap();
+        fs::write(dir.join("file.txt"), b"content").unwrap();
+    }
+
+    let comparison = run_comparison(&first, &second);
+    assert_eq!(comparison.get_information().number_of_identical_files, 1);
+    assert!(!comparison.found_any_items());
+}
+
+#[test]
+fn test_compare_nested_directories() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first");
+    let second = first.join("second");
+    fs::create_dir_all(&second).unwrap();
+
+    let comparison = run_comparison(&first, &second);
+    assert!(comparison.get_text_messages().critical.is_some());
+    assert!(!comparison.found_any_items());
+}
+
+#[test]
+fn test_compare_missing_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first");
+    fs::create_dir(&first).unwrap();
+
+    let comparison = run_comparison(&first, &temp_dir.path().join("not_existing"));
+    assert!(comparison.get_text_messages().critical.is_some());
+}
@@ -0,0 +1,216 @@
+use std::io::prelude::*;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Instant;
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use humansize::{BINARY, format_size};
+use serde::Serialize;
+
+use crate::common::flat_results::ResultsFormat;
+use crate::common::model::{FileEntry, WorkContinueStatus};
+use crate::common::progress_data::ProgressData;
+use crate::common::tool_data::{CommonData, CommonToolData};
+use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
+use crate::tools::directory_comparison::{ChangedFileEntry, DirectoryComparison, DirectoryComparisonParameters, Info, MovedFilesEntry};
+
+impl AllTraits for DirectoryComparison {}
+
+impl DeletingItems for DirectoryComparison {
+    fn delete_files(&mut self, _stop_flag: &Arc<AtomicBool>, _progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        unreachable!("DirectoryComparison does not support deleting files");
+    }
+}
+
+impl Search for DirectoryComparison {
+    #[fun_time(message = "compare_directories", level = "info")]
+    fn search(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) {
+        let start_time = Instant::now();
+
+        let () = (|| {
+            let Some((first_directory, second_directory)) = self.prepare_compared_directories() else {
+                return;
+            };
+            if self.prepare_items(None).is_err() {
+                return;
+            }
+            let Some(found_files) = self.collect_files(stop_flag, progress_sender) else {
+                self.common_data.stopped_search = true;
+                return;
+            };
+
+            let same_size_files = self.pair_files_by_relative_path(found_files, &first_directory, &second_directory);
+            let files_to_hash = same_size_files
+                .iter()
+                .flat_map(|(_, first_file, second_file)| [first_file.clone(), second_file.clone()])
+                .chain(self.get_possibly_moved_files())
+                .collect();
+            let Some(hashes) = self.hash_files(stop_flag, progress_sender, files_to_hash) else {
+                self.common_data.stopped_search = true;
+                return;
+            };
+            self.compare_hashes(same_size_files, &hashes);
+        })();
+
+        self.information.scanning_time = start_time.elapsed();
+
+        if !self.common_data.stopped_search {
+            self.debug_print();
+        }
+    }
+}
+
+impl DebugPrint for DirectoryComparison {
+    #[expect(clippy::print_stdout)]
+    fn debug_print(&self) {
+        if !cfg!(debug_assertions) || cfg!(test) {
+            return;
+        }
+        println!("---------------DEBUG PRINT---------------");
+        println!("Number of identical files - {}", self.information.number_of_identical_files);
+        println!("Number of files only in first directory - {}", self.information.number_of_files_only_in_first);
+        println!("Number of files only in second directory - {}", self.information.number_of_files_only_in_second);
+        println!("Number of changed files - {}", self.information.number_of_changed_files);
+        println!("Number of moved files groups - {}", self.information.number_of_moved_files);
+        self.debug_print_common();
+        println!("-----------------------------------------");
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct DirectoryComparisonResults<'a> {
+    first_directory: &'a Path,
+    second_directory: &'a Path,
+    files_only_in_first: &'a Vec<FileEntry>,
+    files_only_in_second: &'a Vec<FileEntry>,
+    changed_files: &'a Vec<ChangedFileEntry>,
+    moved_files: &'a Vec<MovedFilesEntry>,
+}
+
+impl PrintResults for DirectoryComparison {
+    fn write_results<T: Write>(&self, writer: &mut T) -> std::io::Result<()> {
+        writeln!(
+            writer,
+            "Comparing \"{}\" (first) with \"{}\" (second).",
+            self.params.first_directory.to_string_lossy(),
+            self.params.second_directory.to_string_lossy()
+        )?;
+        writeln!(writer, "Found {} identical files.", self.information.number_of_identical_files)?;
+
+        if !self.files_only_in_first.is_empty() {
+            writeln!(
+                writer,
+                "\n-------------------------------------------------Files only in first directory-------------------------------------------------"
+            )?;
+            writeln!(
+                writer,
+                "Found {} files, which exist only in first directory.",
+                self.information.number_of_files_only_in_first
+            )?;
+            for file_entry in &self.files_only_in_first {
+                writeln!(writer, "\"{}\" - {}", file_entry.path.to_string_lossy(), format_size(file_entry.size, BINARY))?;
+            }
+        }
+
+        if !self.files_only_in_second.is_empty() {
+            writeln!(
+                writer,
+                "\n-------------------------------------------------Files only in second directory-------------------------------------------------"
+            )?;
+            writeln!(
+                writer,
+                "Found {} files, which exist only in second directory.",
+                self.information.number_of_files_only_in_second
+            )?;
+            for file_entry in &self.files_only_in_second {
+                writeln!(writer, "\"{}\" - {}", file_entry.path.to_string_lossy(), format_size(file_entry.size, BINARY))?;
+            }
+        }
+
+        if !self.changed_files.is_empty() {
+            writeln!(
+                writer,
+                "\n-------------------------------------------------Files with different content-------------------------------------------------"
+            )?;
+            writeln!(
+                writer,
+                "Found {} files at same relative path, which have different content.",
+                self.information.number_of_changed_files
+            )?;
+            for entry in &self.changed_files {
+                writeln!(
+                    writer,
+                    "\"{}\" - {} / {}",
+                    entry.relative_path.to_string_lossy(),
+                    format_size(entry.first_file.size, BINARY),
+                    format_size(entry.second_file.size, BINARY)
+                )?;
+            }
+        }
+
+        if !self.moved_files.is_empty() {
+            writeln!(
+                writer,
+                "\n-------------------------------------------------Moved files-------------------------------------------------"
+            )?;
+            writeln!(
+                writer,
+                "Found {} groups of files with same content, placed at different paths.",
+                self.information.number_of_moved_files
+            )?;
+            for entry in &self.moved_files {
+                writeln!(writer, "---- Size {} ({})", format_size(entry.size, BINARY), entry.size)?;
+                for file_entry in &entry.first_files {
+                    writeln!(writer, "first: \"{}\"", file_entry.path.to_string_lossy())?;
+                }
+                for file_entry in &entry.second_files {
+                    writeln!(writer, "second: \"{}\"", file_entry.path.to_string_lossy())?;
+                }
+            }
+        }
+
+        if !self.found_any_items() {
+            writeln!(writer, "Directories are identical.")?;
+        }
+
+        Ok(())
+    }
+
+    fn save_results_to_file(&self, file_name: &str, format: ResultsFormat) -> std::io::Result<()> {
+        let results = DirectoryComparisonResults {
+            first_directory: &self.params.first_directory,
+            second_directory: &self.params.second_directory,
+            files_only_in_first: &self.files_only_in_first,
+            files_only_in_second: &self.files_only_in_second,
+            changed_files: &self.changed_files,
+            moved_files: &self.moved_files,
+        };
+        self.save_results_to_file_internal(file_name, &results, format)
+    }
+}
+
+impl CommonData for DirectoryComparison {
+    type Info = Info;
+    type Parameters = DirectoryComparisonParameters;
+
+    fn get_information(&self) -> Self::Info {
+        self.information
+    }
+    fn get_params(&self) -> Self::Parameters {
+        self.params.clone()
+    }
+    fn get_cd(&self) -> &CommonToolData {
+        &self.common_data
+    }
+    fn get_cd_mut(&mut self) -> &mut CommonToolData {
+        &mut self.common_data
+    }
+    fn found_any_items(&self) -> bool {
+        self.information.number_of_files_only_in_first > 0
+            || self.information.number_of_files_only_in_second > 0
+            || self.information.number_of_changed_files > 0
+            || self.information.number_of_moved_files > 0
+    }
+}
@@ -0,0 +1,359 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::Read;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use rayon::prelude::*;
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
+use crate::common::io_throttle::throttle_read;
+use crate::common::model::{CheckingMethod, FileEntry, WorkContinueStatus};
+use crate::common::progress_data::{DuplicateStage, ProgressData, ToolStage};
+use crate::common::progress_stop_handler::{ProgressThreadHandler, check_if_stop_received, prepare_thread_handler_common};
+use crate::common::tool_data::CommonData;
+use crate::flc;
+use crate::tools::duplicate::{ContentChunksEntry, DuplicateFinder, THREAD_BUFFER, filter_hard_links};
+
+// Chunk boundaries are found with gear rolling hash (the same as in FastCDC), which depends only on last 64 bytes,
+// so inserting or removing data in the middle of file changes only chunks around modified place.
+const MIN_CHUNK_SIZE: usize = 4 * 1024;
+const MAX_CHUNK_SIZE: usize = 64 * 1024;
+// Boundary is placed when 14 highest bits of rolling hash are zero, so on average every 16 KiB after minimal chunk size
+const BOUNDARY_SHIFT: u32 = 64 - 14;
+// Only this number of smallest chunk hashes is kept per file (bottom-k sketch), which bounds memory usage for huge files
+// and allows estimating similarity with ~5% error
+pub const CONTENT_CHUNKS_SKETCH_SIZE: usize = 512;
+
+#[expect(clippy::indexing_slicing)] // Safe, i < 256
+const GEAR_TABLE: [u64; 256] = {
+    // Splitmix64, to get deterministic random numbers without any additional dependency
+    let mut table = [0u64; 256];
+    let mut state: u64 = 0;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+};
+
+pub struct ContentChunker {
+    rolling_hash: u64,
+    chunk_size: usize,
+    chunk_hasher: Xxh3,
+    sketch: BTreeSet<u64>,
+}
+
+impl Default for ContentChunker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContentChunker {
+    pub fn new() -> Self {
+        Self {
+            rolling_hash: 0,
+            chunk_size: 0,
+            chunk_hasher: Xxh3::new(),
+            sketch: BTreeSet::new(),
+        }
+    }
+
+    #[expect(clippy::indexing_slicing)] // Safe, byte is always < 256 and chunk_start <= idx < bytes.len()
+    pub fn update(&mut self, bytes: &[u8]) {
+        let mut chunk_start = 0;
+        for (idx, byte) in bytes.iter().enumerate() {
+            self.rolling_hash = (self.rolling_hash << 1).wrapping_add(GEAR_TABLE[*byte as usize]);
+            self.chunk_size += 1;
+            if self.chunk_size >= MAX_CHUNK_SIZE || (self.chunk_size >= MIN_CHUNK_SIZE && self.rolling_hash >> BOUNDARY_SHIFT == 0) {
+                self.chunk_hasher.update(&bytes[chunk_start..=idx]);
+                self.finish_chunk();
+                chunk_start = idx + 1;
+            }
+        }
+        self.chunk_hasher.update(&bytes[chunk_start..]);
+    }
+
+    // Returns sorted hashes of smallest chunks
+    pub fn finalize(mut self) -> Vec<u64> {
+        if self.chunk_size > 0 {
+            self.finish_chunk();
+        }
+        self.sketch.into_iter().collect()
+    }
+
+    fn finish_chunk(&mut self) {
+        self.sketch.insert(self.chunk_hasher.digest());
+        if self.sketch.len() > CONTENT_CHUNKS_SKETCH_SIZE {
+            self.sketch.pop_last();
+        }
+        self.chunk_hasher.reset();
+        self.chunk_size = 0;
+    }
+}
+
+// Estimates percentage of distinct chunks of smaller file, which are also found in bigger file(containment), from bottom-k sketches.
+// Unlike Jaccard index, it is not lowered by content appended to one of files, e.g. to logs.
+// Sketches contain all chunks of files with hashes up to smaller of their biggest hashes, so only these hashes are compared.
+// For files with less chunks than sketch size, result is exact.
+pub fn calculate_chunks_similarity(first: &[u64], second: &[u64]) -> u8 {
+    let (Some(first_max), Some(second_max)) = (first.last(), second.last()) else {
+        return 0;
+    };
+    let threshold = *first_max.min(second_max);
+    let mut first_iter = first.iter().take_while(|hash| **hash <= threshold).peekable();
+    let mut second_iter = second.iter().take_while(|hash| **hash <= threshold).peekable();
+    let (mut first_size, mut second_size, mut common_size) = (0, 0, 0);
+    loop {
+        match (first_iter.peek(), second_iter.peek()) {
+            (Some(a), Some(b)) if a == b => {
+                common_size += 1;
+                first_size += 1;
+                second_size += 1;
+                first_iter.next();
+                second_iter.next();
+            }
+            (Some(a), Some(b)) if a < b => {
+                first_size += 1;
+                first_iter.next();
+            }
+            (Some(_) | None, Some(_)) => {
+                second_size += 1;
+                second_iter.next();
+            }
+            (Some(_), None) => {
+                first_size += 1;
+                first_iter.next();
+            }
+            (None, None) => break,
+        }
+    }
+    // Biggest hash of one of sketches is always counted, so there is no division by zero
+    (common_size * 100 / first_size.min(second_size)) as u8
+}
+
+fn calculate_chunks_sketch(buffer: &mut [u8], file_entry: &FileEntry, size_counter: &Arc<AtomicU64>, stop_flag: &Arc<AtomicBool>) -> Result<Option<Vec<u64>>, String> {
+    let mut file_handler = File::open(&file_entry.path).map_err(|e| flc!("core_unable_check_hash_of_file", file = file_entry.path.to_string_lossy(), reason = e.to_string()))?;
+    let mut chunker = ContentChunker::new();
+    loop {
+        let n = match file_handler.read(buffer) {
+            Ok(0) => break,
+            Ok(t) => t,
+            Err(e) => return Err(flc!("core_error_checking_hash_of_file", file = file_entry.path.to_string_lossy(), reason = e.to_string())),
+        };
+
+        #[expect(clippy::indexing_slicing)] // Safe, because we read only n bytes, which is always <= buffer size
+        chunker.update(&buffer[..n]);
+        size_counter.fetch_add(n as u64, Ordering::Relaxed);
+        throttle_read(n as u64);
+        if check_if_stop_received(stop_flag) {
+            return Ok(None);
+        }
+    }
+    Ok(Some(chunker.finalize()))
+}
+
+impl DuplicateFinder {
+    #[fun_time(message = "check_files_content_chunks", level = "debug")]
+    pub(crate) fn check_files_content_chunks(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        let result = DirTraversalBuilder::new()
+            .common_data(&self.common_data)
+            .group_by(|_fe| ())
+            .checking_method(CheckingMethod::ContentChunks)
+            .stop_flag(stop_flag)
+            .progress_sender(progress_sender)
+            .build()
+            .run();
+
+        let files = match result {
+            DirTraversalResult::SuccessFiles { grouped_file_entries, warnings } => {
+                self.common_data.text_messages.warnings.extend(warnings);
+                grouped_file_entries.into_values().flatten().collect::<Vec<_>>()
+            }
+            DirTraversalResult::Stopped => return WorkContinueStatus::Stop,
+        };
+        let files = if self.get_hide_hard_links() { filter_hard_links(files) } else { files };
+
+        let Some(sketches) = self.calculate_chunks_sketches(stop_flag, progress_sender, files) else {
+            return WorkContinueStatus::Stop;
+        };
+
+        let minimal_similarity = self.get_params().minimal_content_similarity;
+        let groups = if self.common_data.use_reference_folders {
+            let (reference, normal): (Vec<_>, Vec<_>) = sketches
+                .into_iter()
+                .partition(|(file_entry, _)| self.common_data.directories.is_in_referenced_directory(&file_entry.path));
+            let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::Duplicate(DuplicateStage::ComparingChunks), reference.len(), 0);
+            let groups = group_similar_files(stop_flag, &progress_handler, &reference, Some(&normal), minimal_similarity);
+            progress_handler.join_thread();
+            groups
+        } else {
+            let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::Duplicate(DuplicateStage::ComparingChunks), sketches.len(), 0);
+            let groups = group_similar_files(stop_flag, &progress_handler, &sketches, None, minimal_similarity);
+            progress_handler.join_thread();
+            groups
+        };
+        let Some(groups) = groups else {
+            return WorkContinueStatus::Stop;
+        };
+
+        if self.common_data.use_reference_folders {
+            self.files_with_similar_content_chunks_referenced = groups;
+            self.information.number_of_groups_by_content_chunks = self.files_with_similar_content_chunks_referenced.len();
+            self.information.number_of_similar_files_by_content_chunks = self.files_with_similar_content_chunks_referenced.iter().map(|(_, files)| files.len()).sum();
+        } else {
+            self.files_with_similar_content_chunks = groups
+                .into_iter()
+                .map(|(base, mut files)| {
+                    files.insert(0, base);
+                    files
+                })
+                .collect();
+            self.information.number_of_groups_by_content_chunks = self.files_with_similar_content_chunks.len();
+            self.information.number_of_similar_files_by_content_chunks = self.files_with_similar_content_chunks.iter().map(|files| files.len() - 1).sum();
+        }
+
+        WorkContinueStatus::Continue
+    }
+
+    #[fun_time(message = "calculate_chunks_sketches", level = "debug")]
+    fn calculate_chunks_sketches(
+        &mut self,
+        stop_flag: &Arc<AtomicBool>,
+        progress_sender: Option<&Sender<ProgressData>>,
+        files: Vec<FileEntry>,
+    ) -> Option<Vec<(ContentChunksEntry, Vec<u64>)>> {
+        let progress_handler = prepare_thread_handler_common(
+            progress_sender,
+            ToolStage::Duplicate(DuplicateStage::CalculatingChunks),
+            files.len(),
+            files.iter().map(|fe| fe.size).sum(),
+        );
+
+        let results: Vec<Result<(ContentChunksEntry, Vec<u64>), String>> = files
+            .into_par_iter()
+            .map(|file_entry| {
+                if check_if_stop_received(stop_flag) {
+                    return None;
+                }
+                let result = THREAD_BUFFER.with_borrow_mut(|buffer| calculate_chunks_sketch(buffer, &file_entry, progress_handler.size_counter(), stop_flag));
+                progress_handler.increase_items(1);
+                match result {
+                    Ok(sketch) => Some(Ok((file_entry.into_content_chunks_entry(), sketch?))),
+                    Err(e) => Some(Err(e)),
+                }
+            })
+            .while_some()
+            .collect();
+        progress_handler.join_thread();
+
+        if check_if_stop_received(stop_flag) {
+            return None;
+        }
+
+        let mut sketches = Vec::with_capacity(results.len());
+        for result in results {
+            match result {
+                Ok((entry, sketch)) if !sketch.is_empty() => sketches.push((entry, sketch)),
+                Ok(_) => {}
+                Err(e) => self.common_data.text_messages.warnings.push(e),
+            }
+        }
+        // Biggest files are used as base of groups, so e.g. newest version of log file is shown first
+        sketches.sort_unstable_by(|(a, _), (b, _)| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+        Some(sketches)
+    }
+}
+
+impl FileEntry {
+    fn into_content_chunks_entry(self) -> ContentChunksEntry {
+        ContentChunksEntry {
+            path: self.path,
+            modified_date: self.modified_date,
+            size: self.size,
+            similarity: 0,
+        }
+    }
+}
+
+// Each base file creates group with all not yet grouped candidates, which are similar enough to it.
+// Without separate candidates, files are compared with each other and file already used in any group cannot be base of another group.
+#[expect(clippy::indexing_slicing)] // Safe, all indexes are taken from enumerate of same vectors
+fn group_similar_files(
+    stop_flag: &Arc<AtomicBool>,
+    progress_handler: &ProgressThreadHandler,
+    bases: &[(ContentChunksEntry, Vec<u64>)],
+    candidates: Option<&[(ContentChunksEntry, Vec<u64>)]>,
+    minimal_similarity: u8,
+) -> Option<Vec<(ContentChunksEntry, Vec<ContentChunksEntry>)>> {
+    let same_lists = candidates.is_none();
+    let candidates = candidates.unwrap_or(bases);
+
+    // Files, which share at least one chunk hash from sketch, are the only possible candidates
+    let mut files_with_chunk: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (idx, (_, sketch)) in candidates.iter().enumerate() {
+        for chunk_hash in sketch {
+            files_with_chunk.entry(*chunk_hash).or_default().push(idx);
+        }
+    }
+
+    let mut used_candidates = vec![false; candidates.len()];
+    let mut groups = Vec::new();
+    for (base_idx, (base_entry, base_sketch)) in bases.iter().enumerate() {
+        if check_if_stop_received(stop_flag) {
+            return None;
+        }
+        progress_handler.increase_items(1);
+        if same_lists && used_candidates[base_idx] {
+            continue;
+        }
+
+        let mut possible_candidates: Vec<usize> = base_sketch
+            .iter()
+            .filter_map(|chunk_hash| files_with_chunk.get(chunk_hash))
+            .flatten()
+            .copied()
+            .filter(|idx| !(used_candidates[*idx] || same_lists && *idx == base_idx))
+            .collect();
+        possible_candidates.sort_unstable();
+        possible_candidates.dedup();
+
+        let mut similar_files: Vec<(usize, u8)> = possible_candidates
+            .into_par_iter()
+            .filter_map(|idx| {
+                let similarity = calculate_chunks_similarity(base_sketch, &candidates[idx].1);
+                (similarity >= minimal_similarity).then_some((idx, similarity))
+            })
+            .collect();
+        if similar_files.is_empty() {
+            continue;
+        }
+        similar_files.sort_unstable_by(|(idx_a, similarity_a), (idx_b, similarity_b)| similarity_b.cmp(similarity_a).then(idx_a.cmp(idx_b)));
+
+        if same_lists {
+            used_candidates[base_idx] = true;
+        }
+        let mut base_entry = base_entry.clone();
+        base_entry.similarity = 100;
+        let similar_entries = similar_files
+            .into_iter()
+            .map(|(idx, similarity)| {
+                used_candidates[idx] = true;
+                let mut entry = candidates[idx].0.clone();
+                entry.similarity = similarity;
+                entry
+            })
+            .collect();
+        groups.push((base_entry, similar_entries));
+    }
+    Some(groups)
+}
@@ -1,4 +1,6 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::time::Instant;
@@ -9,15 +11,27 @@ use fun_time::fun_time;
 use log::debug;
 use rayon::prelude::*;
 
-use crate::common::cache::{CACHE_DUPLICATE_PREHASH_VERSION, CACHE_DUPLICATE_VERSION, load_and_split_cache_generalized_by_size, save_cache_to_file_generalized};
-use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult};
+use crate::common::archive::{get_archive_member_path, get_compressed_file_member, is_archive_member_path, is_supported_archive, list_archive_members, split_archive_member_path};
+use crate::common::cache::{
+    CACHE_DUPLICATE_PREHASH_VERSION, CACHE_DUPLICATE_VERSION, load_and_split_cache_generalized_by_size, load_cache_from_file_streamed, save_cache_to_file_generalized,
+    save_cache_to_file_spilled,
+};
+use crate::common::checkpoint::{Checkpoint, PartialResults};
+use crate::common::deletion::split_item_to_keep;
+use crate::common::device_io::DeviceIoScheduler;
+use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult, get_modified_time};
 use crate::common::model::{CheckingMethod, FileEntry, HashType, ToolType, WorkContinueStatus};
 use crate::common::progress_data::{CacheLoadPhase, DuplicateStage, ProgressData, ToolStage};
-use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
-use crate::common::tool_data::{CommonData, CommonToolData};
+use crate::common::progress_stop_handler::{ProgressThreadHandler, check_if_stop_received, prepare_thread_handler_common};
+use crate::common::spill_store::{MemoryUsage, SpillStore};
+use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
 use crate::common::traits::ResultEntry;
+use crate::flc;
+use crate::helpers::messages::Messages;
 use crate::tools::duplicate::{
-    DuplicateEntry, DuplicateFinder, DuplicateFinderParameters, Info, PREHASHING_BUFFER_SIZE, THREAD_BUFFER, filter_hard_links, hash_calculation, hash_calculation_limit,
+    ArchiveMemberHashes, BatchHashCache, BatchHashCaches, DuplicateCheckpointStage, DuplicateEntry, DuplicateFinder, DuplicateFinderParameters, Info, PREHASHING_BUFFER_SIZE,
+    THREAD_BUFFER, compare_files_content, filter_hard_links, get_prehash_ranges, get_sampled_hash_ranges, hash_archive_members, hash_calculation, hash_calculation_limit,
+    hash_calculation_sampled, hash_compressed_file,
 };
 
 impl DuplicateFinder {
@@ -33,6 +47,12 @@ impl DuplicateFinder {
             files_with_identical_size_names_referenced: Default::default(),
             files_with_identical_size_referenced: Default::default(),
             files_with_identical_hashes_referenced: Default::default(),
+            files_with_similar_content_chunks: Default::default(),
+            files_with_similar_content_chunks_referenced: Default::default(),
+            files_with_unique_hashes: Default::default(),
+            spilled_unique_hashes: None,
+            batch_hash_caches: None,
+            archive_member_hashes: Default::default(),
             params,
         }
     }
@@ -212,11 +232,23 @@ impl DuplicateFinder {
             .run();
 
         match result {
-            DirTraversalResult::SuccessFiles { grouped_file_entries, warnings } => {
+            DirTraversalResult::SuccessFiles {
+                mut grouped_file_entries,
+                warnings,
+            } => {
                 self.common_data.text_messages.warnings.extend(warnings);
 
+                if self.get_params().search_in_archives {
+                    let archives = grouped_file_entries.values().flatten().filter(|fe| is_supported_archive(&fe.path)).cloned().collect();
+                    let add_member = |member: FileEntry| grouped_file_entries.entry(member.size).or_default().push(member);
+                    if self.add_archive_members(stop_flag, progress_sender, archives, add_member) == WorkContinueStatus::Stop {
+                        return WorkContinueStatus::Stop;
+                    }
+                }
+
                 let grouped_file_entries: Vec<(u64, Vec<FileEntry>)> = grouped_file_entries.into_iter().collect();
                 let rayon_max_len = if self.get_hide_hard_links() { 3 } else { 100 };
+                let minimal_group_len = self.minimal_size_group_len();
 
                 let start_time = Instant::now();
                 // We only gather files with more than 1 entry, because only this will be later used
@@ -238,13 +270,13 @@ impl DuplicateFinder {
                     })
                     .while_some()
                     .filter_map(|(size, vec)| {
-                        if vec.len() <= 1 {
+                        if vec.len() < minimal_group_len {
                             return None;
                         }
 
                         let vector = if self.get_hide_hard_links() { filter_hard_links(vec) } else { vec };
 
-                        if vector.len() > 1 {
+                        if vector.len() >= minimal_group_len {
                             Some((size, vector.into_iter().map(FileEntry::into_duplicate_entry).collect()))
                         } else {
                             None
@@ -285,6 +317,90 @@ impl DuplicateFinder {
         }
     }
 
+    // Files inside archives are added as virtual entries, so they can be grouped with normal files by size and later by hash
+    #[fun_time(message = "add_archive_members", level = "debug")]
+    fn add_archive_members(
+        &mut self,
+        stop_flag: &Arc<AtomicBool>,
+        progress_sender: Option<&Sender<ProgressData>>,
+        archives: Vec<FileEntry>,
+        mut add_member: impl FnMut(FileEntry),
+    ) -> WorkContinueStatus {
+        let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::Duplicate(DuplicateStage::ReadingArchives), archives.len(), 0);
+
+        let minimal_file_size = self.common_data.minimal_file_size;
+        let maximal_file_size = self.common_data.maximal_file_size;
+        let hash_type = self.get_params().hash_type;
+        // Single compressed files must be decompressed to get their size, so when they will be hashed later, hashes are calculated at the same time
+        let hash_compressed_files = matches!(self.get_params().check_method, CheckingMethod::Hash | CheckingMethod::SampledHash);
+        let calculate_full_hash = self.get_params().check_method == CheckingMethod::Hash;
+        #[expect(clippy::type_complexity)]
+        let results: Vec<Result<Vec<(FileEntry, Option<ArchiveMemberHashes>)>, String>> = archives
+            .into_par_iter()
+            .map(|archive| {
+                if check_if_stop_received(stop_flag) {
+                    return None;
+                }
+                let members = match get_compressed_file_member(&archive.path).filter(|_| hash_compressed_files) {
+                    Some(member) => match THREAD_BUFFER.with_borrow_mut(|buffer| hash_compressed_file(buffer, &archive.path, &member, hash_type, calculate_full_hash, stop_flag)) {
+                        Ok(Some((size, hashes))) => Ok(vec![(member, size, Some(hashes))]),
+                        Ok(None) => return None,
+                        Err(e) => Err(e),
+                    },
+                    None => list_archive_members(&archive.path).map(|members| members.into_iter().map(|(member, size)| (member, size, None)).collect()),
+                };
+                let members = members.map(|members| {
+                    members
+                        .into_iter()
+                        .filter(|(_member, size, _hashes)| (minimal_file_size..=maximal_file_size).contains(size))
+                        .map(|(member, size, hashes)| {
+                            let file_entry = FileEntry {
+                                path: get_archive_member_path(&archive.path, &member),
+                                size,
+                                // Members are treated as changed, when archive was modified
+                                modified_date: archive.modified_date,
+                            };
+                            (file_entry, hashes)
+                        })
+                        .collect()
+                });
+                progress_handler.increase_items(1);
+                Some(members)
+            })
+            .while_some()
+            .collect();
+
+        progress_handler.join_thread();
+        if check_if_stop_received(stop_flag) {
+            return WorkContinueStatus::Stop;
+        }
+
+        for result in results {
+            match result {
+                Ok(members) => {
+                    for (member, hashes) in members {
+                        if let Some(hashes) = hashes {
+                            self.archive_member_hashes.insert(member.path.clone(), hashes);
+                        }
+                        add_member(member);
+                    }
+                }
+                Err(e) => self.common_data.text_messages.warnings.push(e),
+            }
+        }
+
+        WorkContinueStatus::Continue
+    }
+
+    // Files with unique size are kept only to be hashed for hash manifest
+    fn minimal_size_group_len(&self) -> usize {
+        if self.get_params().hash_all_files && self.get_params().check_method == CheckingMethod::Hash {
+            1
+        } else {
+            2
+        }
+    }
+
     fn calculate_size_stats(&mut self) {
         if self.common_data.use_reference_folders {
             for (size, (_fe, vector)) in &self.files_with_identical_size_referenced {
@@ -293,7 +409,7 @@ impl DuplicateFinder {
                 self.information.lost_space_by_size += (vector.len() as u64) * size;
             }
         } else {
-            for (size, vector) in &self.files_with_identical_size {
+            for (size, vector) in self.files_with_identical_size.iter().filter(|(_size, vector)| vector.len() > 1) {
                 self.information.number_of_duplicated_files_by_size += vector.len() - 1;
                 self.information.number_of_groups_by_size += 1;
                 self.information.lost_space_by_size += (vector.len() as u64 - 1) * size;
@@ -316,6 +432,10 @@ impl DuplicateFinder {
 
     #[fun_time(message = "prehash_load_cache_at_start", level = "debug")]
     fn prehash_load_cache_at_start(&mut self) -> (BTreeMap<u64, Vec<DuplicateEntry>>, BTreeMap<u64, Vec<DuplicateEntry>>, BTreeMap<u64, Vec<DuplicateEntry>>) {
+        if let Some(batch_hash_caches) = &self.batch_hash_caches {
+            let (records_already_cached, non_cached_files_to_check) = split_by_batch_cache(&batch_hash_caches.prehash.entries, mem::take(&mut self.files_with_identical_size));
+            return (Default::default(), records_already_cached, non_cached_files_to_check);
+        }
         load_and_split_cache_generalized_by_size(
             &get_duplicate_cache_file(self.get_params().hash_type, true),
             self.get_params().use_prehash_cache,
@@ -327,6 +447,16 @@ impl DuplicateFinder {
     #[fun_time(message = "prehash_save_cache_at_exit", level = "debug")]
     fn prehash_save_cache_at_exit(&mut self, loaded_hash_map: BTreeMap<u64, Vec<DuplicateEntry>>, combined: &BTreeMap<u64, BTreeMap<String, Vec<DuplicateEntry>>>) {
         if self.get_params().use_prehash_cache {
+            let minimal_prehash_cache_file_size = self.get_params().minimal_prehash_cache_file_size;
+            if let Some(batch_hash_caches) = &mut self.batch_hash_caches {
+                for (_size, hash_map) in combined.range(minimal_prehash_cache_file_size..) {
+                    for file_entry in hash_map.values().flatten() {
+                        batch_hash_caches.prehash.entries.insert(file_entry.path.to_string_lossy().to_string(), file_entry.clone());
+                    }
+                }
+                return;
+            }
+
             // All results = records already cached + computed results
             let mut save_cache_to_hashmap: BTreeMap<String, DuplicateEntry> = Default::default();
 
@@ -363,6 +493,7 @@ impl DuplicateFinder {
         &mut self,
         stop_flag: &Arc<AtomicBool>,
         progress_sender: Option<&Sender<ProgressData>>,
+        checkpoint: &Checkpoint,
         pre_checked_map: &mut BTreeMap<u64, Vec<DuplicateEntry>>,
     ) -> WorkContinueStatus {
         if self.files_with_identical_size.is_empty() {
@@ -372,7 +503,8 @@ impl DuplicateFinder {
         let check_type = self.get_params().hash_type;
         let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::Duplicate(DuplicateStage::LoadingPreHashCache(CacheLoadPhase::Loading)), 0, 0);
 
-        let (loaded_hash_map, records_already_cached, non_cached_files_to_check) = self.prehash_load_cache_at_start();
+        let (loaded_hash_map, mut records_already_cached, mut non_cached_files_to_check) = self.prehash_load_cache_at_start();
+        move_files_hashed_before_interruption(checkpoint.load_partial_results(), &mut records_already_cached, &mut non_cached_files_to_check);
 
         progress_handler.join_thread();
         if check_if_stop_received(stop_flag) {
@@ -391,44 +523,62 @@ impl DuplicateFinder {
                 .sum::<u64>(),
         );
 
+        let archive_members = take_archive_members(&mut non_cached_files_to_check);
         // Convert to vector to be able to use with_max_len method from rayon
         let non_cached_files_to_check: Vec<(u64, Vec<DuplicateEntry>)> = non_cached_files_to_check.into_iter().collect();
 
         debug!("Starting calculating prehash");
-        #[expect(clippy::type_complexity)]
-        let pre_hash_results: Vec<(u64, BTreeMap<String, Vec<DuplicateEntry>>, Vec<String>)> = non_cached_files_to_check
-            .into_par_iter()
-            .with_max_len(3) // Vectors and BTreeMaps for really big inputs, leave some jobs to 0 thread, to avoid that I minimized max tasks for each thread to 3, which improved performance
-            .map(|(size, vec_file_entry)| {
-                let mut hashmap_with_hash: BTreeMap<String, Vec<DuplicateEntry>> = Default::default();
-                let mut errors: Vec<String> = Vec::new();
-
-                THREAD_BUFFER.with_borrow_mut(|buffer| {
-                    for mut file_entry in vec_file_entry {
-                        if check_if_stop_received(stop_flag) {
-                            return None;
-                        }
-                        match hash_calculation_limit(buffer, &file_entry, check_type, PREHASHING_BUFFER_SIZE, progress_handler.size_counter()) {
-                            Ok(hash_string) => {
-                                file_entry.hash = hash_string.clone();
-                                hashmap_with_hash.entry(hash_string).or_default().push(file_entry);
-                            }
-                            Err(s) => errors.push(s),
+        let device_scheduler = DeviceIoScheduler::new(self.common_data.device_io_limits);
+        let partial_results = checkpoint.partial_results();
+        let prehash_group = |(size, vec_file_entry): (u64, Vec<DuplicateEntry>)| {
+            let mut hashmap_with_hash: BTreeMap<String, Vec<DuplicateEntry>> = Default::default();
+            let mut errors: Vec<String> = Vec::new();
+
+            THREAD_BUFFER.with_borrow_mut(|buffer| {
+                for mut file_entry in vec_file_entry {
+                    if check_if_stop_received(stop_flag) {
+                        return None;
+                    }
+                    match hash_calculation_limit(buffer, &file_entry, check_type, PREHASHING_BUFFER_SIZE, progress_handler.size_counter()) {
+                        Ok(hash_string) => {
+                            file_entry.hash = hash_string.clone();
+                            partial_results.add(&file_entry);
+                            hashmap_with_hash.entry(hash_string).or_default().push(file_entry);
                         }
-                        progress_handler.increase_items(1);
+                        Err(s) => errors.push(s),
                     }
+                    progress_handler.increase_items(1);
+                }
 
-                    Some(())
-                })?;
+                Some(())
+            })?;
 
-                Some((size, hashmap_with_hash, errors))
-            })
-            .while_some()
-            .collect();
+            Some((size, hashmap_with_hash, errors))
+        };
+        #[expect(clippy::type_complexity)]
+        let pre_hash_results: Vec<(u64, BTreeMap<String, Vec<DuplicateEntry>>, Vec<String>)> = if device_scheduler.is_active() {
+            let non_cached_files_to_check = split_size_groups_by_device(&device_scheduler, non_cached_files_to_check);
+            device_scheduler.map_grouped_by_device(non_cached_files_to_check, |(_size, vec_file_entry)| get_first_path(vec_file_entry), prehash_group)
+        } else {
+            non_cached_files_to_check
+                .into_par_iter()
+                .with_max_len(3) // Vectors and BTreeMaps for really big inputs, leave some jobs to 0 thread, to avoid that I minimized max tasks for each thread to 3, which improved performance
+                .map(prehash_group)
+                .while_some()
+                .collect()
+        };
+
+        // Full or sampled hash of archive members is calculated at the same time, so archives do not need to be read again in full hashing
+        let sampled_hash_samples = (self.get_params().check_method == CheckingMethod::SampledHash).then_some(self.get_params().sampled_hash_samples);
+        let get_ranges = |size| vec![get_prehash_ranges(size), get_hash_ranges(size, sampled_hash_samples)];
+        let (archive_results, archive_errors) = self.hash_grouped_archive_members(archive_members, true, &get_ranges, &progress_handler, &partial_results, stop_flag);
 
         debug!("Completed calculating prehash");
 
         progress_handler.join_thread();
+        if check_if_stop_received(stop_flag) {
+            partial_results.flush();
+        }
 
         // Saving into cache
         let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::Duplicate(DuplicateStage::SavingPreHashCache), 0, 0);
@@ -452,13 +602,24 @@ impl DuplicateFinder {
                 combined.entry(size).or_default().entry(hash).or_default().extend(vec_file_entry);
             }
         }
+        self.common_data.text_messages.warnings.extend(archive_errors);
+        for file_entry in archive_results {
+            combined.entry(file_entry.size).or_default().entry(file_entry.hash.clone()).or_default().push(file_entry);
+        }
 
         self.prehash_save_cache_at_exit(loaded_hash_map, &combined);
 
+        let keep_unique_hashes = self.get_params().check_method == CheckingMethod::Hash;
+        let hash_all_files = keep_unique_hashes && self.get_params().hash_all_files;
         for (size, hash_groups) in combined {
             for group in hash_groups.into_values() {
                 if group.len() > 1 {
                     pre_checked_map.entry(size).or_default().extend(group);
+                } else if keep_unique_hashes && size <= 2 * PREHASHING_BUFFER_SIZE {
+                    // Prehash of small file covers its whole content, so it is the same as its full hash
+                    self.files_with_unique_hashes.extend(group);
+                } else if hash_all_files {
+                    pre_checked_map.entry(size).or_default().extend(group);
                 }
             }
         }
@@ -472,17 +633,25 @@ impl DuplicateFinder {
         WorkContinueStatus::Continue
     }
 
+    // Sampled hashes differ from full hashes, so they are kept in separate cache for each number of samples
+    fn get_hash_cache_file(&self) -> String {
+        if self.get_params().check_method == CheckingMethod::SampledHash {
+            get_duplicate_sampled_cache_file(self.get_params().hash_type, self.get_params().sampled_hash_samples)
+        } else {
+            get_duplicate_cache_file(self.get_params().hash_type, false)
+        }
+    }
+
     #[fun_time(message = "full_hashing_load_cache_at_start", level = "debug")]
     fn full_hashing_load_cache_at_start(
         &mut self,
         pre_checked_map: BTreeMap<u64, Vec<DuplicateEntry>>,
     ) -> (BTreeMap<u64, Vec<DuplicateEntry>>, BTreeMap<u64, Vec<DuplicateEntry>>, BTreeMap<u64, Vec<DuplicateEntry>>) {
-        load_and_split_cache_generalized_by_size(
-            &get_duplicate_cache_file(self.get_params().hash_type, false),
-            self.common_data.use_cache,
-            pre_checked_map,
-            self,
-        )
+        if let Some(batch_hash_caches) = &self.batch_hash_caches {
+            let (records_already_cached, non_cached_files_to_check) = split_by_batch_cache(&batch_hash_caches.hash.entries, pre_checked_map);
+            return (Default::default(), records_already_cached, non_cached_files_to_check);
+        }
+        load_and_split_cache_generalized_by_size(&self.get_hash_cache_file(), self.common_data.use_cache, pre_checked_map, self)
     }
 
     #[fun_time(message = "full_hashing_save_cache_at_exit", level = "debug")]
@@ -513,6 +682,15 @@ impl DuplicateFinder {
             full_hash_results.push((size, temp_hashmap, Vec::new()));
         }
 
+        if let Some(batch_hash_caches) = &mut self.batch_hash_caches {
+            for (_size, hashmap, _errors) in full_hash_results {
+                for file_entry in hashmap.values().flatten() {
+                    batch_hash_caches.hash.entries.insert(file_entry.path.to_string_lossy().to_string(), file_entry.clone());
+                }
+            }
+            return;
+        }
+
         // Must save all results to file, old loaded from file with all currently counted results
         let mut all_results: BTreeMap<String, DuplicateEntry> = Default::default();
         for (_size, vec_file_entry) in loaded_hash_map {
@@ -529,7 +707,7 @@ impl DuplicateFinder {
         }
 
         let messages = save_cache_to_file_generalized(
-            &get_duplicate_cache_file(self.get_params().hash_type, false),
+            &self.get_hash_cache_file(),
             &all_results,
             self.common_data.save_also_as_json,
             self.get_params().minimal_cache_file_size,
@@ -542,6 +720,7 @@ impl DuplicateFinder {
         &mut self,
         stop_flag: &Arc<AtomicBool>,
         progress_sender: Option<&Sender<ProgressData>>,
+        checkpoint: &Checkpoint,
         pre_checked_map: BTreeMap<u64, Vec<DuplicateEntry>>,
     ) -> WorkContinueStatus {
         if pre_checked_map.is_empty() {
@@ -550,20 +729,41 @@ impl DuplicateFinder {
 
         let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::Duplicate(DuplicateStage::LoadingHashCache(CacheLoadPhase::Loading)), 0, 0);
 
-        let (loaded_hash_map, records_already_cached, non_cached_files_to_check) = self.full_hashing_load_cache_at_start(pre_checked_map);
+        let (loaded_hash_map, mut records_already_cached, mut non_cached_files_to_check) = self.full_hashing_load_cache_at_start(pre_checked_map);
+        move_files_hashed_before_interruption(checkpoint.load_partial_results(), &mut records_already_cached, &mut non_cached_files_to_check);
+        if !self.archive_member_hashes.is_empty() {
+            for file_entry in records_already_cached.values().flatten() {
+                self.archive_member_hashes.remove(&file_entry.path);
+            }
+        }
 
         progress_handler.join_thread();
         if check_if_stop_received(stop_flag) {
             return WorkContinueStatus::Stop;
         }
 
+        let sampled_hash_samples = (self.get_params().check_method == CheckingMethod::SampledHash).then_some(self.get_params().sampled_hash_samples);
         let progress_handler = prepare_thread_handler_common(
             progress_sender,
-            ToolStage::Duplicate(DuplicateStage::FullHashing),
+            ToolStage::Duplicate(if sampled_hash_samples.is_some() {
+                DuplicateStage::SampledHashing
+            } else {
+                DuplicateStage::FullHashing
+            }),
             non_cached_files_to_check.values().map(Vec::len).sum(),
-            non_cached_files_to_check.iter().map(|(size, items)| (*size) * items.len() as u64).sum::<u64>(),
+            non_cached_files_to_check
+                .iter()
+                .map(|(&size, items)| {
+                    let bytes_per_file = match sampled_hash_samples {
+                        Some(samples) => get_sampled_hash_ranges(size, samples).iter().map(|(_offset, length)| length).sum(),
+                        None => size,
+                    };
+                    items.len() as u64 * bytes_per_file
+                })
+                .sum::<u64>(),
         );
 
+        let archive_members = take_archive_members(&mut non_cached_files_to_check);
         let non_cached_files_to_check: Vec<(u64, Vec<DuplicateEntry>)> = non_cached_files_to_check.into_iter().collect();
 
         let check_type = self.get_params().hash_type;
@@ -571,37 +771,59 @@ impl DuplicateFinder {
             "Starting full hashing of {} files",
             non_cached_files_to_check.iter().map(|(_size, v)| v.len() as u64).sum::<u64>()
         );
-        let mut full_hash_results: Vec<(u64, BTreeMap<String, Vec<DuplicateEntry>>, Vec<String>)> = non_cached_files_to_check
-            .into_par_iter()
-            .with_max_len(3)
-            .map(|(size, vec_file_entry)| {
-                let mut hashmap_with_hash: BTreeMap<String, Vec<DuplicateEntry>> = Default::default();
-                let mut errors: Vec<String> = Vec::new();
-
-                THREAD_BUFFER.with_borrow_mut(|buffer| {
-                    for mut file_entry in vec_file_entry {
-                        if check_if_stop_received(stop_flag) {
-                            return None;
-                        }
+        let device_scheduler = DeviceIoScheduler::new(self.common_data.device_io_limits);
+        let partial_results = checkpoint.partial_results();
+        let full_hash_group = |(size, vec_file_entry): (u64, Vec<DuplicateEntry>)| {
+            let mut hashmap_with_hash: BTreeMap<String, Vec<DuplicateEntry>> = Default::default();
+            let mut errors: Vec<String> = Vec::new();
+
+            THREAD_BUFFER.with_borrow_mut(|buffer| {
+                for mut file_entry in vec_file_entry {
+                    if check_if_stop_received(stop_flag) {
+                        return None;
+                    }
 
-                        match hash_calculation(buffer, &file_entry, check_type, progress_handler.size_counter(), stop_flag) {
-                            Ok(hash_string) => {
-                                let hash_string = hash_string?;
-                                file_entry.hash = hash_string.clone();
-                                hashmap_with_hash.entry(hash_string).or_default().push(file_entry);
-                            }
-                            Err(s) => errors.push(s),
+                    let hash_result = match sampled_hash_samples {
+                        Some(samples) => hash_calculation_sampled(buffer, &file_entry, check_type, samples, progress_handler.size_counter()).map(Some),
+                        None => hash_calculation(buffer, &file_entry, check_type, progress_handler.size_counter(), stop_flag),
+                    };
+                    match hash_result {
+                        Ok(hash_string) => {
+                            let hash_string = hash_string?;
+                            file_entry.hash = hash_string.clone();
+                            partial_results.add(&file_entry);
+                            hashmap_with_hash.entry(hash_string).or_default().push(file_entry);
                         }
-                        progress_handler.increase_items(1);
+                        Err(s) => errors.push(s),
                     }
-                    Some(())
-                })?;
+                    progress_handler.increase_items(1);
+                }
+                Some(())
+            })?;
 
-                Some((size, hashmap_with_hash, errors))
-            })
-            .while_some()
-            .collect();
+            Some((size, hashmap_with_hash, errors))
+        };
+        let mut full_hash_results: Vec<(u64, BTreeMap<String, Vec<DuplicateEntry>>, Vec<String>)> = if device_scheduler.is_active() {
+            let non_cached_files_to_check = split_size_groups_by_device(&device_scheduler, non_cached_files_to_check);
+            device_scheduler.map_grouped_by_device(non_cached_files_to_check, |(_size, vec_file_entry)| get_first_path(vec_file_entry), full_hash_group)
+        } else {
+            non_cached_files_to_check.into_par_iter().with_max_len(3).map(full_hash_group).while_some().collect()
+        };
+
+        let get_ranges = |size| vec![get_hash_ranges(size, sampled_hash_samples)];
+        let (archive_results, archive_errors) = self.hash_grouped_archive_members(archive_members, false, &get_ranges, &progress_handler, &partial_results, stop_flag);
+        full_hash_results.extend(
+            archive_results
+                .into_iter()
+                .map(|file_entry| (file_entry.size, BTreeMap::from([(file_entry.hash.clone(), vec![file_entry])]), Vec::new())),
+        );
+        // Files with same size may be hashed on different devices or be placed inside archives, so results must be joined
+        let mut full_hash_results = merge_size_groups(full_hash_results);
+        self.common_data.text_messages.warnings.extend(archive_errors);
         debug!("Finished full hashing");
+        if check_if_stop_received(stop_flag) {
+            partial_results.flush();
+        }
 
         // Even if clicked stop, save items to cache and show results
 
@@ -617,6 +839,8 @@ impl DuplicateFinder {
             for (_hash, vec_file_entry) in hash_map {
                 if vec_file_entry.len() > 1 {
                     self.files_with_identical_hashes.entry(size).or_default().push(vec_file_entry);
+                } else if sampled_hash_samples.is_none() {
+                    self.files_with_unique_hashes.extend(vec_file_entry);
                 }
             }
         }
@@ -624,10 +848,85 @@ impl DuplicateFinder {
         WorkContinueStatus::Continue
     }
 
+    // Members of archives are hashed separately from other files, so every archive is read only once, no matter how many of its members are hashed
+    // Hashes calculated earlier, while archive was read for other purpose, are used without reading archive again
+    // First hash from `get_ranges` is returned in entries, second one, if exists, is kept to be used in full hashing
+    fn hash_grouped_archive_members(
+        &mut self,
+        archive_members: BTreeMap<PathBuf, Vec<DuplicateEntry>>,
+        is_prehash: bool,
+        get_ranges: &(dyn Fn(u64) -> Vec<Vec<(u64, u64)>> + Sync),
+        progress_handler: &ProgressThreadHandler,
+        partial_results: &PartialResults<DuplicateEntry>,
+        stop_flag: &Arc<AtomicBool>,
+    ) -> (Vec<DuplicateEntry>, Vec<String>) {
+        if archive_members.is_empty() || check_if_stop_received(stop_flag) {
+            return Default::default();
+        }
+        let mut hashed_members = Vec::new();
+        let mut archives_to_read = Vec::new();
+        for (archive_path, vec_file_entry) in archive_members {
+            let mut members_to_read = Vec::new();
+            for mut file_entry in vec_file_entry {
+                let stored_hash = if is_prehash {
+                    self.archive_member_hashes.get_mut(&file_entry.path).and_then(|hashes| hashes.prehash.take())
+                } else {
+                    self.archive_member_hashes.remove(&file_entry.path).and_then(|hashes| hashes.hash)
+                };
+                match stored_hash {
+                    Some(hash) => {
+                        progress_handler.increase_items(1);
+                        progress_handler.increase_size(get_ranges(file_entry.size).first().map_or(0, |ranges| ranges.iter().map(|(_offset, length)| length).sum()));
+                        file_entry.hash = hash;
+                        partial_results.add(&file_entry);
+                        hashed_members.push(file_entry);
+                    }
+                    None => members_to_read.push(file_entry),
+                }
+            }
+            if !members_to_read.is_empty() {
+                archives_to_read.push((archive_path, members_to_read));
+            }
+        }
+
+        let check_type = self.get_params().hash_type;
+        let hash_archive = |(archive_path, vec_file_entry): (PathBuf, Vec<DuplicateEntry>)| {
+            let (hashed_members, errors) =
+                THREAD_BUFFER.with_borrow_mut(|buffer| hash_archive_members(buffer, &archive_path, vec_file_entry, check_type, get_ranges, progress_handler, stop_flag))?;
+            for (file_entry, hashes) in &hashed_members {
+                let mut file_entry = file_entry.clone();
+                file_entry.hash = hashes.first().cloned().unwrap_or_default();
+                partial_results.add(&file_entry);
+            }
+            Some((hashed_members, errors))
+        };
+        let device_scheduler = DeviceIoScheduler::new(self.common_data.device_io_limits);
+        let results: Vec<_> = if device_scheduler.is_active() {
+            device_scheduler.map_grouped_by_device(archives_to_read, |(archive_path, _vec_file_entry)| archive_path.as_path(), hash_archive)
+        } else {
+            archives_to_read.into_par_iter().map(hash_archive).while_some().collect()
+        };
+
+        let mut errors = Vec::new();
+        for (archive_hashed_members, archive_errors) in results {
+            errors.extend(archive_errors);
+            for (mut file_entry, hashes) in archive_hashed_members {
+                let mut hashes = hashes.into_iter();
+                file_entry.hash = hashes.next().unwrap_or_default();
+                if let Some(hash) = hashes.next() {
+                    self.archive_member_hashes.entry(file_entry.path.clone()).or_default().hash = Some(hash);
+                }
+                hashed_members.push(file_entry);
+            }
+        }
+        (hashed_members, errors)
+    }
+
     #[fun_time(message = "hash_reference_folders", level = "debug")]
     fn hash_reference_folders(&mut self) {
         // Reference - only use in size, because later hash will be counted differently
         if self.common_data.use_reference_folders {
+            let mut files_not_shown = Vec::new();
             let vec = mem::take(&mut self.files_with_identical_hashes)
                 .into_values()
                 .filter_map(|vec_vec_file_entry| {
@@ -638,11 +937,15 @@ impl DuplicateFinder {
                             .partition(|e| self.common_data.directories.is_in_referenced_directory(e.get_path()));
 
                         if normal_files.is_empty() {
+                            files_not_shown.append(&mut files_from_referenced_folders);
                             continue;
                         }
                         if let Some(file) = files_from_referenced_folders.pop() {
                             all_results_with_same_size.push((file, normal_files));
+                        } else {
+                            files_not_shown.extend(normal_files);
                         }
+                        files_not_shown.append(&mut files_from_referenced_folders);
                     }
                     if all_results_with_same_size.is_empty() {
                         None
@@ -651,6 +954,9 @@ impl DuplicateFinder {
                     }
                 })
                 .collect::<Vec<Vec<(DuplicateEntry, Vec<DuplicateEntry>)>>>();
+            if self.get_params().check_method == CheckingMethod::Hash {
+                self.files_with_unique_hashes.append(&mut files_not_shown);
+            }
             #[expect(clippy::indexing_slicing)] // Safe, because here, empty vectors cannot exist
             for vec_of_vec in vec {
                 self.files_with_identical_hashes_referenced.insert(vec_of_vec[0].0.size, vec_of_vec);
@@ -676,18 +982,50 @@ impl DuplicateFinder {
         }
     }
 
-    #[fun_time(message = "check_files_hash", level = "debug")]
-    pub(crate) fn check_files_hash(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
-        assert_eq!(self.get_params().check_method, CheckingMethod::Hash);
+    pub(crate) fn get_checkpoint(&self) -> Checkpoint {
+        Checkpoint::new(&self.common_data, self.get_params())
+    }
 
-        let mut pre_checked_map: BTreeMap<u64, Vec<DuplicateEntry>> = Default::default();
-        if self.prehashing(stop_flag, progress_sender, &mut pre_checked_map) == WorkContinueStatus::Stop {
-            return WorkContinueStatus::Stop;
+    // Files grouped by size and files left after prehashing are saved in checkpoint, so interrupted search may be resumed from last finished stage
+    #[fun_time(message = "check_files_size_and_hash", level = "debug")]
+    pub(crate) fn check_files_size_and_hash(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        assert!(matches!(self.get_params().check_method, CheckingMethod::Hash | CheckingMethod::SampledHash));
+
+        if self.get_params().memory_limit > 0 {
+            return self.check_files_size_and_hash_with_memory_limit(stop_flag, progress_sender);
         }
 
-        if self.full_hashing(stop_flag, progress_sender, pre_checked_map) == WorkContinueStatus::Stop {
+        let checkpoint = self.get_checkpoint();
+        let resumed_stage = checkpoint.resume::<(DuplicateCheckpointStage, BTreeMap<u64, Vec<DuplicateEntry>>)>(&mut self.common_data);
+
+        let pre_checked_map = if let Some((DuplicateCheckpointStage::PreHashed, pre_checked_map)) = resumed_stage {
+            pre_checked_map
+        } else {
+            if let Some((_, files_with_identical_size)) = resumed_stage {
+                self.files_with_identical_size = files_with_identical_size;
+                self.calculate_size_stats();
+            } else {
+                if self.check_files_size(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                    return WorkContinueStatus::Stop;
+                }
+                checkpoint.save_stage(&(DuplicateCheckpointStage::GroupedBySize, &self.files_with_identical_size));
+            }
+
+            let mut pre_checked_map: BTreeMap<u64, Vec<DuplicateEntry>> = Default::default();
+            if self.prehashing(stop_flag, progress_sender, &checkpoint, &mut pre_checked_map) == WorkContinueStatus::Stop {
+                return WorkContinueStatus::Stop;
+            }
+            checkpoint.save_stage(&(DuplicateCheckpointStage::PreHashed, &pre_checked_map));
+            pre_checked_map
+        };
+
+        if self.full_hashing(stop_flag, progress_sender, &checkpoint, pre_checked_map) == WorkContinueStatus::Stop {
             return WorkContinueStatus::Stop;
         }
+        // Full hashing shows partial results when stopped, so checkpoint is still needed to finish it later
+        if !check_if_stop_received(stop_flag) {
+            checkpoint.remove();
+        }
 
         self.hash_reference_folders();
 
@@ -697,12 +1035,510 @@ impl DuplicateFinder {
 
         WorkContinueStatus::Continue
     }
+
+    // Files are grouped by size in temporary files, and only batch of size groups which fits into memory limit is hashed at once.
+    // Files with same size are always in same batch, so results are same as without limit.
+    // Caches are loaded and saved only once, and groups found in each batch are kept in temporary files, until all batches are hashed,
+    // so memory is used mostly by currently hashed batch and caches.
+    #[fun_time(message = "check_files_size_and_hash_with_memory_limit", level = "debug")]
+    fn check_files_size_and_hash_with_memory_limit(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        let memory_limit = self.get_params().memory_limit;
+        let search_in_archives = self.get_params().search_in_archives;
+        let minimal_group_len = self.minimal_size_group_len();
+        if self.common_data.resume {
+            self.common_data.text_messages.warnings.push(flc!("core_resume_not_supported_with_memory_limit"));
+        }
+
+        let mut size_groups: SpillStore<u64, FileEntry> = SpillStore::new(memory_limit);
+        let mut archives = Vec::new();
+        let warnings = DirTraversalBuilder::new()
+            .common_data(&self.common_data)
+            .group_by(|fe| fe.size)
+            .checking_method(CheckingMethod::Size)
+            .stop_flag(stop_flag)
+            .progress_sender(progress_sender)
+            .build()
+            .run_with(|size, fe| {
+                if search_in_archives && is_supported_archive(&fe.path) {
+                    archives.push(fe.clone());
+                }
+                size_groups.push(size, fe);
+            });
+        let Some(warnings) = warnings else {
+            return WorkContinueStatus::Stop;
+        };
+        self.common_data.text_messages.warnings.extend(warnings);
+
+        if search_in_archives && self.add_archive_members(stop_flag, progress_sender, archives, |member| size_groups.push(member.size, member)) == WorkContinueStatus::Stop {
+            return WorkContinueStatus::Stop;
+        }
+
+        let mut size_groups = match size_groups.into_groups() {
+            Ok(size_groups) => size_groups,
+            Err(e) => {
+                self.common_data.text_messages.errors.push(e);
+                return WorkContinueStatus::Stop;
+            }
+        };
+
+        // Checkpoint would require saving all groups at once
+        let checkpoint = Checkpoint::disabled();
+        self.batch_hash_caches = Some(self.load_batch_hash_caches());
+        let mut hash_groups: SpillStore<u64, Vec<DuplicateEntry>> = SpillStore::new(memory_limit);
+        let mut unique_hashes: SpillStore<PathBuf, DuplicateEntry> = SpillStore::new(memory_limit);
+        let mut status = WorkContinueStatus::Continue;
+        let mut batch: BTreeMap<u64, Vec<DuplicateEntry>> = BTreeMap::new();
+        let mut batch_memory_usage = 0;
+        loop {
+            // Like in full hashing, results found before stop are shown
+            if check_if_stop_received(stop_flag) {
+                break;
+            }
+
+            let next_group = size_groups.next();
+            let finished = next_group.is_none();
+            if let Some((size, files)) = next_group
+                && files.len() >= minimal_group_len
+            {
+                let files = if self.get_hide_hard_links() { filter_hard_links(files) } else { files };
+                if files.len() >= minimal_group_len {
+                    batch_memory_usage += files.iter().map(MemoryUsage::memory_usage).sum::<usize>() as u64;
+                    batch.insert(size, files.into_iter().map(FileEntry::into_duplicate_entry).collect());
+                }
+            }
+            if !finished && batch_memory_usage < memory_limit {
+                continue;
+            }
+
+            if !batch.is_empty() {
+                debug!("Hashing batch of {} size groups, using around {batch_memory_usage} bytes", batch.len());
+                self.files_with_identical_size = mem::take(&mut batch);
+                batch_memory_usage = 0;
+                self.calculate_size_stats();
+                if let Some(batch_hash_caches) = &mut self.batch_hash_caches {
+                    batch_hash_caches.prehash.start_batch(&self.files_with_identical_size);
+                    batch_hash_caches.hash.start_batch(&self.files_with_identical_size);
+                }
+
+                let mut pre_checked_map: BTreeMap<u64, Vec<DuplicateEntry>> = Default::default();
+                status = self.prehashing(stop_flag, progress_sender, &checkpoint, &mut pre_checked_map);
+                if status == WorkContinueStatus::Continue {
+                    status = self.full_hashing(stop_flag, progress_sender, &checkpoint, pre_checked_map);
+                }
+                for (size, vec_vec_file_entry) in mem::take(&mut self.files_with_identical_hashes) {
+                    for vec_file_entry in vec_vec_file_entry {
+                        hash_groups.push(size, vec_file_entry);
+                    }
+                }
+                for file_entry in mem::take(&mut self.files_with_unique_hashes) {
+                    unique_hashes.push(file_entry.path.clone(), file_entry);
+                }
+                if status == WorkContinueStatus::Stop {
+                    break;
+                }
+            }
+            if finished {
+                break;
+            }
+        }
+        if let Some(e) = size_groups.take_error() {
+            self.common_data.text_messages.errors.push(e);
+        }
+        drop(size_groups);
+
+        if let Some(batch_hash_caches) = self.batch_hash_caches.take() {
+            self.save_batch_hash_caches(batch_hash_caches);
+        }
+
+        // Batch and caches are no longer needed, so found groups, which are results of scan, may be loaded back into memory
+        match hash_groups.into_groups() {
+            Ok(mut groups) => {
+                self.files_with_identical_hashes.extend(groups.by_ref());
+                self.common_data.text_messages.errors.extend(groups.take_error());
+            }
+            Err(e) => self.common_data.text_messages.errors.push(e),
+        }
+        // Unique hashes are needed only by hash manifest, which reads them from disk
+        self.spilled_unique_hashes = Some(unique_hashes);
+        if status == WorkContinueStatus::Stop {
+            return WorkContinueStatus::Stop;
+        }
+
+        self.hash_reference_folders();
+
+        WorkContinueStatus::Continue
+    }
+
+    // Caches are moved to temporary files sorted by size, so entries may be read together with batches
+    fn load_batch_hash_caches(&mut self) -> BatchHashCaches {
+        let delete_outdated_cache = self.common_data.delete_outdated_cache;
+        let memory_limit = self.get_params().memory_limit;
+        let prehash_cache_file = self.get_params().use_prehash_cache.then(|| get_duplicate_cache_file(self.get_params().hash_type, true));
+        let hash_cache_file = self.common_data.use_cache.then(|| self.get_hash_cache_file());
+        BatchHashCaches {
+            prehash: BatchHashCache::load(prehash_cache_file.as_deref(), delete_outdated_cache, memory_limit, &mut self.common_data.text_messages),
+            hash: BatchHashCache::load(hash_cache_file.as_deref(), delete_outdated_cache, memory_limit, &mut self.common_data.text_messages),
+        }
+    }
+
+    fn save_batch_hash_caches(&mut self, batch_hash_caches: BatchHashCaches) {
+        let BatchHashCaches { mut prehash, mut hash } = batch_hash_caches;
+        if self.get_params().use_prehash_cache {
+            prehash.finish(&mut self.common_data.text_messages);
+            let messages = save_cache_to_file_spilled(
+                &get_duplicate_cache_file(self.get_params().hash_type, true),
+                &prehash.to_save,
+                self.common_data.save_also_as_json,
+                self.get_params().minimal_prehash_cache_file_size,
+            );
+            self.common_data.text_messages.extend_with_another_messages(messages);
+        }
+        if self.common_data.use_cache {
+            hash.finish(&mut self.common_data.text_messages);
+            let messages = save_cache_to_file_spilled(
+                &self.get_hash_cache_file(),
+                &hash.to_save,
+                self.common_data.save_also_as_json,
+                self.get_params().minimal_cache_file_size,
+            );
+            self.common_data.text_messages.extend_with_another_messages(messages);
+        }
+    }
+
+    // Hashes may collide (especially CRC32), files found by size or name may differ and files may be changed after scan,
+    // so right before deleting or linking, every file is compared byte by byte with the file kept in its group.
+    // Sampled hashes may be confirmed instead by comparing full hashes of files, when byte by byte verification is disabled.
+    // Files which are different or changed are removed from groups and reported as errors.
+    #[fun_time(message = "verify_files_before_action", level = "debug")]
+    pub(crate) fn verify_files_before_action(
+        &mut self,
+        stop_flag: &Arc<AtomicBool>,
+        progress_sender: Option<&Sender<ProgressData>>,
+        groups: Vec<(DuplicateEntry, Vec<DuplicateEntry>)>,
+    ) -> Option<Vec<(DuplicateEntry, Vec<DuplicateEntry>)>> {
+        let confirm_with_full_hash = !self.get_params().verify_before_action;
+        let hash_type = self.get_params().hash_type;
+        // Full hash of original must be also calculated
+        let original_reads = u64::from(confirm_with_full_hash);
+        let progress_handler = prepare_thread_handler_common(
+            progress_sender,
+            ToolStage::Duplicate(DuplicateStage::VerifyingFiles),
+            groups.iter().map(|(_original, files)| files.len()).sum(),
+            groups.iter().map(|(original, files)| original.size * (files.len() as u64 + original_reads)).sum(),
+        );
+
+        let verified_groups: Vec<((DuplicateEntry, Vec<DuplicateEntry>), Vec<String>)> = groups
+            .into_par_iter()
+            .map(|(original, files)| {
+                let mut errors = Vec::new();
+                if let Err(e) = check_if_file_was_not_changed(&original) {
+                    errors.push(e);
+                    progress_handler.increase_items(files.len());
+                    return Some(((original, Vec::new()), errors));
+                }
+
+                THREAD_BUFFER.with_borrow_mut(|buffer| {
+                    let original_hash = if confirm_with_full_hash {
+                        match hash_calculation(buffer, &original, hash_type, progress_handler.size_counter(), stop_flag) {
+                            Ok(Some(hash)) => Some(hash),
+                            Ok(None) => return None,
+                            Err(e) => {
+                                errors.push(e);
+                                progress_handler.increase_items(files.len());
+                                return Some(((original, Vec::new()), errors));
+                            }
+                        }
+                    } else {
+                        None
+                    };
+
+                    let mut verified_files = Vec::with_capacity(files.len());
+                    for file in files {
+                        if check_if_stop_received(stop_flag) {
+                            return None;
+                        }
+                        let result = check_if_file_was_not_changed(&file).and_then(|()| match &original_hash {
+                            Some(original_hash) => {
+                                hash_calculation(buffer, &file, hash_type, progress_handler.size_counter(), stop_flag).map(|hash| hash.map(|hash| &hash == original_hash))
+                            }
+                            None => compare_files_content(buffer, &original.path, &file.path, progress_handler.size_counter(), stop_flag),
+                        });
+                        progress_handler.increase_items(1);
+                        match result {
+                            Ok(Some(true)) => verified_files.push(file),
+                            Ok(Some(false)) => errors.push(flc!(
+                                "core_verification_content_mismatch",
+                                file = file.path.to_string_lossy(),
+                                original = original.path.to_string_lossy()
+                            )),
+                            Ok(None) => return None,
+                            Err(e) => errors.push(e),
+                        }
+                    }
+                    Some(((original, verified_files), errors))
+                })
+            })
+            .while_some()
+            .collect();
+
+        progress_handler.join_thread();
+        if check_if_stop_received(stop_flag) {
+            return None;
+        }
+
+        let mut groups = Vec::with_capacity(verified_groups.len());
+        for ((original, files), mut errors) in verified_groups {
+            self.common_data.text_messages.errors.append(&mut errors);
+            if !files.is_empty() {
+                groups.push((original, files));
+            }
+        }
+        Some(groups)
+    }
+
+    // Content chunks groups contain only similar files, so all other checking methods can be verified
+    #[fun_time(message = "delete_verified_files", level = "debug")]
+    pub(crate) fn delete_verified_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        let use_reference_folders = self.common_data.use_reference_folders;
+        let check_method = self.get_params().check_method;
+        // Files inside archives cannot be removed or used as source of links, so they are not verified
+        let groups = if use_reference_folders {
+            let groups: Vec<(DuplicateEntry, Vec<DuplicateEntry>)> = match check_method {
+                CheckingMethod::Name => self.files_with_identical_names_referenced.values().cloned().collect(),
+                CheckingMethod::SizeName => self.files_with_identical_size_names_referenced.values().cloned().collect(),
+                CheckingMethod::Size => self.files_with_identical_size_referenced.values().cloned().collect(),
+                _ => self.files_with_identical_hashes_referenced.values().flatten().cloned().collect(),
+            };
+            groups
+                .into_iter()
+                .filter(|(original, _files)| !is_archive_member_path(&original.path))
+                .map(|(original, files)| (original, files.into_iter().filter(|fe| !is_archive_member_path(&fe.path)).collect::<Vec<_>>()))
+                .filter(|(_original, files)| !files.is_empty())
+                .collect()
+        } else {
+            let groups: Vec<Vec<DuplicateEntry>> = match check_method {
+                CheckingMethod::Name => self.files_with_identical_names.values().cloned().collect(),
+                CheckingMethod::SizeName => self.files_with_identical_size_names.values().cloned().collect(),
+                CheckingMethod::Size => self.files_with_identical_size.values().cloned().collect(),
+                _ => self.files_with_identical_hashes.values().flatten().cloned().collect(),
+            };
+            // Files are compared with the one chosen by delete method and keep rules, because only it stays on disk
+            let delete_method = self.common_data.delete_method;
+            groups
+                .into_iter()
+                .map(|files| files.into_iter().filter(|fe| !is_archive_member_path(&fe.path)).collect::<Vec<_>>())
+                .filter(|files| files.len() > 1)
+                .map(|files| split_item_to_keep(delete_method, &self.common_data.keep_rules, files))
+                .collect()
+        };
+
+        let Some(groups) = self.verify_files_before_action(stop_flag, progress_sender, groups) else {
+            return WorkContinueStatus::Stop;
+        };
+
+        if !use_reference_folders {
+            let groups = groups
+                .into_iter()
+                .map(|(original, mut files)| {
+                    files.insert(0, original);
+                    files
+                })
+                .collect();
+            return self.delete_advanced_elements_and_add_to_messages(stop_flag, progress_sender, groups);
+        }
+
+        let delete_item_type = match self.common_data.delete_method {
+            DeleteMethod::Reflink => DeleteItemType::ReflinkingFiles(groups),
+            DeleteMethod::HardLink => DeleteItemType::HardlinkingFiles(groups),
+            _ => DeleteItemType::DeletingFiles(groups.into_iter().flat_map(|(_original, files)| files).collect()),
+        };
+        self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, delete_item_type)
+    }
+}
+
+// Files of same size may be placed on different devices, so each device gets its own part of group
+fn split_size_groups_by_device(device_scheduler: &DeviceIoScheduler, groups: Vec<(u64, Vec<DuplicateEntry>)>) -> Vec<(u64, Vec<DuplicateEntry>)> {
+    groups
+        .into_iter()
+        .flat_map(|(size, vec_file_entry)| {
+            device_scheduler
+                .split_by_device(vec_file_entry, |file_entry| file_entry.path.as_path())
+                .into_iter()
+                .map(move |vec_file_entry| (size, vec_file_entry))
+        })
+        .collect()
+}
+
+// Members of archives are taken out of size groups and grouped by archive, because every archive should be read only once
+fn take_archive_members(files: &mut BTreeMap<u64, Vec<DuplicateEntry>>) -> BTreeMap<PathBuf, Vec<DuplicateEntry>> {
+    let mut archive_members: BTreeMap<PathBuf, Vec<DuplicateEntry>> = BTreeMap::new();
+    for vec_file_entry in files.values_mut() {
+        let (members, other_files): (Vec<_>, Vec<_>) = mem::take(vec_file_entry).into_iter().partition(|file_entry| is_archive_member_path(&file_entry.path));
+        *vec_file_entry = other_files;
+        for file_entry in members {
+            if let Some((archive_path, _member)) = split_archive_member_path(&file_entry.path) {
+                archive_members.entry(archive_path.to_path_buf()).or_default().push(file_entry);
+            }
+        }
+    }
+    files.retain(|_size, vec_file_entry| !vec_file_entry.is_empty());
+    archive_members
+}
+
+// Parts of file used by full hash or by sampled hash, when number of samples is given
+fn get_hash_ranges(size: u64, sampled_hash_samples: Option<u32>) -> Vec<(u64, u64)> {
+    match sampled_hash_samples {
+        Some(samples) => get_sampled_hash_ranges(size, samples),
+        None => vec![(0, size)],
+    }
+}
+
+// Parts of groups split by device must be joined again, to find identical files placed on different devices
+#[expect(clippy::type_complexity)]
+fn merge_size_groups(results: Vec<(u64, BTreeMap<String, Vec<DuplicateEntry>>, Vec<String>)>) -> Vec<(u64, BTreeMap<String, Vec<DuplicateEntry>>, Vec<String>)> {
+    let mut merged: BTreeMap<u64, (BTreeMap<String, Vec<DuplicateEntry>>, Vec<String>)> = BTreeMap::new();
+    for (size, hash_map, errors) in results {
+        let (merged_hash_map, merged_errors) = merged.entry(size).or_default();
+        for (hash, vec_file_entry) in hash_map {
+            merged_hash_map.entry(hash).or_default().extend(vec_file_entry);
+        }
+        merged_errors.extend(errors);
+    }
+    merged.into_iter().map(|(size, (hash_map, errors))| (size, hash_map, errors)).collect()
+}
+
+// Files hashed before scan was interrupted are used like cached ones, but only when they were not modified since then
+// Cache shared by batches is loaded without knowing files from next batches, so modification date must be checked here
+impl BatchHashCache {
+    fn load(cache_file: Option<&str>, delete_outdated_cache: bool, memory_limit: u64, text_messages: &mut Messages) -> Self {
+        let mut loaded_cache: SpillStore<u64, DuplicateEntry> = SpillStore::new(memory_limit);
+        if let Some(cache_file) = cache_file {
+            let messages = load_cache_from_file_streamed(cache_file, delete_outdated_cache, |file_entry: DuplicateEntry| {
+                loaded_cache.push(file_entry.size, file_entry);
+            });
+            text_messages.extend_with_another_messages(messages);
+        }
+        let loaded = match loaded_cache.into_groups() {
+            Ok(groups) => Some(groups),
+            Err(e) => {
+                text_messages.errors.push(e);
+                None
+            }
+        };
+        Self {
+            entries: BTreeMap::new(),
+            loaded,
+            next_loaded_group: None,
+            to_save: SpillStore::new(memory_limit),
+        }
+    }
+
+    // Batches contain increasing sizes, so cache groups with smaller sizes than in batch, will not be needed anymore
+    fn start_batch(&mut self, files_with_identical_size: &BTreeMap<u64, Vec<DuplicateEntry>>) {
+        self.move_entries_to_save();
+        let Some(&max_size) = files_with_identical_size.keys().next_back() else {
+            return;
+        };
+        while let Some((size, vec_file_entry)) = self.next_loaded_group.take().or_else(|| self.loaded.as_mut().and_then(Iterator::next)) {
+            if size > max_size {
+                self.next_loaded_group = Some((size, vec_file_entry));
+                break;
+            }
+            if files_with_identical_size.contains_key(&size) {
+                self.entries
+                    .extend(vec_file_entry.into_iter().map(|file_entry| (file_entry.path.to_string_lossy().to_string(), file_entry)));
+            } else {
+                for file_entry in vec_file_entry {
+                    self.to_save.push(file_entry.path.to_string_lossy().to_string(), file_entry);
+                }
+            }
+        }
+    }
+
+    fn move_entries_to_save(&mut self) {
+        for (path, file_entry) in mem::take(&mut self.entries) {
+            self.to_save.push(path, file_entry);
+        }
+    }
+
+    // Entries of files bigger than any hashed file are also kept
+    fn finish(&mut self, text_messages: &mut Messages) {
+        self.move_entries_to_save();
+        let next_loaded_group = self.next_loaded_group.take();
+        if let Some(mut loaded) = self.loaded.take() {
+            for (_size, vec_file_entry) in next_loaded_group.into_iter().chain(loaded.by_ref()) {
+                for file_entry in vec_file_entry {
+                    self.to_save.push(file_entry.path.to_string_lossy().to_string(), file_entry);
+                }
+            }
+            text_messages.errors.extend(loaded.take_error());
+        }
+    }
+}
+
+fn split_by_batch_cache(
+    cache: &BTreeMap<String, DuplicateEntry>,
+    files_to_check: BTreeMap<u64, Vec<DuplicateEntry>>,
+) -> (BTreeMap<u64, Vec<DuplicateEntry>>, BTreeMap<u64, Vec<DuplicateEntry>>) {
+    let mut records_already_cached: BTreeMap<u64, Vec<DuplicateEntry>> = Default::default();
+    let mut non_cached_files_to_check: BTreeMap<u64, Vec<DuplicateEntry>> = Default::default();
+    for (size, vec_file_entry) in files_to_check {
+        for file_entry in vec_file_entry {
+            match cache.get(file_entry.path.to_string_lossy().as_ref()) {
+                Some(cached) if cached.size == file_entry.size && cached.modified_date == file_entry.modified_date => {
+                    records_already_cached.entry(size).or_default().push(cached.clone());
+                }
+                _ => non_cached_files_to_check.entry(size).or_default().push(file_entry),
+            }
+        }
+    }
+    (records_already_cached, non_cached_files_to_check)
+}
+
+fn move_files_hashed_before_interruption(
+    hashed_files: Vec<DuplicateEntry>,
+    records_already_cached: &mut BTreeMap<u64, Vec<DuplicateEntry>>,
+    non_cached_files_to_check: &mut BTreeMap<u64, Vec<DuplicateEntry>>,
+) {
+    if hashed_files.is_empty() {
+        return;
+    }
+    let hashed_files: HashMap<PathBuf, DuplicateEntry> = hashed_files.into_iter().map(|file_entry| (file_entry.path.clone(), file_entry)).collect();
+    for (size, vec_file_entry) in non_cached_files_to_check.iter_mut() {
+        for file_entry in mem::take(vec_file_entry) {
+            match hashed_files.get(&file_entry.path) {
+                Some(hashed) if hashed.size == file_entry.size && hashed.modified_date == file_entry.modified_date => {
+                    records_already_cached.entry(*size).or_default().push(hashed.clone());
+                }
+                _ => vec_file_entry.push(file_entry),
+            }
+        }
+    }
+    non_cached_files_to_check.retain(|_size, vec_file_entry| !vec_file_entry.is_empty());
+}
+
+fn get_first_path(vec_file_entry: &[DuplicateEntry]) -> &Path {
+    vec_file_entry.first().map_or(Path::new(""), |file_entry| file_entry.path.as_path())
+}
+
+fn check_if_file_was_not_changed(file_entry: &DuplicateEntry) -> Result<(), String> {
+    let metadata = fs::metadata(&file_entry.path).map_err(|e| flc!("core_verification_cannot_read", file = file_entry.path.to_string_lossy(), reason = e.to_string()))?;
+    // Warnings were already reported while collecting files
+    let mut warnings = Vec::new();
+    if metadata.len() != file_entry.size || get_modified_time(&metadata, &mut warnings, &file_entry.path, false) != file_entry.modified_date {
+        return Err(flc!("core_verification_file_changed", file = file_entry.path.to_string_lossy()));
+    }
+    Ok(())
 }
 
 pub fn get_duplicate_cache_file(type_of_hash: HashType, is_prehash: bool) -> String {
     if is_prehash {
-        format!("cache_duplicates_{type_of_hash:?}_prehash_{CACHE_DUPLICATE_PREHASH_VERSION}.bin")
+        format!("cache_duplicates_{}_prehash_{CACHE_DUPLICATE_PREHASH_VERSION}.bin", type_of_hash.cache_name())
     } else {
-        format!("cache_duplicates_{type_of_hash:?}_{CACHE_DUPLICATE_VERSION}.bin")
+        format!("cache_duplicates_{}_{CACHE_DUPLICATE_VERSION}.bin", type_of_hash.cache_name())
     }
 }
+
+pub fn get_duplicate_sampled_cache_file(type_of_hash: HashType, samples: u32) -> String {
+    format!("cache_duplicates_{}_sampled_{samples}_{CACHE_DUPLICATE_VERSION}.bin", type_of_hash.cache_name())
+}
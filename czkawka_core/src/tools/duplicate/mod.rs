@@ -1,10 +1,11 @@
+mod content_chunks;
 pub mod core;
 #[cfg(test)]
 mod tests;
 pub mod traits;
 
 use std::cell::RefCell;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Debug;
 #[cfg(target_family = "unix")]
 use std::fs;
@@ -20,21 +21,30 @@ use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
 
 use indexmap::IndexSet;
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use static_assertions::const_assert;
 use xxhash_rust::xxh3::Xxh3;
 
+use crate::common::archive::{get_archive_member_path, read_archive_members, split_archive_member_path};
+use crate::common::hash_manifest::{is_hash_type_supported_in_manifest, save_hash_manifest};
+use crate::common::io_throttle::throttle_read;
+use crate::common::keep_rules::KeepRuleEntry;
 use crate::common::model::{CheckingMethod, FileEntry, HashType};
-use crate::common::progress_stop_handler::check_if_stop_received;
+use crate::common::progress_stop_handler::{ProgressThreadHandler, check_if_stop_received};
+use crate::common::spill_store::{MemoryUsage, SpillStore, SpilledGroups};
 use crate::common::tool_data::CommonToolData;
 use crate::common::traits::ResultEntry;
 use crate::flc;
 
 pub const PREHASHING_BUFFER_SIZE: u64 = 4 * 1024;
 pub const THREAD_BUFFER_SIZE: usize = 2 * 1024 * 1024;
+pub const DEFAULT_MINIMAL_CONTENT_SIMILARITY: u8 = 80;
+pub const DEFAULT_SAMPLED_HASH_SAMPLES: u32 = 16;
+pub const SAMPLED_HASH_SAMPLE_SIZE: u64 = 64 * 1024;
 
 thread_local! {
-    static THREAD_BUFFER: RefCell<Vec<u8>> = RefCell::new(vec![0u8; THREAD_BUFFER_SIZE]);
+    pub(crate) static THREAD_BUFFER: RefCell<Vec<u8>> = RefCell::new(vec![0u8; THREAD_BUFFER_SIZE]);
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
@@ -44,6 +54,7 @@ pub struct DuplicateEntry {
     pub size: u64,
     pub hash: String,
 }
+impl KeepRuleEntry for DuplicateEntry {}
 impl ResultEntry for DuplicateEntry {
     fn get_path(&self) -> &Path {
         &self.path
@@ -56,8 +67,38 @@ impl ResultEntry for DuplicateEntry {
     }
 }
 
+impl MemoryUsage for DuplicateEntry {
+    fn memory_usage(&self) -> usize {
+        size_of::<Self>() + self.path.as_os_str().len() + self.hash.len()
+    }
+}
+
+// Caches used when files are hashed in batches because of memory limit
+pub(crate) struct BatchHashCaches {
+    pub(crate) prehash: BatchHashCache,
+    pub(crate) hash: BatchHashCache,
+}
+
+// Batches are hashed in order of file sizes, so cache is read in the same order and only entries with sizes from current batch are kept in memory
+pub(crate) struct BatchHashCache {
+    // Entries of files with sizes from current batch, updated with newly calculated hashes
+    pub(crate) entries: BTreeMap<String, DuplicateEntry>,
+    pub(crate) loaded: Option<SpilledGroups<u64, DuplicateEntry>>,
+    // Group already read from loaded cache, but with size bigger than any in current batch
+    pub(crate) next_loaded_group: Option<(u64, Vec<DuplicateEntry>)>,
+    pub(crate) to_save: SpillStore<String, DuplicateEntry>,
+}
+
+// Hashes of file inside archive, calculated while archive was read for other purpose, so it does not need to be decompressed again
+#[derive(Default)]
+pub(crate) struct ArchiveMemberHashes {
+    pub(crate) prehash: Option<String>,
+    // Full or sampled hash, depending on checking method
+    pub(crate) hash: Option<String>,
+}
+
 impl FileEntry {
-    fn into_duplicate_entry(self) -> DuplicateEntry {
+    pub(crate) fn into_duplicate_entry(self) -> DuplicateEntry {
         DuplicateEntry {
             size: self.size,
             path: self.path,
@@ -67,6 +108,34 @@ impl FileEntry {
     }
 }
 
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct ContentChunksEntry {
+    pub path: PathBuf,
+    pub modified_date: u64,
+    pub size: u64,
+    // Percentage of content chunks shared with first file in group
+    pub similarity: u8,
+}
+impl KeepRuleEntry for ContentChunksEntry {}
+impl ResultEntry for ContentChunksEntry {
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+    fn get_modified_date(&self) -> u64 {
+        self.modified_date
+    }
+    fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
+// Last finished stage of search by hash, saved in checkpoint together with files which should be checked in next stage
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub(crate) enum DuplicateCheckpointStage {
+    GroupedBySize,
+    PreHashed,
+}
+
 #[derive(Default, Clone, Copy)]
 pub struct Info {
     pub number_of_groups_by_size: usize,
@@ -79,10 +148,12 @@ pub struct Info {
     pub number_of_duplicated_files_by_size_name: usize,
     pub lost_space_by_size: u64,
     pub lost_space_by_hash: u64,
+    pub number_of_groups_by_content_chunks: usize,
+    pub number_of_similar_files_by_content_chunks: usize,
     pub scanning_time: Duration,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct DuplicateFinderParameters {
     pub check_method: CheckingMethod,
     pub hash_type: HashType,
@@ -90,24 +161,39 @@ pub struct DuplicateFinderParameters {
     pub minimal_cache_file_size: u64,
     pub minimal_prehash_cache_file_size: u64,
     pub case_sensitive_name_comparison: bool,
+    // Compare files byte by byte with kept file, before deleting or linking them, works with all checking methods except content chunks
+    pub verify_before_action: bool,
+    // Minimal percentage of shared content chunks, used only with content chunks checking method
+    pub minimal_content_similarity: u8,
+    // Files inside zip, 7z and tar archives are also checked, works only with size and hash checking methods
+    pub search_in_archives: bool,
+    // Number of evenly spaced samples hashed between start and end of file, used only with sampled hash checking method
+    pub sampled_hash_samples: u32,
+    // Compare full hashes of files before deleting or linking, required by sampled hash checking method when content is not verified byte by byte
+    pub confirm_with_full_hash: bool,
+    // Maximal size in bytes of files grouped in memory by hash checking methods, bigger groups are kept in temporary files, 0 means no limit
+    pub memory_limit: u64,
+    // Files with unique size or prehash are also fully hashed, so hash manifest contains all scanned files, used only with hash checking method
+    pub hash_all_files: bool,
 }
 
-impl DuplicateFinderParameters {
-    pub fn new(
-        check_method: CheckingMethod,
-        hash_type: HashType,
-        use_prehash_cache: bool,
-        minimal_cache_file_size: u64,
-        minimal_prehash_cache_file_size: u64,
-        case_sensitive_name_comparison: bool,
-    ) -> Self {
+// Parameters have many options, so they are created with struct literal, with not used options taken from default values
+impl Default for DuplicateFinderParameters {
+    fn default() -> Self {
         Self {
-            check_method,
-            hash_type,
-            use_prehash_cache,
-            minimal_cache_file_size,
-            minimal_prehash_cache_file_size,
-            case_sensitive_name_comparison,
+            check_method: CheckingMethod::Hash,
+            hash_type: HashType::Blake3,
+            use_prehash_cache: false,
+            minimal_cache_file_size: 0,
+            minimal_prehash_cache_file_size: 0,
+            case_sensitive_name_comparison: false,
+            verify_before_action: false,
+            minimal_content_similarity: DEFAULT_MINIMAL_CONTENT_SIMILARITY,
+            search_in_archives: false,
+            sampled_hash_samples: DEFAULT_SAMPLED_HASH_SAMPLES,
+            confirm_with_full_hash: false,
+            memory_limit: 0,
+            hash_all_files: false,
         }
     }
 }
@@ -131,6 +217,17 @@ pub struct DuplicateFinder {
     files_with_identical_size_referenced: BTreeMap<u64, (DuplicateEntry, Vec<DuplicateEntry>)>,
     // File Size, next grouped by file size, next grouped by hash
     files_with_identical_hashes_referenced: BTreeMap<u64, Vec<(DuplicateEntry, Vec<DuplicateEntry>)>>,
+    // Groups of files with similar content, first file in group is the one to which others were compared
+    files_with_similar_content_chunks: Vec<Vec<ContentChunksEntry>>,
+    files_with_similar_content_chunks_referenced: Vec<(ContentChunksEntry, Vec<ContentChunksEntry>)>,
+    // Files with calculated full hash, which are not shown in any group of duplicates, kept only to be saved in hash manifest
+    files_with_unique_hashes: Vec<DuplicateEntry>,
+    // Same as above, but found with memory limit, so kept on disk, in order of paths
+    spilled_unique_hashes: Option<SpillStore<PathBuf, DuplicateEntry>>,
+    // Set only while files are hashed in batches
+    batch_hash_caches: Option<BatchHashCaches>,
+    // Hashes of files inside archives, which were already calculated, but are not yet used
+    archive_member_hashes: HashMap<PathBuf, ArchiveMemberHashes>,
     params: DuplicateFinderParameters,
 }
 
@@ -217,6 +314,49 @@ impl DuplicateFinder {
     pub fn get_files_with_identical_size_names_referenced(&self) -> &BTreeMap<(u64, String), (DuplicateEntry, Vec<DuplicateEntry>)> {
         &self.files_with_identical_size_names_referenced
     }
+
+    pub const fn get_files_with_similar_content_chunks(&self) -> &Vec<Vec<ContentChunksEntry>> {
+        &self.files_with_similar_content_chunks
+    }
+
+    pub const fn get_files_with_similar_content_chunks_referenced(&self) -> &Vec<(ContentChunksEntry, Vec<ContentChunksEntry>)> {
+        &self.files_with_similar_content_chunks_referenced
+    }
+
+    // Saves full hashes of all hashed files, also of those without duplicates, in format readable by `sha256sum -c` or `b3sum -c`
+    pub fn save_hash_manifest(&self, file_name: &str) -> std::io::Result<()> {
+        if self.params.check_method != CheckingMethod::Hash || !is_hash_type_supported_in_manifest(self.params.hash_type) {
+            return Err(std::io::Error::other(flc!("core_hash_manifest_unsupported")));
+        }
+
+        let mut entries: Vec<&DuplicateEntry> = if self.common_data.use_reference_folders {
+            self.files_with_identical_hashes_referenced
+                .values()
+                .flatten()
+                .flat_map(|(reference, files)| std::iter::once(reference).chain(files))
+                .collect()
+        } else {
+            self.files_with_identical_hashes.values().flatten().flatten().collect()
+        };
+        entries.extend(&self.files_with_unique_hashes);
+        entries.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        let Some(spilled_unique_hashes) = &self.spilled_unique_hashes else {
+            return save_hash_manifest(file_name, entries.into_iter().map(|entry| (entry.path.as_path(), entry.hash.as_str())));
+        };
+        // Both are sorted by path, so spilled entries are merged while saving, without loading them at once
+        let mut spilled_groups = spilled_unique_hashes.groups().map_err(std::io::Error::other)?;
+        let spilled_entries = spilled_groups.by_ref().flat_map(|(_path, entries)| entries);
+        save_hash_manifest(
+            file_name,
+            entries
+                .into_iter()
+                .cloned()
+                .merge_by(spilled_entries, |a, b| a.path <= b.path)
+                .map(|entry| (entry.path, entry.hash)),
+        )?;
+        spilled_groups.take_error().map_or(Ok(()), |e| Err(std::io::Error::other(e)))
+    }
 }
 
 pub(crate) fn hash_calculation_limit(buffer: &mut [u8], file_entry: &DuplicateEntry, hash_type: HashType, limit: u64, size_counter: &Arc<AtomicU64>) -> Result<String, String> {
@@ -280,13 +420,17 @@ pub(crate) fn hash_calculation_limit(buffer: &mut [u8], file_entry: &DuplicateEn
 // `Read::read` is allowed to return short reads, so a single call can
 // hash fewer bytes than expected and produce a different hash on the
 // next run for the same file. This wrapper guarantees a stable prehash.
-fn read_filling(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+// Every read is throttled here, so all hashing functions using it respect read speed limit.
+fn read_filling(reader: &mut (impl Read + ?Sized), buf: &mut [u8]) -> std::io::Result<usize> {
     let mut total = 0;
     while total < buf.len() {
         #[expect(clippy::indexing_slicing)] // Safe: total < buf.len()
         match reader.read(&mut buf[total..]) {
             Ok(0) => break,
-            Ok(n) => total += n,
+            Ok(n) => {
+                total += n;
+                throttle_read(n as u64);
+            }
             Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
             Err(e) => return Err(e),
         }
@@ -308,9 +452,20 @@ pub fn hash_calculation(
             return Err(flc!("core_unable_check_hash_of_file", file = file_entry.path.to_string_lossy(), reason = e.to_string()));
         }
     };
+    hash_calculation_stream(buffer, &mut file_handler, file_entry, hash_type, size_counter, stop_flag)
+}
+
+fn hash_calculation_stream(
+    buffer: &mut [u8],
+    reader: &mut dyn Read,
+    file_entry: &DuplicateEntry,
+    hash_type: HashType,
+    size_counter: &Arc<AtomicU64>,
+    stop_flag: &Arc<AtomicBool>,
+) -> Result<Option<String>, String> {
     let hasher = &mut *hash_type.hasher();
     loop {
-        let n = match file_handler.read(buffer) {
+        let n = match reader.read(buffer) {
             Ok(0) => break,
             Ok(t) => t,
             Err(e) => return Err(flc!("core_error_checking_hash_of_file", file = file_entry.path.to_string_lossy(), reason = e.to_string())),
@@ -319,6 +474,7 @@ pub fn hash_calculation(
         #[expect(clippy::indexing_slicing)] // Safe, because we read only n bytes, which is always <= buffer size
         hasher.update(&buffer[..n]);
         size_counter.fetch_add(n as u64, Ordering::Relaxed);
+        throttle_read(n as u64);
         if check_if_stop_received(stop_flag) {
             return Ok(None);
         }
@@ -326,6 +482,296 @@ pub fn hash_calculation(
     Ok(Some(hasher.finalize()))
 }
 
+// Parts of file (offset, length) used by sampled hash - block from start, evenly spaced samples and block from end
+// Files too small to be sampled are read in whole, so for them sampled hash is also full hash
+pub(crate) fn get_sampled_hash_ranges(size: u64, samples: u32) -> Vec<(u64, u64)> {
+    let samples = u64::from(samples);
+    if size <= 2 * PREHASHING_BUFFER_SIZE + samples * SAMPLED_HASH_SAMPLE_SIZE {
+        return vec![(0, size)];
+    }
+
+    // Middle of file is split into equal parts and every sample is taken from centre of its part, so samples never overlap
+    let middle_size = size - 2 * PREHASHING_BUFFER_SIZE;
+    let part_size = middle_size / samples.max(1);
+    let mut ranges = Vec::with_capacity(samples as usize + 2);
+    ranges.push((0, PREHASHING_BUFFER_SIZE));
+    for i in 0..samples {
+        ranges.push((
+            PREHASHING_BUFFER_SIZE + i * part_size + (part_size - SAMPLED_HASH_SAMPLE_SIZE) / 2,
+            SAMPLED_HASH_SAMPLE_SIZE,
+        ));
+    }
+    ranges.push((size - PREHASHING_BUFFER_SIZE, PREHASHING_BUFFER_SIZE));
+    ranges
+}
+
+// Only parts of file are hashed, so files with same sampled hash may still differ between samples
+pub(crate) fn hash_calculation_sampled(buffer: &mut [u8], file_entry: &DuplicateEntry, hash_type: HashType, samples: u32, size_counter: &Arc<AtomicU64>) -> Result<String, String> {
+    let ranges = get_sampled_hash_ranges(file_entry.size, samples);
+    let bytes_to_read: u64 = ranges.iter().map(|(_offset, length)| length).sum();
+    let read_error = |e: std::io::Error| flc!("core_error_checking_hash_of_file", file = file_entry.path.to_string_lossy(), reason = e.to_string());
+    let hasher = &mut *hash_type.hasher();
+
+    let mut file_handler = match File::open(&file_entry.path) {
+        Ok(t) => t,
+        Err(e) => {
+            size_counter.fetch_add(bytes_to_read, Ordering::Relaxed);
+            return Err(flc!("core_unable_check_hash_of_file", file = file_entry.path.to_string_lossy(), reason = e.to_string()));
+        }
+    };
+    for (offset, length) in ranges {
+        file_handler.seek(SeekFrom::Start(offset)).map_err(read_error)?;
+        hash_part(&mut file_handler, buffer, hasher, length, size_counter).map_err(read_error)?;
+    }
+
+    Ok(hasher.finalize())
+}
+
+// Samples may be bigger than buffer, so they are read in chunks, every chunk is throttled inside read_filling
+fn hash_part(reader: &mut (impl Read + ?Sized), buffer: &mut [u8], hasher: &mut dyn MyHasher, length: u64, size_counter: &Arc<AtomicU64>) -> std::io::Result<()> {
+    let mut remaining = length;
+    while remaining > 0 {
+        let to_read = remaining.min(buffer.len() as u64) as usize;
+        #[expect(clippy::indexing_slicing)] // Safe: to_read <= buffer size
+        let n = read_filling(reader, &mut buffer[..to_read])?;
+        #[expect(clippy::indexing_slicing)] // Safe: n <= to_read <= buffer size
+        hasher.update(&buffer[..n]);
+        size_counter.fetch_add(n as u64, Ordering::Relaxed);
+        if n < to_read {
+            break;
+        }
+        remaining -= n as u64;
+    }
+    Ok(())
+}
+
+// Parts of file (offset, length) used by prehash - whole content for small files or head and tail for bigger, like in `hash_calculation_limit`
+pub(crate) fn get_prehash_ranges(size: u64) -> Vec<(u64, u64)> {
+    if size <= 2 * PREHASHING_BUFFER_SIZE {
+        vec![(0, size)]
+    } else {
+        vec![(0, PREHASHING_BUFFER_SIZE), (size - PREHASHING_BUFFER_SIZE, PREHASHING_BUFFER_SIZE)]
+    }
+}
+
+// Files inside archives cannot be seeked, so all needed hashes are calculated in one pass through content of file
+// Every hash is calculated from its own parts of file, sorted by offset, and only bytes of first one are counted in progress
+// Returns None when stopped
+fn hash_stream_ranges(
+    buffer: &mut [u8],
+    reader: &mut dyn Read,
+    file_entry: &DuplicateEntry,
+    hash_type: HashType,
+    ranges: &[Vec<(u64, u64)>],
+    size_counter: &Arc<AtomicU64>,
+    stop_flag: &Arc<AtomicBool>,
+) -> Result<Option<Vec<String>>, String> {
+    let read_error = |reason: String| flc!("core_error_checking_hash_of_file", file = file_entry.path.to_string_lossy(), reason = reason);
+    let mut hashers: Vec<Box<dyn MyHasher>> = ranges.iter().map(|_| hash_type.hasher()).collect();
+    let mut position = 0;
+    loop {
+        let n = read_filling(reader, buffer).map_err(|e| read_error(e.to_string()))?;
+        if n == 0 {
+            break;
+        }
+        let chunk_end = position + n as u64;
+        for (idx, (hasher, hash_ranges)) in hashers.iter_mut().zip(ranges).enumerate() {
+            for &(offset, length) in hash_ranges {
+                let start = offset.max(position);
+                let end = (offset + length).min(chunk_end);
+                if start >= end {
+                    continue;
+                }
+                #[expect(clippy::indexing_slicing)] // Safe: position <= start < end <= position + n
+                hasher.update(&buffer[(start - position) as usize..(end - position) as usize]);
+                if idx == 0 {
+                    size_counter.fetch_add(end - start, Ordering::Relaxed);
+                }
+            }
+        }
+        position = chunk_end;
+        if check_if_stop_received(stop_flag) {
+            return Ok(None);
+        }
+    }
+
+    // Size is taken from archive headers, so broken archive may contain less or more data
+    if position != file_entry.size {
+        return Err(read_error(format!("expected {} bytes, but found {position}", file_entry.size)));
+    }
+    Ok(Some(hashers.iter().map(|hasher| hasher.finalize()).collect()))
+}
+
+// Every archive is read only once, no matter how many of its members must be hashed
+// Returns hashed members with hashes calculated from parts returned by `get_ranges` for their size, or None when stopped
+#[expect(clippy::type_complexity)]
+pub(crate) fn hash_archive_members(
+    buffer: &mut [u8],
+    archive_path: &Path,
+    members: Vec<DuplicateEntry>,
+    hash_type: HashType,
+    get_ranges: &(impl Fn(u64) -> Vec<Vec<(u64, u64)>> + ?Sized),
+    progress_handler: &ProgressThreadHandler,
+    stop_flag: &Arc<AtomicBool>,
+) -> Option<(Vec<(DuplicateEntry, Vec<String>)>, Vec<String>)> {
+    let mut members: HashMap<String, DuplicateEntry> = members
+        .into_iter()
+        .filter_map(|file_entry| Some((split_archive_member_path(&file_entry.path)?.1.to_string(), file_entry)))
+        .collect();
+    let names: Vec<String> = members.keys().cloned().collect();
+    let mut hashed_members = Vec::new();
+    let mut errors = Vec::new();
+    let mut stopped = false;
+
+    let read_result = read_archive_members(archive_path, &names.iter().map(String::as_str).collect::<HashSet<_>>(), |member, reader| {
+        // Archive may contain multiple members with same name, only first of them is used, like when members are listed
+        let Some(file_entry) = members.remove(member) else {
+            return true;
+        };
+        match hash_stream_ranges(
+            buffer,
+            reader,
+            &file_entry,
+            hash_type,
+            &get_ranges(file_entry.size),
+            progress_handler.size_counter(),
+            stop_flag,
+        ) {
+            Ok(Some(hashes)) => hashed_members.push((file_entry, hashes)),
+            Ok(None) => {
+                stopped = true;
+                return false;
+            }
+            Err(e) => errors.push(e),
+        }
+        progress_handler.increase_items(1);
+        true
+    });
+    if stopped || check_if_stop_received(stop_flag) {
+        return None;
+    }
+
+    match read_result {
+        Err(e) => errors.push(e),
+        Ok(()) => errors.extend(members.keys().map(|member| {
+            flc!(
+                "core_cannot_read_archive",
+                file = archive_path.to_string_lossy(),
+                reason = format!("member \"{member}\" not found")
+            )
+        })),
+    }
+    progress_handler.increase_items(members.len());
+
+    Some((hashed_members, errors))
+}
+
+// Size of single compressed file content is known only after decompressing it, so its prehash and optionally full hash are calculated at the same time
+// Tail used by prehash is known only when end of data is reached, so last part of data is kept in memory
+// Returns size of content and its hashes, or None when stopped
+pub(crate) fn hash_compressed_file(
+    buffer: &mut [u8],
+    archive_path: &Path,
+    member: &str,
+    hash_type: HashType,
+    calculate_full_hash: bool,
+    stop_flag: &Arc<AtomicBool>,
+) -> Result<Option<(u64, ArchiveMemberHashes)>, String> {
+    let member_path = get_archive_member_path(archive_path, member);
+    let read_error = |e: std::io::Error| flc!("core_error_checking_hash_of_file", file = member_path.to_string_lossy(), reason = e.to_string());
+    let mut result = None;
+
+    read_archive_members(archive_path, &HashSet::from([member]), |_member, reader| {
+        let mut full_hasher = calculate_full_hash.then(|| hash_type.hasher());
+        let mut head = Vec::with_capacity(2 * PREHASHING_BUFFER_SIZE as usize);
+        let mut tail = Vec::with_capacity(2 * PREHASHING_BUFFER_SIZE as usize);
+        let mut size = 0;
+        let read_result = loop {
+            let n = match read_filling(reader, buffer) {
+                Ok(0) => break Ok(Some(size)),
+                Ok(n) => n,
+                Err(e) => break Err(read_error(e)),
+            };
+            #[expect(clippy::indexing_slicing)] // Safe: n <= buffer size
+            let chunk = &buffer[..n];
+            if let Some(full_hasher) = &mut full_hasher {
+                full_hasher.update(chunk);
+            }
+            let missing_head = (2 * PREHASHING_BUFFER_SIZE as usize - head.len()).min(n);
+            #[expect(clippy::indexing_slicing)] // Safe: missing_head <= n and n - PREHASHING_BUFFER_SIZE <= n
+            {
+                head.extend_from_slice(&chunk[..missing_head]);
+                tail.extend_from_slice(&chunk[n.saturating_sub(PREHASHING_BUFFER_SIZE as usize)..]);
+            }
+            tail.drain(..tail.len().saturating_sub(PREHASHING_BUFFER_SIZE as usize));
+            size += n as u64;
+            if check_if_stop_received(stop_flag) {
+                break Ok(None);
+            }
+        };
+        result = Some(read_result.map(|size| {
+            size.map(|size| {
+                let prehasher = &mut *hash_type.hasher();
+                if size <= 2 * PREHASHING_BUFFER_SIZE {
+                    prehasher.update(&head);
+                } else {
+                    #[expect(clippy::indexing_slicing)] // Safe: head contains 2 * PREHASHING_BUFFER_SIZE bytes
+                    prehasher.update(&head[..PREHASHING_BUFFER_SIZE as usize]);
+                    prehasher.update(&tail);
+                }
+                let hashes = ArchiveMemberHashes {
+                    prehash: Some(prehasher.finalize()),
+                    hash: full_hasher.map(|full_hasher| full_hasher.finalize()),
+                };
+                (size, hashes)
+            })
+        }));
+        false
+    })?;
+
+    result.unwrap_or_else(|| {
+        Err(flc!(
+            "core_cannot_read_archive",
+            file = archive_path.to_string_lossy(),
+            reason = format!("member \"{member}\" not found")
+        ))
+    })
+}
+
+// Returns None when stopped, otherwise information whether files have identical content
+pub fn compare_files_content(buffer: &mut [u8], first: &Path, second: &Path, size_counter: &Arc<AtomicU64>, stop_flag: &Arc<AtomicBool>) -> Result<Option<bool>, String> {
+    let read_error = |path: &Path, e: std::io::Error| flc!("core_verification_cannot_read", file = path.to_string_lossy(), reason = e.to_string());
+    let mut first_file = File::open(first).map_err(|e| read_error(first, e))?;
+    let mut second_file = File::open(second).map_err(|e| read_error(second, e))?;
+
+    let (first_buffer, second_buffer) = buffer.split_at_mut(buffer.len() / 2);
+    loop {
+        let n = first_file.read(first_buffer).map_err(|e| read_error(first, e))?;
+        if n == 0 {
+            // Second file must end at same place
+            let second_n = second_file.read(second_buffer).map_err(|e| read_error(second, e))?;
+            return Ok(Some(second_n == 0));
+        }
+
+        #[expect(clippy::indexing_slicing)] // Safe, n <= first_buffer.len() <= second_buffer.len()
+        let (first_part, second_part) = (&first_buffer[..n], &mut second_buffer[..n]);
+        match second_file.read_exact(second_part) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(Some(false)),
+            Err(e) => return Err(read_error(second, e)),
+        }
+        if first_part != second_part {
+            return Ok(Some(false));
+        }
+
+        size_counter.fetch_add(n as u64, Ordering::Relaxed);
+        throttle_read(2 * n as u64);
+        if check_if_stop_received(stop_flag) {
+            return Ok(None);
+        }
+    }
+}
+
 impl MyHasher for blake3::Hasher {
     fn update(&mut self, bytes: &[u8]) {
         self.update(bytes);
@@ -353,6 +799,20 @@ impl MyHasher for Xxh3 {
     }
 }
 
+impl MyHasher for sha2::Sha256 {
+    fn update(&mut self, bytes: &[u8]) {
+        sha2::Digest::update(self, bytes);
+    }
+    // Lowercase hex, same as in `sha256sum` output
+    fn finalize(&self) -> String {
+        sha2::Digest::finalize(self.clone())
+            .iter()
+            .flat_map(|byte| [byte >> 4, byte & 0x0f])
+            .filter_map(|nibble| char::from_digit(u32::from(nibble), 16))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests2 {
     use std::fs::File;
@@ -362,6 +822,13 @@ mod tests2 {
     use crate::common::model::FileEntry;
     use crate::tools::duplicate::filter_hard_links;
 
+    #[test]
+    fn test_sha256_hash_is_compatible_with_sha256sum() {
+        let mut hasher = HashType::Sha256.hasher();
+        hasher.update(b"abc");
+        assert_eq!(hasher.finalize(), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+
     #[test]
     fn test_filter_hard_links_empty() {
         let expected: Vec<FileEntry> = Default::default();
@@ -533,4 +1000,53 @@ mod tests2 {
         assert!(!r.is_empty());
         Ok(())
     }
+
+    #[test]
+    fn test_sampled_hash_ranges() {
+        let small_size = 2 * PREHASHING_BUFFER_SIZE + 4 * SAMPLED_HASH_SAMPLE_SIZE;
+        assert_eq!(get_sampled_hash_ranges(small_size, 4), vec![(0, small_size)]);
+
+        let size = 100 * SAMPLED_HASH_SAMPLE_SIZE + 7;
+        let ranges = get_sampled_hash_ranges(size, 4);
+        assert_eq!(ranges.len(), 6);
+        assert_eq!(ranges.first(), Some(&(0, PREHASHING_BUFFER_SIZE)));
+        assert_eq!(ranges.last(), Some(&(size - PREHASHING_BUFFER_SIZE, PREHASHING_BUFFER_SIZE)));
+        for pair in ranges.windows(2) {
+            assert!(pair[0].0 + pair[0].1 <= pair[1].0);
+        }
+    }
+
+    #[test]
+    fn test_hash_calculation_sampled() -> io::Result<()> {
+        let dir = tempfile::Builder::new().tempdir()?;
+        let mut buf = vec![0u8; THREAD_BUFFER_SIZE];
+        let file_size = 100 * SAMPLED_HASH_SAMPLE_SIZE;
+        let content: Vec<u8> = (0..file_size).map(|i| (i % 251) as u8).collect();
+
+        let ranges = get_sampled_hash_ranges(file_size, 4);
+        let (sample_offset, _) = ranges[1];
+        // Byte between samples is not hashed, byte inside sample is
+        let mut content_between = content.clone();
+        content_between[(sample_offset - 1) as usize] ^= 1;
+        let mut content_inside = content.clone();
+        content_inside[sample_offset as usize] ^= 1;
+
+        let mut hashes = Vec::new();
+        for (name, content) in [("a", &content), ("b", &content_between), ("c", &content_inside)] {
+            let path = dir.path().join(name);
+            File::create(&path)?.write_all(content)?;
+            let entry = DuplicateEntry {
+                path,
+                size: file_size,
+                ..Default::default()
+            };
+            let counter = Arc::new(AtomicU64::new(0));
+            hashes.push(hash_calculation_sampled(&mut buf, &entry, HashType::Blake3, 4, &counter).expect("hash_calculation_sampled failed"));
+            assert_eq!(counter.load(Ordering::Relaxed), 2 * PREHASHING_BUFFER_SIZE + 4 * SAMPLED_HASH_SAMPLE_SIZE);
+        }
+
+        assert_eq!(hashes[0], hashes[1]);
+        assert_ne!(hashes[0], hashes[2]);
+        Ok(())
+    }
 }
@@ -1,13 +1,34 @@
 use std::fs;
+use std::io::Write;
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
+use std::time::UNIX_EPOCH;
 
 use tempfile::TempDir;
 
-use crate::common::model::{CheckingMethod, HashType};
-use crate::common::tool_data::CommonData;
-use crate::common::traits::Search;
-use crate::tools::duplicate::{DuplicateFinder, DuplicateFinderParameters};
+use crate::common::model::{CheckingMethod, HashType, WorkContinueStatus};
+use crate::common::progress_data::{DuplicateStage, ToolStage};
+use crate::common::progress_stop_handler::prepare_thread_handler_common;
+use crate::common::tool_data::{CommonData, DeleteMethod, DeviceIoLimits};
+use crate::common::traits::{DeletingItems, PrintResults, Search};
+use crate::flc;
+use crate::tools::duplicate::content_chunks::{ContentChunker, calculate_chunks_similarity};
+use crate::tools::duplicate::core::get_duplicate_cache_file;
+use crate::tools::duplicate::{
+    DEFAULT_MINIMAL_CONTENT_SIMILARITY, DuplicateCheckpointStage, DuplicateEntry, DuplicateFinder, DuplicateFinderParameters, PREHASHING_BUFFER_SIZE, THREAD_BUFFER_SIZE,
+    get_prehash_ranges, get_sampled_hash_ranges, hash_archive_members, hash_calculation, hash_calculation_limit, hash_calculation_sampled,
+};
+
+fn get_duplicate_entry(path: &Path) -> DuplicateEntry {
+    let metadata = fs::metadata(path).unwrap();
+    DuplicateEntry {
+        path: path.to_path_buf(),
+        modified_date: metadata.modified().unwrap().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+        size: metadata.len(),
+        hash: "same_hash".to_string(),
+    }
+}
 
 #[test]
 fn test_find_duplicates_by_hash() {
@@ -19,7 +40,12 @@ fn test_find_duplicates_by_hash() {
     fs::write(path.join("file2.txt"), b"duplicate content").unwrap();
     fs::write(path.join("unique.txt"), b"unique content").unwrap();
 
-    let params = DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Blake3, false, 0, 0, true);
+    let params = DuplicateFinderParameters {
+        check_method: CheckingMethod::Hash,
+        hash_type: HashType::Blake3,
+        case_sensitive_name_comparison: true,
+        ..Default::default()
+    };
 
     let mut finder = DuplicateFinder::new(params);
     finder.set_included_paths(vec![path.to_path_buf()]);
@@ -35,6 +61,214 @@ fn test_find_duplicates_by_hash() {
     assert_eq!(info.number_of_duplicated_files_by_hash, 1, "Should find 1 duplicate file");
 }
 
+#[test]
+fn test_find_duplicates_with_device_io_limits() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    fs::create_dir(path.join("sub")).unwrap();
+
+    fs::write(path.join("file1.txt"), b"duplicate content").unwrap();
+    fs::write(path.join("sub").join("file2.txt"), b"duplicate content").unwrap();
+    fs::write(path.join("sub").join("file3.txt"), b"duplicate content").unwrap();
+    fs::write(path.join("other.txt"), b"other content!!!!").unwrap();
+
+    let params = DuplicateFinderParameters {
+        check_method: CheckingMethod::Hash,
+        hash_type: HashType::Blake3,
+        case_sensitive_name_comparison: true,
+        ..Default::default()
+    };
+
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_minimal_file_size(0);
+    finder.set_recursive_search(true);
+    finder.set_use_cache(false);
+    finder.set_device_io_limits(DeviceIoLimits {
+        threads_per_device: 1,
+        threads_per_rotational_device: 1,
+    });
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+
+    let info = finder.get_information();
+    assert_eq!(info.number_of_groups_by_hash, 1);
+    assert_eq!(info.number_of_duplicated_files_by_hash, 2);
+}
+
+#[test]
+fn test_find_duplicates_resumed_from_checkpoint() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let temp_cache = TempDir::new().unwrap();
+        let temp_config = TempDir::new().unwrap();
+        crate::common::config_cache_path::set_config_cache_path_test(temp_cache.path().to_path_buf(), temp_config.path().to_path_buf());
+        std::mem::forget(temp_cache);
+        std::mem::forget(temp_config);
+    });
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    fs::write(path.join("file1.txt"), b"duplicate content").unwrap();
+    fs::write(path.join("file2.txt"), b"duplicate content").unwrap();
+
+    let create_finder = || {
+        let params = DuplicateFinderParameters {
+            check_method: CheckingMethod::Hash,
+            hash_type: HashType::Blake3,
+            case_sensitive_name_comparison: true,
+            ..Default::default()
+        };
+        let mut finder = DuplicateFinder::new(params);
+        finder.set_included_paths(vec![path.to_path_buf()]);
+        finder.set_minimal_file_size(0);
+        finder.set_use_cache(true);
+        finder.set_resume(true);
+        finder
+    };
+    let stop_flag = Arc::new(AtomicBool::new(false));
+
+    // Scan interrupted right after grouping files by size
+    let mut finder = create_finder();
+    finder.prepare_items(None).unwrap();
+    assert_eq!(finder.check_files_size(&stop_flag, None), WorkContinueStatus::Continue);
+    finder
+        .get_checkpoint()
+        .save_stage(&(DuplicateCheckpointStage::GroupedBySize, &finder.files_with_identical_size));
+
+    // File added after interruption is not visible in resumed scan, because files are taken from checkpoint
+    fs::write(path.join("file3.txt"), b"duplicate content").unwrap();
+    let mut finder = create_finder();
+    finder.search(&stop_flag, None);
+    assert!(finder.get_text_messages().messages.contains(&flc!("core_resumed_from_checkpoint")));
+    assert_eq!(finder.get_information().number_of_duplicated_files_by_hash, 1);
+
+    // Finished scan removes checkpoint
+    let mut finder = create_finder();
+    finder.search(&stop_flag, None);
+    assert!(finder.get_text_messages().messages.contains(&flc!("core_no_checkpoint_to_resume")));
+    assert_eq!(finder.get_information().number_of_duplicated_files_by_hash, 2);
+}
+
+#[test]
+fn test_find_duplicates_with_memory_limit() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let temp_cache = TempDir::new().unwrap();
+        let temp_config = TempDir::new().unwrap();
+        crate::common::config_cache_path::set_config_cache_path_test(temp_cache.path().to_path_buf(), temp_config.path().to_path_buf());
+        std::mem::forget(temp_cache);
+        std::mem::forget(temp_config);
+    });
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    fs::write(path.join("file1.txt"), b"duplicate content").unwrap();
+    fs::write(path.join("file2.txt"), b"duplicate content").unwrap();
+    fs::write(path.join("same_size.txt"), b"different content").unwrap();
+    fs::write(path.join("other1.txt"), b"other").unwrap();
+    fs::write(path.join("other2.txt"), b"other").unwrap();
+    fs::write(path.join("unique.txt"), b"unique").unwrap();
+
+    // Limit of 1 byte moves every file and group to temporary file and hashes every size group separately
+    let create_finder = || {
+        let params = DuplicateFinderParameters {
+            check_method: CheckingMethod::Hash,
+            hash_type: HashType::Blake3,
+            case_sensitive_name_comparison: true,
+            memory_limit: 1,
+            ..Default::default()
+        };
+        let mut finder = DuplicateFinder::new(params);
+        finder.set_included_paths(vec![path.to_path_buf()]);
+        finder.set_minimal_file_size(0);
+        finder.set_use_cache(true);
+        finder
+    };
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let loaded_cache_message = flc!("core_properly_loaded_cache_entries", count = 0);
+    let loaded_cache_prefix = loaded_cache_message.split('0').next().unwrap();
+
+    // Second scan uses cache saved by first one, cache is loaded only once, not for every batch
+    for _ in 0..2 {
+        let mut finder = create_finder();
+        finder.search(&stop_flag, None);
+
+        assert!(finder.get_text_messages().errors.is_empty());
+        assert!(
+            finder
+                .get_text_messages()
+                .messages
+                .iter()
+                .filter(|message| message.starts_with(loaded_cache_prefix))
+                .count()
+                <= 1
+        );
+        let info = finder.get_information();
+        assert_eq!(info.number_of_groups_by_size, 2);
+        assert_eq!(info.number_of_groups_by_hash, 2);
+        assert_eq!(info.number_of_duplicated_files_by_hash, 2);
+        let sizes: Vec<u64> = finder.get_files_sorted_by_hash().keys().copied().collect();
+        assert_eq!(sizes, vec![5, 17]);
+    }
+}
+
+#[test]
+fn test_batch_hash_cache_keeps_entries_of_other_files() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let temp_cache = TempDir::new().unwrap();
+        let temp_config = TempDir::new().unwrap();
+        crate::common::config_cache_path::set_config_cache_path_test(temp_cache.path().to_path_buf(), temp_config.path().to_path_buf());
+        std::mem::forget(temp_cache);
+        std::mem::forget(temp_config);
+    });
+
+    let first_dir = TempDir::new().unwrap();
+    let second_dir = TempDir::new().unwrap();
+    for (dir, content) in [(&first_dir, b"first".as_slice()), (&second_dir, b"second content".as_slice())] {
+        fs::write(dir.path().join("a.txt"), content).unwrap();
+        fs::write(dir.path().join("b.txt"), content).unwrap();
+    }
+
+    // Hash type not used by other tests, so its cache is not changed by them
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let scan = |dir: &Path, memory_limit: u64| {
+        let params = DuplicateFinderParameters {
+            check_method: CheckingMethod::Hash,
+            hash_type: HashType::Xxh3,
+            use_prehash_cache: true,
+            memory_limit,
+            ..Default::default()
+        };
+        let mut finder = DuplicateFinder::new(params);
+        finder.set_included_paths(vec![dir.to_path_buf()]);
+        finder.set_minimal_file_size(0);
+        finder.set_use_cache(true);
+        finder.search(&stop_flag, None);
+        assert!(finder.get_text_messages().errors.is_empty());
+        assert_eq!(finder.get_information().number_of_duplicated_files_by_hash, 1);
+    };
+    scan(first_dir.path(), 0);
+    // Only entries of files from current batch are loaded, but all are saved again
+    scan(second_dir.path(), 1);
+
+    for is_prehash in [true, false] {
+        let (_messages, loaded_cache) = crate::common::cache::load_cache_from_file_generalized_by_path(
+            &get_duplicate_cache_file(HashType::Xxh3, is_prehash),
+            false,
+            &std::collections::BTreeMap::<String, DuplicateEntry>::new(),
+        );
+        let loaded_cache = loaded_cache.unwrap();
+        for dir in [&first_dir, &second_dir] {
+            for name in ["a.txt", "b.txt"] {
+                assert!(loaded_cache.contains_key(dir.path().join(name).to_string_lossy().as_ref()), "prehash {is_prehash}, {name}");
+            }
+        }
+    }
+}
+
 #[test]
 fn test_find_duplicates_by_size() {
     let temp_dir = TempDir::new().unwrap();
@@ -45,7 +279,12 @@ fn test_find_duplicates_by_size() {
     fs::write(path.join("file2.txt"), b"abcde").unwrap();
     fs::write(path.join("unique.txt"), b"123").unwrap();
 
-    let params = DuplicateFinderParameters::new(CheckingMethod::Size, HashType::Blake3, false, 0, 0, true);
+    let params = DuplicateFinderParameters {
+        check_method: CheckingMethod::Size,
+        hash_type: HashType::Blake3,
+        case_sensitive_name_comparison: true,
+        ..Default::default()
+    };
 
     let mut finder = DuplicateFinder::new(params);
     finder.set_included_paths(vec![path.to_path_buf()]);
@@ -76,7 +315,12 @@ fn test_find_duplicates_by_name() {
     fs::write(dir2.join("duplicate.txt"), b"content2").unwrap();
     fs::write(dir1.join("unique.txt"), b"unique").unwrap();
 
-    let params = DuplicateFinderParameters::new(CheckingMethod::Name, HashType::Blake3, false, 0, 0, true);
+    let params = DuplicateFinderParameters {
+        check_method: CheckingMethod::Name,
+        hash_type: HashType::Blake3,
+        case_sensitive_name_comparison: true,
+        ..Default::default()
+    };
 
     let mut finder = DuplicateFinder::new(params);
     finder.set_recursive_search(true);
@@ -100,7 +344,12 @@ fn test_no_duplicates_found() {
     fs::write(path.join("file1.txt"), b"content1").unwrap();
     fs::write(path.join("file2.txt"), b"content2").unwrap();
 
-    let params = DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Blake3, false, 0, 0, true);
+    let params = DuplicateFinderParameters {
+        check_method: CheckingMethod::Hash,
+        hash_type: HashType::Blake3,
+        case_sensitive_name_comparison: true,
+        ..Default::default()
+    };
 
     let mut finder = DuplicateFinder::new(params);
     finder.set_included_paths(vec![path.to_path_buf()]);
@@ -127,7 +376,12 @@ fn test_lost_space_calculation() {
     fs::write(path.join("file2.txt"), &content).unwrap();
     fs::write(path.join("file3.txt"), &content).unwrap();
 
-    let params = DuplicateFinderParameters::new(CheckingMethod::Hash, HashType::Blake3, false, 0, 0, true);
+    let params = DuplicateFinderParameters {
+        check_method: CheckingMethod::Hash,
+        hash_type: HashType::Blake3,
+        case_sensitive_name_comparison: true,
+        ..Default::default()
+    };
 
     let mut finder = DuplicateFinder::new(params);
     finder.set_minimal_file_size(0);
@@ -140,3 +394,600 @@ fn test_lost_space_calculation() {
     let info = finder.get_information();
     assert_eq!(info.lost_space_by_hash, 200, "Should calculate 200 bytes lost space (2 duplicate files * 100 bytes)");
 }
+
+#[test]
+fn test_verify_files_before_action() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    fs::write(path.join("original.txt"), b"same content").unwrap();
+    fs::write(path.join("copy.txt"), b"same content").unwrap();
+    fs::write(path.join("collision.txt"), b"other conten").unwrap();
+    fs::write(path.join("changed.txt"), b"same content").unwrap();
+
+    let mut changed = get_duplicate_entry(&path.join("changed.txt"));
+    changed.modified_date += 100;
+    let groups = vec![(
+        get_duplicate_entry(&path.join("original.txt")),
+        vec![get_duplicate_entry(&path.join("copy.txt")), get_duplicate_entry(&path.join("collision.txt")), changed],
+    )];
+
+    let params = DuplicateFinderParameters {
+        check_method: CheckingMethod::Hash,
+        hash_type: HashType::Crc32,
+        case_sensitive_name_comparison: true,
+        verify_before_action: true,
+        ..Default::default()
+    };
+    let mut finder = DuplicateFinder::new(params);
+    let verified_groups = finder.verify_files_before_action(&Arc::new(AtomicBool::new(false)), None, groups).unwrap();
+
+    assert_eq!(verified_groups.len(), 1);
+    assert_eq!(verified_groups[0].1.len(), 1);
+    assert_eq!(verified_groups[0].1[0].path, path.join("copy.txt"));
+    assert_eq!(finder.get_text_messages().errors.len(), 2);
+}
+
+#[test]
+fn test_delete_duplicates_with_verification() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    fs::write(path.join("file1.txt"), b"duplicate content").unwrap();
+    fs::write(path.join("file2.txt"), b"duplicate content").unwrap();
+    fs::write(path.join("file3.txt"), b"duplicate content").unwrap();
+
+    let params = DuplicateFinderParameters {
+        check_method: CheckingMethod::Hash,
+        hash_type: HashType::Blake3,
+        case_sensitive_name_comparison: true,
+        verify_before_action: true,
+        ..Default::default()
+    };
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_minimal_file_size(0);
+    finder.set_recursive_search(true);
+    finder.set_use_cache(false);
+    finder.set_delete_method(DeleteMethod::AllExceptNewest);
+
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    let remaining_files = ["file1.txt", "file2.txt", "file3.txt"].iter().filter(|name| path.join(name).exists()).count();
+    assert_eq!(remaining_files, 1);
+    assert!(finder.get_text_messages().errors.is_empty());
+}
+
+#[test]
+fn test_verification_compares_files_with_kept_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    for (idx, name) in ["a.txt", "b.txt", "c.txt"].iter().enumerate() {
+        fs::write(path.join(name), b"duplicate content").unwrap();
+        filetime::set_file_mtime(path.join(name), filetime::FileTime::from_unix_time(1_000_000 * (idx as i64 + 1), 0)).unwrap();
+    }
+
+    let params = DuplicateFinderParameters {
+        check_method: CheckingMethod::Hash,
+        hash_type: HashType::Blake3,
+        verify_before_action: true,
+        ..Default::default()
+    };
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_minimal_file_size(0);
+    finder.set_use_cache(false);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+    assert_eq!(finder.get_information().number_of_groups_by_hash, 1);
+
+    // First file of group is changed, but kept newest file is still identical to the second one
+    fs::write(path.join("a.txt"), b"changed content!!").unwrap();
+    finder.set_delete_method(DeleteMethod::AllExceptNewest);
+    assert_eq!(finder.delete_files(&Arc::new(AtomicBool::new(false)), None), WorkContinueStatus::Continue);
+
+    assert!(path.join("a.txt").exists());
+    assert!(!path.join("b.txt").exists());
+    assert!(path.join("c.txt").exists());
+    assert_eq!(finder.get_text_messages().errors.len(), 1, "{:?}", finder.get_text_messages().errors);
+}
+
+#[test]
+fn test_verification_with_size_and_name_methods() {
+    for check_method in [CheckingMethod::Size, CheckingMethod::SizeName, CheckingMethod::Name] {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path();
+        fs::create_dir_all(path.join("first")).unwrap();
+        fs::create_dir_all(path.join("second")).unwrap();
+        fs::write(path.join("first").join("file.txt"), b"first content").unwrap();
+        fs::write(path.join("second").join("file.txt"), b"other content").unwrap();
+
+        let params = DuplicateFinderParameters {
+            check_method,
+            hash_type: HashType::Blake3,
+            case_sensitive_name_comparison: true,
+            verify_before_action: true,
+            ..Default::default()
+        };
+        let mut finder = DuplicateFinder::new(params);
+        finder.set_included_paths(vec![path.to_path_buf()]);
+        finder.set_minimal_file_size(0);
+        finder.set_use_cache(false);
+        finder.set_delete_method(DeleteMethod::AllExceptNewest);
+        finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+        // Files have same size and name, but different content, so none of them can be removed
+        assert!(path.join("first").join("file.txt").exists(), "{check_method:?}");
+        assert!(path.join("second").join("file.txt").exists(), "{check_method:?}");
+        assert_eq!(finder.get_text_messages().errors.len(), 1, "{check_method:?} {:?}", finder.get_text_messages().errors);
+    }
+}
+
+#[test]
+fn test_save_hash_manifest() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    fs::write(path.join("file1.txt"), b"abc").unwrap();
+    fs::write(path.join("file2.txt"), b"abc").unwrap();
+    // Files without duplicates, but with calculated hash, are also saved
+    fs::write(path.join("file3.txt"), b"abd").unwrap();
+    // Not hashed at all, because no other file has the same size
+    fs::write(path.join("file4.txt"), b"abcd").unwrap();
+
+    let params = DuplicateFinderParameters {
+        check_method: CheckingMethod::Hash,
+        hash_type: HashType::Sha256,
+        case_sensitive_name_comparison: true,
+        ..Default::default()
+    };
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_minimal_file_size(0);
+    finder.set_recursive_search(true);
+    finder.set_use_cache(false);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    let manifest_path = path.join("manifest.sha256");
+    finder.save_hash_manifest(&manifest_path.to_string_lossy()).unwrap();
+
+    let hash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    let hash_of_unique_file = "a52d159f262b2c6ddb724a61840befc36eb30c88877a4030b65cbe86298449c9";
+    let expected = format!(
+        "{hash}  {}\n{hash}  {}\n{hash_of_unique_file}  {}\n",
+        path.join("file1.txt").to_string_lossy(),
+        path.join("file2.txt").to_string_lossy(),
+        path.join("file3.txt").to_string_lossy()
+    );
+    assert_eq!(fs::read_to_string(&manifest_path).unwrap(), expected);
+    fs::remove_file(&manifest_path).unwrap();
+
+    // Big file with unique size is also not fully hashed, unless all files are hashed
+    fs::write(path.join("file5.bin"), generate_random_content(5, 3 * PREHASHING_BUFFER_SIZE as usize)).unwrap();
+    for memory_limit in [0, 1] {
+        let params = DuplicateFinderParameters {
+            check_method: CheckingMethod::Hash,
+            hash_type: HashType::Sha256,
+            memory_limit,
+            hash_all_files: true,
+            ..Default::default()
+        };
+        let mut finder = DuplicateFinder::new(params);
+        finder.set_included_paths(vec![path.to_path_buf()]);
+        finder.set_minimal_file_size(0);
+        finder.set_use_cache(false);
+        finder.search(&Arc::new(AtomicBool::new(false)), None);
+        assert_eq!(finder.get_information().number_of_groups_by_size, 1);
+        assert_eq!(finder.get_information().number_of_groups_by_hash, 1);
+
+        let manifest_path = temp_dir.path().with_extension("sha256");
+        finder.save_hash_manifest(&manifest_path.to_string_lossy()).unwrap();
+        let manifest = fs::read_to_string(&manifest_path).unwrap();
+        fs::remove_file(&manifest_path).unwrap();
+        let saved_files: Vec<_> = manifest.lines().map(|line| line.split_once("  ").unwrap().1.to_string()).collect();
+        let expected_files: Vec<_> = ["file1.txt", "file2.txt", "file3.txt", "file4.txt", "file5.bin"]
+            .iter()
+            .map(|name| path.join(name).to_string_lossy().to_string())
+            .collect();
+        assert_eq!(saved_files, expected_files, "memory limit {memory_limit}");
+        assert!(manifest.contains(&format!(
+            "88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589  {}",
+            path.join("file4.txt").to_string_lossy()
+        )));
+    }
+}
+
+#[test]
+fn test_save_hash_manifest_unsupported_hash_type() {
+    let params = DuplicateFinderParameters {
+        check_method: CheckingMethod::Hash,
+        hash_type: HashType::Crc32,
+        case_sensitive_name_comparison: true,
+        ..Default::default()
+    };
+    let finder = DuplicateFinder::new(params);
+    let temp_dir = TempDir::new().unwrap();
+
+    assert!(finder.save_hash_manifest(&temp_dir.path().join("manifest.txt").to_string_lossy()).is_err());
+}
+
+#[test]
+fn test_find_duplicates_inside_archives() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    // Bigger than prehash head and tail, so skipping middle part of archive member is also checked
+    let content = generate_random_content(1, 20 * 1024);
+    let mut writer = zip::ZipWriter::new(fs::File::create(path.join("backup.zip")).unwrap());
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    writer.start_file("folder/file.bin", options).unwrap();
+    writer.write_all(&content).unwrap();
+    writer.start_file("other.bin", options).unwrap();
+    writer.write_all(&generate_random_content(2, 20 * 1024)).unwrap();
+    writer.finish().unwrap();
+
+    for verify_before_action in [false, true] {
+        fs::write(path.join("file.bin"), &content).unwrap();
+        fs::write(path.join("copy.bin"), &content).unwrap();
+        let params = DuplicateFinderParameters {
+            check_method: CheckingMethod::Hash,
+            hash_type: HashType::Blake3,
+            case_sensitive_name_comparison: true,
+            verify_before_action,
+            search_in_archives: true,
+            ..Default::default()
+        };
+        let mut finder = DuplicateFinder::new(params);
+        finder.set_included_paths(vec![path.to_path_buf()]);
+        finder.set_minimal_file_size(0);
+        finder.set_use_cache(false);
+        finder.set_delete_method(DeleteMethod::AllExceptNewest);
+        finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+        assert!(finder.get_text_messages().errors.is_empty(), "{:?}", finder.get_text_messages().errors);
+        let groups: Vec<_> = finder.get_files_sorted_by_hash().values().flatten().collect();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].len(), 3);
+        assert!(groups[0].iter().any(|entry| entry.path == path.join("backup.zip!/folder/file.bin")));
+
+        // Only one of files on disk is removed, archive stays untouched
+        let remaining_files = ["file.bin", "copy.bin"].iter().filter(|name| path.join(name).exists()).count();
+        assert_eq!(remaining_files, 1);
+        assert!(path.join("backup.zip").exists());
+    }
+}
+
+fn create_tar_xz(archive_path: &Path, files: &[(&str, &[u8])]) {
+    let mut builder = tar::Builder::new(Vec::new());
+    for (name, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, *content).unwrap();
+    }
+    let tar_content = builder.into_inner().unwrap();
+    let mut compressed = Vec::new();
+    lzma_rs::xz_compress(&mut tar_content.as_slice(), &mut compressed).unwrap();
+    fs::write(archive_path, compressed).unwrap();
+}
+
+#[test]
+fn test_hash_archive_members_in_one_pass() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+    let small_content = generate_random_content(1, 100);
+    let big_content = generate_random_content(2, 300 * 1024);
+    create_tar_xz(
+        &path.join("archive.tar.xz"),
+        &[("small.bin", &small_content), ("skipped.bin", b"skipped"), ("big.bin", &big_content)],
+    );
+    fs::write(path.join("small.bin"), &small_content).unwrap();
+    fs::write(path.join("big.bin"), &big_content).unwrap();
+
+    let members = ["small.bin", "big.bin", "missing.bin"]
+        .iter()
+        .map(|name| DuplicateEntry {
+            path: path.join(format!("archive.tar.xz!/{name}")),
+            size: fs::metadata(path.join(name)).map_or(1, |metadata| metadata.len()),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    let progress_handler = prepare_thread_handler_common(None, ToolStage::Duplicate(DuplicateStage::PreHashing), 0, 0);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let mut buffer = vec![0u8; THREAD_BUFFER_SIZE];
+    let get_ranges = |size| vec![get_prehash_ranges(size), vec![(0, size)], get_sampled_hash_ranges(size, 4)];
+    let (hashed_members, errors) = hash_archive_members(
+        &mut buffer,
+        &path.join("archive.tar.xz"),
+        members,
+        HashType::Blake3,
+        &get_ranges,
+        &progress_handler,
+        &stop_flag,
+    )
+    .unwrap();
+    assert_eq!(progress_handler.items_counter().load(std::sync::atomic::Ordering::Relaxed), 3);
+    progress_handler.join_thread();
+
+    // Missing member is reported, all others are hashed in same way as files on disk
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("missing.bin"), "{errors:?}");
+    assert_eq!(hashed_members.len(), 2);
+    for (member, hashes) in hashed_members {
+        let file_entry = get_duplicate_entry(&path.join(member.path.file_name().unwrap()));
+        let size_counter = Arc::default();
+        assert_eq!(
+            hashes,
+            vec![
+                hash_calculation_limit(&mut buffer, &file_entry, HashType::Blake3, PREHASHING_BUFFER_SIZE, &size_counter).unwrap(),
+                hash_calculation(&mut buffer, &file_entry, HashType::Blake3, &size_counter, &stop_flag).unwrap().unwrap(),
+                hash_calculation_sampled(&mut buffer, &file_entry, HashType::Blake3, 4, &size_counter).unwrap(),
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_find_duplicates_inside_compressed_archives() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let temp_cache = TempDir::new().unwrap();
+        let temp_config = TempDir::new().unwrap();
+        crate::common::config_cache_path::set_config_cache_path_test(temp_cache.path().to_path_buf(), temp_config.path().to_path_buf());
+        std::mem::forget(temp_cache);
+        std::mem::forget(temp_config);
+    });
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    let content = generate_random_content(1, 40 * 1024);
+    fs::write(path.join("file.bin"), &content).unwrap();
+    let mut encoder = flate2::write::GzEncoder::new(fs::File::create(path.join("file.bin.gz")).unwrap(), flate2::Compression::default());
+    encoder.write_all(&content).unwrap();
+    encoder.finish().unwrap();
+    let mut compressed = Vec::new();
+    lzma_rs::xz_compress(&mut content.as_slice(), &mut compressed).unwrap();
+    fs::write(path.join("file.bin.xz"), compressed).unwrap();
+    // Two identical members inside same archive
+    create_tar_xz(
+        &path.join("backup.txz"),
+        &[("first.bin", &content), ("other.bin", &generate_random_content(2, 40 * 1024)), ("second.bin", &content)],
+    );
+
+    // Memory limit makes files hashed in batches
+    for (check_method, memory_limit) in [(CheckingMethod::Hash, 0), (CheckingMethod::SampledHash, 0), (CheckingMethod::Hash, 1)] {
+        let params = DuplicateFinderParameters {
+            check_method,
+            hash_type: HashType::Blake3,
+            case_sensitive_name_comparison: true,
+            search_in_archives: true,
+            sampled_hash_samples: 4,
+            memory_limit,
+            ..Default::default()
+        };
+        let mut finder = DuplicateFinder::new(params);
+        finder.set_included_paths(vec![path.to_path_buf()]);
+        finder.set_minimal_file_size(0);
+        finder.set_use_cache(false);
+        finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+        assert!(finder.get_text_messages().warnings.is_empty(), "{:?}", finder.get_text_messages().warnings);
+        let groups: Vec<_> = finder.get_files_sorted_by_hash().values().flatten().collect();
+        assert_eq!(groups.len(), 1, "{check_method:?}");
+        let mut paths: Vec<_> = groups[0].iter().map(|entry| entry.path.strip_prefix(path).unwrap().to_string_lossy().to_string()).collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                "backup.txz!/first.bin",
+                "backup.txz!/second.bin",
+                "file.bin",
+                "file.bin.gz!/file.bin",
+                "file.bin.xz!/file.bin"
+            ]
+        );
+    }
+}
+
+fn run_sampled_hash_search(path: &Path, confirm_with_full_hash: bool) -> DuplicateFinder {
+    let params = DuplicateFinderParameters {
+        check_method: CheckingMethod::SampledHash,
+        hash_type: HashType::Blake3,
+        case_sensitive_name_comparison: true,
+        sampled_hash_samples: 4,
+        confirm_with_full_hash,
+        ..Default::default()
+    };
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_minimal_file_size(0);
+    finder.set_use_cache(false);
+    finder.set_delete_method(DeleteMethod::AllExceptNewest);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+    finder
+}
+
+#[test]
+fn test_sampled_hash_requires_confirmation() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    let content = generate_random_content(3, 2 * 1024 * 1024);
+    // Byte right before first sample is not hashed
+    let (sample_offset, _) = get_sampled_hash_ranges(content.len() as u64, 4)[1];
+    let mut modified = content.clone();
+    modified[sample_offset as usize - 1] ^= 1;
+    fs::write(path.join("file.bin"), &content).unwrap();
+    fs::write(path.join("modified.bin"), &modified).unwrap();
+
+    let finder = run_sampled_hash_search(path, false);
+    assert_eq!(finder.get_information().number_of_groups_by_hash, 1);
+    assert_eq!(finder.get_text_messages().errors.len(), 1);
+    assert!(path.join("file.bin").exists() && path.join("modified.bin").exists());
+
+    // Full hashes differ, so nothing is removed
+    let finder = run_sampled_hash_search(path, true);
+    assert_eq!(finder.get_text_messages().errors.len(), 1);
+    assert!(path.join("file.bin").exists() && path.join("modified.bin").exists());
+
+    fs::write(path.join("modified.bin"), &content).unwrap();
+    let finder = run_sampled_hash_search(path, true);
+    assert!(finder.get_text_messages().errors.is_empty(), "{:?}", finder.get_text_messages().errors);
+    let remaining_files = ["file.bin", "modified.bin"].iter().filter(|name| path.join(name).exists()).count();
+    assert_eq!(remaining_files, 1);
+}
+
+#[test]
+fn test_sampled_hash_results_are_labelled() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    fs::write(path.join("file1.txt"), b"duplicate content").unwrap();
+    fs::write(path.join("file2.txt"), b"duplicate content").unwrap();
+
+    let params = DuplicateFinderParameters {
+        check_method: CheckingMethod::SampledHash,
+        hash_type: HashType::Blake3,
+        case_sensitive_name_comparison: true,
+        sampled_hash_samples: 4,
+        ..Default::default()
+    };
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_minimal_file_size(0);
+    finder.set_use_cache(false);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    let mut text_results = Vec::new();
+    finder.write_results(&mut text_results).unwrap();
+    assert!(String::from_utf8(text_results).unwrap().contains("Results are probabilistic"));
+
+    let json_path = path.join("results.json");
+    finder.save_results_to_file_as_json(&json_path.to_string_lossy(), false).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(json_path).unwrap()).unwrap();
+    assert_eq!(json["tool_type"], "Duplicate");
+    assert_eq!(json["results"]["probabilistic"], true);
+    assert_eq!(json["results"]["samples"], 4);
+}
+
+fn generate_random_content(seed: u64, size: usize) -> Vec<u8> {
+    let mut state = seed;
+    (0..size)
+        .map(|_| {
+            state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+            (state >> 56) as u8
+        })
+        .collect()
+}
+
+fn calculate_sketch(content: &[u8]) -> Vec<u64> {
+    let mut chunker = ContentChunker::new();
+    // Split into parts, to check that chunk boundaries do not depend on size of read buffer
+    for part in content.chunks(7_000) {
+        chunker.update(part);
+    }
+    chunker.finalize()
+}
+
+#[test]
+fn test_content_chunks_similarity() {
+    let original = generate_random_content(1, 1024 * 1024);
+    let mut modified = original.clone();
+    modified.splice(500_000..500_000, b"inserted text".iter().copied());
+    let unrelated = generate_random_content(2, 1024 * 1024);
+
+    let original_sketch = calculate_sketch(&original);
+    assert_eq!(calculate_chunks_similarity(&original_sketch, &original_sketch), 100);
+
+    let modified_similarity = calculate_chunks_similarity(&original_sketch, &calculate_sketch(&modified));
+    assert!((90..100).contains(&modified_similarity), "Similarity - {modified_similarity}");
+
+    assert_eq!(calculate_chunks_similarity(&original_sketch, &calculate_sketch(&unrelated)), 0);
+    assert_eq!(calculate_chunks_similarity(&original_sketch, &[]), 0);
+
+    // Log with as much content appended, as it had before, still contains whole original log
+    let mut appended = original;
+    appended.extend(generate_random_content(3, 1024 * 1024));
+    let appended_similarity = calculate_chunks_similarity(&original_sketch, &calculate_sketch(&appended));
+    assert!(appended_similarity >= DEFAULT_MINIMAL_CONTENT_SIMILARITY, "Similarity - {appended_similarity}");
+    assert_eq!(calculate_chunks_similarity(&calculate_sketch(&appended), &original_sketch), appended_similarity);
+
+    // Files bigger than sketch size are compared only by part of their chunks
+    let big = generate_random_content(4, 12 * 1024 * 1024);
+    let mut big_appended = big.clone();
+    big_appended.extend(generate_random_content(5, 12 * 1024 * 1024));
+    let big_similarity = calculate_chunks_similarity(&calculate_sketch(&big), &calculate_sketch(&big_appended));
+    assert!(big_similarity >= DEFAULT_MINIMAL_CONTENT_SIMILARITY, "Similarity - {big_similarity}");
+}
+
+fn run_content_chunks_search(path: &Path, delete_method: DeleteMethod) -> DuplicateFinder {
+    let params = DuplicateFinderParameters {
+        check_method: CheckingMethod::ContentChunks,
+        hash_type: HashType::Blake3,
+        case_sensitive_name_comparison: true,
+        ..Default::default()
+    };
+    let mut finder = DuplicateFinder::new(params);
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_minimal_file_size(0);
+    finder.set_recursive_search(true);
+    finder.set_use_cache(false);
+    finder.set_delete_method(delete_method);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+    finder
+}
+
+#[test]
+fn test_find_files_with_similar_content_chunks() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    let original = generate_random_content(1, 512 * 1024);
+    let mut appended = original.clone();
+    appended.extend(generate_random_content(3, 32 * 1024));
+    let mut modified = original.clone();
+    modified[256 * 1024..256 * 1024 + 100].fill(0);
+
+    fs::write(path.join("original.log"), &original).unwrap();
+    fs::write(path.join("appended.log"), &appended).unwrap();
+    fs::write(path.join("modified.log"), &modified).unwrap();
+    fs::write(path.join("unrelated.log"), generate_random_content(2, 512 * 1024)).unwrap();
+
+    let finder = run_content_chunks_search(path, DeleteMethod::None);
+    let info = finder.get_information();
+    assert_eq!(info.number_of_groups_by_content_chunks, 1);
+    assert_eq!(info.number_of_similar_files_by_content_chunks, 2);
+    assert!(finder.found_any_items());
+
+    let groups = finder.get_files_with_similar_content_chunks();
+    assert_eq!(groups.len(), 1);
+    // The biggest file is the base of group
+    assert_eq!(groups[0][0].path, path.join("appended.log"));
+    assert_eq!(groups[0][0].similarity, 100);
+    assert!(groups[0][1..].iter().all(|entry| (DEFAULT_MINIMAL_CONTENT_SIMILARITY..100).contains(&entry.similarity)));
+    assert!(groups[0].iter().all(|entry| entry.path != path.join("unrelated.log")));
+}
+
+#[test]
+fn test_content_chunks_cannot_be_hardlinked() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    let original = generate_random_content(1, 256 * 1024);
+    let mut appended = original.clone();
+    appended.extend(b"new log line");
+    fs::write(path.join("original.log"), &original).unwrap();
+    fs::write(path.join("appended.log"), &appended).unwrap();
+
+    let finder = run_content_chunks_search(path, DeleteMethod::HardLink);
+    assert_eq!(finder.get_information().number_of_groups_by_content_chunks, 1);
+    assert_eq!(finder.get_text_messages().errors.len(), 1);
+    assert_eq!(fs::read(path.join("original.log")).unwrap(), original);
+
+    let finder = run_content_chunks_search(path, DeleteMethod::AllExceptBiggest);
+    assert!(finder.get_text_messages().errors.is_empty());
+    assert!(!path.join("original.log").exists());
+    assert!(path.join("appended.log").exists());
+}
@@ -7,12 +7,15 @@ use std::time::Instant;
 use crossbeam_channel::Sender;
 use fun_time::fun_time;
 use humansize::{BINARY, format_size};
+use serde::Serialize;
 
+use crate::common::flat_results::ResultsFormat;
 use crate::common::model::{CheckingMethod, WorkContinueStatus};
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
 use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
-use crate::tools::duplicate::{DuplicateFinder, DuplicateFinderParameters, Info};
+use crate::flc;
+use crate::tools::duplicate::{DuplicateFinder, DuplicateFinderParameters, Info, SAMPLED_HASH_SAMPLE_SIZE};
 
 impl AllTraits for DuplicateFinder {}
 
@@ -23,7 +26,51 @@ impl DeletingItems for DuplicateFinder {
             return WorkContinueStatus::Continue;
         }
 
+        if self.common_data.delete_method == DeleteMethod::Reflink && !matches!(self.get_params().check_method, CheckingMethod::Hash | CheckingMethod::SampledHash) {
+            self.common_data.text_messages.errors.push(flc!("core_reflink_requires_hash"));
+            return WorkContinueStatus::Continue;
+        }
+
+        if self.common_data.delete_method == DeleteMethod::HardLink && self.get_params().check_method == CheckingMethod::ContentChunks {
+            self.common_data.text_messages.errors.push(flc!("core_similar_content_cannot_be_linked"));
+            return WorkContinueStatus::Continue;
+        }
+
+        if self.get_params().verify_before_action {
+            if self.get_params().check_method == CheckingMethod::ContentChunks {
+                self.common_data.text_messages.errors.push(flc!("core_similar_content_cannot_be_verified"));
+                return WorkContinueStatus::Continue;
+            }
+            return self.delete_verified_files(stop_flag, progress_sender);
+        }
+
+        // Sampled hash only shows that files are probably identical, so content must be confirmed before any action
+        if self.get_params().check_method == CheckingMethod::SampledHash {
+            if !self.get_params().verify_before_action && !self.get_params().confirm_with_full_hash {
+                self.common_data.text_messages.errors.push(flc!("core_sampled_hash_requires_confirmation"));
+                return WorkContinueStatus::Continue;
+            }
+            return self.delete_verified_files(stop_flag, progress_sender);
+        }
+
+        if self.get_params().check_method == CheckingMethod::ContentChunks {
+            if self.common_data.use_reference_folders {
+                let files_to_delete = self
+                    .files_with_similar_content_chunks_referenced
+                    .iter()
+                    .flat_map(|(_, files)| files.iter().cloned())
+                    .collect();
+                return self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFiles(files_to_delete));
+            }
+            let files_to_delete = self.files_with_similar_content_chunks.clone();
+            return self.delete_advanced_elements_and_add_to_messages(stop_flag, progress_sender, files_to_delete);
+        }
+
         if self.common_data.use_reference_folders {
+            if self.common_data.delete_method == DeleteMethod::Reflink {
+                let reflink_items = self.files_with_identical_hashes_referenced.values().flatten().cloned().collect::<Vec<_>>();
+                return self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::ReflinkingFiles(reflink_items));
+            }
             if self.common_data.delete_method == DeleteMethod::HardLink {
                 // For each group the referenced map already holds (original, destinations).
                 // Feed this directly into HardlinkingFiles so the reference file is used as
@@ -102,12 +149,14 @@ impl Search for DuplicateFinder {
                         return;
                     }
                 }
-                CheckingMethod::Hash => {
-                    self.common_data.stopped_search = self.check_files_size(stop_flag, progress_sender) == WorkContinueStatus::Stop;
+                CheckingMethod::ContentChunks => {
+                    self.common_data.stopped_search = self.check_files_content_chunks(stop_flag, progress_sender) == WorkContinueStatus::Stop;
                     if self.common_data.stopped_search {
                         return;
                     }
-                    self.common_data.stopped_search = self.check_files_hash(stop_flag, progress_sender) == WorkContinueStatus::Stop;
+                }
+                CheckingMethod::Hash | CheckingMethod::SampledHash => {
+                    self.common_data.stopped_search = self.check_files_size_and_hash(stop_flag, progress_sender) == WorkContinueStatus::Stop;
                     if self.common_data.stopped_search {
                         return;
                     }
@@ -154,6 +203,10 @@ impl DebugPrint for DuplicateFinder {
             format_size(self.information.lost_space_by_hash, BINARY),
             self.information.lost_space_by_hash
         );
+        println!(
+            "Number of similar files by content chunks(in groups) - {} ({})",
+            self.information.number_of_similar_files_by_content_chunks, self.information.number_of_groups_by_content_chunks
+        );
 
         println!("### Other");
 
@@ -165,6 +218,8 @@ impl DebugPrint for DuplicateFinder {
         println!("Files with identical size names referenced - {}", self.files_with_identical_size_names_referenced.len());
         println!("Files with identical size referenced - {}", self.files_with_identical_size_referenced.len());
         println!("Files with identical hashes referenced - {}", self.files_with_identical_hashes_referenced.len());
+        println!("Files with similar content chunks - {}", self.files_with_similar_content_chunks.len());
+        println!("Files with similar content chunks referenced - {}", self.files_with_similar_content_chunks_referenced.len());
         println!("Checking Method - {:?}", self.get_params().check_method);
         self.debug_print_common();
         println!("-----------------------------------------");
@@ -298,11 +353,16 @@ impl PrintResults for DuplicateFinder {
                     write!(writer, "Not found any duplicates.")?;
                 }
             }
-            CheckingMethod::Hash => {
+            CheckingMethod::Hash | CheckingMethod::SampledHash => {
+                let hash_name = if self.get_params().check_method == CheckingMethod::SampledHash {
+                    "sampled hashes"
+                } else {
+                    "hashes"
+                };
                 if !self.files_with_identical_hashes.is_empty() {
                     writeln!(
                         writer,
-                        "-------------------------------------------------Files with same hashes-------------------------------------------------"
+                        "-------------------------------------------------Files with same {hash_name}-------------------------------------------------"
                     )?;
                     writeln!(
                         writer,
@@ -311,6 +371,7 @@ impl PrintResults for DuplicateFinder {
                         self.information.number_of_groups_by_hash,
                         format_size(self.information.lost_space_by_hash, BINARY)
                     )?;
+                    self.write_sampled_hash_warning(writer)?;
                     for (size, vectors_vector) in self.files_with_identical_hashes.iter().rev() {
                         for vector in vectors_vector {
                             writeln!(writer, "\n---- Size {} ({}) - {} files", format_size(*size, BINARY), size, vector.len())?;
@@ -322,7 +383,7 @@ impl PrintResults for DuplicateFinder {
                 } else if !self.files_with_identical_hashes_referenced.is_empty() {
                     writeln!(
                         writer,
-                        "-------------------------------------------------Files with same hashes in referenced folders-------------------------------------------------"
+                        "-------------------------------------------------Files with same {hash_name} in referenced folders-------------------------------------------------"
                     )?;
                     writeln!(
                         writer,
@@ -331,6 +392,7 @@ impl PrintResults for DuplicateFinder {
                         self.information.number_of_groups_by_hash,
                         format_size(self.information.lost_space_by_hash, BINARY)
                     )?;
+                    self.write_sampled_hash_warning(writer)?;
                     for (size, vectors_vector) in self.files_with_identical_hashes_referenced.iter().rev() {
                         for (file_entry, vector) in vectors_vector {
                             writeln!(writer, "\n---- Size {} ({}) - {} files", format_size(*size, BINARY), size, vector.len())?;
@@ -344,6 +406,61 @@ impl PrintResults for DuplicateFinder {
                     write!(writer, "Not found any duplicates.")?;
                 }
             }
+            CheckingMethod::ContentChunks => {
+                if !self.files_with_similar_content_chunks.is_empty() {
+                    writeln!(
+                        writer,
+                        "-------------------------------------------------Files with similar content-------------------------------------------------"
+                    )?;
+                    writeln!(
+                        writer,
+                        "Found {} files in {} groups with similar content(files are not identical)",
+                        self.information.number_of_similar_files_by_content_chunks, self.information.number_of_groups_by_content_chunks,
+                    )?;
+                    for vector in &self.files_with_similar_content_chunks {
+                        writeln!(writer, "\n---- {} files", vector.len())?;
+                        for file_entry in vector {
+                            writeln!(
+                                writer,
+                                "\"{}\" - {} - {}%",
+                                file_entry.path.to_string_lossy(),
+                                format_size(file_entry.size, BINARY),
+                                file_entry.similarity
+                            )?;
+                        }
+                    }
+                } else if !self.files_with_similar_content_chunks_referenced.is_empty() {
+                    writeln!(
+                        writer,
+                        "-------------------------------------------------Files with similar content in referenced folders-------------------------------------------------"
+                    )?;
+                    writeln!(
+                        writer,
+                        "Found {} files in {} groups with similar content(files are not identical)",
+                        self.information.number_of_similar_files_by_content_chunks, self.information.number_of_groups_by_content_chunks,
+                    )?;
+                    for (file_entry, vector) in &self.files_with_similar_content_chunks_referenced {
+                        writeln!(writer, "\n---- {} files", vector.len())?;
+                        writeln!(
+                            writer,
+                            "Reference file - \"{}\" - {}",
+                            file_entry.path.to_string_lossy(),
+                            format_size(file_entry.size, BINARY)
+                        )?;
+                        for file_entry in vector {
+                            writeln!(
+                                writer,
+                                "\"{}\" - {} - {}%",
+                                file_entry.path.to_string_lossy(),
+                                format_size(file_entry.size, BINARY),
+                                file_entry.similarity
+                            )?;
+                        }
+                    }
+                } else {
+                    write!(writer, "Not found any files with similar content.")?;
+                }
+            }
             _ => panic!(),
         }
 
@@ -351,29 +468,63 @@ impl PrintResults for DuplicateFinder {
     }
 
     // TODO - check if is possible to save also data in header about size and name in SizeName mode - https://github.com/qarmin/czkawka/issues/1137
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> io::Result<()> {
+    fn save_results_to_file(&self, file_name: &str, format: ResultsFormat) -> io::Result<()> {
         if self.get_use_reference() {
             match self.get_params().check_method {
-                CheckingMethod::Name => self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_names_referenced, pretty_print),
-                CheckingMethod::SizeName => {
-                    self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_size_names_referenced.values().collect::<Vec<_>>(), pretty_print)
-                }
-                CheckingMethod::Size => self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_size_referenced, pretty_print),
-                CheckingMethod::Hash => self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_hashes_referenced, pretty_print),
+                CheckingMethod::Name => self.save_results_to_file_internal(file_name, &self.files_with_identical_names_referenced, format),
+                CheckingMethod::SizeName => self.save_results_to_file_internal(file_name, &self.files_with_identical_size_names_referenced.values().collect::<Vec<_>>(), format),
+                CheckingMethod::Size => self.save_results_to_file_internal(file_name, &self.files_with_identical_size_referenced, format),
+                CheckingMethod::Hash => self.save_results_to_file_internal(file_name, &self.files_with_identical_hashes_referenced, format),
+                CheckingMethod::SampledHash => self.save_results_to_file_internal(file_name, &self.sampled_hash_results(&self.files_with_identical_hashes_referenced), format),
+                CheckingMethod::ContentChunks => self.save_results_to_file_internal(file_name, &self.files_with_similar_content_chunks_referenced, format),
                 _ => panic!(),
             }
         } else {
             match self.get_params().check_method {
-                CheckingMethod::Name => self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_names, pretty_print),
-                CheckingMethod::SizeName => self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_size_names.values().collect::<Vec<_>>(), pretty_print),
-                CheckingMethod::Size => self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_size, pretty_print),
-                CheckingMethod::Hash => self.save_results_to_file_as_json_internal(file_name, &self.files_with_identical_hashes, pretty_print),
+                CheckingMethod::Name => self.save_results_to_file_internal(file_name, &self.files_with_identical_names, format),
+                CheckingMethod::SizeName => self.save_results_to_file_internal(file_name, &self.files_with_identical_size_names.values().collect::<Vec<_>>(), format),
+                CheckingMethod::Size => self.save_results_to_file_internal(file_name, &self.files_with_identical_size, format),
+                CheckingMethod::Hash => self.save_results_to_file_internal(file_name, &self.files_with_identical_hashes, format),
+                CheckingMethod::SampledHash => self.save_results_to_file_internal(file_name, &self.sampled_hash_results(&self.files_with_identical_hashes), format),
+                CheckingMethod::ContentChunks => self.save_results_to_file_internal(file_name, &self.files_with_similar_content_chunks, format),
                 _ => panic!(),
             }
         }
     }
 }
 
+// Sampled hash results are saved together with information, that files in groups only probably have identical content
+#[derive(Serialize, Debug)]
+struct SampledHashResults<'a, T> {
+    probabilistic: bool,
+    samples: u32,
+    sample_size: u64,
+    results: &'a T,
+}
+
+impl DuplicateFinder {
+    fn sampled_hash_results<'a, T>(&self, results: &'a T) -> SampledHashResults<'a, T> {
+        SampledHashResults {
+            probabilistic: true,
+            samples: self.get_params().sampled_hash_samples,
+            sample_size: SAMPLED_HASH_SAMPLE_SIZE,
+            results,
+        }
+    }
+
+    fn write_sampled_hash_warning<T: Write>(&self, writer: &mut T) -> io::Result<()> {
+        if self.get_params().check_method == CheckingMethod::SampledHash {
+            writeln!(
+                writer,
+                "Results are probabilistic - only {} samples of {} from every file were compared, so files may still have different content.",
+                self.get_params().sampled_hash_samples,
+                format_size(SAMPLED_HASH_SAMPLE_SIZE, BINARY)
+            )?;
+        }
+        Ok(())
+    }
+}
+
 impl CommonData for DuplicateFinder {
     type Info = Info;
     type Parameters = DuplicateFinderParameters;
@@ -398,5 +549,6 @@ impl CommonData for DuplicateFinder {
             || self.get_information().number_of_duplicated_files_by_name > 0
             || self.get_information().number_of_duplicated_files_by_size > 0
             || self.get_information().number_of_duplicated_files_by_size_name > 0
+            || self.get_information().number_of_similar_files_by_content_chunks > 0
     }
 }
@@ -0,0 +1,571 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64};
+use std::{fs, mem};
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use log::debug;
+use rayon::prelude::*;
+
+use crate::common::cache::{load_and_split_cache_generalized_by_size, save_cache_to_file_generalized};
+use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult, common_get_metadata_from_path, get_modified_time};
+use crate::common::model::{CheckingMethod, FileEntry, HashType, ToolType, WorkContinueStatus};
+use crate::common::progress_data::{CacheLoadPhase, DuplicateFoldersStage, ProgressData, ToolStage};
+use crate::common::progress_stop_handler::{ProgressThreadHandler, check_if_stop_received, prepare_thread_handler_common};
+use crate::common::tool_data::{CommonData, CommonToolData};
+use crate::flc;
+use crate::tools::duplicate::core::get_duplicate_cache_file;
+use crate::tools::duplicate::{DuplicateEntry, MyHasher, THREAD_BUFFER, hash_calculation};
+use crate::tools::duplicate_folders::{DuplicateFolderEntry, DuplicateFolders, DuplicateFoldersParameters, FolderNode, Info};
+
+impl DuplicateFolders {
+    pub fn new(params: DuplicateFoldersParameters) -> Self {
+        Self {
+            common_data: CommonToolData::new(ToolType::DuplicateFolders),
+            information: Info::default(),
+            duplicated_folders: Vec::new(),
+            duplicated_folders_referenced: Vec::new(),
+            subset_folders: Vec::new(),
+            params,
+        }
+    }
+
+    #[fun_time(message = "collect_files", level = "debug")]
+    pub(crate) fn collect_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> Option<BTreeMap<u64, Vec<FileEntry>>> {
+        let result = DirTraversalBuilder::new()
+            .common_data(&self.common_data)
+            .group_by(|fe| fe.size)
+            .checking_method(CheckingMethod::Hash)
+            .stop_flag(stop_flag)
+            .progress_sender(progress_sender)
+            .build()
+            .run();
+
+        match result {
+            DirTraversalResult::SuccessFiles { grouped_file_entries, warnings } => {
+                self.common_data.text_messages.warnings.extend(warnings);
+                Some(grouped_file_entries)
+            }
+            DirTraversalResult::Stopped => None,
+        }
+    }
+
+    // Only files which share size with at least one other file are really hashed (or taken from duplicate finder cache).
+    // Any other file cannot have a copy, so it gets unique placeholder hash, which makes its folder unique too.
+    #[fun_time(message = "hash_files", level = "debug")]
+    pub(crate) fn hash_files(
+        &mut self,
+        stop_flag: &Arc<AtomicBool>,
+        progress_sender: Option<&Sender<ProgressData>>,
+        grouped_file_entries: BTreeMap<u64, Vec<FileEntry>>,
+    ) -> Option<Vec<DuplicateEntry>> {
+        let mut hashed_files = Vec::new();
+        let mut files_to_hash: BTreeMap<u64, Vec<DuplicateEntry>> = Default::default();
+        for (size, vec_file_entry) in grouped_file_entries {
+            if vec_file_entry.len() > 1 {
+                files_to_hash.insert(size, vec_file_entry.into_iter().map(FileEntry::into_duplicate_entry).collect());
+            } else {
+                hashed_files.extend(vec_file_entry.into_iter().map(|fe| with_placeholder_hash(fe.into_duplicate_entry(), "unique")));
+            }
+        }
+
+        let progress_handler = prepare_thread_handler_common(
+            progress_sender,
+            ToolStage::DuplicateFolders(DuplicateFoldersStage::LoadingHashCache(CacheLoadPhase::Loading)),
+            0,
+            0,
+        );
+        let (loaded_hash_map, records_already_cached, non_cached_files_to_check) =
+            load_and_split_cache_generalized_by_size(&get_duplicate_cache_file(self.params.hash_type, false), self.common_data.use_cache, files_to_hash, self);
+        progress_handler.join_thread();
+        if check_if_stop_received(stop_flag) {
+            return None;
+        }
+
+        let progress_handler = prepare_thread_handler_common(
+            progress_sender,
+            ToolStage::DuplicateFolders(DuplicateFoldersStage::FullHashing),
+            non_cached_files_to_check.values().map(Vec::len).sum(),
+            non_cached_files_to_check.iter().map(|(size, items)| (*size) * items.len() as u64).sum::<u64>(),
+        );
+
+        let hash_type = self.params.hash_type;
+        let non_cached_files_to_check: Vec<(u64, Vec<DuplicateEntry>)> = non_cached_files_to_check.into_iter().collect();
+        let full_hash_results: Vec<(Vec<DuplicateEntry>, Vec<DuplicateEntry>, Vec<String>)> = non_cached_files_to_check
+            .into_par_iter()
+            .with_max_len(3)
+            .map(|(_size, vec_file_entry)| {
+                let mut hashed = Vec::new();
+                let mut not_hashed = Vec::new();
+                let mut errors = Vec::new();
+
+                THREAD_BUFFER.with_borrow_mut(|buffer| {
+                    for mut file_entry in vec_file_entry {
+                        if check_if_stop_received(stop_flag) {
+                            return None;
+                        }
+
+                        match hash_calculation(buffer, &file_entry, hash_type, progress_handler.size_counter(), stop_flag) {
+                            Ok(hash_string) => {
+                                file_entry.hash = hash_string?;
+                                hashed.push(file_entry);
+                            }
+                            Err(e) => {
+                                errors.push(e);
+                                not_hashed.push(with_placeholder_hash(file_entry, "unreadable"));
+                            }
+                        }
+                        progress_handler.increase_items(1);
+                    }
+                    Some(())
+                })?;
+
+                Some((hashed, not_hashed, errors))
+            })
+            .while_some()
+            .collect();
+        progress_handler.join_thread();
+
+        let mut newly_hashed = Vec::new();
+        for (hashed, not_hashed, mut errors) in full_hash_results {
+            self.common_data.text_messages.warnings.append(&mut errors);
+            newly_hashed.extend(hashed);
+            hashed_files.extend(not_hashed);
+        }
+
+        // Even if clicked stop, save already calculated hashes to cache
+        if self.common_data.use_cache {
+            let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::DuplicateFolders(DuplicateFoldersStage::SavingHashCache), 0, 0);
+            let mut all_results: BTreeMap<String, DuplicateEntry> = Default::default();
+            for file_entry in loaded_hash_map.into_values().flatten() {
+                all_results.insert(file_entry.path.to_string_lossy().to_string(), file_entry);
+            }
+            for file_entry in &newly_hashed {
+                all_results.insert(file_entry.path.to_string_lossy().to_string(), file_entry.clone());
+            }
+            let messages = save_cache_to_file_generalized(
+                &get_duplicate_cache_file(self.params.hash_type, false),
+                &all_results,
+                self.common_data.save_also_as_json,
+                self.params.minimal_cache_file_size,
+            );
+            self.get_text_messages_mut().extend_with_another_messages(messages);
+            progress_handler.join_thread();
+        }
+
+        if check_if_stop_received(stop_flag) {
+            return None;
+        }
+
+        hashed_files.extend(newly_hashed);
+        hashed_files.extend(records_already_cached.into_values().flatten());
+        Some(hashed_files)
+    }
+
+    #[fun_time(message = "build_folder_tree", level = "debug")]
+    pub(crate) fn build_folder_tree(&self, files: Vec<DuplicateEntry>) -> Vec<FolderNode> {
+        let roots = &self.common_data.directories.included_directories;
+        let mut nodes: Vec<FolderNode> = Vec::new();
+        let mut indexes: HashMap<PathBuf, usize> = HashMap::new();
+
+        for file in files {
+            let (Some(parent), Some(file_name)) = (file.path.parent(), file.path.file_name()) else {
+                continue;
+            };
+            // Single files added directly to included paths, are not part of any scanned folder
+            if !roots.iter().any(|root| parent.starts_with(root)) {
+                continue;
+            }
+
+            let folder_idx = get_or_insert_folder(&mut nodes, &mut indexes, roots, parent);
+            let mut current = Some(folder_idx);
+            while let Some(idx) = current {
+                #[expect(clippy::indexing_slicing)] // Safe, indexes are always taken from nodes
+                let node = &mut nodes[idx];
+                node.size += file.size;
+                node.files_number += 1;
+                current = node.parent;
+            }
+            #[expect(clippy::indexing_slicing)] // Safe, index was just taken from nodes
+            nodes[folder_idx].files.push((file_name.to_os_string(), file.hash));
+        }
+
+        for idx in 0..nodes.len() {
+            #[expect(clippy::indexing_slicing)] // Safe, idx < nodes.len()
+            let mut subfolders = mem::take(&mut nodes[idx].subfolders);
+            // All subfolders have same parent, so comparing whole paths is same as comparing names
+            #[expect(clippy::indexing_slicing)] // Safe, indexes are always taken from nodes
+            subfolders.sort_unstable_by(|a, b| nodes[*a].path.cmp(&nodes[*b].path));
+            #[expect(clippy::indexing_slicing)] // Safe, idx < nodes.len()
+            let node = &mut nodes[idx];
+            node.subfolders = subfolders;
+            node.files.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        }
+
+        nodes
+    }
+
+    // Merkle-like hash - every folder hash is built from names and hashes of its files and subfolders.
+    // Parents are always inserted before their children, so iterating from the end visits children first.
+    #[expect(clippy::indexing_slicing)] // Safe, idx < nodes.len() and subfolders indexes are always taken from nodes
+    pub(crate) fn calculate_folder_hashes(&self, nodes: &mut [FolderNode]) {
+        for idx in (0..nodes.len()).rev() {
+            let mut hasher = self.params.hash_type.hasher();
+            for (name, hash) in &nodes[idx].files {
+                hash_tree_item(&mut *hasher, b"f", name, hash);
+            }
+            for sub_idx in &nodes[idx].subfolders {
+                let sub = &nodes[*sub_idx];
+                hash_tree_item(&mut *hasher, b"d", sub.path.file_name().unwrap_or_default(), &sub.hash);
+            }
+            nodes[idx].hash = hasher.finalize();
+        }
+    }
+
+    #[fun_time(message = "find_identical_folders", level = "debug")]
+    pub(crate) fn find_identical_folders(&mut self, nodes: &[FolderNode]) {
+        let mut folders_by_hash: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (idx, node) in nodes.iter().enumerate() {
+            folders_by_hash.entry(node.hash.as_str()).or_default().push(idx);
+        }
+
+        let mut groups = folders_by_hash
+            .into_values()
+            .filter(|group| group.len() > 1 && !is_covered_by_parents(nodes, group))
+            .map(|group| {
+                let mut group = group.into_iter().map(|idx| folder_entry(nodes, idx)).collect::<Vec<_>>();
+                group.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+                group
+            })
+            .collect::<Vec<_>>();
+        #[expect(clippy::indexing_slicing)] // Safe, groups have at least 2 items
+        groups.sort_unstable_by(|a, b| b[0].size.cmp(&a[0].size).then_with(|| a[0].path.cmp(&b[0].path)));
+
+        if self.common_data.use_reference_folders {
+            self.duplicated_folders_referenced = self.common_data.directories.filter_reference_folders(groups);
+            for (reference, folders) in &self.duplicated_folders_referenced {
+                self.information.number_of_groups += 1;
+                self.information.number_of_duplicated_folders += folders.len();
+                self.information.lost_space += folders.len() as u64 * reference.size;
+            }
+        } else {
+            for group in &groups {
+                self.information.number_of_groups += 1;
+                self.information.number_of_duplicated_folders += group.len() - 1;
+                self.information.lost_space += (group.len() as u64 - 1) * group.first().map_or(0, |e| e.size);
+            }
+            self.duplicated_folders = groups;
+        }
+    }
+
+    // Folder A is strict subset of folder B, when every file of A can be found under the same relative path in B, with same content,
+    // and B contains at least one additional file.
+    #[fun_time(message = "find_subset_folders", level = "debug")]
+    pub(crate) fn find_subset_folders(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>, nodes: &[FolderNode]) -> WorkContinueStatus {
+        let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::DuplicateFolders(DuplicateFoldersStage::ComparingFolders), nodes.len(), 0);
+        let supersets = find_supersets(stop_flag, &progress_handler, nodes);
+        progress_handler.join_thread();
+        let Some(supersets) = supersets else {
+            return WorkContinueStatus::Stop;
+        };
+
+        self.report_subset_folders(nodes, &supersets);
+        debug!("find_subset_folders - found {} folders which are subsets of other folders", self.subset_folders.len());
+
+        WorkContinueStatus::Continue
+    }
+
+    // Parents are visited first, so when parent is reported, there is no need to report also its subfolders
+    #[expect(clippy::indexing_slicing)] // Safe, supersets have same length as nodes and all indexes are always taken from nodes
+    fn report_subset_folders(&mut self, nodes: &[FolderNode], supersets: &[Vec<usize>]) {
+        let mut reported = vec![false; nodes.len()];
+        for (idx, node) in nodes.iter().enumerate() {
+            if supersets[idx].is_empty() || node.parent.is_some_and(|parent| reported[parent]) {
+                continue;
+            }
+            let mut bigger_folders = supersets[idx].clone();
+            if self.common_data.use_reference_folders {
+                if self.common_data.directories.is_in_referenced_directory(&node.path) {
+                    continue;
+                }
+                bigger_folders.retain(|other_idx| self.common_data.directories.is_in_referenced_directory(&nodes[*other_idx].path));
+                if bigger_folders.is_empty() {
+                    continue;
+                }
+            }
+            reported[idx] = true;
+
+            let mut bigger_folders = bigger_folders.into_iter().map(|other_idx| folder_entry(nodes, other_idx)).collect::<Vec<_>>();
+            bigger_folders.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+            self.information.number_of_subset_folders += 1;
+            self.subset_folders.push((folder_entry(nodes, idx), bigger_folders));
+        }
+        self.subset_folders.sort_unstable_by(|a, b| b.0.size.cmp(&a.0.size).then_with(|| a.0.path.cmp(&b.0.path)));
+    }
+}
+
+impl DuplicateFolders {
+    // Scan checks only files which passed all filters (so e.g. excluded files or symlinks are not part of folder hash),
+    // and folders may be changed after scan, so right before removing, every folder from results is read again
+    // and all items inside it are hashed. Returns folders whose new hash is identical to the one from scan, or None when stopped.
+    #[fun_time(message = "verify_folders_before_removing", level = "debug")]
+    pub(crate) fn verify_folders_before_removing(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> Option<HashSet<PathBuf>> {
+        let mut folders: BTreeMap<&Path, &DuplicateFolderEntry> = BTreeMap::new();
+        let referenced = self
+            .duplicated_folders_referenced
+            .iter()
+            .flat_map(|(reference, folders)| std::iter::once(reference).chain(folders));
+        let subsets = self
+            .subset_folders
+            .iter()
+            .flat_map(|(subset, bigger_folders)| std::iter::once(subset).chain(bigger_folders));
+        for folder in self.duplicated_folders.iter().flatten().chain(referenced).chain(subsets) {
+            folders.insert(&folder.path, folder);
+        }
+        let folders: Vec<DuplicateFolderEntry> = folders.into_values().cloned().collect();
+
+        let progress_handler = prepare_thread_handler_common(
+            progress_sender,
+            ToolStage::DuplicateFolders(DuplicateFoldersStage::VerifyingFolders),
+            folders.len(),
+            folders.iter().map(|folder| folder.size).sum(),
+        );
+        let hash_type = self.params.hash_type;
+        let results: Vec<(PathBuf, Result<bool, String>)> = folders
+            .into_par_iter()
+            .map(|folder| {
+                let result = THREAD_BUFFER.with_borrow_mut(|buffer| hash_folder_tree(buffer, &folder.path, hash_type, progress_handler.size_counter(), stop_flag));
+                progress_handler.increase_items(1);
+                (folder.path, result.map(|hash| hash.is_some_and(|hash| hash == folder.hash)))
+            })
+            .collect();
+        progress_handler.join_thread();
+        if check_if_stop_received(stop_flag) {
+            return None;
+        }
+
+        let mut verified_folders = HashSet::new();
+        for (path, result) in results {
+            match result {
+                Ok(true) => {
+                    verified_folders.insert(path);
+                }
+                Ok(false) => self
+                    .common_data
+                    .text_messages
+                    .errors
+                    .push(flc!("core_folder_changed_since_scan", path = path.to_string_lossy().to_string())),
+                Err(e) => self.common_data.text_messages.errors.push(e),
+            }
+        }
+        Some(verified_folders)
+    }
+}
+
+// Hash calculated in the same way as during scan, but from all items inside folder, not only ones which passed filters.
+// Returns None when folder contains no files or when stopped.
+fn hash_folder_tree(buffer: &mut [u8], folder: &Path, hash_type: HashType, size_counter: &Arc<AtomicU64>, stop_flag: &Arc<AtomicBool>) -> Result<Option<String>, String> {
+    let read_error = |path: &Path, e: std::io::Error| flc!("core_folder_verification_cannot_read", path = path.to_string_lossy().to_string(), reason = e.to_string());
+    let mut files = Vec::new();
+    let mut subfolders = Vec::new();
+    for entry in fs::read_dir(folder).map_err(|e| read_error(folder, e))? {
+        if check_if_stop_received(stop_flag) {
+            return Ok(None);
+        }
+        let entry = entry.map_err(|e| read_error(folder, e))?;
+        let path = entry.path();
+        let metadata = fs::symlink_metadata(&path).map_err(|e| read_error(&path, e))?;
+        if metadata.is_dir() {
+            // Folders without any files are not part of folder tree built during scan
+            if let Some(hash) = hash_folder_tree(buffer, &path, hash_type, size_counter, stop_flag)? {
+                subfolders.push((entry.file_name(), hash));
+            }
+        } else if metadata.is_file() {
+            let file_entry = DuplicateEntry {
+                path,
+                size: metadata.len(),
+                ..Default::default()
+            };
+            let Some(hash) = hash_calculation(buffer, &file_entry, hash_type, size_counter, stop_flag)? else {
+                return Ok(None);
+            };
+            files.push((entry.file_name(), hash));
+        } else {
+            // Symlinks and special files are never checked during scan
+            return Err(flc!("core_folder_contains_unchecked_item", path = path.to_string_lossy().to_string()));
+        }
+    }
+    if files.is_empty() && subfolders.is_empty() {
+        return Ok(None);
+    }
+
+    files.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    subfolders.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    let mut hasher = hash_type.hasher();
+    for (name, hash) in &files {
+        hash_tree_item(&mut *hasher, b"f", name, hash);
+    }
+    for (name, hash) in &subfolders {
+        hash_tree_item(&mut *hasher, b"d", name, hash);
+    }
+    Ok(Some(hasher.finalize()))
+}
+
+// Returns for every folder list of folders, which are its strict supersets, or None when search was stopped
+#[expect(clippy::indexing_slicing)] // Safe, all indexes are always taken from nodes
+fn find_supersets(stop_flag: &Arc<AtomicBool>, progress_handler: &ProgressThreadHandler, nodes: &[FolderNode]) -> Option<Vec<Vec<usize>>> {
+    // (File name, hash) -> folders which directly contain such file
+    let mut files_index: HashMap<(&OsStr, &str), Vec<usize>> = HashMap::new();
+    let mut folders_by_hash: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (idx, node) in nodes.iter().enumerate() {
+        for (name, hash) in &node.files {
+            files_index.entry((name.as_os_str(), hash.as_str())).or_default().push(idx);
+        }
+        folders_by_hash.entry(node.hash.as_str()).or_default().push(idx);
+    }
+
+    // Children are visited before parents, so their supersets are already known when checking parent
+    let mut supersets: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    for idx in (0..nodes.len()).rev() {
+        if check_if_stop_received(stop_flag) {
+            return None;
+        }
+        progress_handler.increase_items(1);
+
+        let node = &nodes[idx];
+        // The rarer the file, the fewer candidates need to be checked
+        let candidates: Vec<usize> = if let Some((name, hash)) = node
+            .files
+            .iter()
+            .min_by_key(|(name, hash)| files_index.get(&(name.as_os_str(), hash.as_str())).map_or(0, Vec::len))
+        {
+            files_index.get(&(name.as_os_str(), hash.as_str())).cloned().unwrap_or_default()
+        } else if let Some(sub_idx) = node.subfolders.first() {
+            // Folder contains only subfolders, so bigger folder must contain identical or bigger subfolder with same name
+            let sub = &nodes[*sub_idx];
+            folders_by_hash
+                .get(sub.hash.as_str())
+                .into_iter()
+                .flatten()
+                .chain(supersets[*sub_idx].iter())
+                .filter(|other_idx| **other_idx != *sub_idx && nodes[**other_idx].path.file_name() == sub.path.file_name())
+                .filter_map(|other_idx| nodes[*other_idx].parent)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        supersets[idx] = candidates
+            .into_iter()
+            .filter(|other_idx| *other_idx != idx && is_strict_subset(nodes, &supersets, idx, *other_idx))
+            .collect();
+    }
+    Some(supersets)
+}
+
+fn folder_entry(nodes: &[FolderNode], idx: usize) -> DuplicateFolderEntry {
+    #[expect(clippy::indexing_slicing)] // Safe, indexes are always taken from nodes
+    let node = &nodes[idx];
+    // Warnings are not interesting here, folder was already visited while collecting files
+    let mut warnings = Vec::new();
+    let modified_date = common_get_metadata_from_path(&node.path, &mut warnings).map_or(0, |metadata| get_modified_time(&metadata, &mut warnings, &node.path, true));
+    DuplicateFolderEntry {
+        path: node.path.clone(),
+        modified_date,
+        size: node.size,
+        files_number: node.files_number,
+        hash: node.hash.clone(),
+    }
+}
+
+fn with_placeholder_hash(mut file_entry: DuplicateEntry, reason: &str) -> DuplicateEntry {
+    // Size is part of placeholder, so lossy conversion of two different paths cannot produce same hash
+    file_entry.hash = format!("{reason}_{}_{}", file_entry.size, file_entry.path.to_string_lossy());
+    file_entry
+}
+
+fn hash_tree_item(hasher: &mut dyn MyHasher, kind: &[u8], name: &OsStr, hash: &str) {
+    hasher.update(kind);
+    hasher.update(name.as_encoded_bytes());
+    hasher.update(b"\0");
+    hasher.update(hash.as_bytes());
+    hasher.update(b"\n");
+}
+
+#[expect(clippy::indexing_slicing)] // Safe, indexes are always taken from nodes
+fn get_or_insert_folder(nodes: &mut Vec<FolderNode>, indexes: &mut HashMap<PathBuf, usize>, roots: &[PathBuf], path: &Path) -> usize {
+    if let Some(idx) = indexes.get(path) {
+        return *idx;
+    }
+
+    let parent = if roots.iter().any(|root| root == path) {
+        None
+    } else {
+        path.parent()
+            .filter(|parent| roots.iter().any(|root| parent.starts_with(root)))
+            .map(|parent| get_or_insert_folder(nodes, indexes, roots, parent))
+    };
+
+    let idx = nodes.len();
+    nodes.push(FolderNode {
+        path: path.to_path_buf(),
+        parent,
+        ..Default::default()
+    });
+    indexes.insert(path.to_path_buf(), idx);
+    if let Some(parent) = parent {
+        nodes[parent].subfolders.push(idx);
+    }
+    idx
+}
+
+// When parents of all folders in group are distinct and identical, then they are already reported in another group
+#[expect(clippy::indexing_slicing)] // Safe, indexes are always taken from nodes
+fn is_covered_by_parents(nodes: &[FolderNode], group: &[usize]) -> bool {
+    let mut parents = HashSet::new();
+    let mut parent_hash = None;
+    for idx in group {
+        let Some(parent) = nodes[*idx].parent else {
+            return false;
+        };
+        if !parents.insert(parent) {
+            return false;
+        }
+        match parent_hash {
+            None => parent_hash = Some(nodes[parent].hash.as_str()),
+            Some(hash) if hash == nodes[parent].hash => {}
+            Some(_) => return false,
+        }
+    }
+    true
+}
+
+#[expect(clippy::indexing_slicing)] // Safe, indexes are always taken from nodes
+fn is_strict_subset(nodes: &[FolderNode], supersets: &[Vec<usize>], smaller_idx: usize, bigger_idx: usize) -> bool {
+    let (smaller, bigger) = (&nodes[smaller_idx], &nodes[bigger_idx]);
+    // Nested folders are not interesting, and removing one of them would remove also part of other
+    if smaller.files_number >= bigger.files_number || bigger.path.starts_with(&smaller.path) || smaller.path.starts_with(&bigger.path) {
+        return false;
+    }
+
+    let files_present = smaller.files.iter().all(|(name, hash)| {
+        bigger
+            .files
+            .binary_search_by(|(bigger_name, _)| bigger_name.as_os_str().cmp(name))
+            .is_ok_and(|pos| bigger.files[pos].1 == *hash)
+    });
+
+    files_present
+        && smaller.subfolders.iter().all(|sub_idx| {
+            let sub = &nodes[*sub_idx];
+            bigger
+                .subfolders
+                .iter()
+                .find(|bigger_sub_idx| nodes[**bigger_sub_idx].path.file_name() == sub.path.file_name())
+                .is_some_and(|bigger_sub_idx| nodes[*bigger_sub_idx].hash == sub.hash || supersets[*sub_idx].contains(bigger_sub_idx))
+        })
+}
@@ -0,0 +1,115 @@
+pub mod core;
+#[cfg(test)]
+mod tests;
+pub mod traits;
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::keep_rules::KeepRuleEntry;
+use crate::common::model::HashType;
+use crate::common::tool_data::CommonToolData;
+use crate::common::traits::ResultEntry;
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct DuplicateFolderEntry {
+    pub path: PathBuf,
+    pub modified_date: u64,
+    // Summed size of all files inside folder and its subfolders
+    pub size: u64,
+    pub files_number: usize,
+    pub hash: String,
+}
+
+impl KeepRuleEntry for DuplicateFolderEntry {}
+
+impl ResultEntry for DuplicateFolderEntry {
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+    fn get_modified_date(&self) -> u64 {
+        self.modified_date
+    }
+    fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct Info {
+    pub number_of_groups: usize,
+    pub number_of_duplicated_folders: usize,
+    pub number_of_subset_folders: usize,
+    pub lost_space: u64,
+    pub scanning_time: Duration,
+}
+
+#[derive(Clone)]
+pub struct DuplicateFoldersParameters {
+    pub hash_type: HashType,
+    pub find_subsets: bool,
+    pub minimal_cache_file_size: u64,
+}
+
+impl DuplicateFoldersParameters {
+    pub fn new(hash_type: HashType, find_subsets: bool, minimal_cache_file_size: u64) -> Self {
+        Self {
+            hash_type,
+            find_subsets,
+            minimal_cache_file_size,
+        }
+    }
+}
+
+// Single node of folder tree, built only from files found during scan
+#[derive(Debug, Default)]
+pub(crate) struct FolderNode {
+    pub(crate) path: PathBuf,
+    pub(crate) parent: Option<usize>,
+    // File name, file hash - sorted by name after building tree
+    pub(crate) files: Vec<(OsString, String)>,
+    pub(crate) subfolders: Vec<usize>,
+    pub(crate) hash: String,
+    pub(crate) size: u64,
+    pub(crate) files_number: usize,
+}
+
+pub struct DuplicateFolders {
+    common_data: CommonToolData,
+    information: Info,
+    // Groups of folders with identical content
+    duplicated_folders: Vec<Vec<DuplicateFolderEntry>>,
+    duplicated_folders_referenced: Vec<(DuplicateFolderEntry, Vec<DuplicateFolderEntry>)>,
+    // Folder, which whole content can be found in other folders, with list of such folders
+    subset_folders: Vec<(DuplicateFolderEntry, Vec<DuplicateFolderEntry>)>,
+    params: DuplicateFoldersParameters,
+}
+
+impl DuplicateFolders {
+    pub fn get_params(&self) -> &DuplicateFoldersParameters {
+        &self.params
+    }
+
+    pub const fn get_information(&self) -> Info {
+        self.information
+    }
+
+    pub fn get_use_reference(&self) -> bool {
+        self.common_data.use_reference_folders
+    }
+
+    pub const fn get_duplicated_folders(&self) -> &Vec<Vec<DuplicateFolderEntry>> {
+        &self.duplicated_folders
+    }
+
+    pub const fn get_duplicated_folders_referenced(&self) -> &Vec<(DuplicateFolderEntry, Vec<DuplicateFolderEntry>)> {
+        &self.duplicated_folders_referenced
+    }
+
+    pub const fn get_subset_folders(&self) -> &Vec<(DuplicateFolderEntry, Vec<DuplicateFolderEntry>)> {
+        &self.subset_folders
+    }
+}
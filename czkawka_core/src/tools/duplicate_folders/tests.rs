@@ -0,0 +1,133 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use tempfile::TempDir;
+
+use crate::common::model::HashType;
+use crate::common::model::WorkContinueStatus;
+use crate::common::tool_data::{CommonData, DeleteMethod};
+use crate::common::traits::{DeletingItems, Search};
+use crate::tools::duplicate_folders::{DuplicateFolders, DuplicateFoldersParameters};
+
+fn create_tree(root: &Path, files: &[(&str, &[u8])]) {
+    for (name, content) in files {
+        let path = root.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+}
+
+fn get_finder(path: &Path, find_subsets: bool) -> DuplicateFolders {
+    let mut finder = DuplicateFolders::new(DuplicateFoldersParameters::new(HashType::Blake3, find_subsets, 0));
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_recursive_search(true);
+    finder.set_use_cache(false);
+    finder
+}
+
+#[test]
+fn test_find_identical_folders() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    let tree: &[(&str, &[u8])] = &[("a.txt", b"first"), ("sub/b.txt", b"second content")];
+    create_tree(&path.join("copy1"), tree);
+    create_tree(&path.join("copy2"), tree);
+    create_tree(&path.join("other"), &[("a.txt", b"first"), ("sub/b.txt", b"changed content")]);
+
+    let mut finder = get_finder(path, false);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    let info = finder.get_information();
+    // Only top level folders are reported, identical subfolders are already covered by them
+    assert_eq!(info.number_of_groups, 1);
+    assert_eq!(info.number_of_duplicated_folders, 1);
+    let group = &finder.get_duplicated_folders()[0];
+    assert_eq!(group.len(), 2);
+    assert_eq!(group[0].path, path.join("copy1"));
+    assert_eq!(group[1].path, path.join("copy2"));
+    assert_eq!(group[0].files_number, 2);
+    assert_eq!(group[0].size, 19);
+}
+
+#[test]
+fn test_different_file_names_are_not_identical() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    create_tree(&path.join("first"), &[("a.txt", b"content")]);
+    create_tree(&path.join("second"), &[("b.txt", b"content")]);
+
+    let mut finder = get_finder(path, false);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    assert_eq!(finder.get_information().number_of_groups, 0);
+}
+
+#[test]
+fn test_find_subset_folders() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    create_tree(&path.join("small"), &[("a.txt", b"first"), ("sub/b.txt", b"second")]);
+    create_tree(&path.join("big"), &[("a.txt", b"first"), ("sub/b.txt", b"second"), ("sub/c.txt", b"third")]);
+
+    let mut finder = get_finder(path, true);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    assert_eq!(finder.get_information().number_of_groups, 0);
+    assert_eq!(finder.get_information().number_of_subset_folders, 1);
+    let (subset, bigger_folders) = &finder.get_subset_folders()[0];
+    assert_eq!(subset.path, path.join("small"));
+    assert_eq!(bigger_folders.len(), 1);
+    assert_eq!(bigger_folders[0].path, path.join("big"));
+}
+
+#[test]
+fn test_delete_duplicated_folders_with_reference() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    let tree: &[(&str, &[u8])] = &[("a.txt", b"first"), ("sub/b.txt", b"second")];
+    create_tree(&path.join("reference"), tree);
+    create_tree(&path.join("copy"), tree);
+
+    let mut finder = get_finder(path, false);
+    finder.set_reference_paths(vec![path.join("reference")]);
+    finder.set_delete_method(DeleteMethod::Delete);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+
+    assert_eq!(finder.get_duplicated_folders_referenced().len(), 1);
+    assert!(path.join("reference").exists());
+    assert!(!path.join("copy").exists());
+}
+
+#[test]
+fn test_folders_not_matching_scan_are_not_deleted() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    let tree: &[(&str, &[u8])] = &[("a.txt", b"first"), ("sub/b.txt", b"second")];
+    create_tree(&path.join("reference"), tree);
+    create_tree(&path.join("with_skipped_file"), tree);
+    create_tree(&path.join("with_skipped_file"), &[("sub/notes.log", b"not checked")]);
+    create_tree(&path.join("changed_after_scan"), tree);
+
+    let mut finder = get_finder(path, false);
+    finder.set_reference_paths(vec![path.join("reference")]);
+    finder.set_excluded_extensions(vec!["log".to_string()]);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    finder.search(&stop_flag, None);
+    assert_eq!(finder.get_duplicated_folders_referenced()[0].1.len(), 2);
+
+    fs::write(path.join("changed_after_scan/a.txt"), b"other").unwrap();
+    finder.set_delete_method(DeleteMethod::Delete);
+    assert_eq!(finder.delete_files(&stop_flag, None), WorkContinueStatus::Continue);
+
+    // Folder hash from scan doesn't cover skipped file, so folder is read again and compared before removing
+    assert!(path.join("with_skipped_file/sub/notes.log").exists());
+    assert!(path.join("changed_after_scan/a.txt").exists());
+    assert_eq!(finder.get_text_messages().errors.len(), 2, "{:?}", finder.get_text_messages().errors);
+}
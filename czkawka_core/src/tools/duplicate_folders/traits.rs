@@ -0,0 +1,269 @@
+use std::io::prelude::*;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Instant;
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use humansize::{BINARY, format_size};
+use serde::Serialize;
+
+use crate::common::flat_results::ResultsFormat;
+use crate::common::model::WorkContinueStatus;
+use crate::common::progress_data::ProgressData;
+use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
+use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
+use crate::flc;
+use crate::tools::duplicate_folders::{DuplicateFolderEntry, DuplicateFolders, DuplicateFoldersParameters, Info};
+
+impl AllTraits for DuplicateFolders {}
+
+impl DeletingItems for DuplicateFolders {
+    #[fun_time(message = "delete_files", level = "debug")]
+    fn delete_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        match self.common_data.delete_method {
+            DeleteMethod::None => return WorkContinueStatus::Continue,
+            DeleteMethod::HardLink | DeleteMethod::Reflink => {
+                self.common_data.text_messages.errors.push(flc!("core_folders_cannot_be_linked"));
+                return WorkContinueStatus::Continue;
+            }
+            _ => {}
+        }
+
+        // Folder is removed only when it and all folders with which it was compared are still identical to scanned ones
+        let Some(verified_folders) = self.verify_folders_before_removing(stop_flag, progress_sender) else {
+            return WorkContinueStatus::Stop;
+        };
+        let is_verified = |folder: &DuplicateFolderEntry| verified_folders.contains(&folder.path);
+
+        let status = if self.common_data.use_reference_folders {
+            // Reference folder is kept, so every other folder from group can be removed
+            let folders_to_delete = self
+                .duplicated_folders_referenced
+                .iter()
+                .filter(|(reference, _)| is_verified(reference))
+                .flat_map(|(_, folders)| folders.iter().filter(|folder| is_verified(folder)).cloned())
+                .collect();
+            self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFolderTrees(folders_to_delete))
+        } else {
+            let groups = self
+                .duplicated_folders
+                .iter()
+                .map(|group| group.iter().filter(|folder| is_verified(folder)).cloned().collect::<Vec<_>>())
+                .filter(|group| group.len() > 1)
+                .collect();
+            self.delete_advanced_elements_and_add_to_messages(stop_flag, progress_sender, groups)
+        };
+        if status == WorkContinueStatus::Stop || self.subset_folders.is_empty() {
+            return status;
+        }
+
+        // Subset folder may be already removed together with one of identical folders
+        let dry_run = self.common_data.dry_run;
+        let subsets_to_delete = self
+            .subset_folders
+            .iter()
+            .filter(|(folder, bigger_folders)| is_verified(folder) && bigger_folders.iter().any(is_verified))
+            .filter(|(folder, _)| dry_run || folder.path.exists())
+            .map(|(folder, _)| folder.clone())
+            .collect();
+        self.delete_simple_elements_and_add_to_messages(stop_flag, progress_sender, DeleteItemType::DeletingFolderTrees(subsets_to_delete))
+    }
+}
+
+impl Search for DuplicateFolders {
+    #[fun_time(message = "find_duplicate_folders", level = "info")]
+    fn search(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) {
+        let start_time = Instant::now();
+
+        let () = (|| {
+            if self.prepare_items(None).is_err() {
+                return;
+            }
+            self.common_data.use_reference_folders = !self.common_data.directories.reference_directories.is_empty();
+
+            let Some(grouped_file_entries) = self.collect_files(stop_flag, progress_sender) else {
+                self.common_data.stopped_search = true;
+                return;
+            };
+            let Some(hashed_files) = self.hash_files(stop_flag, progress_sender, grouped_file_entries) else {
+                self.common_data.stopped_search = true;
+                return;
+            };
+
+            let mut nodes = self.build_folder_tree(hashed_files);
+            self.calculate_folder_hashes(&mut nodes);
+            self.find_identical_folders(&nodes);
+
+            if self.params.find_subsets && self.find_subset_folders(stop_flag, progress_sender, &nodes) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+                return;
+            }
+
+            if self.delete_files(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+            }
+        })();
+
+        self.information.scanning_time = start_time.elapsed();
+
+        if !self.common_data.stopped_search {
+            self.debug_print();
+        }
+    }
+}
+
+impl DebugPrint for DuplicateFolders {
+    #[expect(clippy::print_stdout)]
+    fn debug_print(&self) {
+        if !cfg!(debug_assertions) || cfg!(test) {
+            return;
+        }
+        println!("---------------DEBUG PRINT---------------");
+        println!(
+            "Number of duplicated folders(in groups) - {} ({})",
+            self.information.number_of_duplicated_folders, self.information.number_of_groups
+        );
+        println!("Number of subset folders - {}", self.information.number_of_subset_folders);
+        println!("Lost space - {} ({} bytes)", format_size(self.information.lost_space, BINARY), self.information.lost_space);
+        self.debug_print_common();
+        println!("-----------------------------------------");
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct DuplicateFoldersResults<'a, T> {
+    duplicated_folders: &'a T,
+    subset_folders: &'a Vec<(DuplicateFolderEntry, Vec<DuplicateFolderEntry>)>,
+}
+
+impl PrintResults for DuplicateFolders {
+    fn write_results<T: Write>(&self, writer: &mut T) -> std::io::Result<()> {
+        self.write_base_search_paths(writer)?;
+
+        if !self.duplicated_folders.is_empty() {
+            writeln!(
+                writer,
+                "-------------------------------------------------Folders with same content-------------------------------------------------"
+            )?;
+            writeln!(
+                writer,
+                "Found {} duplicated folders in {} groups which takes {}.",
+                self.information.number_of_duplicated_folders,
+                self.information.number_of_groups,
+                format_size(self.information.lost_space, BINARY)
+            )?;
+            for group in &self.duplicated_folders {
+                if let Some(first) = group.first() {
+                    writeln!(
+                        writer,
+                        "\n---- Size {} ({}) - {} files - {} folders",
+                        format_size(first.size, BINARY),
+                        first.size,
+                        first.files_number,
+                        group.len()
+                    )?;
+                }
+                for entry in group {
+                    writeln!(writer, "\"{}\"", entry.path.to_string_lossy())?;
+                }
+            }
+        } else if !self.duplicated_folders_referenced.is_empty() {
+            writeln!(
+                writer,
+                "-------------------------------------------------Folders with same content in referenced folders-------------------------------------------------"
+            )?;
+            writeln!(
+                writer,
+                "Found {} duplicated folders in {} groups which takes {}.",
+                self.information.number_of_duplicated_folders,
+                self.information.number_of_groups,
+                format_size(self.information.lost_space, BINARY)
+            )?;
+            for (reference, group) in &self.duplicated_folders_referenced {
+                writeln!(
+                    writer,
+                    "\n---- Size {} ({}) - {} files - {} folders",
+                    format_size(reference.size, BINARY),
+                    reference.size,
+                    reference.files_number,
+                    group.len()
+                )?;
+                writeln!(writer, "Reference folder - \"{}\"", reference.path.to_string_lossy())?;
+                for entry in group {
+                    writeln!(writer, "\"{}\"", entry.path.to_string_lossy())?;
+                }
+            }
+        } else {
+            writeln!(writer, "Not found any duplicated folders.")?;
+        }
+
+        if self.params.find_subsets {
+            if self.subset_folders.is_empty() {
+                write!(writer, "Not found any folders which are subsets of other folders.")?;
+            } else {
+                writeln!(
+                    writer,
+                    "\n-------------------------------------------------Folders contained in other folders-------------------------------------------------"
+                )?;
+                writeln!(
+                    writer,
+                    "Found {} folders which whole content exists also in other folders.",
+                    self.information.number_of_subset_folders
+                )?;
+                for (subset, bigger_folders) in &self.subset_folders {
+                    writeln!(
+                        writer,
+                        "\n---- \"{}\" - {} ({}) - {} files, is contained in:",
+                        subset.path.to_string_lossy(),
+                        format_size(subset.size, BINARY),
+                        subset.size,
+                        subset.files_number
+                    )?;
+                    for entry in bigger_folders {
+                        writeln!(writer, "\"{}\"", entry.path.to_string_lossy())?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn save_results_to_file(&self, file_name: &str, format: ResultsFormat) -> std::io::Result<()> {
+        if self.get_use_reference() {
+            let results = DuplicateFoldersResults {
+                duplicated_folders: &self.duplicated_folders_referenced,
+                subset_folders: &self.subset_folders,
+            };
+            self.save_results_to_file_internal(file_name, &results, format)
+        } else {
+            let results = DuplicateFoldersResults {
+                duplicated_folders: &self.duplicated_folders,
+                subset_folders: &self.subset_folders,
+            };
+            self.save_results_to_file_internal(file_name, &results, format)
+        }
+    }
+}
+
+impl CommonData for DuplicateFolders {
+    type Info = Info;
+    type Parameters = DuplicateFoldersParameters;
+
+    fn get_information(&self) -> Self::Info {
+        self.information
+    }
+    fn get_params(&self) -> Self::Parameters {
+        self.params.clone()
+    }
+    fn get_cd(&self) -> &CommonToolData {
+        &self.common_data
+    }
+    fn get_cd_mut(&mut self) -> &mut CommonToolData {
+        &mut self.common_data
+    }
+    fn found_any_items(&self) -> bool {
+        self.information.number_of_duplicated_folders > 0 || self.information.number_of_subset_folders > 0
+    }
+}
@@ -1,3 +1,4 @@
+use std::fs;
 use std::fs::DirEntry;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -68,11 +69,15 @@ impl EmptyFolder {
         let mut start_folder_entries = Vec::with_capacity(folders_to_check.len());
         let mut new_folder_entries_list = Vec::new();
         for dir in &folders_to_check {
+            let modified_date = match fs::metadata(dir) {
+                Ok(metadata) => get_modified_time(&metadata, &mut self.common_data.text_messages.warnings, dir, true),
+                Err(_) => 0,
+            };
             start_folder_entries.push(FolderEntry {
                 path: dir.clone(),
                 parent_path: None,
                 is_empty: FolderEmptiness::Maybe,
-                modified_date: 0,
+                modified_date,
             });
         }
 
@@ -159,6 +164,17 @@ impl EmptyFolder {
             Self::set_as_not_empty_folder(&mut folder_entries, &current_folder);
         }
 
+        // Folders modified outside of allowed dates are not reported, so folders containing them cannot be reported too
+        let allowed_dates = self.common_data.minimal_modified_date..=self.common_data.maximal_modified_date;
+        let folders_outside_allowed_dates: Vec<String> = folder_entries
+            .iter()
+            .filter(|(_name, folder_entry)| !allowed_dates.contains(&folder_entry.modified_date))
+            .map(|(name, _folder_entry)| name.clone())
+            .collect();
+        for current_folder in folders_outside_allowed_dates {
+            Self::set_as_not_empty_folder(&mut folder_entries, &current_folder);
+        }
+
         for (name, folder_entry) in folder_entries {
             if folder_entry.is_empty != FolderEmptiness::No {
                 self.empty_folder_list.insert(name, folder_entry);
@@ -1,6 +1,7 @@
-use std::fs;
+use std::fs::{self, File};
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
+use std::time::{Duration, UNIX_EPOCH};
 
 use tempfile::TempDir;
 
@@ -102,3 +103,30 @@ fn test_folder_with_only_empty_subfolders() {
         "Should find 1 empty folder (the parent) - which contains only empty subfolders"
     );
 }
+
+#[test]
+fn test_empty_folders_filtered_by_modification_date() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    let old = path.join("old");
+    let new = path.join("new");
+    // Recently modified folder, which contains old empty folder
+    let parent = path.join("parent");
+    let old_child = parent.join("old_child");
+    for folder in [&old, &new, &old_child] {
+        fs::create_dir_all(folder).unwrap();
+    }
+    for folder in [&old, &old_child] {
+        File::open(folder).unwrap().set_modified(UNIX_EPOCH + Duration::from_secs(946_684_800)).unwrap();
+    }
+
+    // Only folders modified after 2010
+    let mut finder = EmptyFolder::new();
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_recursive_search(true);
+    finder.set_minimal_modified_date(1_262_304_000);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+    let folders: Vec<_> = finder.get_empty_folder_list().values().map(|entry| entry.path.clone()).collect();
+    assert_eq!(folders, vec![new]);
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use log::debug;
+use rayon::prelude::*;
+
+use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult, get_modified_time};
+use crate::common::directories::Directories;
+use crate::common::hash_manifest::load_hash_manifest;
+use crate::common::model::{FileEntry, ToolType, WorkContinueStatus};
+use crate::common::progress_data::{ProgressData, ToolStage};
+use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
+use crate::common::tool_data::CommonToolData;
+use crate::flc;
+use crate::tools::duplicate::{DuplicateEntry, THREAD_BUFFER, hash_calculation};
+use crate::tools::integrity_checker::{Info, IntegrityChecker, IntegrityCheckerParameters, IntegrityEntry};
+
+impl IntegrityChecker {
+    pub fn new(params: IntegrityCheckerParameters) -> Self {
+        Self {
+            common_data: CommonToolData::new(ToolType::IntegrityChecker),
+            information: Info::default(),
+            corrupted_files: Vec::new(),
+            modified_files: Vec::new(),
+            missing_files: Vec::new(),
+            new_files: Vec::new(),
+            params,
+        }
+    }
+
+    // Returns manifest entries and modification date of manifest
+    #[fun_time(message = "load_manifest", level = "debug")]
+    pub(crate) fn load_manifest(&mut self) -> Option<(Vec<(PathBuf, String)>, u64)> {
+        // Scanned folders are canonicalized, so manifest path and relative paths inside it must be too, to be able to match them
+        if let (Some(manifest_path), _messages) = Directories::canonicalize_and_clear_path(&self.params.manifest_path, true) {
+            self.params.manifest_path = manifest_path;
+        }
+        let manifest_path = &self.params.manifest_path;
+        let (entries, warnings) = match load_hash_manifest(manifest_path) {
+            Ok(t) => t,
+            Err(e) => {
+                self.common_data.text_messages.critical = Some(e);
+                return None;
+            }
+        };
+        self.common_data.text_messages.warnings.extend(warnings);
+
+        let manifest_date = match fs::metadata(manifest_path) {
+            Ok(metadata) => get_modified_time(&metadata, &mut self.common_data.text_messages.warnings, manifest_path, false),
+            Err(_) => 0,
+        };
+        debug!("load_manifest - loaded {} entries", entries.len());
+        Some((entries, manifest_date))
+    }
+
+    #[fun_time(message = "collect_files", level = "debug")]
+    pub(crate) fn collect_files(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> Option<Vec<FileEntry>> {
+        let result = DirTraversalBuilder::new()
+            .common_data(&self.common_data)
+            .group_by(|_fe| ())
+            .stop_flag(stop_flag)
+            .progress_sender(progress_sender)
+            .build()
+            .run();
+
+        match result {
+            DirTraversalResult::SuccessFiles { grouped_file_entries, warnings } => {
+                self.common_data.text_messages.warnings.extend(warnings);
+                Some(grouped_file_entries.into_values().flatten().collect())
+            }
+            DirTraversalResult::Stopped => None,
+        }
+    }
+
+    // Pairs manifest entries with found files - entries without file become missing files, files without entry become new files.
+    // Only entries inside scanned paths are checked, so it is possible to verify only part of tree described by manifest.
+    #[fun_time(message = "match_manifest_with_files", level = "debug")]
+    pub(crate) fn match_manifest_with_files(&mut self, manifest_entries: Vec<(PathBuf, String)>, found_files: Vec<FileEntry>) -> Vec<(DuplicateEntry, String)> {
+        let expected_hash_length = self.params.hash_type.hasher().finalize().len();
+        let roots = &self.common_data.directories.included_directories;
+
+        let mut found_files: HashMap<PathBuf, FileEntry> = found_files.into_iter().map(|fe| (fe.path.clone(), fe)).collect();
+        let mut files_to_check = Vec::new();
+        for (path, expected_hash) in manifest_entries {
+            if !roots.iter().any(|root| path.starts_with(root)) {
+                continue;
+            }
+            if expected_hash.len() != expected_hash_length {
+                self.common_data
+                    .text_messages
+                    .warnings
+                    .push(flc!("core_hash_manifest_wrong_hash_type", file = path.to_string_lossy()));
+                continue;
+            }
+
+            if let Some(file_entry) = found_files.remove(&path) {
+                files_to_check.push((file_entry.into_duplicate_entry(), expected_hash));
+            } else if fs::symlink_metadata(&path).is_err() {
+                self.missing_files.push(IntegrityEntry {
+                    path,
+                    expected_hash,
+                    ..Default::default()
+                });
+            }
+            // Otherwise file exists, but was skipped by used filters(e.g. excluded items or not recursive search)
+        }
+
+        // Manifest is often saved inside checked folder
+        found_files.remove(&self.params.manifest_path);
+        self.new_files = found_files.into_values().collect();
+        self.new_files.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+        self.missing_files.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        files_to_check
+    }
+
+    // Files with different hash are corrupted, when they were not modified after manifest was created.
+    // Standard manifests do not contain size or modification date of files, so modification date of manifest itself is used as reference.
+    #[fun_time(message = "check_files_integrity", level = "debug")]
+    pub(crate) fn check_files_integrity(
+        &mut self,
+        stop_flag: &Arc<AtomicBool>,
+        progress_sender: Option<&Sender<ProgressData>>,
+        files_to_check: Vec<(DuplicateEntry, String)>,
+        manifest_date: u64,
+    ) -> WorkContinueStatus {
+        let progress_handler = prepare_thread_handler_common(
+            progress_sender,
+            ToolStage::IntegrityChecking,
+            files_to_check.len(),
+            files_to_check.iter().map(|(fe, _)| fe.size).sum(),
+        );
+
+        let hash_type = self.params.hash_type;
+        let results: Vec<Result<(DuplicateEntry, String), String>> = files_to_check
+            .into_par_iter()
+            .map(|(mut file_entry, expected_hash)| {
+                if check_if_stop_received(stop_flag) {
+                    return None;
+                }
+                let result = THREAD_BUFFER.with_borrow_mut(|buffer| hash_calculation(buffer, &file_entry, hash_type, progress_handler.size_counter(), stop_flag));
+                progress_handler.increase_items(1);
+                match result {
+                    Ok(hash) => {
+                        file_entry.hash = hash?;
+                        Some(Ok((file_entry, expected_hash)))
+                    }
+                    Err(e) => Some(Err(e)),
+                }
+            })
+            .while_some()
+            .collect();
+        progress_handler.join_thread();
+
+        if check_if_stop_received(stop_flag) {
+            return WorkContinueStatus::Stop;
+        }
+
+        self.information.number_of_checked_files = results.len();
+        for result in results {
+            let (file_entry, expected_hash) = match result {
+                Ok(t) => t,
+                Err(e) => {
+                    self.common_data.text_messages.errors.push(e);
+                    continue;
+                }
+            };
+            if file_entry.hash == expected_hash {
+                continue;
+            }
+
+            let integrity_entry = IntegrityEntry {
+                path: file_entry.path,
+                size: file_entry.size,
+                modified_date: file_entry.modified_date,
+                expected_hash,
+                current_hash: file_entry.hash,
+            };
+            if integrity_entry.modified_date <= manifest_date {
+                self.corrupted_files.push(integrity_entry);
+            } else {
+                self.modified_files.push(integrity_entry);
+            }
+        }
+        self.corrupted_files.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+        self.modified_files.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        self.information.number_of_corrupted_files = self.corrupted_files.len();
+        self.information.number_of_modified_files = self.modified_files.len();
+        self.information.number_of_missing_files = self.missing_files.len();
+        self.information.number_of_new_files = self.new_files.len();
+
+        WorkContinueStatus::Continue
+    }
+}
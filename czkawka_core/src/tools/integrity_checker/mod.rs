@@ -0,0 +1,98 @@
+pub mod core;
+#[cfg(test)]
+mod tests;
+pub mod traits;
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::model::{FileEntry, HashType};
+use crate::common::tool_data::CommonToolData;
+use crate::common::traits::ResultEntry;
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct IntegrityEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified_date: u64,
+    // Hash saved in manifest
+    pub expected_hash: String,
+    // Hash of current content, empty for missing files
+    pub current_hash: String,
+}
+
+impl ResultEntry for IntegrityEntry {
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+    fn get_modified_date(&self) -> u64 {
+        self.modified_date
+    }
+    fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct Info {
+    pub number_of_checked_files: usize,
+    pub number_of_corrupted_files: usize,
+    pub number_of_modified_files: usize,
+    pub number_of_missing_files: usize,
+    pub number_of_new_files: usize,
+    pub scanning_time: Duration,
+}
+
+#[derive(Clone)]
+pub struct IntegrityCheckerParameters {
+    // Manifest in `sha256sum`/`b3sum` format, e.g. saved by duplicate finder
+    pub manifest_path: PathBuf,
+    pub hash_type: HashType,
+}
+
+impl IntegrityCheckerParameters {
+    pub fn new(manifest_path: PathBuf, hash_type: HashType) -> Self {
+        Self { manifest_path, hash_type }
+    }
+}
+
+pub struct IntegrityChecker {
+    common_data: CommonToolData,
+    information: Info,
+    // Content changed, but file was not modified after manifest was created, so this is most likely silent corruption
+    corrupted_files: Vec<IntegrityEntry>,
+    // Content changed and file was modified after manifest was created
+    modified_files: Vec<IntegrityEntry>,
+    missing_files: Vec<IntegrityEntry>,
+    // Files found in scanned folders, but absent in manifest
+    new_files: Vec<FileEntry>,
+    params: IntegrityCheckerParameters,
+}
+
+impl IntegrityChecker {
+    pub fn get_params(&self) -> &IntegrityCheckerParameters {
+        &self.params
+    }
+
+    pub const fn get_information(&self) -> Info {
+        self.information
+    }
+
+    pub const fn get_corrupted_files(&self) -> &Vec<IntegrityEntry> {
+        &self.corrupted_files
+    }
+
+    pub const fn get_modified_files(&self) -> &Vec<IntegrityEntry> {
+        &self.modified_files
+    }
+
+    pub const fn get_missing_files(&self) -> &Vec<IntegrityEntry> {
+        &self.missing_files
+    }
+
+    pub const fn get_new_files(&self) -> &Vec<FileEntry> {
+        &self.new_files
+    }
+}
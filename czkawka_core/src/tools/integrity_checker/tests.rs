@@ -0,0 +1,145 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, SystemTime};
+
+use tempfile::TempDir;
+
+use crate::common::hash_manifest::save_hash_manifest;
+use crate::common::model::HashType;
+use crate::common::tool_data::CommonData;
+use crate::common::traits::Search;
+use crate::tools::integrity_checker::{IntegrityChecker, IntegrityCheckerParameters};
+
+fn calculate_hash(hash_type: HashType, content: &[u8]) -> String {
+    let mut hasher = hash_type.hasher();
+    hasher.update(content);
+    hasher.finalize()
+}
+
+fn set_modified_date(path: &Path, date: SystemTime) {
+    fs::File::options().write(true).open(path).unwrap().set_modified(date).unwrap();
+}
+
+fn run_check(temp_dir: &TempDir, manifest_path: PathBuf, hash_type: HashType) -> IntegrityChecker {
+    let mut checker = IntegrityChecker::new(IntegrityCheckerParameters::new(manifest_path, hash_type));
+    checker.set_included_paths(vec![temp_dir.path().to_path_buf()]);
+    checker.set_recursive_search(true);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    checker.search(&stop_flag, None);
+    checker
+}
+
+#[test]
+fn test_integrity_check_finds_all_kinds_of_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let data_dir = temp_dir.path().join("data");
+    fs::create_dir(&data_dir).unwrap();
+    let manifest_path = temp_dir.path().join("checksums.b3");
+
+    let valid = data_dir.join("valid.txt");
+    let corrupted = data_dir.join("corrupted.txt");
+    let modified = data_dir.join("modified.txt");
+    let missing = data_dir.join("missing.txt");
+    let new = data_dir.join("new.txt");
+    for path in [&valid, &corrupted, &modified, &new] {
+        fs::write(path, b"original content").unwrap();
+    }
+
+    let hash = calculate_hash(HashType::Blake3, b"original content");
+    let entries = [&valid, &corrupted, &modified, &missing];
+    save_hash_manifest(&manifest_path.to_string_lossy(), entries.iter().map(|path| (path.as_path(), hash.as_str()))).unwrap();
+    set_modified_date(&manifest_path, SystemTime::now());
+
+    // Same size and date, but different content
+    fs::write(&corrupted, b"original_content").unwrap();
+    set_modified_date(&corrupted, SystemTime::now() - Duration::from_secs(3600));
+    fs::write(&modified, b"changed content").unwrap();
+    set_modified_date(&modified, SystemTime::now() + Duration::from_secs(3600));
+
+    let checker = run_check(&temp_dir, manifest_path, HashType::Blake3);
+    let info = checker.get_information();
+    assert_eq!(info.number_of_checked_files, 3);
+
+    assert_eq!(checker.get_corrupted_files().len(), 1);
+    assert_eq!(checker.get_corrupted_files()[0].path, corrupted);
+    assert_eq!(checker.get_corrupted_files()[0].expected_hash, hash);
+    assert_eq!(checker.get_corrupted_files()[0].current_hash, calculate_hash(HashType::Blake3, b"original_content"));
+
+    assert_eq!(checker.get_modified_files().len(), 1);
+    assert_eq!(checker.get_modified_files()[0].path, modified);
+
+    assert_eq!(checker.get_missing_files().len(), 1);
+    assert_eq!(checker.get_missing_files()[0].path, missing);
+
+    // Manifest itself is not reported as new file
+    assert_eq!(checker.get_new_files().len(), 1);
+    assert_eq!(checker.get_new_files()[0].path, new);
+    assert!(checker.found_any_items());
+}
+
+#[test]
+fn test_integrity_check_with_sha256sum_manifest() {
+    let temp_dir = TempDir::new().unwrap();
+    let manifest_path = temp_dir.path().join("checksums.sha256");
+    fs::write(temp_dir.path().join("file.txt"), b"abc").unwrap();
+    // Relative path in binary mode, as produced by `sha256sum -b file.txt`
+    fs::write(&manifest_path, "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD *file.txt\n").unwrap();
+
+    let checker = run_check(&temp_dir, manifest_path, HashType::Sha256);
+    let info = checker.get_information();
+    assert_eq!(info.number_of_checked_files, 1);
+    assert_eq!(info.number_of_corrupted_files, 0);
+    assert_eq!(info.number_of_modified_files, 0);
+    assert_eq!(info.number_of_missing_files, 0);
+    assert_eq!(info.number_of_new_files, 0);
+    assert!(!checker.found_any_items());
+}
+
+#[test]
+fn test_integrity_check_ignores_entries_outside_scanned_folders() {
+    let temp_dir = TempDir::new().unwrap();
+    let manifest_path = temp_dir.path().join("checksums.b3");
+    let hash = calculate_hash(HashType::Blake3, b"abc");
+    let outside_file = PathBuf::from("/not/existing/outside/file.txt");
+    let inside_file = temp_dir.path().join("file.txt");
+    fs::write(&inside_file, b"abc").unwrap();
+    save_hash_manifest(
+        &manifest_path.to_string_lossy(),
+        [(outside_file.as_path(), hash.as_str()), (inside_file.as_path(), hash.as_str())],
+    )
+    .unwrap();
+
+    let checker = run_check(&temp_dir, manifest_path, HashType::Blake3);
+    assert_eq!(checker.get_information().number_of_checked_files, 1);
+    assert!(checker.get_missing_files().is_empty());
+}
+
+#[test]
+fn test_integrity_check_with_wrong_hash_type() {
+    let temp_dir = TempDir::new().unwrap();
+    let manifest_path = temp_dir.path().join("checksums.b3");
+    let file = temp_dir.path().join("file.txt");
+    fs::write(&file, b"abc").unwrap();
+    let hash = calculate_hash(HashType::Blake3, b"abc");
+    save_hash_manifest(&manifest_path.to_string_lossy(), [(file.as_path(), hash.as_str())]).unwrap();
+
+    // Different length of hash
+    let checker = run_check(&temp_dir, manifest_path.clone(), HashType::Xxh3);
+    assert_eq!(checker.get_information().number_of_checked_files, 0);
+    assert_eq!(checker.get_text_messages().warnings.len(), 1);
+
+    // Same length of hash, so all files look as corrupted
+    let checker = run_check(&temp_dir, manifest_path, HashType::Sha256);
+    assert_eq!(checker.get_information().number_of_checked_files, 1);
+    assert_eq!(checker.get_corrupted_files().len(), 1);
+}
+
+#[test]
+fn test_integrity_check_without_manifest() {
+    let temp_dir = TempDir::new().unwrap();
+    let checker = run_check(&temp_dir, temp_dir.path().join("not_existing.b3"), HashType::Blake3);
+    assert!(checker.get_text_messages().critical.is_some());
+    assert!(!checker.found_any_items());
+}
@@ -0,0 +1,191 @@
+use std::io::prelude::*;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Instant;
+
+use crossbeam_channel::Sender;
+use fun_time::fun_time;
+use humansize::{BINARY, format_size};
+use serde::Serialize;
+
+use crate::common::flat_results::ResultsFormat;
+use crate::common::model::{FileEntry, WorkContinueStatus};
+use crate::common::progress_data::ProgressData;
+use crate::common::tool_data::{CommonData, CommonToolData};
+use crate::common::traits::{AllTraits, DebugPrint, DeletingItems, PrintResults, Search};
+use crate::tools::integrity_checker::{Info, IntegrityChecker, IntegrityCheckerParameters, IntegrityEntry};
+
+impl AllTraits for IntegrityChecker {}
+
+impl DeletingItems for IntegrityChecker {
+    fn delete_files(&mut self, _stop_flag: &Arc<AtomicBool>, _progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+        unreachable!("IntegrityChecker does not support deleting files");
+    }
+}
+
+impl Search for IntegrityChecker {
+    #[fun_time(message = "find_integrity_problems", level = "info")]
+    fn search(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) {
+        let start_time = Instant::now();
+
+        let () = (|| {
+            if self.prepare_items(None).is_err() {
+                return;
+            }
+            let Some((manifest_entries, manifest_date)) = self.load_manifest() else {
+                return;
+            };
+            let Some(found_files) = self.collect_files(stop_flag, progress_sender) else {
+                self.common_data.stopped_search = true;
+                return;
+            };
+
+            let files_to_check = self.match_manifest_with_files(manifest_entries, found_files);
+            if self.check_files_integrity(stop_flag, progress_sender, files_to_check, manifest_date) == WorkContinueStatus::Stop {
+                self.common_data.stopped_search = true;
+            }
+        })();
+
+        self.information.scanning_time = start_time.elapsed();
+
+        if !self.common_data.stopped_search {
+            self.debug_print();
+        }
+    }
+}
+
+impl DebugPrint for IntegrityChecker {
+    #[expect(clippy::print_stdout)]
+    fn debug_print(&self) {
+        if !cfg!(debug_assertions) || cfg!(test) {
+            return;
+        }
+        println!("---------------DEBUG PRINT---------------");
+        println!("Number of checked files - {}", self.information.number_of_checked_files);
+        println!("Number of corrupted files - {}", self.information.number_of_corrupted_files);
+        println!("Number of modified files - {}", self.information.number_of_modified_files);
+        println!("Number of missing files - {}", self.information.number_of_missing_files);
+        println!("Number of new files - {}", self.information.number_of_new_files);
+        self.debug_print_common();
+        println!("-----------------------------------------");
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct IntegrityCheckerResults<'a> {
+    corrupted_files: &'a Vec<IntegrityEntry>,
+    modified_files: &'a Vec<IntegrityEntry>,
+    missing_files: &'a Vec<IntegrityEntry>,
+    new_files: &'a Vec<FileEntry>,
+}
+
+impl PrintResults for IntegrityChecker {
+    fn write_results<T: Write>(&self, writer: &mut T) -> std::io::Result<()> {
+        self.write_base_search_paths(writer)?;
+        writeln!(
+            writer,
+            "Checked {} files from manifest \"{}\".",
+            self.information.number_of_checked_files,
+            self.params.manifest_path.to_string_lossy()
+        )?;
+
+        if !self.corrupted_files.is_empty() {
+            writeln!(
+                writer,
+                "\n-------------------------------------------------Corrupted files-------------------------------------------------"
+            )?;
+            writeln!(
+                writer,
+                "Found {} files with changed content, which were not modified after manifest was created.",
+                self.information.number_of_corrupted_files
+            )?;
+            for entry in &self.corrupted_files {
+                writeln!(
+                    writer,
+                    "\"{}\" - {} - expected hash {}, current hash {}",
+                    entry.path.to_string_lossy(),
+                    format_size(entry.size, BINARY),
+                    entry.expected_hash,
+                    entry.current_hash
+                )?;
+            }
+        }
+
+        if !self.modified_files.is_empty() {
+            writeln!(
+                writer,
+                "\n-------------------------------------------------Modified files-------------------------------------------------"
+            )?;
+            writeln!(
+                writer,
+                "Found {} files with changed content, which were modified after manifest was created.",
+                self.information.number_of_modified_files
+            )?;
+            for entry in &self.modified_files {
+                writeln!(writer, "\"{}\" - {}", entry.path.to_string_lossy(), format_size(entry.size, BINARY))?;
+            }
+        }
+
+        if !self.missing_files.is_empty() {
+            writeln!(
+                writer,
+                "\n-------------------------------------------------Missing files-------------------------------------------------"
+            )?;
+            writeln!(writer, "Found {} files from manifest, which no longer exist.", self.information.number_of_missing_files)?;
+            for entry in &self.missing_files {
+                writeln!(writer, "\"{}\"", entry.path.to_string_lossy())?;
+            }
+        }
+
+        if !self.new_files.is_empty() {
+            writeln!(
+                writer,
+                "\n-------------------------------------------------New files-------------------------------------------------"
+            )?;
+            writeln!(writer, "Found {} files, which are not present in manifest.", self.information.number_of_new_files)?;
+            for entry in &self.new_files {
+                writeln!(writer, "\"{}\" - {}", entry.path.to_string_lossy(), format_size(entry.size, BINARY))?;
+            }
+        }
+
+        if self.information.number_of_checked_files > 0 && !self.found_any_items() {
+            writeln!(writer, "All files match manifest.")?;
+        }
+
+        Ok(())
+    }
+
+    fn save_results_to_file(&self, file_name: &str, format: ResultsFormat) -> std::io::Result<()> {
+        let results = IntegrityCheckerResults {
+            corrupted_files: &self.corrupted_files,
+            modified_files: &self.modified_files,
+            missing_files: &self.missing_files,
+            new_files: &self.new_files,
+        };
+        self.save_results_to_file_internal(file_name, &results, format)
+    }
+}
+
+impl CommonData for IntegrityChecker {
+    type Info = Info;
+    type Parameters = IntegrityCheckerParameters;
+
+    fn get_information(&self) -> Self::Info {
+        self.information
+    }
+    fn get_params(&self) -> Self::Parameters {
+        self.params.clone()
+    }
+    fn get_cd(&self) -> &CommonToolData {
+        &self.common_data
+    }
+    fn get_cd_mut(&mut self) -> &mut CommonToolData {
+        &mut self.common_data
+    }
+    fn found_any_items(&self) -> bool {
+        self.information.number_of_corrupted_files > 0
+            || self.information.number_of_modified_files > 0
+            || self.information.number_of_missing_files > 0
+            || self.information.number_of_new_files > 0
+    }
+}
@@ -2,10 +2,13 @@ pub mod bad_extensions;
 pub mod bad_names;
 pub mod big_file;
 pub mod broken_files;
+pub mod directory_comparison;
 pub mod duplicate;
+pub mod duplicate_folders;
 pub mod empty_files;
 pub mod empty_folder;
 pub mod exif_remover;
+pub mod integrity_checker;
 pub mod invalid_symlinks;
 pub mod same_music;
 pub mod similar_images;
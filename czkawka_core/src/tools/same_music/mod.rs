@@ -12,6 +12,7 @@ use std::time::Duration;
 use rusty_chromaprint::Configuration;
 use serde::{Deserialize, Serialize};
 
+use crate::common::keep_rules::KeepRuleEntry;
 use crate::common::model::{CheckingMethod, FileEntry};
 use crate::common::tool_data::CommonToolData;
 use crate::common::traits::ResultEntry;
@@ -46,6 +47,12 @@ pub struct MusicEntry {
     pub bitrate: u32,
 }
 
+impl KeepRuleEntry for MusicEntry {
+    fn get_bitrate(&self) -> Option<u64> {
+        Some(u64::from(self.bitrate))
+    }
+}
+
 impl ResultEntry for MusicEntry {
     fn get_path(&self) -> &Path {
         &self.path
@@ -7,6 +7,7 @@ use crossbeam_channel::Sender;
 use fun_time::fun_time;
 
 use crate::common::consts::{AUDIO_FILES_CONTENT_EXTENSIONS, AUDIO_FILES_TAGS_EXTENSIONS};
+use crate::common::flat_results::ResultsFormat;
 use crate::common::model::{CheckingMethod, WorkContinueStatus};
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
@@ -126,11 +127,11 @@ impl PrintResults for SameMusic {
         Ok(())
     }
 
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
+    fn save_results_to_file(&self, file_name: &str, format: ResultsFormat) -> std::io::Result<()> {
         if self.get_use_reference() {
-            self.save_results_to_file_as_json_internal(file_name, &self.duplicated_music_entries_referenced, pretty_print)
+            self.save_results_to_file_internal(file_name, &self.duplicated_music_entries_referenced, format)
         } else {
-            self.save_results_to_file_as_json_internal(file_name, &self.duplicated_music_entries, pretty_print)
+            self.save_results_to_file_internal(file_name, &self.duplicated_music_entries, format)
         }
     }
 }
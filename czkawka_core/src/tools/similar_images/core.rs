@@ -14,8 +14,11 @@ use log::{debug, error};
 use rayon::prelude::*;
 
 use crate::common::cache::{CACHE_IMAGE_VERSION, load_and_split_cache_generalized_by_path, save_and_connect_cache_generalized_by_path};
+use crate::common::checkpoint::Checkpoint;
+use crate::common::device_io::DeviceIoScheduler;
 use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult, inode, take_1_per_inode};
 use crate::common::image::get_dynamic_image_from_path;
+use crate::common::io_throttle::throttle_read;
 use crate::common::model::{ToolType, WorkContinueStatus};
 use crate::common::progress_data::{ProgressData, SimilarImagesStage, ToolStage};
 use crate::common::progress_stop_handler::{check_if_stop_received, prepare_thread_handler_common};
@@ -93,6 +96,20 @@ impl SimilarImages {
         }
     }
 
+    pub(crate) fn get_checkpoint(&self) -> Checkpoint {
+        Checkpoint::new(&self.common_data, self.get_params())
+    }
+
+    // Images collected by interrupted scan are used instead of searching them again
+    pub(crate) fn load_images_from_checkpoint(&mut self, checkpoint: &Checkpoint) -> bool {
+        let Some(images_to_check) = checkpoint.resume(&mut self.common_data) else {
+            return false;
+        };
+        self.images_to_check = images_to_check;
+        self.information.initial_found_files = self.images_to_check.len();
+        true
+    }
+
     #[fun_time(message = "hash_images_load_cache", level = "debug")]
     fn hash_images_load_cache(&mut self) -> (BTreeMap<String, ImagesEntry>, BTreeMap<String, ImagesEntry>, BTreeMap<String, ImagesEntry>) {
         load_and_split_cache_generalized_by_path(
@@ -123,12 +140,13 @@ impl SimilarImages {
     }
 
     #[fun_time(message = "hash_images", level = "debug")]
-    pub(crate) fn hash_images(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+    pub(crate) fn hash_images(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>, checkpoint: &Checkpoint) -> WorkContinueStatus {
         if self.images_to_check.is_empty() {
             return WorkContinueStatus::Continue;
         }
 
-        let (loaded_hash_map, records_already_cached, non_cached_files_to_check) = self.hash_images_load_cache();
+        let (loaded_hash_map, mut records_already_cached, mut non_cached_files_to_check) = self.hash_images_load_cache();
+        checkpoint.move_partial_results_by_path(&mut records_already_cached, &mut non_cached_files_to_check);
 
         let progress_handler = prepare_thread_handler_common(
             progress_sender,
@@ -138,29 +156,48 @@ impl SimilarImages {
         );
 
         debug!("hash_images - start hashing images");
-        let (mut vec_file_entry, errors): (Vec<ImagesEntry>, Vec<String>) = non_cached_files_to_check
-            .into_par_iter()
-            .map(|(_s, file_entry)| {
-                if check_if_stop_received(stop_flag) {
-                    return None;
-                }
-                let size = file_entry.size;
-                let res = self.collect_image_file_entry(file_entry);
-                progress_handler.increase_items(1);
-                progress_handler.increase_size(size);
+        let device_scheduler = DeviceIoScheduler::new(self.common_data.device_io_limits);
+        let partial_results = checkpoint.partial_results();
+        let hash_image = |file_entry: ImagesEntry| {
+            if check_if_stop_received(stop_flag) {
+                return None;
+            }
+            let size = file_entry.size;
+            let res = self.collect_image_file_entry(file_entry);
+            if let Ok(entry) = &res {
+                partial_results.add(entry);
+            }
+            throttle_read(size);
+            progress_handler.increase_items(1);
+            progress_handler.increase_size(size);
 
-                Some(res)
-            })
-            .while_some()
-            .partition_map(|res| match res {
-                Ok(entry) => itertools::Either::Left(entry),
-                Err(err) => itertools::Either::Right(err),
-            });
+            Some(res)
+        };
+        let split_result = |res| match res {
+            Ok(entry) => itertools::Either::Left(entry),
+            Err(err) => itertools::Either::Right(err),
+        };
+        let (mut vec_file_entry, errors): (Vec<ImagesEntry>, Vec<String>) = if device_scheduler.is_active() {
+            let non_cached_files_to_check = non_cached_files_to_check.into_values().collect();
+            device_scheduler
+                .map_grouped_by_device(non_cached_files_to_check, |file_entry| file_entry.path.as_path(), hash_image)
+                .into_par_iter()
+                .partition_map(split_result)
+        } else {
+            non_cached_files_to_check
+                .into_par_iter()
+                .map(|(_s, file_entry)| hash_image(file_entry))
+                .while_some()
+                .partition_map(split_result)
+        };
 
         self.common_data.text_messages.errors.extend(errors);
         debug!("hash_images - end hashing {} images", vec_file_entry.len());
 
         progress_handler.join_thread();
+        if check_if_stop_received(stop_flag) {
+            partial_results.flush();
+        }
 
         vec_file_entry.extend(records_already_cached.into_values());
 
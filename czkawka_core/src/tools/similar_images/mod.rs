@@ -16,6 +16,7 @@ use image_hasher::{FilterType, HashAlg};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
+use crate::common::keep_rules::KeepRuleEntry;
 use crate::common::model::FileEntry;
 use crate::common::tool_data::CommonToolData;
 use crate::common::traits::ResultEntry;
@@ -41,6 +42,12 @@ pub struct ImagesEntry {
     pub difference: u32,
 }
 
+impl KeepRuleEntry for ImagesEntry {
+    fn get_resolution(&self) -> Option<u64> {
+        Some(u64::from(self.width) * u64::from(self.height))
+    }
+}
+
 impl ResultEntry for ImagesEntry {
     fn get_path(&self) -> &Path {
         &self.path
@@ -113,7 +120,7 @@ impl bk_tree::Metric<ImHash> for Hamming {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct SimilarImagesParameters {
     pub max_difference: u32,
     pub hash_size: u8,
@@ -8,6 +8,7 @@ use fun_time::fun_time;
 use humansize::{BINARY, format_size};
 
 use crate::common::consts::{HEIC_EXTENSIONS, IMAGE_RS_SIMILAR_IMAGES_EXTENSIONS, RAW_IMAGE_EXTENSIONS};
+use crate::common::flat_results::ResultsFormat;
 use crate::common::model::WorkContinueStatus;
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
@@ -33,11 +34,16 @@ impl Search for SimilarImages {
                 return;
             }
             self.common_data.use_reference_folders = !self.common_data.directories.reference_directories.is_empty() || !self.common_data.directories.reference_files.is_empty();
-            if self.check_for_similar_images(stop_flag, progress_sender) == WorkContinueStatus::Stop {
-                self.common_data.stopped_search = true;
-                return;
+            // Collected images are saved in checkpoint, so interrupted scan may be resumed without searching them again
+            let checkpoint = self.get_checkpoint();
+            if !self.load_images_from_checkpoint(&checkpoint) {
+                if self.check_for_similar_images(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                    self.common_data.stopped_search = true;
+                    return;
+                }
+                checkpoint.save_stage(&self.images_to_check);
             }
-            if self.hash_images(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+            if self.hash_images(stop_flag, progress_sender, &checkpoint) == WorkContinueStatus::Stop {
                 self.common_data.stopped_search = true;
                 return;
             }
@@ -45,6 +51,7 @@ impl Search for SimilarImages {
                 self.common_data.stopped_search = true;
                 return;
             }
+            checkpoint.remove();
             if self.delete_files(stop_flag, progress_sender) == WorkContinueStatus::Stop {
                 self.common_data.stopped_search = true;
             }
@@ -127,11 +134,11 @@ impl PrintResults for SimilarImages {
         Ok(())
     }
 
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
+    fn save_results_to_file(&self, file_name: &str, format: ResultsFormat) -> std::io::Result<()> {
         if self.get_use_reference() {
-            self.save_results_to_file_as_json_internal(file_name, &self.similar_referenced_vectors, pretty_print)
+            self.save_results_to_file_internal(file_name, &self.similar_referenced_vectors, format)
         } else {
-            self.save_results_to_file_as_json_internal(file_name, &self.similar_vectors, pretty_print)
+            self.save_results_to_file_internal(file_name, &self.similar_vectors, format)
         }
     }
 }
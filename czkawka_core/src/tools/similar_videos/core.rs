@@ -15,6 +15,7 @@ use similario_core::visual::{FfmpegTimeout, VideoSignature};
 
 use crate::common::audio_fingerprint::calc_fingerprint_and_duration;
 use crate::common::cache::{CACHE_VERSION, CACHE_VIDEO_VERSION, load_and_split_cache_generalized_by_path, save_and_connect_cache_generalized_by_path};
+use crate::common::checkpoint::Checkpoint;
 use crate::common::config_cache_path::get_config_cache_path;
 use crate::common::dir_traversal::{DirTraversalBuilder, DirTraversalResult, inode, take_1_per_inode};
 use crate::common::model::{ToolType, WorkContinueStatus};
@@ -127,6 +128,34 @@ impl SimilarVideos {
         }
     }
 
+    pub(crate) fn get_checkpoint(&self) -> Checkpoint {
+        Checkpoint::new(&self.common_data, &self.params)
+    }
+
+    // Videos collected by interrupted scan are used instead of searching them again
+    pub(crate) fn load_videos_from_checkpoint(&mut self, checkpoint: &Checkpoint) -> bool {
+        if self.params.check_audio_content {
+            let Some(audio_to_check) = checkpoint.resume(&mut self.common_data) else {
+                return false;
+            };
+            self.audio_to_check = audio_to_check;
+        } else {
+            let Some(videos_to_check) = checkpoint.resume(&mut self.common_data) else {
+                return false;
+            };
+            self.videos_to_check = videos_to_check;
+        }
+        true
+    }
+
+    pub(crate) fn save_videos_to_checkpoint(&self, checkpoint: &Checkpoint) {
+        if self.params.check_audio_content {
+            checkpoint.save_stage(&self.audio_to_check);
+        } else {
+            checkpoint.save_stage(&self.videos_to_check);
+        }
+    }
+
     fn check_video_file_entry(&self, mut file_entry: VideosEntry, stop_flag: &Arc<AtomicBool>) -> VideosEntry {
         let sig_config = self.signature_config();
         let path = file_entry.path.to_string_lossy().to_string();
@@ -164,12 +193,13 @@ impl SimilarVideos {
     }
 
     #[fun_time(message = "sort_videos", level = "debug")]
-    pub(crate) fn sort_videos(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+    pub(crate) fn sort_videos(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>, checkpoint: &Checkpoint) -> WorkContinueStatus {
         if self.videos_to_check.is_empty() {
             return WorkContinueStatus::Continue;
         }
 
-        let (loaded_hash_map, records_already_cached, non_cached_files_to_check) = self.load_cache_at_start();
+        let (loaded_hash_map, mut records_already_cached, mut non_cached_files_to_check) = self.load_cache_at_start();
+        checkpoint.move_partial_results_by_path(&mut records_already_cached, &mut non_cached_files_to_check);
 
         let mode = if self.params.check_audio_content {
             SimilarVideosMode::AudioContent
@@ -184,6 +214,7 @@ impl SimilarVideos {
         );
 
         let non_cached_files_to_check: Vec<_> = non_cached_files_to_check.into_iter().map(|f| f.1).collect();
+        let partial_results = checkpoint.partial_results();
         let mut vec_file_entry: Vec<VideosEntry> = non_cached_files_to_check
             .into_par_iter()
             .with_max_len(2)
@@ -195,6 +226,10 @@ impl SimilarVideos {
                 let size = file_entry.size;
                 let res = self.check_video_file_entry(file_entry, stop_flag);
                 let res = Self::read_video_properties(res);
+                // Hashing interrupted by stop ends with error, which should not be reused by resumed scan
+                if !check_if_stop_received(stop_flag) {
+                    partial_results.add(&res);
+                }
 
                 progress_handler.increase_items(1);
                 progress_handler.increase_size(size);
@@ -205,6 +240,9 @@ impl SimilarVideos {
             .collect::<Vec<VideosEntry>>();
 
         progress_handler.join_thread();
+        if check_if_stop_received(stop_flag) {
+            partial_results.flush();
+        }
 
         // Just connect loaded results with already calculated hashes
         vec_file_entry.extend(records_already_cached.into_values());
@@ -393,7 +431,12 @@ impl SimilarVideos {
         }
     }
     #[fun_time(message = "calculate_audio_fingerprints", level = "debug")]
-    pub(crate) fn calculate_audio_fingerprints(&mut self, stop_flag: &Arc<AtomicBool>, progress_sender: Option<&Sender<ProgressData>>) -> WorkContinueStatus {
+    pub(crate) fn calculate_audio_fingerprints(
+        &mut self,
+        stop_flag: &Arc<AtomicBool>,
+        progress_sender: Option<&Sender<ProgressData>>,
+        checkpoint: &Checkpoint,
+    ) -> WorkContinueStatus {
         if self.audio_to_check.is_empty() {
             return WorkContinueStatus::Continue;
         }
@@ -410,8 +453,9 @@ impl SimilarVideos {
             0,
         );
 
-        let (loaded_hash_map, records_already_cached, non_cached_files_to_check) =
+        let (loaded_hash_map, mut records_already_cached, mut non_cached_files_to_check) =
             load_and_split_cache_generalized_by_path(&get_similar_videos_audio_cache_file(), mem::take(&mut self.audio_to_check), self);
+        checkpoint.move_partial_results_by_path(&mut records_already_cached, &mut non_cached_files_to_check);
 
         progress_handler.join_thread();
         if check_if_stop_received(stop_flag) {
@@ -427,6 +471,7 @@ impl SimilarVideos {
         let configuration = &self.audio_config;
 
         let non_cached_vec: Vec<_> = non_cached_files_to_check.into_iter().collect();
+        let partial_results = checkpoint.partial_results();
         let mut vec_audio_entries: Vec<VideoAudioEntry> = non_cached_vec
             .into_par_iter()
             .with_max_len(2)
@@ -449,6 +494,7 @@ impl SimilarVideos {
                     Ok(Some((fingerprint, duration_seconds))) => {
                         audio_entry.fingerprint = fingerprint;
                         audio_entry.audio_duration_seconds = duration_seconds;
+                        partial_results.add(&audio_entry);
                         Some(Some(audio_entry))
                     }
                 }
@@ -458,6 +504,9 @@ impl SimilarVideos {
             .collect();
 
         progress_handler.join_thread();
+        if check_if_stop_received(stop_flag) {
+            partial_results.flush();
+        }
 
         let progress_handler = prepare_thread_handler_common(progress_sender, ToolStage::SimilarVideos(mode, SimilarVideosStage::SavingAudioCache), 0, 0);
 
@@ -13,6 +13,7 @@ use rusty_chromaprint::Configuration;
 use serde::{Deserialize, Serialize};
 use similario_core::VideoSignature;
 
+use crate::common::keep_rules::KeepRuleEntry;
 use crate::common::model::FileEntry;
 use crate::common::tool_data::CommonToolData;
 use crate::common::traits::ResultEntry;
@@ -91,6 +92,15 @@ impl ResultEntry for VideoAudioEntry {
     }
 }
 
+impl KeepRuleEntry for VideosEntry {
+    fn get_resolution(&self) -> Option<u64> {
+        Some(u64::from(self.width?) * u64::from(self.height?))
+    }
+    fn get_bitrate(&self) -> Option<u64> {
+        self.bitrate
+    }
+}
+
 impl ResultEntry for VideosEntry {
     fn get_path(&self) -> &Path {
         &self.path
@@ -9,6 +9,7 @@ use humansize::{BINARY, format_size};
 
 use crate::common::consts::VIDEO_FILES_EXTENSIONS;
 use crate::common::ffmpeg_utils::check_if_ffprobe_ffmpeg_exists;
+use crate::common::flat_results::ResultsFormat;
 use crate::common::model::{CheckingMethod, WorkContinueStatus};
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData, DeleteItemType, DeleteMethod};
@@ -31,14 +32,20 @@ impl Search for SimilarVideos {
                     return;
                 }
                 self.common_data.use_reference_folders = !self.common_data.directories.reference_directories.is_empty() || !self.common_data.directories.reference_files.is_empty();
-                if self.check_for_similar_videos(stop_flag, progress_sender) == WorkContinueStatus::Stop {
-                    self.common_data.stopped_search = true;
-                    return;
+                // Collected videos are saved in checkpoint, so interrupted scan may be resumed without searching them again
+                let checkpoint = self.get_checkpoint();
+                if !self.load_videos_from_checkpoint(&checkpoint) {
+                    if self.check_for_similar_videos(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                        self.common_data.stopped_search = true;
+                        return;
+                    }
+                    self.save_videos_to_checkpoint(&checkpoint);
                 }
-                if self.calculate_audio_fingerprints(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                if self.calculate_audio_fingerprints(stop_flag, progress_sender, &checkpoint) == WorkContinueStatus::Stop {
                     self.common_data.stopped_search = true;
                     return;
                 }
+                checkpoint.remove();
                 if self.compare_audio_fingerprints(stop_flag, progress_sender) == WorkContinueStatus::Stop {
                     self.common_data.stopped_search = true;
                     return;
@@ -60,14 +67,19 @@ impl Search for SimilarVideos {
                     return;
                 }
                 self.common_data.use_reference_folders = !self.common_data.directories.reference_directories.is_empty() || !self.common_data.directories.reference_files.is_empty();
-                if self.check_for_similar_videos(stop_flag, progress_sender) == WorkContinueStatus::Stop {
-                    self.common_data.stopped_search = true;
-                    return;
+                let checkpoint = self.get_checkpoint();
+                if !self.load_videos_from_checkpoint(&checkpoint) {
+                    if self.check_for_similar_videos(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                        self.common_data.stopped_search = true;
+                        return;
+                    }
+                    self.save_videos_to_checkpoint(&checkpoint);
                 }
-                if self.sort_videos(stop_flag, progress_sender) == WorkContinueStatus::Stop {
+                if self.sort_videos(stop_flag, progress_sender, &checkpoint) == WorkContinueStatus::Stop {
                     self.common_data.stopped_search = true;
                     return;
                 }
+                checkpoint.remove();
             }
             if self.delete_files(stop_flag, progress_sender) == WorkContinueStatus::Stop {
                 self.common_data.stopped_search = true;
@@ -175,11 +187,11 @@ impl PrintResults for SimilarVideos {
         Ok(())
     }
 
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
+    fn save_results_to_file(&self, file_name: &str, format: ResultsFormat) -> std::io::Result<()> {
         if self.get_use_reference() {
-            self.save_results_to_file_as_json_internal(file_name, &self.similar_referenced_vectors, pretty_print)
+            self.save_results_to_file_internal(file_name, &self.similar_referenced_vectors, format)
         } else {
-            self.save_results_to_file_as_json_internal(file_name, &self.similar_vectors, pretty_print)
+            self.save_results_to_file_internal(file_name, &self.similar_vectors, format)
         }
     }
 }
@@ -120,9 +120,14 @@ impl Temporary {
             return None;
         };
 
+        let modified_date = get_modified_time(&metadata, warnings, &current_file_name, false);
+        if !(self.common_data.minimal_modified_date..=self.common_data.maximal_modified_date).contains(&modified_date) {
+            return None;
+        }
+
         // Creating new file entry
         Some(TemporaryFileEntry {
-            modified_date: get_modified_time(&metadata, warnings, &current_file_name, false),
+            modified_date,
             size: metadata.len(),
             path: current_file_name,
         })
@@ -1,4 +1,6 @@
 pub mod core;
+#[cfg(test)]
+mod tests;
 pub mod traits;
 
 use std::path::{Path, PathBuf};
@@ -0,0 +1,49 @@
+use std::fs::{self, File};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, UNIX_EPOCH};
+
+use tempfile::TempDir;
+
+use crate::common::tool_data::CommonData;
+use crate::common::traits::Search;
+use crate::tools::temporary::{Temporary, TemporaryParameters};
+
+#[test]
+fn test_temporary_files_filtered_by_modification_date() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path();
+
+    fs::write(path.join("old.tmp"), b"old").unwrap();
+    fs::write(path.join("new.tmp"), b"new").unwrap();
+    File::options()
+        .write(true)
+        .open(path.join("old.tmp"))
+        .unwrap()
+        .set_modified(UNIX_EPOCH + Duration::from_secs(946_684_800))
+        .unwrap();
+
+    let mut finder = Temporary::new(TemporaryParameters::new());
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_recursive_search(true);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+    assert_eq!(finder.get_temporary_files().len(), 2);
+
+    // Only files modified after 2010
+    let mut finder = Temporary::new(TemporaryParameters::new());
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_recursive_search(true);
+    finder.set_minimal_modified_date(1_262_304_000);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+    let files: Vec<_> = finder.get_temporary_files().iter().map(|entry| entry.path.clone()).collect();
+    assert_eq!(files, vec![path.join("new.tmp")]);
+
+    // Only files modified before 2010
+    let mut finder = Temporary::new(TemporaryParameters::new());
+    finder.set_included_paths(vec![path.to_path_buf()]);
+    finder.set_recursive_search(true);
+    finder.set_maximal_modified_date(1_262_304_000);
+    finder.search(&Arc::new(AtomicBool::new(false)), None);
+    let files: Vec<_> = finder.get_temporary_files().iter().map(|entry| entry.path.clone()).collect();
+    assert_eq!(files, vec![path.join("old.tmp")]);
+}
@@ -9,6 +9,7 @@ use humansize::{BINARY, format_size};
 
 use crate::common::consts::VIDEO_FILES_EXTENSIONS;
 use crate::common::ffmpeg_utils::check_if_ffprobe_ffmpeg_exists;
+use crate::common::flat_results::ResultsFormat;
 use crate::common::model::WorkContinueStatus;
 use crate::common::progress_data::ProgressData;
 use crate::common::tool_data::{CommonData, CommonToolData};
@@ -114,10 +115,10 @@ impl PrintResults for VideoOptimizer {
         Ok(())
     }
 
-    fn save_results_to_file_as_json(&self, file_name: &str, pretty_print: bool) -> std::io::Result<()> {
+    fn save_results_to_file(&self, file_name: &str, format: ResultsFormat) -> std::io::Result<()> {
         match &self.params {
-            VideoOptimizerParameters::VideoTranscode(_) => self.save_results_to_file_as_json_internal(file_name, &self.video_transcode_result_entries, pretty_print),
-            VideoOptimizerParameters::VideoCrop(_) => self.save_results_to_file_as_json_internal(file_name, &self.video_crop_result_entries, pretty_print),
+            VideoOptimizerParameters::VideoTranscode(_) => self.save_results_to_file_internal(file_name, &self.video_transcode_result_entries, format),
+            VideoOptimizerParameters::VideoCrop(_) => self.save_results_to_file_internal(file_name, &self.video_crop_result_entries, format),
         }
     }
 }
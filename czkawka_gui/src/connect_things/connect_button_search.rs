@@ -262,14 +262,15 @@ fn duplicate_search(
     thread::Builder::new()
         .stack_size(DEFAULT_THREAD_SIZE)
         .spawn(move || {
-            let params = DuplicateFinderParameters::new(
+            let params = DuplicateFinderParameters {
                 check_method,
                 hash_type,
                 use_prehash_cache,
-                loaded_commons.minimal_cache_file_size,
+                minimal_cache_file_size: loaded_commons.minimal_cache_file_size,
                 minimal_prehash_cache_file_size,
                 case_sensitive_name_comparison,
-            );
+                ..Default::default()
+            };
             let mut tool = DuplicateFinder::new(params);
 
             set_common_settings(&mut tool, &loaded_commons);
@@ -127,7 +127,7 @@ pub(crate) fn connect_settings(gui_data: &GuiData) {
                     if response_type == ResponseType::Ok {
                         let mut messages: Messages = Messages::new();
                         for use_prehash in [true, false] {
-                            for type_of_hash in [HashType::Xxh3, HashType::Blake3, HashType::Crc32] {
+                            for type_of_hash in [HashType::Xxh3, HashType::Blake3, HashType::Crc32, HashType::Sha256] {
                                 let file_name = get_duplicate_cache_file(type_of_hash, use_prehash);
                                 let (mut cache_messages, loaded_items) = load_cache_from_file_generalized_by_size::<DuplicateEntry>(&file_name, true, &Default::default());
 
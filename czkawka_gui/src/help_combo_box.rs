@@ -8,7 +8,7 @@ pub struct HashTypeStruct {
     pub hash_type: HashType,
 }
 
-pub const DUPLICATES_HASH_TYPE_COMBO_BOX: [HashTypeStruct; 3] = [
+pub const DUPLICATES_HASH_TYPE_COMBO_BOX: [HashTypeStruct; 4] = [
     HashTypeStruct {
         eng_name: "Blake3",
         hash_type: HashType::Blake3,
@@ -21,6 +21,10 @@ pub const DUPLICATES_HASH_TYPE_COMBO_BOX: [HashTypeStruct; 3] = [
         eng_name: "XXH3",
         hash_type: HashType::Xxh3,
     },
+    HashTypeStruct {
+        eng_name: "SHA256",
+        hash_type: HashType::Sha256,
+    },
 ];
 
 pub struct CheckMethodStruct {
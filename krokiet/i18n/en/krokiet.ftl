@@ -46,6 +46,7 @@ rust_delete_summary = Deleted { $deleted } items, failed to remove { $failed } i
 rust_rename_summary = Renamed { $renamed } items, failed to rename { $failed } items, out of { $total } items
 rust_move_summary = Moved { $moved } items, failed to move { $failed } items, out of { $total } items
 rust_hardlink_summary = Hardlinked { $hardlinked } items, failed to hardlink { $failed } items, out of { $total } items
+rust_reflink_summary = Reflinked { $reflinked } items, failed to reflink { $failed } items, out of { $total } items
 rust_symlink_summary = Symlinked { $symlinked } items, failed to symlink { $failed } items, out of { $total } items
 rust_optimize_video_summary = Optimized { $optimized } videos, failed to optimize { $failed } videos, out of { $total } videos
 rust_clean_exif_summary = Cleaned EXIF from { $cleaned } files, failed to clean { $failed } files, out of { $total } files
@@ -53,6 +54,7 @@ rust_no_files_deleted = No files or folders selected for deletion
 rust_no_files_renamed = No files or folders selected for renaming
 rust_no_files_moved = No files or folders selected for moving
 rust_no_files_hardlinked = No files or folders selected for hardlinking
+rust_no_files_reflinked = No files selected for reflinking
 rust_no_files_symlinked = No files or folders selected for symlinking
 rust_no_videos_optimized = No videos selected for optimization
 rust_no_exif_cleaned = No files selected for EXIF cleaning
@@ -81,12 +83,21 @@ rust_optimize_video_confirmation_number_simple = { $items } items selected.
 rust_hardlink_confirmation = Are you sure you want to create hardlinks for the selected items?
 rust_hardlink_confirmation_number_simple = { $items } items selected.
 
+rust_reflink_confirmation = Are you sure you want to replace the selected items with reflinks (copy-on-write clones)?
+rust_reflink_confirmation_number_simple = { $items } items selected.
+
 rust_symlink_confirmation = Are you sure you want to create symlinks for the selected items?
 rust_symlink_confirmation_number_simple = { $items } items selected.
 
 rust_rename_confirmation = Are you sure you want to rename the selected items?
 rust_rename_confirmation_number_simple = { $items } items selected.
 
+rust_undo_confirmation = Are you sure you want to revert the last operation? Moved and renamed items are moved back, trashed items are restored and links are replaced by copies.
+rust_undo_confirmation_number_simple = { $items } items were changed by this operation.
+rust_undo_nothing_to_revert = There is no operation to revert
+rust_undo_journal_error = Cannot read undo journal, reason { $reason }
+rust_undo_finished = Reverted last operation, failed to restore { $errors } items
+
 rust_cache_processed_files = Processed { $files } cache files
 rust_cache_entries_stats = Removed { $removed } entries out of all { $all }, { $left } left
 rust_cache_size_reduced = Reduced cache files size by { $size }
@@ -94,6 +105,9 @@ rust_cache_time_elapsed = Time elapsed: { $time }
 
 rust_symlink_failed = Failed to symlink {$name} to {$target}, reason {$reason}
 rust_hardlink_failed = Failed to hardlink { $name } to { $target }, reason { $reason }
+rust_reflink_failed = Failed to reflink { $name } to { $target }, reason { $reason }
+rust_reflink_content_differs = Cannot reflink { $name } to { $target }, because files have different content
+rust_reflink_stopped = Reflinking { $name } to { $target } was stopped before files were compared
 
 # Slint translations, but in arrays
 
@@ -206,6 +220,7 @@ selection_all_except_newest = Select all except newest
 selection_all_except_oldest = Select all except oldest
 selection_all_except_longest_path = Select all except longest path
 selection_all_except_shortest_path = Select all except shortest path
+selection_all_except_kept_by_rules = Select all except kept by rules
 selection_custom_select_unselect = Custom Select/Unselect
 stage_current = Current Stage:
 stage_all = All Stages:
@@ -310,6 +325,7 @@ settings_excluded_extensions = Excluded extensions:
 settings_file_size = File Size(Kilobytes)
 settings_minimum_file_size = Min:
 settings_maximum_file_size = Max:
+settings_file_age = File Age(Days, 0 - no limit)
 settings_recursive_search = Recursive search
 settings_use_cache = Use cache
 settings_save_as_json = Also save cache as JSON file
@@ -318,7 +334,12 @@ settings_delete_outdated_cache_entries = Delete automatically outdated cache ent
 settings_delete_outdated_cache_entries_hint = When enabled, the app will verify during cache loading (at most once per week) whether the cached records still point to existing and unmodified files/data
 settings_hide_hard_links = Hide hard links
 settings_hide_hard_links_hint = Hide hard links to same files in results
+settings_resume_interrupted_scans = Resume interrupted scans
+settings_resume_interrupted_scans_hint = Duplicate, similar image, similar video and broken file scans, interrupted by stop or e.g. reboot, continue from last checkpoint when started again with same settings. Requires cache
 settings_thread_number = Thread number
+settings_maximum_read_speed = Maximum read speed(MB/s, 0 - no limit)
+settings_idle_io_priority = Read files with idle priority
+settings_io_throttling_hint = Limits how much scan slows down other programs, changes are applied also to running scan
 settings_restart_required = ---You need to restart app to apply changes in thread number---
 settings_duplicate_image_preview = Image preview
 settings_duplicate_minimal_hash_cache_size = Minimal size of cached files - Hash (KB)
@@ -357,6 +378,8 @@ settings_select_group_size_text = Size
 settings_select_group_date_text = Modification Date
 settings_select_group_resolution_text = Resolution
 settings_select_group_path_text = Path
+settings_select_keep_rules_text = Keep rules
+settings_select_keep_rules_hint_text = Rules choosing item left unselected in each group, separated by semicolons and checked in order, e.g. "path:/home/user/Photos; no-copy; resolution; oldest". Available rules: path:FOLDER, shortest-path, longest-path, resolution, bitrate, no-copy, oldest, newest, biggest, smallest
 settings_select_label_one_smallest_text = One smallest
 settings_select_label_one_biggest_text = One biggest
 settings_select_label_except_smallest_text = Except smallest
@@ -460,9 +483,15 @@ optimize_noise_reduction_strength_hint = 1 = minimal, 10 = maximum noise reducti
 hardlink_button_text = Hardlink
 hardlink_text = Create hardlinks
 hardlink_confirmation_text = Are you sure you want to create hardlinks for the selected items?
+reflink_button_text = Reflink
+reflink_text = Create reflinks
+reflink_confirmation_text = Are you sure you want to replace the selected items with reflinks (copy-on-write clones)?
 softlink_button_text = Softlink
 softlink_text = Create softlinks
 softlink_confirmation_text = Are you sure you want to create softlinks (symlinks) for the selected items?
+undo_button_text = Undo
+undo_text = Undo last operation
+undo_confirmation_text = Are you sure you want to revert the last operation?
 popup_custom_select_title_text = Custom Select / Unselect
 popup_custom_select_button_text = Select
 popup_custom_unselect_button_text = Unselect
@@ -186,6 +186,14 @@ where
     component.set_recursive_search(custom_settings.recursive_search);
     component.set_minimal_file_size(custom_settings.minimum_file_size as u64 * 1024);
     component.set_maximal_file_size(custom_settings.maximum_file_size as u64 * 1024);
+    // Minimum age limits newest allowed modification date, maximum age the oldest one
+    let now = Utc::now().timestamp().max(0) as u64;
+    if custom_settings.minimum_file_age > 0 {
+        component.set_maximal_modified_date(now.saturating_sub(custom_settings.minimum_file_age as u64 * 24 * 60 * 60));
+    }
+    if custom_settings.maximum_file_age > 0 {
+        component.set_minimal_modified_date(now.saturating_sub(custom_settings.maximum_file_age as u64 * 24 * 60 * 60));
+    }
     component.set_allowed_extensions(custom_settings.allowed_extensions.split(',').map(str::to_string).collect());
     component.set_excluded_extensions(custom_settings.excluded_extensions.split(',').map(str::to_string).collect());
     component.set_excluded_items(custom_settings.excluded_items.split(',').map(str::to_string).collect());
@@ -194,4 +202,5 @@ where
     component.set_save_also_as_json(custom_settings.save_also_as_json);
     component.set_delete_outdated_cache(custom_settings.delete_outdated_cache_entries);
     component.set_hide_hard_links(custom_settings.hide_hard_links);
+    component.set_resume(custom_settings.resume_interrupted_scans);
 }
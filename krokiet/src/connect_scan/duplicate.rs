@@ -23,14 +23,15 @@ pub(crate) fn scan_duplicates(a: Weak<MainWindow>, sd: ScanData) {
             let hash_type = sd.combo_box_items.duplicates_hash_type.value;
             let check_method = sd.combo_box_items.duplicates_check_method.value;
 
-            let params = DuplicateFinderParameters::new(
+            let params = DuplicateFinderParameters {
                 check_method,
                 hash_type,
-                sd.custom_settings.duplicate_use_prehash,
-                sd.custom_settings.duplicate_minimal_hash_cache_size as u64,
-                sd.custom_settings.duplicate_minimal_prehash_cache_size as u64,
-                sd.custom_settings.duplicates_sub_name_case_sensitive,
-            );
+                use_prehash_cache: sd.custom_settings.duplicate_use_prehash,
+                minimal_cache_file_size: sd.custom_settings.duplicate_minimal_hash_cache_size as u64,
+                minimal_prehash_cache_file_size: sd.custom_settings.duplicate_minimal_prehash_cache_size as u64,
+                case_sensitive_name_comparison: sd.custom_settings.duplicates_sub_name_case_sensitive,
+                ..Default::default()
+            };
             let mut tool = DuplicateFinder::new(params);
 
             set_common_settings(&mut tool, &sd.custom_settings, &sd.stop_flag);
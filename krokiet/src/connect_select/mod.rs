@@ -1,13 +1,16 @@
 pub(crate) mod custom_select;
 
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
+use czkawka_core::common::keep_rules::{KeepRule, KeepRuleEntry, choose_item_to_keep, parse_keep_rules};
+use czkawka_core::common::traits::ResultEntry;
 use log::error;
 use regex::Regex;
 use slint::{ComponentHandle, Model, ModelRc, SharedString, VecModel};
 
-use crate::common::{connect_i32_into_u64, create_model_from_model_vec};
+use crate::common::{IntDataSimilarMusic, IntDataSimilarVideos, connect_i32_into_u64, create_model_from_model_vec};
 use crate::connect_row_selection::checker::change_number_of_enabled_items;
 use crate::connect_translation::translate_select_mode;
 use crate::settings::model::{SavedCustomSelectColumnState, SavedCustomSelectTabState};
@@ -59,6 +62,13 @@ pub(crate) fn connect_select(app: &MainWindow, shared_models: &Arc<Mutex<SharedM
             SelectMode::SelectAllExceptOldest => select_all_except_by_property(&current_model, active_tab, Property::Date, false),
             SelectMode::SelectAllExceptLongestPath => select_all_except_by_property(&current_model, active_tab, Property::PathLength, true),
             SelectMode::SelectAllExceptShortestPath => select_all_except_by_property(&current_model, active_tab, Property::PathLength, false),
+            SelectMode::SelectAllExceptKeptByRules => match parse_keep_rules(&app.global::<Settings>().get_select_keep_rules()) {
+                Ok(keep_rules) => select_all_except_kept_by_rules(&current_model, active_tab, &keep_rules),
+                Err(e) => {
+                    error!("Cannot select items by keep rules - {e}");
+                    return;
+                }
+            },
 
             SelectMode::SelectCustom => return,
         };
@@ -171,6 +181,7 @@ pub(crate) fn set_select_buttons(app: &MainWindow) {
     let active_tab = app.global::<GuiState>().get_active_tab();
     let settings = app.global::<Settings>();
     let mut base_buttons = vec![SelectMode::SelectCustom, SelectMode::SelectAll, SelectMode::UnselectAll, SelectMode::InvertSelection];
+    let keep_rules_set = parse_keep_rules(&settings.get_select_keep_rules()).is_ok_and(|keep_rules| !keep_rules.is_empty());
 
     let additional_buttons = match active_tab {
         ActiveTab::DuplicateFiles | ActiveTab::SimilarVideos | ActiveTab::SimilarMusic => vec![
@@ -187,6 +198,7 @@ pub(crate) fn set_select_buttons(app: &MainWindow) {
             (SelectMode::SelectAllExceptBiggestSize, settings.get_select_show_except_biggest_size()),
             (SelectMode::SelectAllExceptShortestPath, settings.get_select_show_except_shortest_path()),
             (SelectMode::SelectAllExceptLongestPath, settings.get_select_show_except_longest_path()),
+            (SelectMode::SelectAllExceptKeptByRules, keep_rules_set),
         ],
         ActiveTab::SimilarImages => vec![
             (SelectMode::InvertSelectionInGroup, true),
@@ -206,6 +218,7 @@ pub(crate) fn set_select_buttons(app: &MainWindow) {
             (SelectMode::SelectAllExceptBiggestResolution, settings.get_select_show_except_biggest_resolution()),
             (SelectMode::SelectAllExceptShortestPath, settings.get_select_show_except_shortest_path()),
             (SelectMode::SelectAllExceptLongestPath, settings.get_select_show_except_longest_path()),
+            (SelectMode::SelectAllExceptKeptByRules, keep_rules_set),
         ],
         ActiveTab::EmptyFolders
         | ActiveTab::BigFiles
@@ -310,6 +323,86 @@ fn select_by_property(model: &ModelRc<SingleMainListModel>, active_tab: ActiveTa
 // `spare_max: true`  → spares the biggest/newest/longest item (selects all others).
 // `spare_max: false` → spares the smallest/oldest/shortest item (selects all others).
 fn select_all_except_by_property(model: &ModelRc<SingleMainListModel>, active_tab: ActiveTab, property: Property, spare_max: bool) -> SelectionResult {
+    select_all_except_spared(model, active_tab, |group| {
+        // Find the extreme item to spare.
+        let mut extreme_val = if spare_max { 0u64 } else { u64::MAX };
+        let mut extreme_idx = 0;
+        for (j, item) in group.iter().enumerate() {
+            let val = extract_comparable_field(item, property, active_tab);
+            if spare_max && val > extreme_val || !spare_max && val < extreme_val {
+                extreme_val = val;
+                extreme_idx = j;
+            }
+        }
+        extreme_idx
+    })
+}
+
+// Row properties used by keep rules from core, properties not available in tab are equal for all rows
+struct KeepRuleRow {
+    path: PathBuf,
+    size: u64,
+    modified_date: u64,
+    resolution: Option<u64>,
+    bitrate: Option<u64>,
+}
+
+impl ResultEntry for KeepRuleRow {
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+    fn get_modified_date(&self) -> u64 {
+        self.modified_date
+    }
+    fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl KeepRuleEntry for KeepRuleRow {
+    fn get_resolution(&self) -> Option<u64> {
+        self.resolution
+    }
+    fn get_bitrate(&self) -> Option<u64> {
+        self.bitrate
+    }
+}
+
+impl KeepRuleRow {
+    fn from_model(model: &SingleMainListModel, active_tab: ActiveTab) -> Self {
+        let val_int = |idx: usize| model.val_int.row_data(idx).expect("can find int property");
+        let val_str = |idx: usize| model.val_str.row_data(idx).expect("can find string property");
+        let (resolution, bitrate) = match active_tab {
+            ActiveTab::SimilarImages => (Some(val_int(active_tab.get_int_pixel_count_idx()) as u64), None),
+            ActiveTab::SimilarVideos => (
+                Some(val_int(IntDataSimilarVideos::Dimensions as usize) as u64),
+                Some(connect_i32_into_u64(
+                    val_int(IntDataSimilarVideos::BitratePart1 as usize),
+                    val_int(IntDataSimilarVideos::BitratePart2 as usize),
+                )),
+            ),
+            ActiveTab::SimilarMusic => (None, Some(val_int(IntDataSimilarMusic::Bitrate as usize) as u64)),
+            _ => (None, None),
+        };
+        Self {
+            path: Path::new(val_str(active_tab.get_str_path_idx()).as_str()).join(val_str(active_tab.get_str_name_idx()).as_str()),
+            size: extract_comparable_field(model, Property::Size, active_tab),
+            modified_date: extract_comparable_field(model, Property::Date, active_tab),
+            resolution,
+            bitrate,
+        }
+    }
+}
+
+fn select_all_except_kept_by_rules(model: &ModelRc<SingleMainListModel>, active_tab: ActiveTab, keep_rules: &[KeepRule]) -> SelectionResult {
+    select_all_except_spared(model, active_tab, |group| {
+        let rows = group.iter().map(|item| KeepRuleRow::from_model(item, active_tab)).collect::<Vec<_>>();
+        choose_item_to_keep(keep_rules, &rows).unwrap_or_default()
+    })
+}
+
+// Selects all items in each group except one, whose index inside group is returned by `choose_spared`
+fn select_all_except_spared(model: &ModelRc<SingleMainListModel>, active_tab: ActiveTab, choose_spared: impl Fn(&[SingleMainListModel]) -> usize) -> SelectionResult {
     let mut checked_items = 0;
     let mut unchecked_items = 0;
 
@@ -325,17 +418,7 @@ fn select_all_except_by_property(model: &ModelRc<SingleMainListModel>, active_ta
     for i in 0..(headers_idx.len() - 1) {
         let group_start = headers_idx[i] + 1;
         let group_end = headers_idx[i + 1];
-
-        // Find the extreme item to spare.
-        let mut extreme_val = if spare_max { 0u64 } else { u64::MAX };
-        let mut extreme_idx = group_start;
-        for j in group_start..group_end {
-            let val = extract_comparable_field(&old_data[j], property, active_tab);
-            if spare_max && val > extreme_val || !spare_max && val < extreme_val {
-                extreme_val = val;
-                extreme_idx = j;
-            }
-        }
+        let extreme_idx = group_start + choose_spared(&old_data[group_start..group_end]);
 
         // Select every item except the extreme one.
         for j in group_start..group_end {
@@ -626,6 +709,28 @@ mod tests {
         assert!(!new_model.row_data(3).unwrap().checked); // 300 - spared (biggest)
     }
 
+    #[test]
+    fn select_all_except_kept_by_rules_spares_item_chosen_by_rules() {
+        let mut header = crate::test_common::get_main_list_model();
+        header.header_row = true;
+        let items = vec![
+            header,
+            make_item_with_path("/home/downloads", "photo(1).jpg"),
+            make_item_with_path("/srv/originals", "photo - copy.jpg"),
+            make_item_with_path("/srv/originals", "photo.jpg"),
+        ];
+        let model = create_model_from_model_vec(&items);
+        let keep_rules = parse_keep_rules("path:/srv/originals; no-copy").unwrap();
+
+        let (checked_items, unchecked_items, new_model) = select_all_except_kept_by_rules(&model, ActiveTab::DuplicateFiles, &keep_rules);
+
+        assert_eq!(checked_items, 2);
+        assert_eq!(unchecked_items, 0);
+        assert!(new_model.row_data(1).unwrap().checked);
+        assert!(new_model.row_data(2).unwrap().checked);
+        assert!(!new_model.row_data(3).unwrap().checked);
+    }
+
     #[test]
     fn select_all_except_smallest_spares_smallest_item() {
         // Layout: [header, small(100), medium(200), large(300)]
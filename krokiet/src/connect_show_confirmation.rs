@@ -1,5 +1,6 @@
 use std::sync::{Arc, Mutex};
 
+use czkawka_core::common::undo_journal::get_last_operation;
 use czkawka_core::tools::video_optimizer::VideoOptimizerParameters;
 use rfd::FileDialog;
 use slint::ComponentHandle;
@@ -7,7 +8,7 @@ use slint::ComponentHandle;
 use crate::connect_rfd::{hide_file_dialog_overlay, show_file_dialog_overlay};
 use crate::model_operations::get_checked_info_from_app;
 use crate::shared_models::SharedModels;
-use crate::{MainWindow, PopupRequest, Translations, flk};
+use crate::{GuiState, MainWindow, PopupRequest, Translations, flk};
 
 pub(crate) fn connect_show_confirmation(app: &MainWindow, shared_models: Arc<Mutex<SharedModels>>) {
     let a = app.as_weak();
@@ -129,6 +130,11 @@ pub(crate) fn connect_show_confirmation(app: &MainWindow, shared_models: Arc<Mut
                 base.push_str(format!("\n{}", flk!("rust_hardlink_confirmation_number_simple", items = res.checked_items_number)).as_str());
                 translation.set_hardlink_confirmation_text(base.into());
             }
+            PopupRequest::Reflink => {
+                let mut base = flk!("rust_reflink_confirmation");
+                base.push_str(format!("\n{}", flk!("rust_reflink_confirmation_number_simple", items = res.checked_items_number)).as_str());
+                translation.set_reflink_confirmation_text(base.into());
+            }
             PopupRequest::RenameBadExtension | PopupRequest::RenameBadFileName => {
                 let mut base = flk!("rust_rename_confirmation");
                 base.push_str(format!("\n{}", flk!("rust_rename_confirmation_number_simple", items = res.checked_items_number)).as_str());
@@ -137,6 +143,21 @@ pub(crate) fn connect_show_confirmation(app: &MainWindow, shared_models: Arc<Mut
             PopupRequest::Save => {
                 // There is no confirmation saving
             }
+            PopupRequest::Undo => match get_last_operation() {
+                Ok(entries) if entries.is_empty() => {
+                    app.global::<GuiState>().set_info_text(flk!("rust_undo_nothing_to_revert").into());
+                    return;
+                }
+                Ok(entries) => {
+                    let mut base = flk!("rust_undo_confirmation");
+                    base.push_str(format!("\n{}", flk!("rust_undo_confirmation_number_simple", items = entries.len())).as_str());
+                    translation.set_undo_confirmation_text(base.into());
+                }
+                Err(e) => {
+                    app.global::<GuiState>().set_info_text(flk!("rust_undo_journal_error", reason = e).into());
+                    return;
+                }
+            },
         }
 
         app.invoke_show_action_popup(popup_request, data.into());
@@ -277,9 +277,15 @@ fn translate_items(app: &MainWindow) {
     translation.set_hardlink_button_text(flk!("hardlink_button_text").into());
     translation.set_hardlink_text(flk!("hardlink_text").into());
     translation.set_hardlink_confirmation_text(flk!("hardlink_confirmation_text").into());
+    translation.set_reflink_button_text(flk!("reflink_button_text").into());
+    translation.set_reflink_text(flk!("reflink_text").into());
+    translation.set_reflink_confirmation_text(flk!("reflink_confirmation_text").into());
     translation.set_softlink_button_text(flk!("softlink_button_text").into());
     translation.set_softlink_text(flk!("softlink_text").into());
     translation.set_softlink_confirmation_text(flk!("softlink_confirmation_text").into());
+    translation.set_undo_button_text(flk!("undo_button_text").into());
+    translation.set_undo_text(flk!("undo_text").into());
+    translation.set_undo_confirmation_text(flk!("undo_confirmation_text").into());
     translation.set_move_confirmation_text(flk!("move_confirmation_text").into());
     translation.set_rename_confirmation_text(flk!("rename_confirmation_text").into());
     translation.set_settings_excluded_items_text(flk!("settings_excluded_items").into());
@@ -288,11 +294,15 @@ fn translate_items(app: &MainWindow) {
     translation.set_settings_file_size_text(flk!("settings_file_size").into());
     translation.set_settings_minimum_file_size_text(flk!("settings_minimum_file_size").into());
     translation.set_settings_maximum_file_size_text(flk!("settings_maximum_file_size").into());
+    translation.set_settings_file_age_text(flk!("settings_file_age").into());
     translation.set_settings_recursive_search_text(flk!("settings_recursive_search").into());
     translation.set_settings_use_cache_text(flk!("settings_use_cache").into());
     translation.set_settings_save_as_json_text(flk!("settings_save_as_json").into());
     translation.set_settings_ignore_other_filesystems_text(flk!("settings_ignore_other_filesystems").into());
     translation.set_settings_thread_number_text(flk!("settings_thread_number").into());
+    translation.set_settings_maximum_read_speed_text(flk!("settings_maximum_read_speed").into());
+    translation.set_settings_idle_io_priority_text(flk!("settings_idle_io_priority").into());
+    translation.set_settings_io_throttling_hint_text(flk!("settings_io_throttling_hint").into());
     translation.set_settings_restart_required_text(flk!("settings_restart_required").into());
     translation.set_settings_duplicate_image_preview_text(flk!("settings_duplicate_image_preview").into());
     translation.set_settings_similar_videos_preview_text(flk!("settings_video_thumbnails_preview").into());
@@ -308,6 +318,8 @@ fn translate_items(app: &MainWindow) {
     translation.set_settings_delete_outdated_cache_entries_hint_text(flk!("settings_delete_outdated_cache_entries_hint").into());
     translation.set_settings_hide_hard_links_text(flk!("settings_hide_hard_links").into());
     translation.set_settings_hide_hard_links_hint_text(flk!("settings_hide_hard_links_hint").into());
+    translation.set_settings_resume_interrupted_scans_text(flk!("settings_resume_interrupted_scans").into());
+    translation.set_settings_resume_interrupted_scans_hint_text(flk!("settings_resume_interrupted_scans_hint").into());
     translation.set_settings_similar_images_show_image_preview_text(flk!("settings_similar_images_show_image_preview").into());
     translation.set_settings_open_config_folder_text(flk!("settings_open_config_folder").into());
     translation.set_settings_open_cache_folder_text(flk!("settings_open_cache_folder").into());
@@ -372,6 +384,8 @@ fn translate_items(app: &MainWindow) {
     translation.set_settings_select_group_date_text(flk!("settings_select_group_date_text").into());
     translation.set_settings_select_group_resolution_text(flk!("settings_select_group_resolution_text").into());
     translation.set_settings_select_group_path_text(flk!("settings_select_group_path_text").into());
+    translation.set_settings_select_keep_rules_text(flk!("settings_select_keep_rules_text").into());
+    translation.set_settings_select_keep_rules_hint_text(flk!("settings_select_keep_rules_hint_text").into());
     translation.set_settings_select_label_one_smallest_text(flk!("settings_select_label_one_smallest_text").into());
     translation.set_settings_select_label_one_biggest_text(flk!("settings_select_label_one_biggest_text").into());
     translation.set_settings_select_label_except_smallest_text(flk!("settings_select_label_except_smallest_text").into());
@@ -549,6 +563,7 @@ pub(crate) fn translate_select_mode(select_mode: SelectMode) -> SharedString {
         SelectMode::SelectAllExceptOldest => flk!("selection_all_except_oldest").into(),
         SelectMode::SelectAllExceptLongestPath => flk!("selection_all_except_longest_path").into(),
         SelectMode::SelectAllExceptShortestPath => flk!("selection_all_except_shortest_path").into(),
+        SelectMode::SelectAllExceptKeptByRules => flk!("selection_all_except_kept_by_rules").into(),
         SelectMode::SelectCustom => flk!("selection_custom_select_unselect").into(),
     }
 }
@@ -5,6 +5,7 @@ use std::thread;
 
 use crossbeam_channel::Sender;
 use czkawka_core::common::progress_data::ProgressData;
+use czkawka_core::common::undo_journal::JournalRecorder;
 use slint::{ComponentHandle, Weak};
 
 use crate::model_operations::model_processor::{MessageType, ModelProcessor, ProcessFunction};
@@ -50,11 +51,13 @@ impl ModelProcessor {
             let path_idx = self.active_tab.get_str_path_idx();
             let name_idx = self.active_tab.get_str_name_idx();
 
+            let journal = JournalRecorder::new();
             let dlt_fnc = move |data: &SimplerSingleMainListModel| {
                 remove_single_item(
                     &format!("{}{MAIN_SEPARATOR}{}", data.val_str[path_idx], data.val_str[name_idx]),
                     is_empty_folder_tab,
                     remove_to_trash,
+                    &journal,
                 )
             };
 
@@ -72,15 +75,22 @@ impl ModelProcessor {
 }
 
 #[cfg(not(test))]
-fn remove_single_item(full_path: &str, is_folder_tab: bool, remove_to_trash: bool) -> Result<(), String> {
+fn remove_single_item(full_path: &str, is_folder_tab: bool, remove_to_trash: bool, journal: &JournalRecorder) -> Result<(), String> {
+    use czkawka_core::common::undo_journal::JournalOperation;
+
+    let size = std::fs::symlink_metadata(full_path).map(|metadata| metadata.len()).unwrap_or_default();
     if is_folder_tab {
-        return czkawka_core::common::remove_folder_if_contains_only_empty_folders(full_path, remove_to_trash);
+        czkawka_core::common::remove_folder_if_contains_only_empty_folders(full_path, remove_to_trash)?;
+    } else {
+        czkawka_core::common::remove_single_file(full_path, remove_to_trash)?;
     }
-    czkawka_core::common::remove_single_file(full_path, remove_to_trash)
+    let operation = if remove_to_trash { JournalOperation::Trash } else { JournalOperation::Delete };
+    journal.record(operation, std::path::Path::new(full_path), None, size);
+    Ok(())
 }
 
 #[cfg(test)]
-fn remove_single_item(full_path: &str, _is_folder_tab: bool, _remove_to_trash: bool) -> Result<(), String> {
+fn remove_single_item(full_path: &str, _is_folder_tab: bool, _remove_to_trash: bool, _journal: &JournalRecorder) -> Result<(), String> {
     if full_path.contains("test_error") {
         return Err(format!("Test error for item: {full_path}"));
     }
@@ -115,11 +125,13 @@ mod tests {
 
             let path_idx = 0;
             let name_idx = 0;
+            let journal = JournalRecorder::new();
             let dlt_fnc = move |data: &SimplerSingleMainListModel| {
                 remove_single_item(
                     &format!("{}{MAIN_SEPARATOR}{}", data.val_str[path_idx], data.val_str[name_idx]),
                     is_empty_folder_tab,
                     remove_to_trash,
+                    &journal,
                 )
             };
 
@@ -6,6 +6,7 @@ use std::{fs, path, thread};
 
 use crossbeam_channel::Sender;
 use czkawka_core::common::progress_data::ProgressData;
+use czkawka_core::common::undo_journal::{JournalOperation, JournalRecorder};
 use slint::{ComponentHandle, Weak};
 
 use crate::model_operations::model_processor::{MessageType, ModelProcessor, ProcessFunction};
@@ -57,8 +58,19 @@ impl ModelProcessor {
             let name_idx = self.active_tab.get_str_name_idx();
 
             let claimed_paths = Mutex::new(HashSet::new());
+            let journal = JournalRecorder::new();
             let mlt_fnc = move |data: &SimplerSingleMainListModel| {
-                move_single_item(data, path_idx, name_idx, &output_folder, preserve_structure, copy_mode, rename_on_conflict, &claimed_paths)
+                move_single_item(
+                    data,
+                    path_idx,
+                    name_idx,
+                    &output_folder,
+                    preserve_structure,
+                    copy_mode,
+                    rename_on_conflict,
+                    &claimed_paths,
+                    &journal,
+                )
             };
 
             self.process_and_update_gui_state(
@@ -84,6 +96,7 @@ fn move_single_item(
     copy_mode: bool,
     rename_on_conflict: bool,
     claimed_paths: &Mutex<HashSet<PathBuf>>,
+    journal: &JournalRecorder,
 ) -> Result<(), String> {
     let path = &data.val_str[path_idx];
     let name = &data.val_str[name_idx];
@@ -107,7 +120,9 @@ fn move_single_item(
     } else {
         // Try to rename file, may fail due various reasons
         // It is the easiest way to move file, but only on same partition
+        let size = fs::symlink_metadata(&input_file).map(|metadata| metadata.len()).unwrap_or_default();
         if fs::rename(&input_file, &output_file).is_ok() {
+            journal.record(JournalOperation::Move, &input_file, Some(&output_file), size);
             return Ok(());
         }
 
@@ -122,6 +137,7 @@ fn move_single_item(
                 reason = e.to_string()
             ));
         }
+        journal.record(JournalOperation::Move, &input_file, Some(&output_file), size);
         Ok(())
     }
 }
@@ -0,0 +1,86 @@
+use std::path::MAIN_SEPARATOR;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use crossbeam_channel::Sender;
+use czkawka_core::common::progress_data::ProgressData;
+use slint::{ComponentHandle, Weak};
+
+use crate::model_operations::model_processor::{MessageType, ModelProcessor, ProcessFunction};
+use crate::simpler_model::{SimplerSingleMainListModel, ToSimplerVec};
+use crate::{Callabler, GuiState, MainWindow};
+
+pub(crate) fn connect_reflink(app: &MainWindow, progress_sender: Sender<ProgressData>, stop_flag: Arc<AtomicBool>) {
+    let a = app.as_weak();
+    app.global::<Callabler>().on_reflink_items(move || {
+        let weak_app = a.clone();
+        let progress_sender = progress_sender.clone();
+        let stop_flag = stop_flag.clone();
+        stop_flag.store(false, Ordering::Relaxed);
+        let app = a.upgrade().expect("Failed to upgrade app :(");
+        let active_tab = app.global::<GuiState>().get_active_tab();
+
+        let processor = ModelProcessor::new(active_tab);
+        processor.reflink_selected_items(progress_sender, weak_app, stop_flag);
+    });
+}
+
+impl ModelProcessor {
+    fn reflink_selected_items(self, progress_sender: Sender<ProgressData>, weak_app: Weak<MainWindow>, stop_flag: Arc<AtomicBool>) {
+        let model = self.active_tab.get_tool_model(&weak_app.upgrade().expect("Failed to upgrade app :("));
+        let simpler_model = model.to_simpler_enumerated_vec();
+        thread::spawn(move || {
+            let path_idx = self.active_tab.get_str_path_idx();
+            let name_idx = self.active_tab.get_str_name_idx();
+
+            let compare_stop_flag = stop_flag.clone();
+            let reflink_fnc = move |original: &SimplerSingleMainListModel, derived: &SimplerSingleMainListModel| {
+                reflink_single_item(
+                    &format!("{}{MAIN_SEPARATOR}{}", original.val_str[path_idx], original.val_str[name_idx]),
+                    &format!("{}{MAIN_SEPARATOR}{}", derived.val_str[path_idx], derived.val_str[name_idx]),
+                    &compare_stop_flag,
+                )
+            };
+            self.process_and_update_gui_state(
+                &weak_app,
+                stop_flag,
+                &progress_sender,
+                simpler_model,
+                &ProcessFunction::Related(Box::new(reflink_fnc)),
+                MessageType::Reflink,
+                false,
+            );
+        });
+    }
+}
+
+// Groups found by size or name may contain files with different content, which would be silently replaced,
+// so files are compared byte by byte before cloning
+#[cfg(not(test))]
+fn reflink_single_item(original_path: &str, derived_path: &str, stop_flag: &Arc<AtomicBool>) -> Result<(), String> {
+    use std::path::Path;
+    use std::sync::atomic::AtomicU64;
+
+    use czkawka_core::tools::duplicate::{THREAD_BUFFER_SIZE, compare_files_content};
+
+    let mut buffer = vec![0u8; THREAD_BUFFER_SIZE];
+    let identical = compare_files_content(&mut buffer, Path::new(original_path), Path::new(derived_path), &Arc::new(AtomicU64::new(0)), stop_flag)
+        .map_err(|e| crate::flk!("rust_reflink_failed", name = original_path, target = derived_path, reason = e))?;
+    match identical {
+        Some(true) => {}
+        Some(false) => return Err(crate::flk!("rust_reflink_content_differs", name = original_path, target = derived_path)),
+        None => return Err(crate::flk!("rust_reflink_stopped", name = original_path, target = derived_path)),
+    }
+
+    czkawka_core::common::make_reflink(original_path, derived_path)
+        .map_err(|e| crate::flk!("rust_reflink_failed", name = original_path, target = derived_path, reason = e.to_string()))
+}
+
+#[cfg(test)]
+fn reflink_single_item(original_path: &str, _derived_path: &str, _stop_flag: &Arc<AtomicBool>) -> Result<(), String> {
+    if original_path.contains("test_error") {
+        return Err(format!("Test error for item: {original_path}"));
+    }
+    Ok(())
+}
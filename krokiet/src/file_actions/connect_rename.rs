@@ -5,6 +5,7 @@ use std::thread;
 
 use crossbeam_channel::Sender;
 use czkawka_core::common::progress_data::ProgressData;
+use czkawka_core::common::undo_journal::{JournalOperation, JournalRecorder};
 use log::{error, info, warn};
 use slint::{ComponentHandle, Model, ModelRc, SharedString, VecModel, Weak};
 
@@ -95,6 +96,8 @@ fn connect_rename_single_file(app: &MainWindow) {
             return;
         }
         info!("Renamed {old_full_path:?} to {new_full_path:?}");
+        let size = std::fs::symlink_metadata(&new_full_path).map(|metadata| metadata.len()).unwrap_or_default();
+        JournalRecorder::new().record(JournalOperation::Rename, Path::new(&old_full_path), Some(Path::new(&new_full_path)), size);
 
         // Update the Name cell in place - keeps the file in its duplicate/similar group.
         let new_val_str: Vec<SharedString> = row.val_str.iter().enumerate().map(|(i, s)| if i == name_idx { new_name.into() } else { s }).collect();
@@ -161,7 +164,8 @@ impl ModelProcessor {
             let name_idx = self.active_tab.get_str_name_idx();
             let ext_idx = self.active_tab.get_str_proper_extension();
 
-            let rm_fnc = move |data: &SimplerSingleMainListModel| rename_single_extension_item(data, path_idx, name_idx, ext_idx);
+            let journal = JournalRecorder::new();
+            let rm_fnc = move |data: &SimplerSingleMainListModel| rename_single_extension_item(data, path_idx, name_idx, ext_idx, &journal);
 
             self.process_and_update_gui_state(
                 &weak_app,
@@ -182,7 +186,8 @@ impl ModelProcessor {
             let name_idx = self.active_tab.get_str_name_idx();
             let new_name_idx = StrDataBadNames::NewName as usize;
 
-            let rm_fnc = move |data: &SimplerSingleMainListModel| rename_single_file_name_item(data, path_idx, name_idx, new_name_idx);
+            let journal = JournalRecorder::new();
+            let rm_fnc = move |data: &SimplerSingleMainListModel| rename_single_file_name_item(data, path_idx, name_idx, new_name_idx, &journal);
 
             self.process_and_update_gui_state(
                 &weak_app,
@@ -198,7 +203,7 @@ impl ModelProcessor {
 }
 
 #[cfg(not(test))]
-fn rename_single_file_name_item(data: &SimplerSingleMainListModel, path_idx: usize, name_idx: usize, new_file_name_idx: usize) -> Result<(), String> {
+fn rename_single_file_name_item(data: &SimplerSingleMainListModel, path_idx: usize, name_idx: usize, new_file_name_idx: usize, journal: &JournalRecorder) -> Result<(), String> {
     use std::path::MAIN_SEPARATOR;
     let folder = &data.val_str[path_idx];
     let file_name = &data.val_str[name_idx];
@@ -215,12 +220,14 @@ fn rename_single_file_name_item(data: &SimplerSingleMainListModel, path_idx: usi
             error = e.to_string()
         ))
     } else {
+        let size = std::fs::symlink_metadata(&new_full_path).map(|metadata| metadata.len()).unwrap_or_default();
+        journal.record(JournalOperation::Rename, Path::new(&old_full_path), Some(Path::new(&new_full_path)), size);
         Ok(())
     }
 }
 
 #[cfg(not(test))]
-fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usize, name_idx: usize, ext_idx: usize) -> Result<(), String> {
+fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usize, name_idx: usize, ext_idx: usize, journal: &JournalRecorder) -> Result<(), String> {
     use std::path::MAIN_SEPARATOR;
     let folder = &data.val_str[path_idx];
     let file_name = &data.val_str[name_idx];
@@ -238,12 +245,14 @@ fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usi
             error = e.to_string()
         ))
     } else {
+        let size = std::fs::symlink_metadata(&new_full_path).map(|metadata| metadata.len()).unwrap_or_default();
+        journal.record(JournalOperation::Rename, Path::new(&old_full_path), Some(Path::new(&new_full_path)), size);
         Ok(())
     }
 }
 
 #[cfg(test)]
-fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usize, _name_idx: usize, _ext_idx: usize) -> Result<(), String> {
+fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usize, _name_idx: usize, _ext_idx: usize, _journal: &JournalRecorder) -> Result<(), String> {
     let full_path = &data.val_str[path_idx];
     if full_path.contains("test_error") {
         return Err(format!("Test error for item: {full_path}"));
@@ -252,7 +261,7 @@ fn rename_single_extension_item(data: &SimplerSingleMainListModel, path_idx: usi
 }
 
 #[cfg(test)]
-fn rename_single_file_name_item(data: &SimplerSingleMainListModel, path_idx: usize, _name_idx: usize, _file_name: usize) -> Result<(), String> {
+fn rename_single_file_name_item(data: &SimplerSingleMainListModel, path_idx: usize, _name_idx: usize, _file_name: usize, _journal: &JournalRecorder) -> Result<(), String> {
     let full_path = &data.val_str[path_idx];
     if full_path.contains("test_error") {
         return Err(format!("Test error for item: {full_path}"));
@@ -287,7 +296,8 @@ mod tests {
             let name_idx = 0;
             let ext_idx = 0;
 
-            let rm_fnc = move |data: &SimplerSingleMainListModel| rename_single_extension_item(data, path_idx, name_idx, ext_idx);
+            let journal = JournalRecorder::new();
+            let rm_fnc = move |data: &SimplerSingleMainListModel| rename_single_extension_item(data, path_idx, name_idx, ext_idx, &journal);
 
             let output = Self::process_items(
                 simplified_model,
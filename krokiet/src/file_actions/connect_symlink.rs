@@ -5,6 +5,7 @@ use std::thread;
 
 use crossbeam_channel::Sender;
 use czkawka_core::common::progress_data::ProgressData;
+use czkawka_core::common::undo_journal::JournalRecorder;
 use slint::{ComponentHandle, Weak};
 
 use crate::model_operations::model_processor::{MessageType, ModelProcessor, ProcessFunction};
@@ -34,10 +35,12 @@ impl ModelProcessor {
             let path_idx = self.active_tab.get_str_path_idx();
             let name_idx = self.active_tab.get_str_name_idx();
 
+            let journal = JournalRecorder::new();
             let symlink_fnc = move |original: &SimplerSingleMainListModel, derived: &SimplerSingleMainListModel| {
                 symlink_single_item(
                     &format!("{}{MAIN_SEPARATOR}{}", original.val_str[path_idx], original.val_str[name_idx]),
                     &format!("{}{MAIN_SEPARATOR}{}", derived.val_str[path_idx], derived.val_str[name_idx]),
+                    &journal,
                 )
             };
 
@@ -55,13 +58,20 @@ impl ModelProcessor {
 }
 
 #[cfg(not(test))]
-fn symlink_single_item(original_path: &str, derived_path: &str) -> Result<(), String> {
+fn symlink_single_item(original_path: &str, derived_path: &str, journal: &JournalRecorder) -> Result<(), String> {
+    use std::path::Path;
+
+    use czkawka_core::common::undo_journal::JournalOperation;
+
     czkawka_core::common::make_file_symlink(original_path, derived_path)
-        .map_err(|e| crate::flk!("rust_symlink_failed", name = original_path, target = derived_path, reason = e.to_string()))
+        .map_err(|e| crate::flk!("rust_symlink_failed", name = original_path, target = derived_path, reason = e.to_string()))?;
+    let size = std::fs::metadata(original_path).map(|metadata| metadata.len()).unwrap_or_default();
+    journal.record(JournalOperation::Symlink, Path::new(derived_path), Some(Path::new(original_path)), size);
+    Ok(())
 }
 
 #[cfg(test)]
-fn symlink_single_item(original_path: &str, _derived_path: &str) -> Result<(), String> {
+fn symlink_single_item(original_path: &str, _derived_path: &str, _journal: &JournalRecorder) -> Result<(), String> {
     if original_path.contains("test_error") {
         return Err(format!("Test error for item: {original_path}"));
     }
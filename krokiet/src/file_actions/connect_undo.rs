@@ -0,0 +1,27 @@
+use std::thread;
+
+use czkawka_core::common::undo_journal::undo_last_operation;
+use czkawka_core::helpers::messages::MessageLimit;
+use slint::ComponentHandle;
+
+use crate::{Callabler, GuiState, MainWindow, flk};
+
+pub(crate) fn connect_undo(app: &MainWindow) {
+    let a = app.as_weak();
+    app.global::<Callabler>().on_undo_last_operation(move || {
+        let weak_app = a.clone();
+        let app = a.upgrade().expect("Failed to upgrade app :(");
+        app.set_processing(true);
+
+        thread::spawn(move || {
+            let messages = undo_last_operation(false);
+            weak_app
+                .upgrade_in_event_loop(move |app| {
+                    // Results shown in lists may be outdated now, but are left untouched, because restored items were removed from them before
+                    app.global::<GuiState>().set_info_text(messages.create_messages_text(MessageLimit::NoLimit).into());
+                    app.invoke_processing_ended(flk!("rust_undo_finished", errors = messages.errors.len()).into());
+                })
+                .expect("Failed to update app after undo");
+        });
+    });
+}
@@ -3,5 +3,7 @@ pub mod connect_delete;
 pub mod connect_hardlink;
 pub mod connect_move;
 pub mod connect_optimize_video;
+pub mod connect_reflink;
 pub mod connect_rename;
 pub mod connect_symlink;
+pub mod connect_undo;
@@ -20,8 +20,10 @@ use file_actions::connect_delete::{connect_delete_button, connect_trash_button};
 use file_actions::connect_hardlink::connect_hardlink;
 use file_actions::connect_move::connect_move;
 use file_actions::connect_optimize_video::connect_optimize_video;
+use file_actions::connect_reflink::connect_reflink;
 use file_actions::connect_rename::connect_rename;
 use file_actions::connect_symlink::connect_symlink;
+use file_actions::connect_undo::connect_undo;
 use log::{error, info};
 use slint::VecModel;
 
@@ -165,7 +167,9 @@ fn main() {
     connect_optimize_video(&app, progress_sender.clone(), stop_flag.clone());
     connect_clean(&app, progress_sender.clone(), stop_flag.clone());
     connect_hardlink(&app, progress_sender.clone(), stop_flag.clone());
+    connect_reflink(&app, progress_sender.clone(), stop_flag.clone());
     connect_symlink(&app, progress_sender, stop_flag);
+    connect_undo(&app);
     connect_save(&app, Arc::clone(&shared_models));
     connect_row_selections(&app);
     connect_sort(&app);
@@ -31,6 +31,7 @@ pub enum MessageType {
     Rename,
     Move,
     Hardlink,
+    Reflink,
     Symlink,
     OptimizeVideo,
     CleanExif,
@@ -43,6 +44,7 @@ impl MessageType {
             Self::Rename => flk!("rust_no_files_renamed"),
             Self::Move => flk!("rust_no_files_moved"),
             Self::Hardlink => flk!("rust_no_files_hardlinked"),
+            Self::Reflink => flk!("rust_no_files_reflinked"),
             Self::Symlink => flk!("rust_no_files_symlinked"),
             Self::OptimizeVideo => flk!("rust_no_videos_optimized"),
             Self::CleanExif => flk!("rust_no_exif_cleaned"),
@@ -54,6 +56,7 @@ impl MessageType {
             Self::Rename => flk!("rust_rename_summary", renamed = processed, failed = failed, total = total),
             Self::Move => flk!("rust_move_summary", moved = processed, failed = failed, total = total),
             Self::Hardlink => flk!("rust_hardlink_summary", hardlinked = processed, failed = failed, total = total),
+            Self::Reflink => flk!("rust_reflink_summary", reflinked = processed, failed = failed, total = total),
             Self::Symlink => flk!("rust_symlink_summary", symlinked = processed, failed = failed, total = total),
             Self::OptimizeVideo => flk!("rust_optimize_video_summary", optimized = processed, failed = failed, total = total),
             Self::CleanExif => flk!("rust_clean_exif_summary", cleaned = processed, failed = failed, total = total),
@@ -65,6 +68,7 @@ impl MessageType {
             Self::Rename => ToolStage::RenamingFiles,
             Self::Move => ToolStage::MovingFiles,
             Self::Hardlink => ToolStage::HardlinkingFiles,
+            Self::Reflink => ToolStage::ReflinkingFiles,
             Self::Symlink => ToolStage::SymlinkingFiles,
             Self::OptimizeVideo => ToolStage::OptimizingVideos,
             Self::CleanExif => ToolStage::CleaningExif,
@@ -77,6 +81,7 @@ impl MessageType {
             Self::Rename => "rename",
             Self::Move => "move",
             Self::Hardlink => "hardlink",
+            Self::Reflink => "reflink",
             Self::Symlink => "symlink",
             Self::OptimizeVideo => "optimize_video",
             Self::CleanExif => "clean_exif",
@@ -124,6 +124,7 @@ impl StringComboBoxItems {
             ("blake3", "Blake3", HashType::Blake3),
             ("crc32", "CRC32", HashType::Crc32),
             ("xxh3", "XXH3", HashType::Xxh3),
+            ("sha256", "SHA256", HashType::Sha256),
         ]);
 
         let biggest_files_method = Self::convert_to_combobox_items_i18n(&[
@@ -8,6 +8,7 @@ use std::path::PathBuf;
 use czkawka_core::TOOLS_NUMBER;
 use czkawka_core::common::basic_gui_cli::CliResult;
 use czkawka_core::common::config_cache_path::get_config_cache_path;
+use czkawka_core::common::io_throttle::{set_idle_priority, set_read_speed_limit};
 use czkawka_core::common::{get_all_available_threads, set_number_of_threads};
 use czkawka_core::tools::similar_videos::{
     ALLOWED_AUDIO_LENGTH_RATIO, ALLOWED_AUDIO_SIMILARITY_PERCENT, ALLOWED_DURATION_TOLERANCE_PCT, ALLOWED_MATCH_FRACTION, ALLOWED_SKIP_FORWARD_AMOUNT, ALLOWED_VID_HASH_DURATION,
@@ -92,6 +93,19 @@ pub(crate) fn connect_changing_settings_preset(app: &MainWindow) {
             }
         }
     });
+    let a = app.as_weak();
+    app.global::<Callabler>().on_io_throttling_changed(move || {
+        let app = a.upgrade().expect("Failed to upgrade app :(");
+        apply_io_throttling(&app);
+    });
+}
+
+// Limits are global in core, so they are changed immediately, even when scan is running
+fn apply_io_throttling(app: &MainWindow) {
+    let settings = app.global::<Settings>();
+    let maximum_read_speed = settings.get_maximum_read_speed().parse::<u64>().unwrap_or(0);
+    set_read_speed_limit(maximum_read_speed * 1024 * 1024);
+    set_idle_priority(settings.get_idle_io_priority());
 }
 
 pub(crate) fn create_default_settings_files() {
@@ -300,6 +314,7 @@ pub(crate) fn set_base_settings_to_gui(app: &MainWindow, basic_settings: &BasicS
     settings.set_select_show_except_biggest_resolution(basic_settings.select_show_except_biggest_resolution);
     settings.set_select_show_except_shortest_path(basic_settings.select_show_except_shortest_path);
     settings.set_select_show_except_longest_path(basic_settings.select_show_except_longest_path);
+    settings.set_select_keep_rules(basic_settings.select_keep_rules.clone().into());
 
     set_combobox_basic_settings_items(&settings, basic_settings);
 }
@@ -423,10 +438,15 @@ pub(crate) fn set_settings_to_gui(app: &MainWindow, custom_settings: &SettingsCu
     settings.set_excluded_extensions(custom_settings.excluded_extensions.clone().into());
     settings.set_minimum_file_size(custom_settings.minimum_file_size.to_string().into());
     settings.set_maximum_file_size(custom_settings.maximum_file_size.to_string().into());
+    settings.set_minimum_file_age(custom_settings.minimum_file_age.to_string().into());
+    settings.set_maximum_file_age(custom_settings.maximum_file_age.to_string().into());
     settings.set_use_cache(custom_settings.use_cache);
     settings.set_save_as_json(custom_settings.save_also_as_json);
     settings.set_ignore_other_filesystems(custom_settings.ignore_other_file_systems);
     settings.set_thread_number(custom_settings.thread_number as f32);
+    settings.set_maximum_read_speed(custom_settings.maximum_read_speed.to_string().into());
+    settings.set_idle_io_priority(custom_settings.idle_io_priority);
+    apply_io_throttling(app);
 
     settings.set_recursive_search(custom_settings.recursive_search);
     settings.set_duplicate_image_preview(custom_settings.duplicate_image_preview);
@@ -435,6 +455,7 @@ pub(crate) fn set_settings_to_gui(app: &MainWindow, custom_settings: &SettingsCu
     settings.set_duplicate_minimal_prehash_cache_size(custom_settings.duplicate_minimal_prehash_cache_size.to_string().into());
     settings.set_delete_outdated_cache_entries(custom_settings.delete_outdated_cache_entries);
     settings.set_hide_hard_links(custom_settings.hide_hard_links);
+    settings.set_resume_interrupted_scans(custom_settings.resume_interrupted_scans);
     settings.set_duplicates_sub_name_case_sensitive(custom_settings.duplicates_sub_name_case_sensitive);
     settings.set_similar_images_show_image_preview(custom_settings.similar_images_show_image_preview);
     settings.set_video_thumbnails_preview(custom_settings.video_thumbnails_preview);
@@ -653,12 +674,16 @@ pub(crate) fn collect_settings(app: &MainWindow) -> SettingsCustom {
     let excluded_extensions = settings.get_excluded_extensions().to_string();
     let minimum_file_size = settings.get_minimum_file_size().parse::<i32>().unwrap_or(DEFAULT_MINIMUM_SIZE_KB);
     let maximum_file_size = settings.get_maximum_file_size().parse::<i32>().unwrap_or(DEFAULT_MAXIMUM_SIZE_KB);
+    let minimum_file_age = settings.get_minimum_file_age().parse::<i32>().unwrap_or(0).max(0);
+    let maximum_file_age = settings.get_maximum_file_age().parse::<i32>().unwrap_or(0).max(0);
 
     let recursive_search = settings.get_recursive_search();
     let use_cache = settings.get_use_cache();
     let save_also_as_json = settings.get_save_as_json();
     let ignore_other_file_systems = settings.get_ignore_other_filesystems();
     let thread_number = settings.get_thread_number().round() as i32;
+    let maximum_read_speed = settings.get_maximum_read_speed().parse::<i32>().unwrap_or(0).max(0);
+    let idle_io_priority = settings.get_idle_io_priority();
 
     let duplicate_image_preview = settings.get_duplicate_image_preview();
     let duplicate_use_prehash = settings.get_duplicate_use_prehash();
@@ -669,6 +694,7 @@ pub(crate) fn collect_settings(app: &MainWindow) -> SettingsCustom {
         .unwrap_or(DEFAULT_MINIMUM_PREHASH_CACHE_SIZE);
     let delete_outdated_cache_entries = settings.get_delete_outdated_cache_entries();
     let hide_hard_links = settings.get_hide_hard_links();
+    let resume_interrupted_scans = settings.get_resume_interrupted_scans();
     let duplicates_sub_name_case_sensitive = settings.get_duplicates_sub_name_case_sensitive();
 
     let similar_images_show_image_preview = settings.get_similar_images_show_image_preview();
@@ -815,17 +841,22 @@ pub(crate) fn collect_settings(app: &MainWindow) -> SettingsCustom {
         excluded_extensions,
         minimum_file_size,
         maximum_file_size,
+        minimum_file_age,
+        maximum_file_age,
         recursive_search,
         use_cache,
         save_also_as_json,
         ignore_other_file_systems,
         thread_number,
+        maximum_read_speed,
+        idle_io_priority,
         duplicate_image_preview,
         duplicate_use_prehash,
         duplicate_minimal_hash_cache_size,
         duplicate_minimal_prehash_cache_size,
         delete_outdated_cache_entries,
         hide_hard_links,
+        resume_interrupted_scans,
         similar_images_show_image_preview,
         video_thumbnails_preview,
         clear_unused_video_thumbnails,
@@ -1018,5 +1049,6 @@ pub(crate) fn collect_base_settings(app: &MainWindow) -> BasicSettings {
         select_show_except_biggest_resolution: settings.get_select_show_except_biggest_resolution(),
         select_show_except_shortest_path: settings.get_select_show_except_shortest_path(),
         select_show_except_longest_path: settings.get_select_show_except_longest_path(),
+        select_keep_rules: settings.get_select_keep_rules().to_string(),
     }
 }
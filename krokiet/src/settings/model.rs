@@ -60,6 +60,10 @@ pub struct SettingsCustom {
     pub minimum_file_size: i32,
     #[serde(default = "maximum_file_size")]
     pub maximum_file_size: i32,
+    #[serde(default)]
+    pub minimum_file_age: i32,
+    #[serde(default)]
+    pub maximum_file_age: i32,
     #[serde(default = "ttrue")]
     pub recursive_search: bool,
     #[serde(default = "ttrue")]
@@ -70,6 +74,10 @@ pub struct SettingsCustom {
     pub ignore_other_file_systems: bool,
     #[serde(default)]
     pub thread_number: i32,
+    #[serde(default)]
+    pub maximum_read_speed: i32,
+    #[serde(default)]
+    pub idle_io_priority: bool,
     #[serde(default = "ttrue")]
     pub duplicate_image_preview: bool,
     #[serde(default = "ttrue")]
@@ -82,6 +90,8 @@ pub struct SettingsCustom {
     pub delete_outdated_cache_entries: bool,
     #[serde(default = "ttrue")]
     pub hide_hard_links: bool,
+    #[serde(default)]
+    pub resume_interrupted_scans: bool,
     #[serde(default = "ttrue")]
     pub similar_images_show_image_preview: bool,
     #[serde(default = "ttrue")]
@@ -374,6 +384,8 @@ pub struct BasicSettings {
     pub select_show_except_shortest_path: bool,
     #[serde(default = "ttrue")]
     pub select_show_except_longest_path: bool,
+    #[serde(default)]
+    pub select_keep_rules: String,
 }
 
 impl Default for BasicSettings {